@@ -68,6 +68,9 @@ pub fn bench_downscale_rgba(bench: &mut Bench) {
     {
         cpu_ext_and_name.push((CpuExtensions::Sse4_1, "sse4.1"));
         cpu_ext_and_name.push((CpuExtensions::Avx2, "avx2"));
+        if CpuExtensions::Avx512.is_supported() {
+            cpu_ext_and_name.push((CpuExtensions::Avx512, "avx512"));
+        }
     }
     for (cpu_ext, ext_name) in cpu_ext_and_name {
         for alg_name in alg_names {
@@ -97,9 +100,9 @@ pub fn bench_downscale_rgba(bench: &mut Bench) {
 
             let mut fast_resizer = Resizer::new(resize_alg);
 
+            fast_resizer.reset_internal_buffers();
+            fast_resizer.set_cpu_extensions(cpu_ext);
             unsafe {
-                fast_resizer.reset_internal_buffers();
-                fast_resizer.set_cpu_extensions(cpu_ext);
                 mul_div.set_cpu_extensions(cpu_ext);
             }
 
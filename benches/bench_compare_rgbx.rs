@@ -44,10 +44,8 @@ pub fn bench_downscale_rgbx(bench: &mut Bench) {
             };
             let mut fast_resizer = Resizer::new(resize_alg);
 
-            unsafe {
-                fast_resizer.reset_internal_buffers();
-                fast_resizer.set_cpu_extensions(cpu_ext);
-            }
+            fast_resizer.reset_internal_buffers();
+            fast_resizer.set_cpu_extensions(cpu_ext);
 
             bench.task(format!("fir {} - {}", ext_name, alg_name), |task| {
                 task.iter(|| {
@@ -3,7 +3,7 @@ use std::num::NonZeroU32;
 use glassbench::*;
 
 use fast_image_resize::Image;
-use fast_image_resize::{CpuExtensions, FilterType, PixelType, ResizeAlg, Resizer};
+use fast_image_resize::{CpuExtensions, FilterType, PixelType, PreparedResize, ResizeAlg, Resizer};
 
 mod utils;
 
@@ -70,6 +70,24 @@ fn get_big_i32_image() -> Image<'static> {
     .unwrap()
 }
 
+fn get_big_f32_image() -> Image<'static> {
+    let img = utils::get_big_luma16_image();
+    let img_data: Vec<u32> = img
+        .as_raw()
+        .iter()
+        .map(|&p| (p as f32 * (i16::MAX as f32 + 1.0)).to_bits())
+        .collect();
+    let width = img.width();
+    let height = img.height();
+    Image::from_vec_u32(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        img_data,
+        PixelType::F32,
+    )
+    .unwrap()
+}
+
 fn get_big_u8_image() -> Image<'static> {
     let img = utils::get_big_luma8_image();
     let width = img.width();
@@ -106,9 +124,7 @@ fn native_nearest_u8x4_bench(bench: &mut Bench) {
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Nearest);
-    unsafe {
-        resizer.set_cpu_extensions(CpuExtensions::None);
-    }
+    resizer.set_cpu_extensions(CpuExtensions::None);
     bench.task("nearest wo SIMD", |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -116,6 +132,24 @@ fn native_nearest_u8x4_bench(bench: &mut Bench) {
     });
 }
 
+fn u8x4_nearest_upscale_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
+    let image = get_small_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_BIG_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_BIG_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Nearest);
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
 fn u8x4_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
     let image = get_big_source_image();
     let mut res_image = Image::new(
@@ -126,9 +160,7 @@ fn u8x4_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
     bench.task(name, |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -147,9 +179,7 @@ fn avx2_supersampling_lanczos3_bench(bench: &mut Bench) {
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::SuperSampling(FilterType::Lanczos3, 2));
-    unsafe {
-        resizer.set_cpu_extensions(CpuExtensions::Avx2);
-    }
+    resizer.set_cpu_extensions(CpuExtensions::Avx2);
     bench.task("supersampling lanczos3 avx2", |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -168,9 +198,7 @@ fn avx2_lanczos3_upscale_bench(bench: &mut Bench) {
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(CpuExtensions::Avx2);
-    }
+    resizer.set_cpu_extensions(CpuExtensions::Avx2);
     bench.task("lanczos3 upscale avx2", |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -178,7 +206,24 @@ fn avx2_lanczos3_upscale_bench(bench: &mut Bench) {
     });
 }
 
-fn native_lanczos3_i32_bench(bench: &mut Bench) {
+fn u8x4_bilinear_upscale_bench(bench: &mut Bench, resize_alg: ResizeAlg, name: &str) {
+    let image = get_small_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_BIG_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_BIG_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(resize_alg);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn i32_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
     let image = get_big_i32_image();
     let mut res_image = Image::new(
         NonZeroU32::new(NEW_WIDTH).unwrap(),
@@ -188,10 +233,26 @@ fn native_lanczos3_i32_bench(bench: &mut Bench) {
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(CpuExtensions::None);
-    }
-    bench.task("i32 lanczos3 wo SIMD", |task| {
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn f32_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
+    let image = get_big_f32_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
         })
@@ -208,9 +269,24 @@ fn u8_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &st
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn u8x4_integral_image_bench(bench: &mut Bench, resize_alg: ResizeAlg, name: &str) {
+    let image = get_big_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(64).unwrap(),
+        NonZeroU32::new(43).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(resize_alg);
     bench.task(name, |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -228,9 +304,7 @@ fn native_nearest_u8_bench(bench: &mut Bench) {
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Nearest);
-    unsafe {
-        resizer.set_cpu_extensions(CpuExtensions::None);
-    }
+    resizer.set_cpu_extensions(CpuExtensions::None);
     bench.task("u8 nearest wo SIMD", |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -248,9 +322,7 @@ fn u8x3_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
     bench.task(name, |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -268,9 +340,7 @@ fn u16x3_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name:
     let src_image = image.view();
     let mut dst_image = res_image.view_mut();
     let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
     bench.task(name, |task| {
         task.iter(|| {
             resizer.resize(&src_image, &mut dst_image).unwrap();
@@ -278,6 +348,148 @@ fn u16x3_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name:
     });
 }
 
+/// Downscales 16-bit RGB image of size 4000x3000 that is typical
+/// for medical and TIFF images.
+fn u8x3_6000x4000_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
+    let big_image = get_big_u8x3_source_image();
+    let mut image = Image::new(
+        NonZeroU32::new(6000).unwrap(),
+        NonZeroU32::new(4000).unwrap(),
+        big_image.pixel_type(),
+    );
+    Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear))
+        .resize(&big_image.view(), &mut image.view_mut())
+        .unwrap();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn u16x3_4000x3000_lanczos3_bench(bench: &mut Bench, cpu_extensions: CpuExtensions, name: &str) {
+    let big_image = get_big_u16x3_source_image();
+    let mut image = Image::new(
+        NonZeroU32::new(4000).unwrap(),
+        NonZeroU32::new(3000).unwrap(),
+        big_image.pixel_type(),
+    );
+    Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear))
+        .resize(&big_image.view(), &mut image.view_mut())
+        .unwrap();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_cpu_extensions(cpu_extensions);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn u8x4_lanczos3_prepared_bench(bench: &mut Bench) {
+    let image = get_big_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let prepared = PreparedResize::new(
+        FilterType::Lanczos3,
+        &src_image,
+        dst_image.width(),
+        dst_image.height(),
+    );
+    bench.task("u8x4 lanczos3 prepared", |task| {
+        task.iter(|| {
+            resizer
+                .resize_prepared(&prepared, &src_image, &mut dst_image)
+                .unwrap();
+        })
+    });
+}
+
+#[cfg(feature = "rayon")]
+fn u8x4_lanczos3_threads_bench(bench: &mut Bench, num_threads: usize) {
+    let image = get_big_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap();
+    let name = format!("u8x4 lanczos3 {} threads", num_threads);
+    bench.task(name, |task| {
+        task.iter(|| {
+            pool.install(|| resizer.resize(&src_image, &mut dst_image).unwrap());
+        })
+    });
+}
+
+fn u8x4_lanczos3_std_threads_bench(bench: &mut Bench, num_threads: usize) {
+    let image = get_big_source_image();
+    let mut res_image = Image::new(
+        NonZeroU32::new(NEW_WIDTH).unwrap(),
+        NonZeroU32::new(NEW_HEIGHT).unwrap(),
+        image.pixel_type(),
+    );
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_num_threads(num_threads);
+    let name = format!("u8x4 lanczos3 {} std threads", num_threads);
+    bench.task(name, |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+}
+
+fn u8x4_same_size_bench(bench: &mut Bench) {
+    let image = get_big_source_image();
+    let mut res_image = Image::new(image.width(), image.height(), image.pixel_type());
+    let src_image = image.view();
+    let mut dst_image = res_image.view_mut();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    bench.task("u8x4 lanczos3 same size", |task| {
+        task.iter(|| {
+            resizer.resize(&src_image, &mut dst_image).unwrap();
+        })
+    });
+
+    // Copying of the whole buffer as a baseline
+    let mut buffer = vec![0u8; image.buffer().len()];
+    bench.task("u8x4 memcpy", |task| {
+        task.iter(|| {
+            buffer.copy_from_slice(image.buffer());
+        })
+    });
+}
+
 pub fn main() {
     use glassbench::*;
     let name = env!("CARGO_CRATE_NAME");
@@ -286,26 +498,91 @@ pub fn main() {
         let mut bench = create_bench(name, "Resize", &cmd);
         native_nearest_u8x4_bench(&mut bench);
         native_nearest_u8_bench(&mut bench);
+        u8x4_nearest_upscale_bench(
+            &mut bench,
+            CpuExtensions::None,
+            "u8x4 nearest upscale wo SIMD",
+        );
 
         u8_lanczos3_bench(&mut bench, CpuExtensions::None, "u8 lanczos3 wo SIMD");
         u8x3_lanczos3_bench(&mut bench, CpuExtensions::None, "u8x3 lanczos3 wo SIMD");
         u8x4_lanczos3_bench(&mut bench, CpuExtensions::None, "u8x4 lanczos3 wo SIMD");
+        u8x3_6000x4000_lanczos3_bench(
+            &mut bench,
+            CpuExtensions::None,
+            "u8x3 6000x4000 lanczos3 wo SIMD",
+        );
         u16x3_lanczos3_bench(&mut bench, CpuExtensions::None, "u16x3 lanczos3 wo SIMD");
-        native_lanczos3_i32_bench(&mut bench);
+        u16x3_4000x3000_lanczos3_bench(
+            &mut bench,
+            CpuExtensions::None,
+            "u16x3 4000x3000 lanczos3 wo SIMD",
+        );
+        i32_lanczos3_bench(&mut bench, CpuExtensions::None, "i32 lanczos3 wo SIMD");
+        f32_lanczos3_bench(&mut bench, CpuExtensions::None, "f32 lanczos3 wo SIMD");
+        u8x4_bilinear_upscale_bench(&mut bench, ResizeAlg::Bilinear, "u8x4 bilinear upscale");
+        u8x4_bilinear_upscale_bench(
+            &mut bench,
+            ResizeAlg::Convolution(FilterType::Bilinear),
+            "u8x4 bilinear convolution upscale",
+        );
+        u8x4_integral_image_bench(
+            &mut bench,
+            ResizeAlg::IntegralImage,
+            "u8x4 integral image to 64x43",
+        );
+        u8x4_integral_image_bench(
+            &mut bench,
+            ResizeAlg::Convolution(FilterType::Lanczos3),
+            "u8x4 lanczos3 to 64x43",
+        );
         #[cfg(target_arch = "x86_64")]
         {
+            u8x4_nearest_upscale_bench(
+                &mut bench,
+                CpuExtensions::Avx2,
+                "u8x4 nearest upscale avx2",
+            );
             u8_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "u8 lanczos3 avx2");
 
             u8x3_lanczos3_bench(&mut bench, CpuExtensions::Sse4_1, "u8x3 lanczos3 sse4.1");
             u8x3_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "u8x3 lanczos3 avx2");
+            u8x3_6000x4000_lanczos3_bench(
+                &mut bench,
+                CpuExtensions::Avx2,
+                "u8x3 6000x4000 lanczos3 avx2",
+            );
             u16x3_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "u16x3 lanczos3 avx2");
+            u16x3_4000x3000_lanczos3_bench(
+                &mut bench,
+                CpuExtensions::Avx2,
+                "u16x3 4000x3000 lanczos3 avx2",
+            );
+
+            i32_lanczos3_bench(&mut bench, CpuExtensions::Sse4_1, "i32 lanczos3 sse4.1");
+            i32_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "i32 lanczos3 avx2");
+            f32_lanczos3_bench(&mut bench, CpuExtensions::Sse4_1, "f32 lanczos3 sse4.1");
+            f32_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "f32 lanczos3 avx2");
 
             u8x4_lanczos3_bench(&mut bench, CpuExtensions::Sse4_1, "u8x4 lanczos3 sse4.1");
             u8x4_lanczos3_bench(&mut bench, CpuExtensions::Avx2, "u8x4 lanczos3 avx2");
+            if CpuExtensions::Avx512.is_supported() {
+                u8x4_lanczos3_bench(&mut bench, CpuExtensions::Avx512, "u8x4 lanczos3 avx512");
+            }
 
             avx2_supersampling_lanczos3_bench(&mut bench);
             avx2_lanczos3_upscale_bench(&mut bench);
         }
+        u8x4_lanczos3_bench(&mut bench, CpuExtensions::default(), "u8x4 lanczos3");
+        u8x4_lanczos3_prepared_bench(&mut bench);
+        u8x4_same_size_bench(&mut bench);
+        #[cfg(feature = "rayon")]
+        for num_threads in [1, 2, 4, 8] {
+            u8x4_lanczos3_threads_bench(&mut bench, num_threads);
+        }
+        for num_threads in [1, 2, 4, 8] {
+            u8x4_lanczos3_std_threads_bench(&mut bench, num_threads);
+        }
         if let Err(e) = after_bench(&mut bench, &cmd) {
             eprintln!("{:?}", e);
         }
@@ -0,0 +1,31 @@
+//! Stretches an image twice horizontally (anamorphic stretch) with help
+//! of horizontal convolution only, without the vertical pass of resizing.
+use std::num::NonZeroU32;
+
+use fast_image_resize as fr;
+
+fn main() {
+    let width = NonZeroU32::new(8).unwrap();
+    let height = NonZeroU32::new(2).unwrap();
+    let buffer: Vec<u8> = (0..width.get() * height.get())
+        .map(|i| (i % width.get() * 32) as u8)
+        .collect();
+    let src_image = fr::Image::from_vec_u8(width, height, buffer, fr::PixelType::U8).unwrap();
+
+    let dst_width = NonZeroU32::new(width.get() * 2).unwrap();
+    let mut dst_image = fr::Image::new(dst_width, height, fr::PixelType::U8);
+    let coeffs = fr::Coefficients::new(fr::FilterType::CatmullRom, width, dst_width);
+
+    let mut resizer = fr::Resizer::default();
+    resizer
+        .horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs)
+        .unwrap();
+
+    for (src_row, dst_row) in src_image
+        .buffer()
+        .chunks_exact(width.get() as usize)
+        .zip(dst_image.buffer().chunks_exact(dst_width.get() as usize))
+    {
+        println!("{:?} -> {:?}", src_row, dst_row);
+    }
+}
@@ -1,7 +1,7 @@
 pub use errors::*;
 
 use crate::image_view::{TypedImageView, TypedImageViewMut};
-use crate::pixels::U8x4;
+use crate::pixels::{Pixel, PixelType};
 use crate::CpuExtensions;
 use crate::{ImageView, ImageViewMut};
 
@@ -15,6 +15,11 @@ mod sse4;
 /// Methods of this structure used to multiply or divide RGB-channels
 /// by alpha-channel.
 ///
+/// Supported pixel types: `U8x2`, `U8x4`, `U16x2`, `U16x4` and `F32x4`.
+/// Images with `U8x2`, `U16x2`, `U16x4` and `F32x4` pixels are always processed
+/// by native Rust-code, regardless of selected CPU-extensions. Dividing of `F32x4` pixels with zero alpha
+/// leaves their channels untouched.
+///
 /// By default, instance of `MulDiv` created with best CPU-extensions provided by your CPU.
 /// You can change this by use method [MulDiv::set_cpu_extensions].
 ///
@@ -58,10 +63,38 @@ impl MulDiv {
         src_image: &ImageView,
         dst_image: &mut ImageViewMut,
     ) -> Result<(), MulDivImagesError> {
-        let (src_image_u8x4, dst_image_u8x4) = assert_images(src_image, dst_image)?;
+        match src_image.pixel_type() {
+            PixelType::U16x4 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u16x4_image(), dst_image.u16x4_image())?;
+                native::mul::multiply_alpha_u16x4_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::F32x4 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.f32x4_image(), dst_image.f32x4_image())?;
+                native::mul::multiply_alpha_f32x4_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::U8x2 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u8x2_image(), dst_image.u8x2_image())?;
+                native::mul::multiply_alpha_u8x2_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::U16x2 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u16x2_image(), dst_image.u16x2_image())?;
+                native::mul::multiply_alpha_u16x2_native(src_image, dst_image);
+                return Ok(());
+            }
+            _ => (),
+        }
+        let (src_image_u8x4, dst_image_u8x4) =
+            assert_images(src_image.u8x4_image(), dst_image.u8x4_image())?;
         match self.cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => unsafe {
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => unsafe {
                 avx2::mul::multiply_alpha_avx2(src_image_u8x4, dst_image_u8x4)
             },
             #[cfg(target_arch = "x86_64")]
@@ -75,10 +108,35 @@ impl MulDiv {
 
     /// Multiplies RGB-channels of image by alpha-channel inplace.
     pub fn multiply_alpha_inplace(&self, image: &mut ImageViewMut) -> Result<(), MulDivImageError> {
-        let image_u8x4 = assert_image(image)?;
+        match image.pixel_type() {
+            PixelType::U16x4 => {
+                let image = assert_image(image.u16x4_image())?;
+                native::mul::multiply_alpha_u16x4_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::F32x4 => {
+                let image = assert_image(image.f32x4_image())?;
+                native::mul::multiply_alpha_f32x4_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::U8x2 => {
+                let image = assert_image(image.u8x2_image())?;
+                native::mul::multiply_alpha_u8x2_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::U16x2 => {
+                let image = assert_image(image.u16x2_image())?;
+                native::mul::multiply_alpha_u16x2_inplace_native(image);
+                return Ok(());
+            }
+            _ => (),
+        }
+        let image_u8x4 = assert_image(image.u8x4_image())?;
         match self.cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => unsafe { avx2::mul::multiply_alpha_inplace_avx2(image_u8x4) },
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => unsafe {
+                avx2::mul::multiply_alpha_inplace_avx2(image_u8x4)
+            },
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => unsafe { sse4::mul::multiply_alpha_inplace_sse4(image_u8x4) },
             _ => native::mul::multiply_alpha_inplace_native(image_u8x4),
@@ -93,10 +151,38 @@ impl MulDiv {
         src_image: &ImageView,
         dst_image: &mut ImageViewMut,
     ) -> Result<(), MulDivImagesError> {
-        let (src_image_u8x4, dst_image_u8x4) = assert_images(src_image, dst_image)?;
+        match src_image.pixel_type() {
+            PixelType::U16x4 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u16x4_image(), dst_image.u16x4_image())?;
+                native::div::divide_alpha_u16x4_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::F32x4 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.f32x4_image(), dst_image.f32x4_image())?;
+                native::div::divide_alpha_f32x4_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::U8x2 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u8x2_image(), dst_image.u8x2_image())?;
+                native::div::divide_alpha_u8x2_native(src_image, dst_image);
+                return Ok(());
+            }
+            PixelType::U16x2 => {
+                let (src_image, dst_image) =
+                    assert_images(src_image.u16x2_image(), dst_image.u16x2_image())?;
+                native::div::divide_alpha_u16x2_native(src_image, dst_image);
+                return Ok(());
+            }
+            _ => (),
+        }
+        let (src_image_u8x4, dst_image_u8x4) =
+            assert_images(src_image.u8x4_image(), dst_image.u8x4_image())?;
         match self.cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => unsafe {
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => unsafe {
                 avx2::div::divide_alpha_avx2(src_image_u8x4, dst_image_u8x4)
             },
             #[cfg(target_arch = "x86_64")]
@@ -110,10 +196,35 @@ impl MulDiv {
 
     /// Divides RGB-channels of image by alpha-channel inplace.
     pub fn divide_alpha_inplace(&self, image: &mut ImageViewMut) -> Result<(), MulDivImageError> {
-        let image_u8x4 = assert_image(image)?;
+        match image.pixel_type() {
+            PixelType::U16x4 => {
+                let image = assert_image(image.u16x4_image())?;
+                native::div::divide_alpha_u16x4_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::F32x4 => {
+                let image = assert_image(image.f32x4_image())?;
+                native::div::divide_alpha_f32x4_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::U8x2 => {
+                let image = assert_image(image.u8x2_image())?;
+                native::div::divide_alpha_u8x2_inplace_native(image);
+                return Ok(());
+            }
+            PixelType::U16x2 => {
+                let image = assert_image(image.u16x2_image())?;
+                native::div::divide_alpha_u16x2_inplace_native(image);
+                return Ok(());
+            }
+            _ => (),
+        }
+        let image_u8x4 = assert_image(image.u8x4_image())?;
         match self.cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => unsafe { avx2::div::divide_alpha_inplace_avx2(image_u8x4) },
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => unsafe {
+                avx2::div::divide_alpha_inplace_avx2(image_u8x4)
+            },
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => unsafe { sse4::div::divide_alpha_inplace_sse4(image_u8x4) },
             _ => native::div::divide_alpha_inplace_native(image_u8x4),
@@ -122,36 +233,41 @@ impl MulDiv {
     }
 }
 
+/// Clamps color channels of premultiplied image by its alpha channel.
+/// Convolution with sharp filters may overshoot near edges of opaque
+/// regions, so color channels of result may exceed alpha channel.
+///
+/// Images with pixels without alpha channel are left untouched.
+pub(crate) fn clamp_by_alpha(image: &mut ImageViewMut) {
+    if let Some(image) = image.u8x4_image() {
+        native::clamp::clamp_by_alpha_u8x4_native(image);
+    } else if let Some(image) = image.u8x2_image() {
+        native::clamp::clamp_by_alpha_u8x2_native(image);
+    } else if let Some(image) = image.u16x4_image() {
+        native::clamp::clamp_by_alpha_u16x4_native(image);
+    } else if let Some(image) = image.u16x2_image() {
+        native::clamp::clamp_by_alpha_u16x2_native(image);
+    } else if let Some(image) = image.f32x4_image() {
+        native::clamp::clamp_by_alpha_f32x4_native(image);
+    }
+}
+
 #[inline]
-fn assert_images<'s, 'd, 'da>(
-    src_image: &'s ImageView<'s>,
-    dst_image: &'d mut ImageViewMut<'da>,
-) -> Result<
-    (
-        TypedImageView<'s, 's, U8x4>,
-        TypedImageViewMut<'d, 'da, U8x4>,
-    ),
-    MulDivImagesError,
-> {
-    let src_image_u8x4 = src_image
-        .u8x4_image()
-        .ok_or(MulDivImagesError::UnsupportedPixelType)?;
-    let dst_image_u8x4 = dst_image
-        .u8x4_image()
-        .ok_or(MulDivImagesError::UnsupportedPixelType)?;
-    if src_image_u8x4.width() != dst_image_u8x4.width()
-        || src_image_u8x4.height() != dst_image_u8x4.height()
-    {
+fn assert_images<'s, 'd, 'da, P: Pixel>(
+    src_image: Option<TypedImageView<'s, 's, P>>,
+    dst_image: Option<TypedImageViewMut<'d, 'da, P>>,
+) -> Result<(TypedImageView<'s, 's, P>, TypedImageViewMut<'d, 'da, P>), MulDivImagesError> {
+    let src_image = src_image.ok_or(MulDivImagesError::UnsupportedPixelType)?;
+    let dst_image = dst_image.ok_or(MulDivImagesError::UnsupportedPixelType)?;
+    if src_image.width() != dst_image.width() || src_image.height() != dst_image.height() {
         return Err(MulDivImagesError::SizeIsDifferent);
     }
-    Ok((src_image_u8x4, dst_image_u8x4))
+    Ok((src_image, dst_image))
 }
 
 #[inline]
-fn assert_image<'a, 'b>(
-    image: &'a mut ImageViewMut<'b>,
-) -> Result<TypedImageViewMut<'a, 'b, U8x4>, MulDivImageError> {
-    image
-        .u8x4_image()
-        .ok_or(MulDivImageError::UnsupportedPixelType)
+fn assert_image<'a, 'b, P: Pixel>(
+    image: Option<TypedImageViewMut<'a, 'b, P>>,
+) -> Result<TypedImageViewMut<'a, 'b, P>, MulDivImageError> {
+    image.ok_or(MulDivImageError::UnsupportedPixelType)
 }
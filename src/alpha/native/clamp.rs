@@ -0,0 +1,45 @@
+use crate::image_view::TypedImageViewMut;
+use crate::pixels::{F32x4, Pixel, U16x2, U16x4, U8x2, U8x4};
+
+/// Applies `clamp` to every pixel of the image.
+#[inline]
+fn clamp_pixels<P: Pixel>(mut image: TypedImageViewMut<P>, clamp: impl Fn(&mut P)) {
+    for row in image.iter_rows_mut() {
+        row.iter_mut().for_each(&clamp);
+    }
+}
+
+pub(crate) fn clamp_by_alpha_u8x4_native(image: TypedImageViewMut<U8x4>) {
+    clamp_pixels(image, |pixel| {
+        let [r, g, b, a] = pixel.0.to_le_bytes();
+        pixel.0 = u32::from_le_bytes([r.min(a), g.min(a), b.min(a), a]);
+    });
+}
+
+pub(crate) fn clamp_by_alpha_u8x2_native(image: TypedImageViewMut<U8x2>) {
+    clamp_pixels(image, |pixel| {
+        let [l, a] = pixel.0;
+        pixel.0 = [l.min(a), a];
+    });
+}
+
+pub(crate) fn clamp_by_alpha_u16x4_native(image: TypedImageViewMut<U16x4>) {
+    clamp_pixels(image, |pixel| {
+        let [r, g, b, a] = pixel.0;
+        pixel.0 = [r.min(a), g.min(a), b.min(a), a];
+    });
+}
+
+pub(crate) fn clamp_by_alpha_u16x2_native(image: TypedImageViewMut<U16x2>) {
+    clamp_pixels(image, |pixel| {
+        let [l, a] = pixel.0;
+        pixel.0 = [l.min(a), a];
+    });
+}
+
+pub(crate) fn clamp_by_alpha_f32x4_native(image: TypedImageViewMut<F32x4>) {
+    clamp_pixels(image, |pixel| {
+        let [r, g, b, a] = pixel.0;
+        pixel.0 = [r.min(a), g.min(a), b.min(a), a];
+    });
+}
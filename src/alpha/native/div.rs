@@ -1,5 +1,5 @@
 use crate::image_view::{TypedImageView, TypedImageViewMut};
-use crate::pixels::U8x4;
+use crate::pixels::{F32x4, U16x2, U16x4, U8x2, U8x4};
 
 #[inline]
 pub(crate) fn divide_alpha_native(
@@ -17,7 +17,7 @@ pub(crate) fn divide_alpha_native(
 #[inline]
 pub(crate) fn divide_alpha_inplace_native(mut image: TypedImageViewMut<U8x4>) {
     for dst_row in image.iter_rows_mut() {
-        let src_row = unsafe { std::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
         divide_alpha_row_native(src_row, dst_row);
     }
 }
@@ -40,6 +40,55 @@ pub(crate) fn divide_alpha_row_native(src_row: &[U8x4], dst_row: &mut [U8x4]) {
         });
 }
 
+#[inline]
+pub(crate) fn divide_alpha_u16x4_native(
+    src_image: TypedImageView<U16x4>,
+    mut dst_image: TypedImageViewMut<U16x4>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        divide_alpha_u16x4_row_native(src_row, dst_row);
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_u16x4_inplace_native(mut image: TypedImageViewMut<U16x4>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        divide_alpha_u16x4_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn divide_alpha_u16x4_row_native(src_row: &[U16x4], dst_row: &mut [U16x4]) {
+    src_row
+        .iter()
+        .zip(dst_row)
+        .for_each(|(src_pixel, dst_pixel)| {
+            let [r, g, b, alpha] = src_pixel.0;
+            dst_pixel.0 = if alpha == 0 {
+                [0, 0, 0, 0]
+            } else {
+                [
+                    div_65535_and_clip(r, alpha),
+                    div_65535_and_clip(g, alpha),
+                    div_65535_and_clip(b, alpha),
+                    alpha,
+                ]
+            };
+        });
+}
+
+/// Returns `round(v * 65535 / alpha)` clipped to `u16`, `alpha` must be non-zero.
+#[inline(always)]
+fn div_65535_and_clip(v: u16, alpha: u16) -> u16 {
+    // Max value of numerator is 0xffff * 0xffff + 0x7fff, it fits into u32.
+    let numerator = v as u32 * 0xffff + (alpha as u32 >> 1);
+    (numerator / alpha as u32).min(0xffff) as u16
+}
+
 const fn recip_alpha_array(precision: u32) -> [u32; 256] {
     let mut res = [0; 256];
     let scale = 1 << (precision + 1);
@@ -60,6 +109,102 @@ fn div_and_clip(v: u8, recip_alpha: u32) -> u8 {
 
 const RECIP_ALPHA: [u32; 256] = recip_alpha_array(PRECISION);
 
+pub(crate) fn divide_alpha_f32x4_native(
+    src_image: TypedImageView<F32x4>,
+    mut dst_image: TypedImageViewMut<F32x4>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        divide_alpha_f32x4_row_native(src_row, dst_row);
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_f32x4_inplace_native(mut image: TypedImageViewMut<F32x4>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        divide_alpha_f32x4_row_native(src_row, dst_row);
+    }
+}
+
+/// Pixels with zero alpha are copied as is.
+#[inline(always)]
+pub(crate) fn divide_alpha_f32x4_row_native(src_row: &[F32x4], dst_row: &mut [F32x4]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [r, g, b, alpha] = src_pixel.0;
+        dst_pixel.0 = if alpha == 0. {
+            src_pixel.0
+        } else {
+            [r / alpha, g / alpha, b / alpha, alpha]
+        };
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_u8x2_native(
+    src_image: TypedImageView<U8x2>,
+    mut dst_image: TypedImageViewMut<U8x2>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        divide_alpha_u8x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_u8x2_inplace_native(mut image: TypedImageViewMut<U8x2>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        divide_alpha_u8x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn divide_alpha_u8x2_row_native(src_row: &[U8x2], dst_row: &mut [U8x2]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [luma, alpha] = src_pixel.0;
+        let recip_alpha = RECIP_ALPHA[alpha as usize];
+        dst_pixel.0 = [div_and_clip(luma, recip_alpha), alpha];
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_u16x2_native(
+    src_image: TypedImageView<U16x2>,
+    mut dst_image: TypedImageViewMut<U16x2>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        divide_alpha_u16x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline]
+pub(crate) fn divide_alpha_u16x2_inplace_native(mut image: TypedImageViewMut<U16x2>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        divide_alpha_u16x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn divide_alpha_u16x2_row_native(src_row: &[U16x2], dst_row: &mut [U16x2]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [luma, alpha] = src_pixel.0;
+        dst_pixel.0 = if alpha == 0 {
+            [0, 0]
+        } else {
+            [div_65535_and_clip(luma, alpha), alpha]
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
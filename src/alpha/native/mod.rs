@@ -1,2 +1,3 @@
+pub(crate) mod clamp;
 pub(crate) mod div;
 pub(crate) mod mul;
@@ -1,5 +1,5 @@
 use crate::image_view::{TypedImageView, TypedImageViewMut};
-use crate::pixels::U8x4;
+use crate::pixels::{F32x4, U16x2, U16x4, U8x2, U8x4};
 
 pub(crate) fn multiply_alpha_native(
     src_image: TypedImageView<U8x4>,
@@ -15,7 +15,7 @@ pub(crate) fn multiply_alpha_native(
 
 pub(crate) fn multiply_alpha_inplace_native(mut image: TypedImageViewMut<U8x4>) {
     for dst_row in image.iter_rows_mut() {
-        let src_row = unsafe { std::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
         multiply_alpha_row_native(src_row, dst_row);
     }
 }
@@ -39,3 +39,123 @@ pub(crate) fn mul_div_255(a: u8, b: u8) -> u8 {
     let tmp = a as u32 * b as u32 + 128;
     (((tmp >> 8) + tmp) >> 8) as u8
 }
+
+pub(crate) fn multiply_alpha_u16x4_native(
+    src_image: TypedImageView<U16x4>,
+    mut dst_image: TypedImageViewMut<U16x4>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        multiply_alpha_u16x4_row_native(src_row, dst_row);
+    }
+}
+
+pub(crate) fn multiply_alpha_u16x4_inplace_native(mut image: TypedImageViewMut<U16x4>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        multiply_alpha_u16x4_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn multiply_alpha_u16x4_row_native(src_row: &[U16x4], dst_row: &mut [U16x4]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [r, g, b, alpha] = src_pixel.0;
+        dst_pixel.0 = [
+            mul_div_65535(r, alpha),
+            mul_div_65535(g, alpha),
+            mul_div_65535(b, alpha),
+            alpha,
+        ];
+    }
+}
+
+#[inline(always)]
+pub(crate) fn mul_div_65535(a: u16, b: u16) -> u16 {
+    // Max value of tmp and sum below fits into u32.
+    let tmp = a as u32 * b as u32 + 0x8000;
+    (((tmp >> 16) + tmp) >> 16) as u16
+}
+
+pub(crate) fn multiply_alpha_f32x4_native(
+    src_image: TypedImageView<F32x4>,
+    mut dst_image: TypedImageViewMut<F32x4>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        multiply_alpha_f32x4_row_native(src_row, dst_row);
+    }
+}
+
+pub(crate) fn multiply_alpha_f32x4_inplace_native(mut image: TypedImageViewMut<F32x4>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        multiply_alpha_f32x4_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn multiply_alpha_f32x4_row_native(src_row: &[F32x4], dst_row: &mut [F32x4]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [r, g, b, alpha] = src_pixel.0;
+        dst_pixel.0 = [r * alpha, g * alpha, b * alpha, alpha];
+    }
+}
+
+pub(crate) fn multiply_alpha_u8x2_native(
+    src_image: TypedImageView<U8x2>,
+    mut dst_image: TypedImageViewMut<U8x2>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        multiply_alpha_u8x2_row_native(src_row, dst_row);
+    }
+}
+
+pub(crate) fn multiply_alpha_u8x2_inplace_native(mut image: TypedImageViewMut<U8x2>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        multiply_alpha_u8x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn multiply_alpha_u8x2_row_native(src_row: &[U8x2], dst_row: &mut [U8x2]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [luma, alpha] = src_pixel.0;
+        dst_pixel.0 = [mul_div_255(luma, alpha), alpha];
+    }
+}
+
+pub(crate) fn multiply_alpha_u16x2_native(
+    src_image: TypedImageView<U16x2>,
+    mut dst_image: TypedImageViewMut<U16x2>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    let dst_rows = dst_image.iter_rows_mut();
+
+    for (src_row, dst_row) in src_rows.zip(dst_rows) {
+        multiply_alpha_u16x2_row_native(src_row, dst_row);
+    }
+}
+
+pub(crate) fn multiply_alpha_u16x2_inplace_native(mut image: TypedImageViewMut<U16x2>) {
+    for dst_row in image.iter_rows_mut() {
+        let src_row = unsafe { core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len()) };
+        multiply_alpha_u16x2_row_native(src_row, dst_row);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn multiply_alpha_u16x2_row_native(src_row: &[U16x2], dst_row: &mut [U16x2]) {
+    for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row) {
+        let [luma, alpha] = src_pixel.0;
+        dst_pixel.0 = [mul_div_65535(luma, alpha), alpha];
+    }
+}
@@ -1,4 +1,4 @@
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
 
 use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::U8x4;
@@ -19,7 +19,7 @@ pub(crate) unsafe fn divide_alpha_sse4(
 #[target_feature(enable = "sse4.1")]
 pub(crate) unsafe fn divide_alpha_inplace_sse4(mut image: TypedImageViewMut<U8x4>) {
     for dst_row in image.iter_rows_mut() {
-        let src_row = std::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len());
+        let src_row = core::slice::from_raw_parts(dst_row.as_ptr(), dst_row.len());
         divide_alpha_row_sse4(src_row, dst_row);
     }
 }
@@ -0,0 +1,61 @@
+//! Clamping of results of convolution by the range of source pixels
+//! used to calculate them, which removes halos (ringing) of sharp
+//! filters near edges.
+use alloc::vec::Vec;
+
+use crate::convolution::Bound;
+use crate::convolution_2d::Components;
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+
+/// Clamps pixels of the result of horizontal pass of convolution,
+/// `bounds` are bounds of chunks of coefficients of the pass.
+pub(crate) fn clamp_horiz<P: Components>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    bounds: &[Bound],
+) {
+    let src_rows = src_image.iter_rows(offset);
+    for (dst_row, src_row) in dst_image.iter_rows_mut().zip(src_rows) {
+        for (dst_pixel, bound) in dst_row.iter_mut().zip(bounds) {
+            let start = bound.start as usize;
+            let src_pixels = &src_row[start..start + bound.size as usize];
+            *dst_pixel = clamp_pixel(*dst_pixel, src_pixels.iter().copied());
+        }
+    }
+}
+
+/// Clamps pixels of the result of vertical pass of convolution,
+/// `bounds` are bounds of chunks of coefficients of the pass.
+pub(crate) fn clamp_vert<P: Components>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    bounds: &[Bound],
+) {
+    for (dst_row, bound) in dst_image.iter_rows_mut().zip(bounds) {
+        let src_rows: Vec<&[P]> = src_image
+            .iter_rows(bound.start)
+            .take(bound.size as usize)
+            .collect();
+        for (x, dst_pixel) in dst_row.iter_mut().enumerate() {
+            *dst_pixel = clamp_pixel(*dst_pixel, src_rows.iter().map(|row| row[x]));
+        }
+    }
+}
+
+/// Clamps every component of the pixel into the range
+/// of the component of given source pixels.
+fn clamp_pixel<P: Components>(pixel: P, src_pixels: impl Iterator<Item = P>) -> P {
+    let mut min = [f64::INFINITY; 4];
+    let mut max = [f64::NEG_INFINITY; 4];
+    for src_pixel in src_pixels {
+        for (i, c) in src_pixel.to_f64().into_iter().enumerate() {
+            min[i] = min[i].min(c);
+            max[i] = max[i].max(c);
+        }
+    }
+    let values = pixel.to_f64();
+    // `max` and `min` instead of `clamp` don't panic
+    // if there are no source pixels.
+    P::from_f64(core::array::from_fn(|i| values[i].max(min[i]).min(max[i])))
+}
@@ -0,0 +1,94 @@
+use core::arch::x86_64::*;
+
+use super::sse4::{load_neighbors, madd_weights};
+use super::{sse4, Neighbors, HORIZ_PRECISION, ROW_PRECISION, VERT_PRECISION};
+use crate::pixels::U8x4;
+
+const HORIZ_SHIFT: i32 = (HORIZ_PRECISION - ROW_PRECISION) as i32;
+const VERT_SHIFT: i32 = (VERT_PRECISION + ROW_PRECISION) as i32;
+
+pub(super) fn horiz_row(src_row: &[U8x4], dst_row: &mut [u16], x_neighbors: &[Neighbors]) {
+    unsafe {
+        horiz_row_avx2(src_row, dst_row, x_neighbors);
+    }
+}
+
+pub(super) fn vert_row(row0: &[u16], row1: &[u16], dst_row: &mut [U8x4], weights: [u16; 2]) {
+    unsafe {
+        vert_row_avx2(row0, row1, dst_row, weights);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == x_neighbors.len() * 4
+/// - indexes of neighbors are less than src_row.len()
+#[target_feature(enable = "avx2")]
+unsafe fn horiz_row_avx2(src_row: &[U8x4], dst_row: &mut [u16], x_neighbors: &[Neighbors]) {
+    #[rustfmt::skip]
+    let shuffle = _mm256_set_epi8(
+        -1, 7, -1, 3, -1, 6, -1, 2,
+        -1, 5, -1, 1, -1, 4, -1, 0,
+        -1, 7, -1, 3, -1, 6, -1, 2,
+        -1, 5, -1, 1, -1, 4, -1, 0,
+    );
+    let half = _mm256_set1_epi32(1 << (HORIZ_SHIFT - 1));
+
+    let neighbors_pairs = x_neighbors.chunks_exact(2);
+    let neighbors_reminder = neighbors_pairs.remainder();
+    let (dst_pairs, dst_reminder) = dst_row.split_at_mut(neighbors_pairs.len() * 8);
+    for (dst, x_n) in dst_pairs.chunks_exact_mut(8).zip(neighbors_pairs) {
+        let pixels = _mm256_set_m128i(
+            load_neighbors(src_row, &x_n[1]),
+            load_neighbors(src_row, &x_n[0]),
+        );
+        // [16] a1 a0 b1 b0 g1 g0 r1 r0 | a1 a0 b1 b0 g1 g0 r1 r0
+        let pixels = _mm256_shuffle_epi8(pixels, shuffle);
+        let weights = _mm256_set_m128i(
+            _mm_set1_epi32(madd_weights(x_n[1].weights)),
+            _mm_set1_epi32(madd_weights(x_n[0].weights)),
+        );
+        // [32] a b g r | a b g r
+        let res = _mm256_madd_epi16(pixels, weights);
+        let res = _mm256_srli_epi32::<HORIZ_SHIFT>(_mm256_add_epi32(res, half));
+        let res = _mm256_packus_epi32(res, res);
+        // Both 128-bit lanes have results in low 64 bits.
+        let res = _mm256_permute4x64_epi64::<0b1000>(res);
+        _mm_storeu_si128(
+            dst.as_mut_ptr() as *mut __m128i,
+            _mm256_castsi256_si128(res),
+        );
+    }
+
+    sse4::horiz_row(src_row, dst_reminder, neighbors_reminder);
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - row0.len() == row1.len() == dst_row.len() * 4
+#[target_feature(enable = "avx2")]
+unsafe fn vert_row_avx2(row0: &[u16], row1: &[u16], dst_row: &mut [U8x4], weights: [u16; 2]) {
+    let mm_weights = _mm256_set1_epi32(madd_weights(weights));
+    let half = _mm256_set1_epi32(1 << (VERT_SHIFT - 1));
+
+    let quads_count = dst_row.len() / 4;
+    let (dst_quads, dst_reminder) = dst_row.split_at_mut(quads_count * 4);
+    for (i, dst) in dst_quads.chunks_exact_mut(4).enumerate() {
+        let x = i * 16;
+        let p0 = _mm256_loadu_si256(row0.get_unchecked(x..).as_ptr() as *const __m256i);
+        let p1 = _mm256_loadu_si256(row1.get_unchecked(x..).as_ptr() as *const __m256i);
+        let lo = _mm256_madd_epi16(_mm256_unpacklo_epi16(p0, p1), mm_weights);
+        let hi = _mm256_madd_epi16(_mm256_unpackhi_epi16(p0, p1), mm_weights);
+        let lo = _mm256_srli_epi32::<VERT_SHIFT>(_mm256_add_epi32(lo, half));
+        let hi = _mm256_srli_epi32::<VERT_SHIFT>(_mm256_add_epi32(hi, half));
+        let res_u16 = _mm256_packus_epi32(lo, hi);
+        let res_u8 = _mm256_packus_epi16(res_u16, res_u16);
+        // Both 128-bit lanes have results in low 64 bits.
+        let res_u8 = _mm256_permute4x64_epi64::<0b1000>(res_u8);
+        _mm_storeu_si128(
+            dst.as_mut_ptr() as *mut __m128i,
+            _mm256_castsi256_si128(res_u8),
+        );
+    }
+
+    let x = quads_count * 16;
+    sse4::vert_row(&row0[x..], &row1[x..], dst_reminder, weights);
+}
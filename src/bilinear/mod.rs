@@ -0,0 +1,152 @@
+//! Direct bilinear interpolation of U8x4 images without the generic
+//! convolution machinery.
+//!
+//! Every used row of source image is interpolated horizontally only once
+//! into a row of fixed-point values with width of destination image.
+//! Every row of destination image is interpolated vertically between
+//! two such rows.
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::U8x4;
+use crate::CpuExtensions;
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+mod native;
+#[cfg(target_arch = "x86_64")]
+mod sse4;
+
+/// Number of bits of fractional part of fixed-point weights
+/// of horizontal interpolation.
+const HORIZ_PRECISION: u32 = 11;
+/// Number of bits of fractional part of horizontally interpolated values.
+/// These values have to fit into `i16`.
+const ROW_PRECISION: u32 = 7;
+/// Number of bits of fractional part of fixed-point weights
+/// of vertical interpolation.
+const VERT_PRECISION: u32 = 14;
+
+/// Two neighboring source pixels and their fixed-point weights.
+#[derive(Debug, Clone, Copy)]
+struct Neighbors {
+    first: u32,
+    second: u32,
+    weights: [u16; 2],
+}
+
+/// Calculates neighbors of the centers of destination pixels projected into
+/// the range of source pixels `start..start + src_size`.
+fn neighbors(start: u32, src_size: u32, dst_size: u32, precision: u32) -> Vec<Neighbors> {
+    let scale = src_size as f64 / dst_size as f64;
+    let last = src_size - 1;
+    let one = 1u16 << precision;
+    (0..dst_size)
+        .map(|i| {
+            let pos = ((i as f64 + 0.5) * scale - 0.5).max(0.);
+            let first = (pos as u32).min(last);
+            let weight = ((pos - first as f64) * one as f64).round() as u16;
+            let weight = weight.min(one);
+            Neighbors {
+                first: start + first,
+                second: start + (first + 1).min(last),
+                weights: [one - weight, weight],
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if the image may be resized by direct bilinear
+/// interpolation with results equal to convolution with bilinear filter.
+/// It is so if the crop box of source image has integer bounds and
+/// is not larger than destination image in both dimensions.
+pub(crate) fn is_applicable(
+    src_image: &TypedImageView<U8x4>,
+    dst_image: &TypedImageViewMut<U8x4>,
+) -> bool {
+    let crop_box = src_image.crop_box();
+    crop_box.is_integral()
+        && crop_box.width <= dst_image.width().get() as f64
+        && crop_box.height <= dst_image.height().get() as f64
+}
+
+/// Returns CPU-extensions that are actually used by [resample_bilinear]
+/// if `cpu_extensions` are requested.
+pub(crate) fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+    match cpu_extensions {
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Sse4_1 => CpuExtensions::Sse4_1,
+        _ => CpuExtensions::None,
+    }
+}
+
+/// Resizes the crop box of source image with help of bilinear interpolation.
+/// `SSE4.1` and `AVX2` instructions are used if they are enabled
+/// by `cpu_extensions`.
+pub(crate) fn resample_bilinear(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    cpu_extensions: CpuExtensions,
+) {
+    let crop_box = src_image.crop_box();
+    let dst_width = dst_image.width().get();
+    let x_neighbors = neighbors(
+        crop_box.left as u32,
+        crop_box.width as u32,
+        dst_width,
+        HORIZ_PRECISION,
+    );
+    let y_neighbors = neighbors(
+        crop_box.top as u32,
+        crop_box.height as u32,
+        dst_image.height().get(),
+        VERT_PRECISION,
+    );
+
+    let horiz_row = match cpu_extensions {
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx2 | CpuExtensions::Avx512 => avx2::horiz_row,
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Sse4_1 => sse4::horiz_row,
+        _ => native::horiz_row,
+    };
+    let vert_row = match cpu_extensions {
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx2 | CpuExtensions::Avx512 => avx2::vert_row,
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Sse4_1 => sse4::vert_row,
+        _ => native::vert_row,
+    };
+
+    // Horizontally interpolated source rows with indexes
+    let row_len = dst_width as usize * 4;
+    let mut first_row = (u32::MAX, vec![0u16; row_len]);
+    let mut second_row = (u32::MAX, vec![0u16; row_len]);
+
+    for (dst_row, y_n) in dst_image.iter_rows_mut().zip(&y_neighbors) {
+        if first_row.0 != y_n.first {
+            if second_row.0 == y_n.first {
+                core::mem::swap(&mut first_row, &mut second_row);
+            } else if let Some(src_row) = src_image.get_row(y_n.first) {
+                horiz_row(src_row, &mut first_row.1, &x_neighbors);
+                first_row.0 = y_n.first;
+            }
+        }
+        if second_row.0 != y_n.second {
+            if first_row.0 == y_n.second {
+                second_row.1.copy_from_slice(&first_row.1);
+                second_row.0 = y_n.second;
+            } else if let Some(src_row) = src_image.get_row(y_n.second) {
+                horiz_row(src_row, &mut second_row.1, &x_neighbors);
+                second_row.0 = y_n.second;
+            }
+        }
+        vert_row(&first_row.1, &second_row.1, dst_row, y_n.weights);
+    }
+}
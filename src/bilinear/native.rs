@@ -0,0 +1,31 @@
+use super::{Neighbors, HORIZ_PRECISION, ROW_PRECISION, VERT_PRECISION};
+use crate::pixels::U8x4;
+
+const HORIZ_SHIFT: u32 = HORIZ_PRECISION - ROW_PRECISION;
+const VERT_SHIFT: u32 = VERT_PRECISION + ROW_PRECISION;
+
+pub(super) fn horiz_row(src_row: &[U8x4], dst_row: &mut [u16], x_neighbors: &[Neighbors]) {
+    let half = 1 << (HORIZ_SHIFT - 1);
+    for (dst, x_n) in dst_row.chunks_exact_mut(4).zip(x_neighbors) {
+        let [w0, w1] = x_n.weights.map(|w| w as u32);
+        let p0 = src_row[x_n.first as usize].0.to_le_bytes();
+        let p1 = src_row[x_n.second as usize].0.to_le_bytes();
+        for ((d, c0), c1) in dst.iter_mut().zip(p0).zip(p1) {
+            *d = ((c0 as u32 * w0 + c1 as u32 * w1 + half) >> HORIZ_SHIFT) as u16;
+        }
+    }
+}
+
+pub(super) fn vert_row(row0: &[u16], row1: &[u16], dst_row: &mut [U8x4], weights: [u16; 2]) {
+    let [w0, w1] = weights.map(|w| w as u32);
+    let half = 1 << (VERT_SHIFT - 1);
+    let src_pixels = row0.chunks_exact(4).zip(row1.chunks_exact(4));
+    for (dst_pixel, (p0, p1)) in dst_row.iter_mut().zip(src_pixels) {
+        let mut res = [0u8; 4];
+        for (i, c) in res.iter_mut().enumerate() {
+            let v = p0[i] as u32 * w0 + p1[i] as u32 * w1;
+            *c = ((v + half) >> VERT_SHIFT) as u8;
+        }
+        *dst_pixel = U8x4(u32::from_le_bytes(res));
+    }
+}
@@ -0,0 +1,81 @@
+use core::arch::x86_64::*;
+
+use super::{native, Neighbors, HORIZ_PRECISION, ROW_PRECISION, VERT_PRECISION};
+use crate::pixels::U8x4;
+
+const HORIZ_SHIFT: i32 = (HORIZ_PRECISION - ROW_PRECISION) as i32;
+const VERT_SHIFT: i32 = (VERT_PRECISION + ROW_PRECISION) as i32;
+
+pub(super) fn horiz_row(src_row: &[U8x4], dst_row: &mut [u16], x_neighbors: &[Neighbors]) {
+    unsafe {
+        horiz_row_sse4(src_row, dst_row, x_neighbors);
+    }
+}
+
+pub(super) fn vert_row(row0: &[u16], row1: &[u16], dst_row: &mut [U8x4], weights: [u16; 2]) {
+    unsafe {
+        vert_row_sse4(row0, row1, dst_row, weights);
+    }
+}
+
+/// Returns pair of weights in the format of `_mm_madd_epi16()`.
+#[inline(always)]
+pub(super) fn madd_weights(weights: [u16; 2]) -> i32 {
+    (weights[0] as u32 | (weights[1] as u32) << 16) as i32
+}
+
+/// Loads two neighboring pixels into low 64 bits of register.
+#[inline(always)]
+pub(super) unsafe fn load_neighbors(src_row: &[U8x4], x_n: &Neighbors) -> __m128i {
+    let first = _mm_cvtsi32_si128(src_row.get_unchecked(x_n.first as usize).0 as i32);
+    let second = _mm_cvtsi32_si128(src_row.get_unchecked(x_n.second as usize).0 as i32);
+    _mm_unpacklo_epi32(first, second)
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == x_neighbors.len() * 4
+/// - indexes of neighbors are less than src_row.len()
+#[target_feature(enable = "sse4.1")]
+unsafe fn horiz_row_sse4(src_row: &[U8x4], dst_row: &mut [u16], x_neighbors: &[Neighbors]) {
+    #[rustfmt::skip]
+    let shuffle = _mm_set_epi8(
+        -1, 7, -1, 3, -1, 6, -1, 2,
+        -1, 5, -1, 1, -1, 4, -1, 0,
+    );
+    let half = _mm_set1_epi32(1 << (HORIZ_SHIFT - 1));
+
+    for (dst, x_n) in dst_row.chunks_exact_mut(4).zip(x_neighbors) {
+        // [16] a1 a0 b1 b0 g1 g0 r1 r0
+        let pixels = _mm_shuffle_epi8(load_neighbors(src_row, x_n), shuffle);
+        // [32] a b g r
+        let res = _mm_madd_epi16(pixels, _mm_set1_epi32(madd_weights(x_n.weights)));
+        let res = _mm_srli_epi32::<HORIZ_SHIFT>(_mm_add_epi32(res, half));
+        _mm_storel_epi64(dst.as_mut_ptr() as *mut __m128i, _mm_packus_epi32(res, res));
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - row0.len() == row1.len() == dst_row.len() * 4
+#[target_feature(enable = "sse4.1")]
+unsafe fn vert_row_sse4(row0: &[u16], row1: &[u16], dst_row: &mut [U8x4], weights: [u16; 2]) {
+    let mm_weights = _mm_set1_epi32(madd_weights(weights));
+    let half = _mm_set1_epi32(1 << (VERT_SHIFT - 1));
+
+    let pairs_count = dst_row.len() / 2;
+    let (dst_pairs, dst_reminder) = dst_row.split_at_mut(pairs_count * 2);
+    for (i, dst) in dst_pairs.chunks_exact_mut(2).enumerate() {
+        let x = i * 8;
+        let p0 = _mm_loadu_si128(row0.get_unchecked(x..).as_ptr() as *const __m128i);
+        let p1 = _mm_loadu_si128(row1.get_unchecked(x..).as_ptr() as *const __m128i);
+        let lo = _mm_madd_epi16(_mm_unpacklo_epi16(p0, p1), mm_weights);
+        let hi = _mm_madd_epi16(_mm_unpackhi_epi16(p0, p1), mm_weights);
+        let lo = _mm_srli_epi32::<VERT_SHIFT>(_mm_add_epi32(lo, half));
+        let hi = _mm_srli_epi32::<VERT_SHIFT>(_mm_add_epi32(hi, half));
+        let res_u16 = _mm_packus_epi32(lo, hi);
+        let res_u8 = _mm_packus_epi16(res_u16, res_u16);
+        _mm_storel_epi64(dst.as_mut_ptr() as *mut __m128i, res_u8);
+    }
+
+    let x = pairs_count * 8;
+    native::vert_row(&row0[x..], &row1[x..], dst_reminder, weights);
+}
@@ -3,7 +3,11 @@ use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::F32;
 use crate::CpuExtensions;
 
+#[cfg(target_arch = "x86_64")]
+mod avx2;
 mod native;
+#[cfg(target_arch = "x86_64")]
+mod sse4;
 
 impl Convolution for F32 {
     fn horiz_convolution(
@@ -14,6 +18,12 @@ impl Convolution for F32 {
         cpu_extensions: CpuExtensions,
     ) {
         match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Sse4_1 => sse4::horiz_convolution(src_image, dst_image, offset, coeffs),
             _ => native::horiz_convolution(src_image, dst_image, offset, coeffs),
         }
     }
@@ -25,7 +35,23 @@ impl Convolution for F32 {
         cpu_extensions: CpuExtensions,
     ) {
         match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::vert_convolution(src_image, dst_image, coeffs)
+            }
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Sse4_1 => sse4::vert_convolution(src_image, dst_image, coeffs),
             _ => native::vert_convolution(src_image, dst_image, coeffs),
         }
     }
+
+    fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Sse4_1 => CpuExtensions::Sse4_1,
+            _ => CpuExtensions::None,
+        }
+    }
 }
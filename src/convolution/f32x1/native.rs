@@ -1,4 +1,7 @@
-use crate::convolution::Coefficients;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::convolution::{src_pixel, src_pixels, Coefficients};
 use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::F32;
 
@@ -15,7 +18,7 @@ pub(crate) fn horiz_convolution(
         for (dst_pixel, coeffs_chunk) in dst_row.iter_mut().zip(&coefficients_chunks) {
             let first_x_src = coeffs_chunk.start as usize;
             let mut ss = 0.;
-            let src_pixels = unsafe { src_row.get_unchecked(first_x_src..) };
+            let src_pixels = unsafe { src_pixels(src_row, first_x_src, coeffs_chunk.values.len()) };
             for (&k, &pixel) in coeffs_chunk.values.iter().zip(src_pixels) {
                 ss += pixel.0 as f64 * k;
             }
@@ -37,7 +40,7 @@ pub(crate) fn vert_convolution(
             let mut ss = 0.;
             let src_rows = src_image.iter_rows(first_y_src);
             for (src_row, &k) in src_rows.zip(coeffs_chunk.values) {
-                let src_pixel = unsafe { src_row.get_unchecked(x_src as usize) };
+                let src_pixel = unsafe { src_pixel(src_row, x_src as usize) };
                 ss += src_pixel.0 as f64 * k;
             }
             dst_pixel.0 = ss.round() as f32;
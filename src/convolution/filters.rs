@@ -1,8 +1,16 @@
-use std::f64::consts::PI;
+use alloc::boxed::Box;
+use core::f64::consts::PI;
 
-pub type FilterFn<'a> = &'a dyn Fn(f64) -> f64;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::errors::{InvalidFilterParameterError, InvalidFilterSupportError};
+
+pub type FilterFn = Box<dyn Fn(f64) -> f64>;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[non_exhaustive]
 pub enum FilterType {
     /// Each pixel of source image contributes to one pixel of the
@@ -12,11 +20,17 @@ pub enum FilterType {
     /// Bilinear filter calculate the output pixel value using linear
     /// interpolation on all pixels that may contribute to the output value.
     Bilinear,
+    /// Triangle (tent) filter with kernel `max(0, 1 - |x|)` and support 1.0.
+    /// It is the same filter as `Bilinear` under the name used by
+    /// other libraries. On 2x upscaling it gives exact linear interpolation
+    /// between source pixels.
+    Triangle,
     /// Hamming filter has the same performance as `Bilinear` filter while
     /// providing the image downscaling quality comparable to bicubic
     /// (`CatmulRom` or `Mitchell`). Produces a sharper image than `Bilinear`,
     /// doesn't have dislocations on local level like with `Box`.
     /// The filter don’t show good quality for the image upscaling.
+    /// Support of the filter is 1.0, the same as of `HAMMING` filter in Pillow.
     Hamming,
     /// Catmull-Rom bicubic filter calculate the output pixel value using
     /// cubic interpolation on all pixels that may contribute to the output
@@ -26,10 +40,69 @@ pub enum FilterType {
     /// using cubic interpolation on all pixels that may contribute to the
     /// output value.
     Mitchell,
+    /// Hermite filter is a cubic filter from the same family as `CatmullRom`
+    /// and `Mitchell` with `B = 0` and `C = 0`. It has support 1.0 and
+    /// doesn't have negative lobes, so it produces smooth result without
+    /// ringing (e.g. for downscaling of masks).
+    Hermite,
+    /// B-spline filter is a cubic filter from the same family as `CatmullRom`
+    /// and `Mitchell` with `B = 1` and `C = 0`. It has support 2.0 and
+    /// doesn't have negative lobes, so it produces very smooth (blurry)
+    /// result without overshoot (e.g. for downscaling of noisy images).
+    BSpline,
+    /// Lanczos2 filter is the same as `Lanczos3` but with support 2.0.
+    /// It produces less ringing near sharp edges (e.g. in line art)
+    /// at the cost of slightly blurrier result.
+    Lanczos2,
     /// Lanczos3 filter calculate the output pixel value using a high-quality
     /// Lanczos filter (a truncated sinc) on all pixels that may contribute
     /// to the output value.
     Lanczos3,
+    /// Blackman filter is a sinc windowed by Blackman window with support 3.0.
+    /// The window has lower side lobes than Lanczos window, so the filter
+    /// produces less ringing near sharp edges than `Lanczos3` filter.
+    Blackman,
+    /// Welch filter is a sinc windowed by parabolic Welch window
+    /// `1 - x^2` with support 1.0.
+    Welch,
+    /// Bartlett filter is a sinc windowed by triangular Bartlett window
+    /// `1 - |x|` with support 3.0.
+    ///
+    /// Don't confuse it with `Triangle` filter: `Triangle` uses the
+    /// triangle itself as a kernel (with support 1.0), while `Bartlett`
+    /// only tapers a sinc by the triangle. It is sharper than `Triangle`,
+    /// but produces some ringing near sharp edges like other
+    /// windowed sinc filters.
+    Bartlett,
+    /// Hann filter is a sinc windowed by raised cosine Hann window
+    /// `0.5 + 0.5 * cos(PI * x)` with support 1.0. The window is smooth
+    /// and goes to zero at the edges of support, so the filter produces
+    /// a bit softer result than `Hamming` filter.
+    Hann,
+    /// Gaussian filter with given standard deviation `sigma`.
+    /// Support of the filter is `3 * sigma`, so a time of resizing grows
+    /// linearly with value of `sigma`.
+    ///
+    /// Use [FilterType::gaussian] to create the filter with validation
+    /// of `sigma`. Otherwise, values of `sigma` are clamped into range
+    /// `[0.05, 100]` (`NaN` is replaced by 0.05).
+    Gaussian { sigma: f32 },
+    /// Kaiser filter is a sinc windowed by Kaiser window with support 3.0.
+    /// Parameter `beta` controls the tradeoff between width of the main
+    /// lobe and level of side lobes of the filter: the greater `beta`,
+    /// the less ringing and the more blurry result. Common value is 8.6.
+    ///
+    /// Use [FilterType::kaiser] to create the filter with validation
+    /// of `beta`. Otherwise, values of `beta` are clamped into range
+    /// `[0, 700]` (0 is a sinc truncated by rectangular window, and
+    /// weights of the window overflow `f64` with greater values).
+    Kaiser { beta: f32 },
+    /// User-defined filter.
+    ///
+    /// This variant can't be serialized or deserialized
+    /// with help of `serde`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(CustomFilter),
 }
 
 impl Default for FilterType {
@@ -38,16 +111,236 @@ impl Default for FilterType {
     }
 }
 
-/// Returns reference to filter function and value of `filter_support`.
+impl FilterType {
+    /// Creates Gaussian filter with the given standard deviation `sigma`.
+    ///
+    /// Returns an error if `sigma` is not positive and finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_image_resize::{FilterType, ResizeAlg};
+    ///
+    /// let filter = FilterType::gaussian(0.8).unwrap();
+    /// let alg = ResizeAlg::Convolution(filter);
+    /// assert!(FilterType::gaussian(f32::INFINITY).is_err());
+    /// ```
+    pub fn gaussian(sigma: f32) -> Result<Self, InvalidFilterParameterError> {
+        if !sigma.is_finite() || sigma <= 0.0 {
+            return Err(InvalidFilterParameterError);
+        }
+        Ok(Self::Gaussian { sigma })
+    }
+
+    /// Creates Kaiser filter with the given `beta`.
+    ///
+    /// Returns an error if `beta` is not positive and finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_image_resize::{FilterType, ResizeAlg};
+    ///
+    /// let filter = FilterType::kaiser(8.6).unwrap();
+    /// let alg = ResizeAlg::Convolution(filter);
+    /// assert!(FilterType::kaiser(0.).is_err());
+    /// ```
+    pub fn kaiser(beta: f32) -> Result<Self, InvalidFilterParameterError> {
+        if !beta.is_finite() || beta <= 0.0 {
+            return Err(InvalidFilterParameterError);
+        }
+        Ok(Self::Kaiser { beta })
+    }
+
+    /// Returns support (radius) of the filter in source pixels,
+    /// e.g. 3.0 for `Lanczos3` or `3 * sigma` for `Gaussian`.
+    ///
+    /// It is the radius for upscaling. On downscaling the filter is
+    /// stretched by the ratio of source and destination sizes, so
+    /// a destination pixel is calculated from source pixels with distance
+    /// up to `support * ratio` from its center (unless the radius is limited
+    /// by [Resizer::set_max_filter_radius](crate::Resizer::set_max_filter_radius)).
+    /// E.g. on 4x downscaling with `Lanczos3` every destination pixel uses
+    /// source pixels within 12 pixels from its center, so tiles of the source
+    /// image have to overlap by `ceil(support * ratio)` pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_image_resize::FilterType;
+    ///
+    /// assert_eq!(FilterType::Lanczos3.support(), 3.0);
+    /// assert_eq!(FilterType::Gaussian { sigma: 0.5 }.support(), 1.5);
+    /// ```
+    pub fn support(&self) -> f32 {
+        filter_support(*self) as f32
+    }
+}
+
+/// Filter with user-defined function.
+///
+/// # Examples
+///
+/// ```
+/// use fast_image_resize::{CustomFilter, FilterType, ResizeAlg};
+///
+/// fn triangle(x: f64) -> f64 {
+///     (1.0 - x.abs()).max(0.0)
+/// }
+///
+/// let filter = CustomFilter::new(triangle, 1.0).unwrap();
+/// let alg = ResizeAlg::Convolution(FilterType::Custom(filter));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CustomFilter {
+    func: fn(f64) -> f64,
+    support: f64,
+}
+
+impl CustomFilter {
+    /// Creates a new filter.
+    ///
+    /// `func` must return weight of source pixel with given distance from
+    /// center of destination pixel. Weights are normalized by resizer,
+    /// so the function doesn't have to do it.
+    /// `support` is the radius of the filter — `func` should return zero
+    /// for arguments whose absolute value exceeds it.
+    /// It must be positive and finite.
+    pub fn new(func: fn(f64) -> f64, support: f64) -> Result<Self, InvalidFilterSupportError> {
+        if !support.is_finite() || support <= 0.0 {
+            return Err(InvalidFilterSupportError);
+        }
+        Ok(Self { func, support })
+    }
+
+    #[inline(always)]
+    pub fn support(&self) -> f64 {
+        self.support
+    }
+}
+
+impl PartialEq for CustomFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.func as usize == other.func as usize && self.support == other.support
+    }
+}
+
+/// Non-separable filter with user-defined function of two arguments
+/// for [ResizeAlg::Convolution2D](crate::ResizeAlg::Convolution2D).
+///
+/// # Examples
+///
+/// ```
+/// use fast_image_resize::{CustomFilter2D, ResizeAlg};
+///
+/// fn cone(x: f64, y: f64) -> f64 {
+///     (1.0 - (x * x + y * y).sqrt()).max(0.0)
+/// }
+///
+/// let filter = CustomFilter2D::new(cone, 1.0).unwrap();
+/// let alg = ResizeAlg::Convolution2D(filter);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CustomFilter2D {
+    func: fn(f64, f64) -> f64,
+    support: f64,
+}
+
+impl CustomFilter2D {
+    /// Creates a new filter.
+    ///
+    /// `func` must return weight of source pixel with given horizontal
+    /// and vertical distances from center of destination pixel.
+    /// Weights are normalized by resizer. `support` is the radius of
+    /// the filter along both axes — `func` should return zero if absolute
+    /// value of any argument exceeds it. It must be positive and finite.
+    pub fn new(func: fn(f64, f64) -> f64, support: f64) -> Result<Self, InvalidFilterSupportError> {
+        if !support.is_finite() || support <= 0.0 {
+            return Err(InvalidFilterSupportError);
+        }
+        Ok(Self { func, support })
+    }
+
+    #[inline(always)]
+    pub fn support(&self) -> f64 {
+        self.support
+    }
+
+    #[inline(always)]
+    pub(crate) fn func(&self) -> fn(f64, f64) -> f64 {
+        self.func
+    }
+}
+
+impl PartialEq for CustomFilter2D {
+    fn eq(&self, other: &Self) -> bool {
+        self.func as usize == other.func as usize && self.support == other.support
+    }
+}
+
+/// Returns filter function and value of `filter_support`.
 #[inline]
-pub fn get_filter_func(filter_type: FilterType) -> (FilterFn<'static>, f64) {
+pub fn get_filter_func(filter_type: FilterType) -> (FilterFn, f64) {
+    let func: FilterFn = match filter_type {
+        FilterType::Box => Box::new(box_filter),
+        FilterType::Bilinear | FilterType::Triangle => Box::new(bilinear_filter),
+        FilterType::Hamming => Box::new(hamming_filter),
+        FilterType::CatmullRom => Box::new(catmul_filter),
+        FilterType::Mitchell => Box::new(mitchell_filter),
+        FilterType::Hermite => Box::new(hermite_filter),
+        FilterType::BSpline => Box::new(b_spline_filter),
+        FilterType::Lanczos2 => Box::new(|x| lanczos_filter(x, 2.0)),
+        FilterType::Lanczos3 => Box::new(|x| lanczos_filter(x, 3.0)),
+        FilterType::Blackman => Box::new(|x| blackman_filter(x, 3.0)),
+        FilterType::Welch => Box::new(|x| welch_filter(x, 1.0)),
+        FilterType::Bartlett => Box::new(|x| bartlett_filter(x, 3.0)),
+        FilterType::Hann => Box::new(|x| hann_filter(x, 1.0)),
+        FilterType::Gaussian { sigma } => {
+            let sigma = clamp_gaussian_sigma(sigma);
+            Box::new(move |x| gaussian_filter(x, sigma))
+        }
+        FilterType::Kaiser { beta } => {
+            let beta = if beta.is_nan() {
+                0.
+            } else {
+                (beta as f64).clamp(0., MAX_KAISER_BETA)
+            };
+            let i0_beta = bessel_i0(beta);
+            Box::new(move |x| kaiser_filter(x, 3.0, beta, i0_beta))
+        }
+        FilterType::Custom(filter) => Box::new(filter.func),
+    };
+    (func, filter_support(filter_type))
+}
+
+/// Returns value of `filter_support` without creating of filter function.
+fn filter_support(filter_type: FilterType) -> f64 {
     match filter_type {
-        FilterType::Box => (&box_filter, 0.5),
-        FilterType::Bilinear => (&bilinear_filter, 1.0),
-        FilterType::Hamming => (&hamming_filter, 1.0),
-        FilterType::CatmullRom => (&catmul_filter, 2.0),
-        FilterType::Mitchell => (&mitchell_filter, 2.0),
-        FilterType::Lanczos3 => (&lanczos_filter, 3.0),
+        FilterType::Box => 0.5,
+        FilterType::Bilinear
+        | FilterType::Triangle
+        | FilterType::Hamming
+        | FilterType::Hermite
+        | FilterType::Welch
+        | FilterType::Hann => 1.0,
+        FilterType::CatmullRom
+        | FilterType::Mitchell
+        | FilterType::BSpline
+        | FilterType::Lanczos2 => 2.0,
+        FilterType::Lanczos3
+        | FilterType::Blackman
+        | FilterType::Bartlett
+        | FilterType::Kaiser { .. } => 3.0,
+        FilterType::Gaussian { sigma } => 3.0 * clamp_gaussian_sigma(sigma),
+        FilterType::Custom(filter) => filter.support,
+    }
+}
+
+fn clamp_gaussian_sigma(sigma: f32) -> f64 {
+    if sigma.is_nan() {
+        MIN_GAUSSIAN_SIGMA
+    } else {
+        (sigma as f64).clamp(MIN_GAUSSIAN_SIGMA, MAX_GAUSSIAN_SIGMA)
     }
 }
 
@@ -83,33 +376,47 @@ fn hamming_filter(mut x: f64) -> f64 {
     }
 }
 
-/// Catmull-Rom (bicubic) filter
+/// Catmull-Rom (bicubic) filter (B = 0, C = 0.5)
 /// https://en.wikipedia.org/wiki/Bicubic_interpolation#Bicubic_convolution_algorithm
 #[inline]
-fn catmul_filter(mut x: f64) -> f64 {
-    const A: f64 = -0.5;
-    x = x.abs();
-    if x < 1.0 {
-        ((A + 2.) * x - (A + 3.)) * x * x + 1.
-    } else if x < 2.0 {
-        (((x - 5.) * x + 8.) * x - 4.) * A
-    } else {
-        0.0
-    }
+fn catmul_filter(x: f64) -> f64 {
+    bc_cubic_spline(x, 0., 0.5)
 }
 
 /// Mitchell–Netravali filter (B = C = 1/3)
 /// https://en.wikipedia.org/wiki/Mitchell%E2%80%93Netravali_filters
 #[inline]
-fn mitchell_filter(mut x: f64) -> f64 {
-    x = x.abs();
-    if x < 1.0 {
-        (7. * x / 6. - 2.) * x * x + 16. / 18.
+fn mitchell_filter(x: f64) -> f64 {
+    bc_cubic_spline(x, 1. / 3., 1. / 3.)
+}
+
+/// Hermite filter (B = 0, C = 0)
+#[inline]
+fn hermite_filter(x: f64) -> f64 {
+    bc_cubic_spline(x, 0., 0.)
+}
+
+/// Cubic B-spline filter (B = 1, C = 0)
+#[inline]
+fn b_spline_filter(x: f64) -> f64 {
+    bc_cubic_spline(x, 1., 0.)
+}
+
+/// Piecewise cubic filter from the family of BC-splines
+/// with support 2.0.
+/// https://en.wikipedia.org/wiki/Mitchell%E2%80%93Netravali_filters
+#[inline(always)]
+fn bc_cubic_spline(x: f64, b: f64, c: f64) -> f64 {
+    let x = x.abs();
+    let res = if x < 1.0 {
+        ((12. - 9. * b - 6. * c) * x + (-18. + 12. * b + 6. * c)) * x * x + (6. - 2. * b)
     } else if x < 2.0 {
-        ((2. - 7. * x / 18.) * x - 10. / 3.) * x + 16. / 9.
+        (((-b - 6. * c) * x + (6. * b + 30. * c)) * x + (-12. * b - 48. * c)) * x
+            + (8. * b + 24. * c)
     } else {
         0.0
-    }
+    };
+    res / 6.
 }
 
 #[inline]
@@ -122,12 +429,83 @@ fn sinc_filter(mut x: f64) -> f64 {
     }
 }
 
-#[inline]
-fn lanczos_filter(x: f64) -> f64 {
-    // truncated sinc
-    if (-3.0..3.0).contains(&x) {
-        sinc_filter(x) * sinc_filter(x / 3.)
+/// Sinc truncated to support `a` and multiplied by `window` function.
+/// Argument of the window is scaled into range `[-1, 1)`.
+#[inline(always)]
+fn windowed_sinc(x: f64, a: f64, window: impl Fn(f64) -> f64) -> f64 {
+    if (-a..a).contains(&x) {
+        sinc_filter(x) * window(x / a)
     } else {
         0.0
     }
 }
+
+/// Lanczos filter with support `a`.
+#[inline]
+fn lanczos_filter(x: f64, a: f64) -> f64 {
+    windowed_sinc(x, a, sinc_filter)
+}
+
+/// Sinc windowed by Blackman window with support `a`.
+#[inline]
+fn blackman_filter(x: f64, a: f64) -> f64 {
+    windowed_sinc(x, a, |x| {
+        let t = PI * x;
+        0.42 + 0.5 * t.cos() + 0.08 * (2. * t).cos()
+    })
+}
+
+/// Sinc windowed by Welch (parabolic) window with support `a`.
+#[inline]
+fn welch_filter(x: f64, a: f64) -> f64 {
+    windowed_sinc(x, a, |x| 1. - x * x)
+}
+
+/// Sinc windowed by Bartlett (triangular) window with support `a`.
+#[inline]
+fn bartlett_filter(x: f64, a: f64) -> f64 {
+    windowed_sinc(x, a, |x| 1. - x.abs())
+}
+
+/// Sinc windowed by Hann (raised cosine) window with support `a`.
+#[inline]
+fn hann_filter(x: f64, a: f64) -> f64 {
+    windowed_sinc(x, a, |x| 0.5 + 0.5 * (PI * x).cos())
+}
+
+/// Sinc windowed by Kaiser window with support `a`.
+/// `i0_beta` must be equal to `bessel_i0(beta)`.
+/// https://en.wikipedia.org/wiki/Kaiser_window
+#[inline]
+fn kaiser_filter(x: f64, a: f64, beta: f64, i0_beta: f64) -> f64 {
+    windowed_sinc(x, a, |x| bessel_i0(beta * (1. - x * x).sqrt()) / i0_beta)
+}
+
+/// Zeroth-order modified Bessel function of the first kind
+/// calculated as a sum of its power series.
+fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-16 {
+        let t = half_x / k;
+        term *= t * t;
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+const MAX_KAISER_BETA: f64 = 700.;
+
+const MIN_GAUSSIAN_SIGMA: f64 = 0.05;
+
+const MAX_GAUSSIAN_SIGMA: f64 = 100.;
+
+/// Gaussian filter. Weights are not normalized here because
+/// the coefficients of every output pixel are normalized later.
+#[inline]
+fn gaussian_filter(x: f64, sigma: f64) -> f64 {
+    (-x * x / (2. * sigma * sigma)).exp()
+}
@@ -1,4 +1,4 @@
-use crate::convolution::Coefficients;
+use crate::convolution::{src_pixel, src_pixels, Coefficients};
 use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::I32;
 
@@ -8,6 +8,7 @@ pub(crate) fn horiz_convolution(
     offset: u32,
     coeffs: Coefficients,
 ) {
+    let rounding_mode = coeffs.rounding_mode;
     let coefficients_chunks = coeffs.get_chunks();
     let src_rows = src_image.iter_rows(offset);
     let dst_rows = dst_image.iter_rows_mut();
@@ -15,11 +16,11 @@ pub(crate) fn horiz_convolution(
         for (dst_pixel, coeffs_chunk) in dst_row.iter_mut().zip(&coefficients_chunks) {
             let first_x_src = coeffs_chunk.start as usize;
             let mut ss = 0.;
-            let src_pixels = unsafe { src_row.get_unchecked(first_x_src..) };
+            let src_pixels = unsafe { src_pixels(src_row, first_x_src, coeffs_chunk.values.len()) };
             for (&k, &pixel) in coeffs_chunk.values.iter().zip(src_pixels) {
                 ss += pixel.0 as f64 * k;
             }
-            dst_pixel.0 = ss.round() as i32;
+            dst_pixel.0 = rounding_mode.round(ss) as i32;
         }
     }
 }
@@ -29,6 +30,7 @@ pub(crate) fn vert_convolution(
     mut dst_image: TypedImageViewMut<I32>,
     coeffs: Coefficients,
 ) {
+    let rounding_mode = coeffs.rounding_mode;
     let coefficients_chunks = coeffs.get_chunks();
     let dst_rows = dst_image.iter_rows_mut();
     for (&coeffs_chunk, dst_row) in coefficients_chunks.iter().zip(dst_rows) {
@@ -37,10 +39,10 @@ pub(crate) fn vert_convolution(
             let mut ss = 0.;
             let src_rows = src_image.iter_rows(first_y_src);
             for (src_row, &k) in src_rows.zip(coeffs_chunk.values) {
-                let src_pixel = unsafe { src_row.get_unchecked(x_src as usize) };
+                let src_pixel = unsafe { src_pixel(src_row, x_src as usize) };
                 ss += src_pixel.0 as f64 * k;
             }
-            dst_pixel.0 = ss.round() as i32;
+            dst_pixel.0 = rounding_mode.round(ss) as i32;
         }
     }
 }
@@ -0,0 +1,142 @@
+use core::arch::x86_64::*;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::convolution::{Coefficients, CoefficientsChunk};
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::I32;
+
+#[inline]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<I32>,
+    mut dst_image: TypedImageViewMut<I32>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let coefficients_chunks = coeffs.get_chunks();
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        unsafe {
+            horiz_convolution_row(src_row, dst_row, &coefficients_chunks);
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<I32>,
+    mut dst_image: TypedImageViewMut<I32>,
+    coeffs: Coefficients,
+) {
+    let coefficients_chunks = coeffs.get_chunks();
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, &coeffs_chunk) in dst_rows.zip(&coefficients_chunks) {
+        unsafe {
+            vert_convolution_row(&src_image, dst_row, coeffs_chunk);
+        }
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - coefficients_chunks.len() == dst_row.len()
+/// - max(chunk.start + chunk.values.len() for chunk in coefficients_chunks) <= src_row.len()
+#[target_feature(enable = "sse4.1")]
+unsafe fn horiz_convolution_row(
+    src_row: &[I32],
+    dst_row: &mut [I32],
+    coefficients_chunks: &[CoefficientsChunk],
+) {
+    for (dst_pixel, coeffs_chunk) in dst_row.iter_mut().zip(coefficients_chunks) {
+        let src_ptr = src_row
+            .get_unchecked(coeffs_chunk.start as usize..)
+            .as_ptr() as *const i32;
+        let coeffs = coeffs_chunk.values;
+        let coeffs_ptr = coeffs.as_ptr();
+
+        let mut sss0 = _mm_setzero_pd();
+        let mut sss1 = _mm_setzero_pd();
+        let mut x: usize = 0;
+        while x + 4 <= coeffs.len() {
+            let pixels = _mm_loadu_si128(src_ptr.add(x) as *const __m128i);
+            let pixels0 = _mm_cvtepi32_pd(pixels);
+            let pixels1 = _mm_cvtepi32_pd(_mm_unpackhi_epi64(pixels, pixels));
+            let mmk0 = _mm_loadu_pd(coeffs_ptr.add(x));
+            let mmk1 = _mm_loadu_pd(coeffs_ptr.add(x + 2));
+            sss0 = _mm_add_pd(sss0, _mm_mul_pd(pixels0, mmk0));
+            sss1 = _mm_add_pd(sss1, _mm_mul_pd(pixels1, mmk1));
+            x += 4;
+        }
+
+        let sss = _mm_add_pd(sss0, sss1);
+        let mut ss = _mm_cvtsd_f64(_mm_add_sd(sss, _mm_unpackhi_pd(sss, sss)));
+        while x < coeffs.len() {
+            ss += *src_ptr.add(x) as f64 * *coeffs.get_unchecked(x);
+            x += 1;
+        }
+        dst_pixel.0 = ss.round() as i32;
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == src_img.width()
+/// - coeffs_chunk.start + coeffs_chunk.values.len() <= src_img.height()
+#[target_feature(enable = "sse4.1")]
+unsafe fn vert_convolution_row(
+    src_img: &TypedImageView<I32>,
+    dst_row: &mut [I32],
+    coeffs_chunk: CoefficientsChunk,
+) {
+    let src_width = dst_row.len();
+    let y_start = coeffs_chunk.start;
+    let coeffs = coeffs_chunk.values;
+    let dst_ptr = dst_row.as_mut_ptr() as *mut i32;
+
+    let mut x: usize = 0;
+    while x + 4 <= src_width {
+        let mut sss0 = _mm_setzero_pd();
+        let mut sss1 = _mm_setzero_pd();
+        for (&k, src_row) in coeffs.iter().zip(src_img.iter_rows(y_start)) {
+            let mmk = _mm_set1_pd(k);
+            let src_ptr = src_row.get_unchecked(x..).as_ptr() as *const i32;
+            let pixels = _mm_loadu_si128(src_ptr as *const __m128i);
+            let pixels0 = _mm_cvtepi32_pd(pixels);
+            let pixels1 = _mm_cvtepi32_pd(_mm_unpackhi_epi64(pixels, pixels));
+            sss0 = _mm_add_pd(sss0, _mm_mul_pd(pixels0, mmk));
+            sss1 = _mm_add_pd(sss1, _mm_mul_pd(pixels1, mmk));
+        }
+        let res0 = _mm_cvttpd_epi32(round_and_clamp_pd(sss0));
+        let res1 = _mm_cvttpd_epi32(round_and_clamp_pd(sss1));
+        _mm_storeu_si128(
+            dst_ptr.add(x) as *mut __m128i,
+            _mm_unpacklo_epi64(res0, res1),
+        );
+        x += 4;
+    }
+
+    while x < src_width {
+        let mut ss = 0.;
+        for (&k, src_row) in coeffs.iter().zip(src_img.iter_rows(y_start)) {
+            ss += src_row.get_unchecked(x).0 as f64 * k;
+        }
+        dst_row.get_unchecked_mut(x).0 = ss.round() as i32;
+        x += 1;
+    }
+}
+
+/// Rounds half-way cases away from zero, like `f64::round()`, and
+/// clamps result into range of `i32` like `as i32` does.
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn round_and_clamp_pd(v: __m128d) -> __m128d {
+    // The largest `f64` value less than 0.5
+    let half = _mm_set1_pd(0.499_999_999_999_999_94);
+    let sign_mask = _mm_set1_pd(-0.0);
+    let half_with_sign = _mm_or_pd(_mm_and_pd(v, sign_mask), half);
+    let rounded =
+        _mm_round_pd::<{ _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC }>(_mm_add_pd(v, half_with_sign));
+    let min = _mm_set1_pd(i32::MIN as f64);
+    let max = _mm_set1_pd(i32::MAX as f64);
+    _mm_min_pd(_mm_max_pd(rounded, min), max)
+}
@@ -1,25 +1,81 @@
-use std::num::NonZeroU32;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
 
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::convolution_2d::Components;
 use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::Pixel;
 use crate::CpuExtensions;
-pub use filters::{get_filter_func, FilterType};
+pub use filters::{get_filter_func, CustomFilter, CustomFilter2D, FilterType};
 
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "half")]
+mod f16x1;
 mod f32x1;
+mod f32x4;
 mod filters;
+mod i16x1;
 mod i32x1;
 mod optimisations;
+pub(crate) mod reference;
+mod u16x2;
 mod u16x3;
+mod u16x4;
 mod u8x1;
+mod u8x2;
 mod u8x3;
 mod u8x4;
 
+/// Returns pixels of the source row that are used by the chunk of
+/// coefficients with given start and length.
+///
+/// # Safety
+/// `start` must not be greater than length of the row. It is checked
+/// only with feature `debug-checks`, that also checks the end of
+/// the chunk, so invalid coefficients cause panic instead of UB.
+#[inline(always)]
+pub(crate) unsafe fn src_pixels<P>(row: &[P], start: usize, len: usize) -> &[P] {
+    if cfg!(feature = "debug-checks") {
+        assert!(
+            start + len <= row.len(),
+            "Coefficients chunk {}..{} is out of the row of source image with width {}",
+            start,
+            start + len,
+            row.len()
+        );
+        &row[start..start + len]
+    } else {
+        row.get_unchecked(start..)
+    }
+}
+
+/// Returns the pixel of the source row with given index.
+///
+/// # Safety
+/// `x` must be less than length of the row. It is checked only with
+/// feature `debug-checks`.
+#[inline(always)]
+pub(crate) unsafe fn src_pixel<P>(row: &[P], x: usize) -> &P {
+    if cfg!(feature = "debug-checks") {
+        assert!(
+            x < row.len(),
+            "Pixel {} is out of the row of source image with width {}",
+            x,
+            row.len()
+        );
+        &row[x]
+    } else {
+        row.get_unchecked(x)
+    }
+}
+
 pub(crate) trait Convolution
 where
-    Self: Pixel + Sized,
+    Self: Pixel + Components + Sized + Send + Sync,
 {
     fn horiz_convolution(
         src_image: TypedImageView<Self>,
@@ -35,6 +91,12 @@ where
         coeffs: Coefficients,
         cpu_extensions: CpuExtensions,
     );
+
+    /// Returns CPU-extensions that are actually used to convolve
+    /// pixels of this type if `cpu_extensions` are requested.
+    fn used_cpu_extensions(_cpu_extensions: CpuExtensions) -> CpuExtensions {
+        CpuExtensions::None
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,20 +105,237 @@ pub struct Bound {
     pub size: u32,
 }
 
+/// Rounding mode of results of convolution of images with integer pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum RoundingMode {
+    /// Rounds to the nearest integer, half-way cases are rounded
+    /// away from zero.
+    #[default]
+    NearestAway,
+    /// Rounds to the nearest integer, half-way cases are rounded
+    /// to the even integer (banker's rounding).
+    NearestEven,
+    /// Rounds toward zero.
+    Truncate,
+}
+
+/// Handling of pixels outside of the image, which are covered
+/// by the filter near borders of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum BoundaryMode {
+    /// The window of the filter is clamped by borders of the image,
+    /// so only pixels inside the image are used (weights of these
+    /// pixels are normalized to sum up to 1).
+    #[default]
+    Clamp,
+    /// Pixels outside the image are mirrored copies of pixels
+    /// near the border (`cba|abcd|dcb`).
+    Mirror,
+    /// Pixels outside the image are taken from the opposite side of
+    /// the image (`bcd|abcd|abc`), it keeps tileable textures seamless.
+    ///
+    /// Resizing with this mode makes a copy of the source image padded
+    /// by wrapped pixels, so every pixel of the result is calculated
+    /// from the same count of source pixels as with other modes.
+    Wrap,
+}
+
+impl BoundaryMode {
+    /// Returns index of the pixel inside the image of given size
+    /// which is used instead of the pixel with index `x`.
+    fn source_index(self, x: i64, size: u32) -> u32 {
+        let size = size as i64;
+        let index = match self {
+            Self::Clamp => x.clamp(0, size - 1),
+            Self::Mirror => {
+                let x = x.rem_euclid(2 * size);
+                if x < size {
+                    x
+                } else {
+                    2 * size - 1 - x
+                }
+            }
+            Self::Wrap => x.rem_euclid(size),
+        };
+        index as u32
+    }
+}
+
+/// Options of precomputing of coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CoefficientsOptions {
+    /// Divide weights of every chunk by their sum.
+    pub normalize: bool,
+    /// Limit of radius of the scaled filter.
+    pub max_filter_radius: Option<NonZeroU32>,
+    pub boundary_mode: BoundaryMode,
+}
+
+impl CoefficientsOptions {
+    pub(crate) fn normalized() -> Self {
+        Self {
+            normalize: true,
+            max_filter_radius: None,
+            boundary_mode: BoundaryMode::Clamp,
+        }
+    }
+}
+
+impl RoundingMode {
+    #[inline(always)]
+    pub(crate) fn round(self, v: f64) -> f64 {
+        match self {
+            Self::NearestAway => v.round(),
+            #[cfg(feature = "std")]
+            Self::NearestEven => v.round_ties_even(),
+            #[cfg(not(feature = "std"))]
+            Self::NearestEven => {
+                let rounded = v.round();
+                if (rounded - v).abs() == 0.5 {
+                    2. * (v / 2.).round()
+                } else {
+                    rounded
+                }
+            }
+            Self::Truncate => v.trunc(),
+        }
+    }
+}
+
+/// Coefficients of one-dimensional convolution.
+///
+/// Coefficients consist of chunks, one chunk for every pixel of
+/// the output row (or column). See [CoefficientsChunk] for details.
+/// Use [Resizer::horiz_convolution](crate::Resizer::horiz_convolution)
+/// and [Resizer::vert_convolution](crate::Resizer::vert_convolution)
+/// to convolve an image with help of these coefficients.
 #[derive(Debug, Clone)]
 pub struct Coefficients {
-    pub values: Vec<f64>,
-    pub window_size: usize,
-    pub bounds: Vec<Bound>,
+    pub(crate) values: Vec<f64>,
+    pub(crate) window_size: usize,
+    pub(crate) bounds: Vec<Bound>,
+    pub(crate) rounding_mode: RoundingMode,
+    /// Convolve with help of the reference implementation
+    /// instead of the optimized one.
+    pub(crate) reference: bool,
+    /// Count of wrapped pixels added before the first and after the last
+    /// pixel of the source axis with [BoundaryMode::Wrap]. Bounds are
+    /// indexes of pixels in the padded axis.
+    pub(crate) padding: u32,
 }
 
+/// Coefficients for one output pixel.
+///
+/// The output pixel with index `i` is a weighted sum of input pixels
+/// with indexes from `start` to `start + values.len()`, where
+/// `values` are weights of these pixels.
 #[derive(Debug, Clone, Copy)]
 pub struct CoefficientsChunk<'a> {
+    /// Index of the first input pixel.
     pub start: u32,
+    /// Weights of input pixels.
     pub values: &'a [f64],
 }
 
 impl Coefficients {
+    /// Precomputes coefficients to convolve `src_size` input pixels
+    /// into `dst_size` output pixels with help of the given filter.
+    pub fn new(filter_type: FilterType, src_size: NonZeroU32, dst_size: NonZeroU32) -> Self {
+        precompute_coefficients(
+            src_size,
+            0.,
+            src_size.get() as f64,
+            dst_size,
+            filter_type,
+            CoefficientsOptions::normalized(),
+        )
+    }
+
+    /// Precomputes coefficients like [Coefficients::new], but weights of
+    /// every chunk are raw values of the filter, which are not divided by
+    /// their sum. So convolution calculates weighted sums instead of
+    /// weighted averages of input pixels.
+    ///
+    /// It makes sense only for images with `I32`, `F32`, `F32x4` and `F16`
+    /// pixels, results for other images are saturated.
+    pub fn new_unnormalized(
+        filter_type: FilterType,
+        src_size: NonZeroU32,
+        dst_size: NonZeroU32,
+    ) -> Self {
+        precompute_coefficients(
+            src_size,
+            0.,
+            src_size.get() as f64,
+            dst_size,
+            filter_type,
+            CoefficientsOptions {
+                normalize: false,
+                ..CoefficientsOptions::normalized()
+            },
+        )
+    }
+
+    /// Creates coefficients from the given chunks, the chunk with
+    /// index `i` is used to calculate the output pixel with index `i`.
+    pub fn from_chunks(chunks: &[CoefficientsChunk]) -> Self {
+        let window_size = chunks.iter().map(|c| c.values.len()).max().unwrap_or(0);
+        let mut values = Vec::with_capacity(window_size * chunks.len());
+        let mut bounds = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            values.extend_from_slice(chunk.values);
+            values.resize(values.len() + window_size - chunk.values.len(), 0.);
+            bounds.push(Bound {
+                start: chunk.start,
+                size: chunk.values.len() as u32,
+            });
+        }
+        Self {
+            values,
+            window_size,
+            bounds,
+            rounding_mode: RoundingMode::default(),
+            reference: false,
+            padding: 0,
+        }
+    }
+
+    /// Returns `true` if all chunks use only input pixels with indexes
+    /// less than `src_size`.
+    pub(crate) fn is_in_bounds(&self, src_size: NonZeroU32) -> bool {
+        self.bounds
+            .iter()
+            .all(|b| b.start as u64 + b.size as u64 <= src_size.get() as u64)
+    }
+
+    /// Returns coefficients for output pixels in the given range.
+    ///
+    /// Precision of fixed-point coefficients of integer pixels is calculated
+    /// from the maximal value of all coefficients. So the maximal value is
+    /// stored after the last chunk (where it isn't used by convolution)
+    /// to get the same results as with unsliced coefficients.
+    pub(crate) fn slice(&self, range: core::ops::Range<usize>) -> Self {
+        let values_range = range.start * self.window_size..range.end * self.window_size;
+        let mut values = self.values[values_range].to_vec();
+        let max_value = self.values.iter().copied().fold(f64::MIN, f64::max);
+        values.push(max_value);
+        Self {
+            values,
+            window_size: self.window_size,
+            bounds: self.bounds[range].to_vec(),
+            rounding_mode: self.rounding_mode,
+            reference: self.reference,
+            padding: self.padding,
+        }
+    }
+
+    /// Returns chunks of coefficients, one chunk per output pixel.
     pub fn get_chunks(&self) -> Vec<CoefficientsChunk> {
         let mut coeffs = self.values.as_slice();
         let mut res = Vec::with_capacity(self.bounds.len());
@@ -73,45 +352,97 @@ impl Coefficients {
     }
 }
 
-pub fn precompute_coefficients(
+/// Precomputes coefficients of convolution with the given filter.
+/// Radius of the scaled filter is limited by `options.max_filter_radius`
+/// (but not less than support of the filter itself).
+///
+/// With [BoundaryMode::Mirror] weights of pixels outside the image are
+/// added to weights of pixels used instead of them. With [BoundaryMode::Wrap]
+/// bounds are indexes of pixels in the axis padded on both sides by
+/// `padding` wrapped pixels (see [Coefficients::padding]), so every chunk
+/// is not longer than the window of the filter. Only windows that cover
+/// the whole axis are folded into the pixels of the axis.
+pub(crate) fn precompute_coefficients(
     in_size: NonZeroU32,
     in0: f64, // Left border for cropping
     in1: f64, // Right border for cropping
     out_size: NonZeroU32,
-    filter: &dyn Fn(f64) -> f64,
-    filter_support: f64,
+    filter_type: FilterType,
+    options: CoefficientsOptions,
 ) -> Coefficients {
+    let (filter, filter_support) = get_filter_func(filter_type);
     let in_size = in_size.get();
     let out_size = out_size.get();
 
     let scale = (in1 - in0) / out_size as f64;
-    let filter_scale = scale.max(1.0);
+    let mut filter_scale = scale.max(1.0);
+    if let Some(max_radius) = options.max_filter_radius {
+        let max_scale = max_radius.get() as f64 / filter_support;
+        filter_scale = filter_scale.min(max_scale.max(1.0));
+    }
 
     // Determine filter radius size (length of resampling filter)
     let filter_radius = filter_support * filter_scale;
     // Maximum number of coeffs per out pixel
-    let window_size = filter_radius.ceil() as usize * 2 + 1;
+    let mut window_size = filter_radius.ceil() as usize * 2 + 1;
     // Optimization: replace division by filter_scale
     // with multiplication by recip_filter_scale
     let recip_filter_scale = 1.0 / filter_scale;
 
-    let count_of_coeffs = window_size * out_size as usize;
-    let mut coeffs: Vec<f64> = Vec::with_capacity(count_of_coeffs);
+    // Weights of all chunks one after another,
+    // they are aligned by `window_size` at the end.
+    let mut chunks_values: Vec<f64> = Vec::with_capacity(window_size * out_size as usize);
     let mut bounds: Vec<Bound> = Vec::with_capacity(out_size as usize);
 
-    for out_x in 0..out_size {
-        // Find the point in the input image corresponding to the centre
-        // of the current pixel in the output image.
+    // Finds the point in the input image corresponding to the centre
+    // of the pixel in the output image and slice bounds for the input
+    // pixels relevant to this output pixel. Pixel x is relevant
+    // if and only if (x >= x_min) && (x < x_max).
+    let window = |out_x: u32| {
         let in_center = in0 + (out_x as f64 + 0.5) * scale;
+        let x_min = (in_center - filter_radius).floor() as i64;
+        let x_max = (in_center + filter_radius).ceil() as i64;
+        (in_center, x_min, x_max)
+    };
+    // Windows shorter than the axis aren't folded, so pixels
+    // outside the image are read from the padded axis.
+    let is_folded = |x_min: i64, x_max: i64| x_max - x_min >= in_size as i64;
+    let padding = if options.boundary_mode == BoundaryMode::Wrap {
+        (0..out_size)
+            .map(window)
+            .filter(|&(_, x_min, x_max)| !is_folded(x_min, x_max))
+            .map(|(_, x_min, x_max)| (-x_min).max(x_max - in_size as i64).max(0))
+            .max()
+            .unwrap_or(0) as u32
+    } else {
+        0
+    };
 
-        // x_min and x_max are slice bounds for the input pixels relevant
-        // to the output pixel we are calculating. Pixel x is relevant
-        // if and only if (x >= x_min) && (x < x_max).
-        // Invariant: 0 <= x_min < x_max <= width
-        let x_min = (in_center - filter_radius).floor().max(0.) as u32;
-        let x_max = (in_center + filter_radius).ceil().min(in_size as f64) as u32;
+    for out_x in 0..out_size {
+        let (in_center, mut x_min, mut x_max) = window(out_x);
+        let folded = is_folded(x_min, x_max);
+        // Indexes of pixels used instead of relevant pixels.
+        let source_index = |x: i64| match options.boundary_mode {
+            BoundaryMode::Wrap if !folded => (x + padding as i64) as u32,
+            BoundaryMode::Wrap => options.boundary_mode.source_index(x, in_size) + padding,
+            boundary_mode => boundary_mode.source_index(x, in_size),
+        };
+        let (start, end) = if options.boundary_mode == BoundaryMode::Clamp {
+            // Invariant: 0 <= x_min < x_max <= width
+            x_min = x_min.max(0);
+            x_max = x_max.min(in_size as i64);
+            (x_min as u32, x_max as u32)
+        } else {
+            (x_min..x_max)
+                .map(source_index)
+                .fold((u32::MAX, 0), |(start, end), i| {
+                    (start.min(i), end.max(i + 1))
+                })
+        };
 
-        let cur_index = coeffs.len();
+        let cur_index = chunks_values.len();
+        chunks_values.resize(cur_index + end.saturating_sub(start) as usize, 0.);
+        let values = &mut chunks_values[cur_index..];
         let mut ww: f64 = 0.0;
 
         // Optimisation for follow for-cycle:
@@ -120,23 +451,129 @@ pub fn precompute_coefficients(
 
         for x in x_min..x_max {
             let w: f64 = filter((x as f64 - center) * recip_filter_scale);
-            coeffs.push(w);
+            let i = source_index(x) - start;
+            values[i as usize] += w;
             ww += w;
         }
-        if ww != 0.0 {
-            coeffs[cur_index..].iter_mut().for_each(|w| *w /= ww);
+        if options.normalize && ww != 0.0 {
+            values.iter_mut().for_each(|w| *w /= ww);
         }
-        // Remaining values should stay empty if they are used despite x_max.
-        coeffs.resize(cur_index + window_size, 0.);
+        window_size = window_size.max(values.len());
         bounds.push(Bound {
-            start: x_min,
-            size: x_max - x_min,
+            start,
+            size: values.len() as u32,
         });
     }
 
+    // Remaining values should stay empty if they are used despite x_max.
+    let mut coeffs: Vec<f64> = Vec::with_capacity(window_size * out_size as usize);
+    let mut chunks_values = chunks_values.as_slice();
+    for bound in &bounds {
+        let (values, rest) = chunks_values.split_at(bound.size as usize);
+        chunks_values = rest;
+        coeffs.extend_from_slice(values);
+        coeffs.resize(coeffs.len() + window_size - values.len(), 0.);
+    }
+
     Coefficients {
         values: coeffs,
         window_size,
         bounds,
+        rounding_mode: RoundingMode::default(),
+        reference: false,
+        padding,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nz(value: u32) -> NonZeroU32 {
+        NonZeroU32::new(value).unwrap()
+    }
+
+    #[test]
+    fn wrapped_windows_are_not_wider_than_filter() {
+        let options = CoefficientsOptions {
+            boundary_mode: BoundaryMode::Wrap,
+            ..CoefficientsOptions::normalized()
+        };
+        let coeffs =
+            precompute_coefficients(nz(4096), 0., 4096., nz(8192), FilterType::Lanczos3, options);
+        assert_eq!(coeffs.window_size, 7);
+        assert_eq!(coeffs.padding, 3);
+        assert!(coeffs.is_in_bounds(nz(4096 + 2 * 3)));
+        let last = coeffs.bounds.last().unwrap();
+        assert_eq!(last.start + last.size, 4096 + 2 * 3);
+
+        // Windows that cover the whole axis are folded into it.
+        let coeffs = precompute_coefficients(nz(64), 0., 64., nz(2), FilterType::Lanczos3, options);
+        assert_eq!(coeffs.padding, 0);
+        for chunk in coeffs.get_chunks() {
+            assert_eq!((chunk.start, chunk.values.len()), (0, 64));
+            assert!((chunk.values.iter().sum::<f64>() - 1.).abs() < 1e-9);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "debug-checks"))]
+mod debug_checks_tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::image::InnerImage;
+    use crate::pixels::{U8x3, F32};
+
+    fn nz(value: u32) -> NonZeroU32 {
+        NonZeroU32::new(value).unwrap()
+    }
+
+    /// Chunk of coefficients starts inside of the row of source image,
+    /// but ends out of it (e.g. because of wrong offset of crop box).
+    fn malformed_coefficients() -> Coefficients {
+        let values = [0.25; 4];
+        let chunks = [
+            CoefficientsChunk {
+                start: 0,
+                values: &values,
+            },
+            CoefficientsChunk {
+                start: 6,
+                values: &values,
+            },
+        ];
+        Coefficients::from_chunks(&chunks)
+    }
+
+    #[test]
+    #[should_panic(expected = "Coefficients chunk 6..10 is out of the row")]
+    fn malformed_coefficients_of_horiz_convolution_panic() {
+        let mut src_pixels = vec![U8x3([255; 3]); 8 * 2];
+        let src_image = InnerImage::new(nz(8), nz(2), &mut src_pixels);
+        let mut dst_pixels = vec![U8x3([0; 3]); 2 * 2];
+        let mut dst_image = InnerImage::new(nz(2), nz(2), &mut dst_pixels);
+        U8x3::horiz_convolution(
+            src_image.src_view(),
+            dst_image.dst_view(),
+            0,
+            malformed_coefficients(),
+            CpuExtensions::None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel 4 is out of the row")]
+    fn too_wide_dst_image_of_vert_convolution_panic() {
+        let mut src_pixels = vec![F32(1.); 4 * 10];
+        let src_image = InnerImage::new(nz(4), nz(10), &mut src_pixels);
+        let mut dst_pixels = vec![F32(0.); 5 * 2];
+        let mut dst_image = InnerImage::new(nz(5), nz(2), &mut dst_pixels);
+        F32::vert_convolution(
+            src_image.src_view(),
+            dst_image.dst_view(),
+            malformed_coefficients(),
+            CpuExtensions::None,
+        );
     }
 }
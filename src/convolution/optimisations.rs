@@ -1,6 +1,10 @@
-use std::slice;
+use alloc::vec::Vec;
+use core::slice;
 
-use super::Bound;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use super::{Bound, RoundingMode};
 
 // This code is based on C-implementation from Pillow-SIMD package for Python
 // https://github.com/uploadcare/pillow-simd
@@ -35,6 +39,7 @@ const MAX_COEFS_PRECISION: u8 = 16 - 1;
 pub struct NormalizerGuard16 {
     values: Vec<f64>,
     precision: u8,
+    rounding_mode: RoundingMode,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -46,11 +51,10 @@ pub struct CoefficientsI16Chunk<'a> {
 impl NormalizerGuard16 {
     #[inline]
     pub fn new(mut values: Vec<f64>) -> Self {
-        let max_weight = values
+        let max_weight = *values
             .iter()
             .max_by(|&x, &y| x.partial_cmp(y).unwrap())
-            .unwrap_or(&0.0)
-            .to_owned();
+            .unwrap_or(&0.0);
 
         let mut precision = 0u8;
         for cur_precision in 0..PRECISION_BITS {
@@ -72,7 +76,11 @@ impl NormalizerGuard16 {
         for (&src, dst) in values.iter().zip(values_i16.iter_mut()) {
             *dst = (src * scale).round() as i16;
         }
-        Self { values, precision }
+        Self {
+            values,
+            precision,
+            rounding_mode: RoundingMode::default(),
+        }
     }
 
     #[inline]
@@ -97,6 +105,37 @@ impl NormalizerGuard16 {
         res
     }
 
+    /// Sets rounding mode used by [Self::initial] and [Self::clip].
+    #[inline]
+    pub fn with_rounding_mode(mut self, rounding_mode: RoundingMode) -> Self {
+        self.rounding_mode = rounding_mode;
+        self
+    }
+
+    /// Returns initial value of sum of products of pixels and coefficients
+    /// that gives rounding of the result with given rounding mode.
+    #[inline]
+    pub fn initial(&self) -> i32 {
+        match self.rounding_mode {
+            RoundingMode::Truncate => 0,
+            _ => 1 << (self.precision() - 1),
+        }
+    }
+
+    /// Rounds half-way cases to even value if it is required by rounding
+    /// mode. `v` must be the sum started from [Self::initial].
+    #[inline(always)]
+    fn round(&self, v: i32) -> i32 {
+        if self.rounding_mode == RoundingMode::NearestEven
+            && v & ((1 << self.precision) - 1) == 0
+            && (v >> self.precision) & 1 == 1
+        {
+            v - 1
+        } else {
+            v
+        }
+    }
+
     #[inline]
     pub fn precision(&self) -> u8 {
         self.precision
@@ -108,9 +147,13 @@ impl NormalizerGuard16 {
     /// produces a result in the range `[-512, 511]`.    
     #[inline(always)]
     pub unsafe fn clip(&self, v: i32) -> u8 {
-        let index = (640 + (v >> self.precision)) as usize;
+        let index = (640 + (self.round(v) >> self.precision)) as usize;
         // index must be in range [(640-512)..(640+511)]
-        *CLIP8_LOOKUPS.get_unchecked(index)
+        if cfg!(feature = "debug-checks") {
+            CLIP8_LOOKUPS[index]
+        } else {
+            *CLIP8_LOOKUPS.get_unchecked(index)
+        }
     }
 }
 
@@ -133,16 +176,16 @@ pub struct CoefficientsI32Chunk<'a> {
 pub struct NormalizerGuard32 {
     values: Vec<f64>,
     precision: u8,
+    rounding_mode: RoundingMode,
 }
 
 impl NormalizerGuard32 {
     #[inline]
     pub fn new(mut values: Vec<f64>) -> Self {
-        let max_weight = values
+        let max_weight = *values
             .iter()
             .max_by(|&x, &y| x.partial_cmp(y).unwrap())
-            .unwrap_or(&0.0)
-            .to_owned();
+            .unwrap_or(&0.0);
 
         let mut precision = 0u8;
         for cur_precision in 0..PRECISION16_BITS {
@@ -164,7 +207,11 @@ impl NormalizerGuard32 {
         for (&src, dst) in values.iter().zip(values_i32.iter_mut()) {
             *dst = (src * scale).round() as i32;
         }
-        Self { values, precision }
+        Self {
+            values,
+            precision,
+            rounding_mode: RoundingMode::default(),
+        }
     }
 
     #[inline]
@@ -189,6 +236,37 @@ impl NormalizerGuard32 {
         res
     }
 
+    /// Sets rounding mode used by [Self::initial] and [Self::clip].
+    #[inline]
+    pub fn with_rounding_mode(mut self, rounding_mode: RoundingMode) -> Self {
+        self.rounding_mode = rounding_mode;
+        self
+    }
+
+    /// Returns initial value of sum of products of pixels and coefficients
+    /// that gives rounding of the result with given rounding mode.
+    #[inline]
+    pub fn initial(&self) -> i64 {
+        match self.rounding_mode {
+            RoundingMode::Truncate => 0,
+            _ => 1 << (self.precision() - 1),
+        }
+    }
+
+    /// Rounds half-way cases to even value if it is required by rounding
+    /// mode. `v` must be the sum started from [Self::initial].
+    #[inline(always)]
+    fn round(&self, v: i64) -> i64 {
+        if self.rounding_mode == RoundingMode::NearestEven
+            && v & ((1 << self.precision) - 1) == 0
+            && (v >> self.precision) & 1 == 1
+        {
+            v - 1
+        } else {
+            v
+        }
+    }
+
     #[inline]
     pub fn precision(&self) -> u8 {
         self.precision
@@ -196,12 +274,16 @@ impl NormalizerGuard32 {
 
     #[inline(always)]
     pub fn clip(&self, v: i64) -> u16 {
-        (v >> self.precision).min(u16::MAX as i64).max(0) as u16
+        (self.round(v) >> self.precision)
+            .min(u16::MAX as i64)
+            .max(0) as u16
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use super::*;
 
     #[test]
@@ -212,4 +294,24 @@ mod tests {
         assert!(NormalizerGuard32::new(vec![0.0]).precision() >= 4);
         assert!(NormalizerGuard32::new(vec![2.0]).precision() >= 4);
     }
+
+    #[test]
+    fn test_clip_saturates() {
+        let guard = NormalizerGuard16::new(vec![1.0]);
+        let one = 1 << guard.precision();
+        unsafe {
+            assert_eq!(guard.clip(-one), 0);
+            assert_eq!(guard.clip(-512 * one), 0);
+            assert_eq!(guard.clip(255 * one), 255);
+            assert_eq!(guard.clip(256 * one), 255);
+            assert_eq!(guard.clip(511 * one), 255);
+        }
+
+        let guard = NormalizerGuard32::new(vec![1.0]);
+        let one = 1i64 << guard.precision();
+        assert_eq!(guard.clip(-one), 0);
+        assert_eq!(guard.clip(i64::MIN / 4), 0);
+        assert_eq!(guard.clip(u16::MAX as i64 * one), u16::MAX);
+        assert_eq!(guard.clip((u16::MAX as i64 + 1) * one), u16::MAX);
+    }
 }
@@ -0,0 +1,72 @@
+//! Reference implementation of convolution for all types of pixels,
+//! see [Resizer::set_reference_mode](crate::Resizer::set_reference_mode).
+//!
+//! Components of pixels are multiplied by weights and summed up in `f64`
+//! in order of source pixels, without SIMD and fixed-point arithmetic.
+//! So results don't depend on CPU and its extensions.
+use super::{src_pixel, src_pixels, Coefficients, RoundingMode};
+use crate::convolution_2d::Components;
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+
+pub(crate) fn horiz_convolution<P: Components>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let coefficients_chunks = coeffs.get_chunks();
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        for (dst_pixel, coeffs_chunk) in dst_row.iter_mut().zip(&coefficients_chunks) {
+            let first_x_src = coeffs_chunk.start as usize;
+            let mut ss = [0.; 4];
+            let src_pixels = unsafe { src_pixels(src_row, first_x_src, coeffs_chunk.values.len()) };
+            for (&k, &pixel) in coeffs_chunk.values.iter().zip(src_pixels) {
+                add_weighted(&mut ss, pixel, k);
+            }
+            *dst_pixel = round_pixel(ss, rounding_mode);
+        }
+    }
+}
+
+pub(crate) fn vert_convolution<P: Components>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let coefficients_chunks = coeffs.get_chunks();
+    let dst_rows = dst_image.iter_rows_mut();
+    for (&coeffs_chunk, dst_row) in coefficients_chunks.iter().zip(dst_rows) {
+        let first_y_src = coeffs_chunk.start;
+        for (x_src, dst_pixel) in dst_row.iter_mut().enumerate() {
+            let mut ss = [0.; 4];
+            let src_rows = src_image.iter_rows(first_y_src);
+            for (src_row, &k) in src_rows.zip(coeffs_chunk.values) {
+                let src_pixel = unsafe { src_pixel(src_row, x_src) };
+                add_weighted(&mut ss, *src_pixel, k);
+            }
+            *dst_pixel = round_pixel(ss, rounding_mode);
+        }
+    }
+}
+
+#[inline(always)]
+fn add_weighted<P: Components>(ss: &mut [f64; 4], pixel: P, k: f64) {
+    for (s, c) in ss.iter_mut().zip(pixel.to_f64()) {
+        *s += c * k;
+    }
+}
+
+/// Creates pixel from sums of components. Sums of integer components
+/// are rounded with help of the given rounding mode and saturated.
+#[inline(always)]
+fn round_pixel<P: Components>(ss: [f64; 4], rounding_mode: RoundingMode) -> P {
+    if P::IS_FLOAT {
+        P::from_f64(ss)
+    } else {
+        P::from_f64(ss.map(|s| rounding_mode.round(s)))
+    }
+}
@@ -0,0 +1,194 @@
+use core::arch::x86_64::*;
+use core::slice;
+
+use crate::convolution::optimisations::{CoefficientsI32Chunk, NormalizerGuard32};
+use crate::convolution::{optimisations, Coefficients};
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::U16x3;
+
+#[inline]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<U16x3>,
+    mut dst_image: TypedImageViewMut<U16x3>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard =
+        optimisations::NormalizerGuard32::new(values).with_rounding_mode(rounding_mode);
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
+
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        unsafe {
+            horiz_convolution_row(src_row, dst_row, &coefficients_chunks, &normalizer_guard);
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<U16x3>,
+    mut dst_image: TypedImageViewMut<U16x3>,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard =
+        optimisations::NormalizerGuard32::new(values).with_rounding_mode(rounding_mode);
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
+
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, &coeffs_chunk) in dst_rows.zip(&coefficients_chunks) {
+        unsafe {
+            vert_convolution_row(&src_image, dst_row, coeffs_chunk, &normalizer_guard);
+        }
+    }
+}
+
+/// Stores sums of products into components of the pixel.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn store_pixel(sss: __m256i, dst_pixel: &mut U16x3, normalizer_guard: &NormalizerGuard32) {
+    let mut ss = [0i64; 4];
+    _mm256_storeu_si256(ss.as_mut_ptr() as *mut __m256i, sss);
+    for (dst, &s) in dst_pixel.0.iter_mut().zip(&ss) {
+        *dst = normalizer_guard.clip(s);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - coefficients_chunks.len() == dst_row.len()
+/// - max(chunk.start + chunk.values.len() for chunk in coefficients_chunks) <= src_row.len()
+#[target_feature(enable = "avx2")]
+unsafe fn horiz_convolution_row(
+    src_row: &[U16x3],
+    dst_row: &mut [U16x3],
+    coefficients_chunks: &[CoefficientsI32Chunk],
+    normalizer_guard: &NormalizerGuard32,
+) {
+    let initial = _mm256_set1_epi64x(normalizer_guard.initial());
+    let src_ptr = src_row.as_ptr() as *const u16;
+    for (dst_pixel, coeffs_chunk) in dst_row.iter_mut().zip(coefficients_chunks) {
+        let x_start = coeffs_chunk.start as usize;
+        let mut coeffs = coeffs_chunk.values;
+        // Every pixel is loaded by 8 bytes, so the first component
+        // of the next pixel is loaded too. The last pixel of the row
+        // hasn't next pixel and must be loaded separately.
+        let mut last_pixel = None;
+        if x_start + coeffs.len() == src_row.len() {
+            if let Some((&k, rest)) = coeffs.split_last() {
+                last_pixel = Some((src_row.len() - 1, k));
+                coeffs = rest;
+            }
+        }
+
+        let mut sss0 = initial;
+        let mut sss1 = _mm256_setzero_si256();
+        let mut x: usize = 0;
+        let mut pixel_ptr = src_ptr.add(x_start * 3);
+        // `_mm256_mul_epi32` multiplies lower 32 bits of 64-bit lanes
+        // into 64-bit results, so the sums can't overflow.
+        while x + 2 <= coeffs.len() {
+            let mmk0 = _mm256_set1_epi64x(*coeffs.get_unchecked(x) as i64);
+            let mmk1 = _mm256_set1_epi64x(*coeffs.get_unchecked(x + 1) as i64);
+            let pixel0 = _mm256_cvtepu16_epi64(_mm_loadl_epi64(pixel_ptr as *const __m128i));
+            let pixel1 = _mm256_cvtepu16_epi64(_mm_loadl_epi64(pixel_ptr.add(3) as *const __m128i));
+            sss0 = _mm256_add_epi64(sss0, _mm256_mul_epi32(pixel0, mmk0));
+            sss1 = _mm256_add_epi64(sss1, _mm256_mul_epi32(pixel1, mmk1));
+            pixel_ptr = pixel_ptr.add(6);
+            x += 2;
+        }
+        if x < coeffs.len() {
+            let mmk = _mm256_set1_epi64x(*coeffs.get_unchecked(x) as i64);
+            let pixel = _mm256_cvtepu16_epi64(_mm_loadl_epi64(pixel_ptr as *const __m128i));
+            sss0 = _mm256_add_epi64(sss0, _mm256_mul_epi32(pixel, mmk));
+        }
+        if let Some((index, k)) = last_pixel {
+            let [r, g, b] = src_row.get_unchecked(index).0;
+            let pixel = _mm256_set_epi64x(0, b as i64, g as i64, r as i64);
+            let mmk = _mm256_set1_epi64x(k as i64);
+            sss1 = _mm256_add_epi64(sss1, _mm256_mul_epi32(pixel, mmk));
+        }
+        store_pixel(_mm256_add_epi64(sss0, sss1), dst_pixel, normalizer_guard);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == src_img.width()
+/// - coeffs_chunk.start + coeffs_chunk.values.len() <= src_img.height()
+#[target_feature(enable = "avx2")]
+unsafe fn vert_convolution_row(
+    src_img: &TypedImageView<U16x3>,
+    dst_row: &mut [U16x3],
+    coeffs_chunk: CoefficientsI32Chunk,
+    normalizer_guard: &NormalizerGuard32,
+) {
+    // Vertical convolution doesn't mix components of pixels,
+    // so rows are processed as plain slices of components.
+    let components = dst_row.len() * 3;
+    let dst_components = slice::from_raw_parts_mut(dst_row.as_mut_ptr() as *mut u16, components);
+    let y_start = coeffs_chunk.start;
+    let coeffs = coeffs_chunk.values;
+    let initial = normalizer_guard.initial();
+    let mut ss = [0i64; 16];
+
+    let mut x: usize = 0;
+    while x + 16 <= components {
+        let mut sss = [_mm256_set1_epi64x(initial); 4];
+        for (&k, src_row) in coeffs.iter().zip(src_img.iter_rows(y_start)) {
+            let mmk = _mm256_set1_epi64x(k as i64);
+            let src_ptr = (src_row.as_ptr() as *const u16).add(x);
+            let source = _mm256_loadu_si256(src_ptr as *const __m256i);
+            let source_lo = _mm256_castsi256_si128(source);
+            let source_hi = _mm256_extracti128_si256::<1>(source);
+            let pixels = [
+                _mm256_cvtepu16_epi64(source_lo),
+                _mm256_cvtepu16_epi64(_mm_srli_si128::<8>(source_lo)),
+                _mm256_cvtepu16_epi64(source_hi),
+                _mm256_cvtepu16_epi64(_mm_srli_si128::<8>(source_hi)),
+            ];
+            for (s, p) in sss.iter_mut().zip(pixels) {
+                *s = _mm256_add_epi64(*s, _mm256_mul_epi32(p, mmk));
+            }
+        }
+        for (i, s) in sss.into_iter().enumerate() {
+            _mm256_storeu_si256(ss.as_mut_ptr().add(i * 4) as *mut __m256i, s);
+        }
+        let dst_chunk = dst_components.get_unchecked_mut(x..x + 16);
+        for (dst, &s) in dst_chunk.iter_mut().zip(&ss) {
+            *dst = normalizer_guard.clip(s);
+        }
+        x += 16;
+    }
+
+    while x + 4 <= components {
+        let mut sss = _mm256_set1_epi64x(initial);
+        for (&k, src_row) in coeffs.iter().zip(src_img.iter_rows(y_start)) {
+            let mmk = _mm256_set1_epi64x(k as i64);
+            let src_ptr = (src_row.as_ptr() as *const u16).add(x);
+            let pixels = _mm256_cvtepu16_epi64(_mm_loadl_epi64(src_ptr as *const __m128i));
+            sss = _mm256_add_epi64(sss, _mm256_mul_epi32(pixels, mmk));
+        }
+        _mm256_storeu_si256(ss.as_mut_ptr() as *mut __m256i, sss);
+        let dst_chunk = dst_components.get_unchecked_mut(x..x + 4);
+        for (dst, &s) in dst_chunk.iter_mut().zip(&ss) {
+            *dst = normalizer_guard.clip(s);
+        }
+        x += 4;
+    }
+
+    while x < components {
+        let mut s = initial;
+        for (&k, src_row) in coeffs.iter().zip(src_img.iter_rows(y_start)) {
+            let src_ptr = (src_row.as_ptr() as *const u16).add(x);
+            s += *src_ptr as i64 * (k as i64);
+        }
+        *dst_components.get_unchecked_mut(x) = normalizer_guard.clip(s);
+        x += 1;
+    }
+}
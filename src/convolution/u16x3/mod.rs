@@ -3,6 +3,8 @@ use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::U16x3;
 use crate::CpuExtensions;
 
+#[cfg(target_arch = "x86_64")]
+mod avx2;
 mod native;
 
 impl Convolution for U16x3 {
@@ -13,7 +15,13 @@ impl Convolution for U16x3 {
         coeffs: Coefficients,
         cpu_extensions: CpuExtensions,
     ) {
-        native::horiz_convolution(src_image, dst_image, offset, coeffs);
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
+            _ => native::horiz_convolution(src_image, dst_image, offset, coeffs),
+        }
     }
 
     fn vert_convolution(
@@ -22,6 +30,20 @@ impl Convolution for U16x3 {
         coeffs: Coefficients,
         cpu_extensions: CpuExtensions,
     ) {
-        native::vert_convolution(src_image, dst_image, coeffs);
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::vert_convolution(src_image, dst_image, coeffs)
+            }
+            _ => native::vert_convolution(src_image, dst_image, coeffs),
+        }
+    }
+
+    fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+            _ => CpuExtensions::None,
+        }
     }
 }
@@ -0,0 +1,72 @@
+use crate::convolution::{optimisations, src_pixel, src_pixels, Coefficients};
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::U16x4;
+
+#[inline(always)]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<U16x4>,
+    mut dst_image: TypedImageViewMut<U16x4>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard =
+        optimisations::NormalizerGuard32::new(values).with_rounding_mode(rounding_mode);
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
+    let initial = normalizer_guard.initial();
+
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        for (&coeffs_chunk, dst_pixel) in coefficients_chunks.iter().zip(dst_row.iter_mut()) {
+            let first_x_src = coeffs_chunk.start as usize;
+            let mut ss = [initial; 4];
+            let src_pixels = unsafe { src_pixels(src_row, first_x_src, coeffs_chunk.values.len()) };
+            for (&k, src_pixel) in coeffs_chunk.values.iter().zip(src_pixels) {
+                for (i, s) in ss.iter_mut().enumerate() {
+                    *s += src_pixel.0[i] as i64 * (k as i64);
+                }
+            }
+            for (i, s) in ss.iter().copied().enumerate() {
+                dst_pixel.0[i] = normalizer_guard.clip(s);
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<U16x4>,
+    mut dst_image: TypedImageViewMut<U16x4>,
+    coeffs: Coefficients,
+) {
+    let rounding_mode = coeffs.rounding_mode;
+    let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard =
+        optimisations::NormalizerGuard32::new(values).with_rounding_mode(rounding_mode);
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
+    let initial = normalizer_guard.initial();
+
+    let dst_rows = dst_image.iter_rows_mut();
+    for (&coeffs_chunk, dst_row) in coefficients_chunks.iter().zip(dst_rows) {
+        let first_y_src = coeffs_chunk.start;
+        let ks = coeffs_chunk.values;
+
+        for (x_src, dst_pixel) in dst_row.iter_mut().enumerate() {
+            let mut ss = [initial; 4];
+            let src_rows = src_image.iter_rows(first_y_src);
+            for (&k, src_row) in ks.iter().zip(src_rows) {
+                let src_pixel = unsafe { src_pixel(src_row, x_src) };
+                for (i, s) in ss.iter_mut().enumerate() {
+                    *s += src_pixel.0[i] as i64 * (k as i64);
+                }
+            }
+            for (i, s) in ss.iter().copied().enumerate() {
+                dst_pixel.0[i] = normalizer_guard.clip(s);
+            }
+        }
+    }
+}
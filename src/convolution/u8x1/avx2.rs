@@ -1,4 +1,4 @@
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
 
 use crate::convolution::optimisations::{CoefficientsI16Chunk, NormalizerGuard16};
 use crate::convolution::{optimisations, Coefficients};
@@ -122,7 +122,7 @@ unsafe fn horiz_convolution_8u4x(
         for &coeff in reminder8 {
             let coeff_i32 = coeff as i32;
             for i in 0..4 {
-                result_i32x4[i] += s_rows[i].get_unchecked(x).0.to_owned() as i32 * coeff_i32;
+                result_i32x4[i] += s_rows[i].get_unchecked(x).0 as i32 * coeff_i32;
             }
             x += 1;
         }
@@ -17,7 +17,9 @@ impl Convolution for U8 {
     ) {
         match cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => avx2::horiz_convolution(src_image, dst_image, offset, coeffs),
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
             _ => native::horiz_convolution(src_image, dst_image, offset, coeffs),
         }
     }
@@ -30,8 +32,18 @@ impl Convolution for U8 {
     ) {
         match cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => avx2::vert_convolution(src_image, dst_image, coeffs),
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::vert_convolution(src_image, dst_image, coeffs)
+            }
             _ => native::vert_convolution(src_image, dst_image, coeffs),
         }
     }
+
+    fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+            _ => CpuExtensions::None,
+        }
+    }
 }
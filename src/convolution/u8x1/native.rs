@@ -1,4 +1,4 @@
-use crate::convolution::{optimisations, Coefficients};
+use crate::convolution::{optimisations, src_pixel, src_pixels, Coefficients};
 use crate::image_view::{TypedImageView, TypedImageViewMut};
 use crate::pixels::U8;
 
@@ -9,12 +9,13 @@ pub(crate) fn horiz_convolution(
     offset: u32,
     coeffs: Coefficients,
 ) {
+    let rounding_mode = coeffs.rounding_mode;
     let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
 
-    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
-    let precision = normalizer_guard.precision();
+    let normalizer_guard =
+        optimisations::NormalizerGuard16::new(values).with_rounding_mode(rounding_mode);
     let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
-    let initial = 1 << (precision - 1);
+    let initial = normalizer_guard.initial();
 
     let src_rows = src_image.iter_rows(offset);
     let dst_rows = dst_image.iter_rows_mut();
@@ -24,7 +25,7 @@ pub(crate) fn horiz_convolution(
             let ks = coeffs_chunk.values;
 
             let mut ss = initial;
-            let src_pixels = unsafe { src_row.get_unchecked(first_x_src..) };
+            let src_pixels = unsafe { src_pixels(src_row, first_x_src, coeffs_chunk.values.len()) };
             for (&k, &src_pixel) in ks.iter().zip(src_pixels) {
                 ss += src_pixel.0 as i32 * (k as i32);
             }
@@ -39,12 +40,13 @@ pub(crate) fn vert_convolution(
     mut dst_image: TypedImageViewMut<U8>,
     coeffs: Coefficients,
 ) {
+    let rounding_mode = coeffs.rounding_mode;
     let (values, window_size, bounds) = (coeffs.values, coeffs.window_size, coeffs.bounds);
 
-    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
-    let precision = normalizer_guard.precision();
+    let normalizer_guard =
+        optimisations::NormalizerGuard16::new(values).with_rounding_mode(rounding_mode);
     let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds);
-    let initial = 1 << (precision - 1);
+    let initial = normalizer_guard.initial();
 
     let dst_rows = dst_image.iter_rows_mut();
     for (&coeffs_chunk, dst_row) in coefficients_chunks.iter().zip(dst_rows) {
@@ -55,7 +57,7 @@ pub(crate) fn vert_convolution(
             let mut ss = initial;
             let src_rows = src_image.iter_rows(first_y_src);
             for (&k, src_row) in ks.iter().zip(src_rows) {
-                let src_pixel = unsafe { src_row.get_unchecked(x_src as usize) };
+                let src_pixel = unsafe { src_pixel(src_row, x_src as usize) };
                 ss += src_pixel.0 as i32 * (k as i32);
             }
             dst_pixel.0 = unsafe { normalizer_guard.clip(ss) };
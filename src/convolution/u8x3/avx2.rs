@@ -1,5 +1,5 @@
-use std::arch::x86_64::*;
-use std::intrinsics::transmute;
+use core::arch::x86_64::*;
+use core::intrinsics::transmute;
 
 use crate::convolution::optimisations::{CoefficientsI16Chunk, NormalizerGuard16};
 use crate::convolution::{optimisations, Coefficients};
@@ -579,14 +579,16 @@ unsafe fn vert_convolution_8u(
 
         sss = _mm_packs_epi32(sss, sss);
         let dst_ptr_i32 = dst_ptr_u8.add(x_in_bytes) as *mut i32;
-        *dst_ptr_i32 = _mm_cvtsi128_si32(_mm_packus_epi16(sss, sss));
+        dst_ptr_i32.write_unaligned(_mm_cvtsi128_si32(_mm_packus_epi16(sss, sss)));
 
         x_in_bytes += 4;
     }
 
     if x_in_bytes < width_in_bytes {
-        let dst_u8 =
-            std::slice::from_raw_parts_mut(dst_ptr_u8.add(x_in_bytes), width_in_bytes - x_in_bytes);
+        let dst_u8 = core::slice::from_raw_parts_mut(
+            dst_ptr_u8.add(x_in_bytes),
+            width_in_bytes - x_in_bytes,
+        );
 
         for dst_pixel in dst_u8 {
             let mut ss0 = 1 << (precision - 1);
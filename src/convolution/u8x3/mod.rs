@@ -19,7 +19,9 @@ impl Convolution for U8x3 {
     ) {
         match cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => avx2::horiz_convolution(src_image, dst_image, offset, coeffs),
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => unsafe {
                 sse4::horiz_convolution(src_image, dst_image, offset, coeffs)
@@ -36,7 +38,9 @@ impl Convolution for U8x3 {
     ) {
         match cpu_extensions {
             #[cfg(target_arch = "x86_64")]
-            CpuExtensions::Avx2 => avx2::vert_convolution(src_image, dst_image, coeffs),
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => {
+                avx2::vert_convolution(src_image, dst_image, coeffs)
+            }
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => unsafe {
                 sse4::vert_convolution(src_image, dst_image, coeffs)
@@ -44,4 +48,14 @@ impl Convolution for U8x3 {
             _ => native::vert_convolution(src_image, dst_image, coeffs),
         }
     }
+
+    fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+        match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Sse4_1 => CpuExtensions::Sse4_1,
+            _ => CpuExtensions::None,
+        }
+    }
 }
@@ -1,5 +1,5 @@
-use std::arch::x86_64::*;
-use std::intrinsics::transmute;
+use core::arch::x86_64::*;
+use core::intrinsics::transmute;
 
 use crate::convolution::optimisations::{CoefficientsI16Chunk, NormalizerGuard16};
 use crate::convolution::{optimisations, Coefficients};
@@ -203,7 +203,7 @@ unsafe fn horiz_convolution_8u4x(
 /// - precision <= MAX_COEFS_PRECISION
 #[inline]
 #[target_feature(enable = "avx2")]
-unsafe fn horiz_convolution_8u(
+pub(crate) unsafe fn horiz_convolution_8u(
     src_row: &[U8x4],
     dst_row: &mut [U8x4],
     coefficients_chunks: &[CoefficientsI16Chunk],
@@ -0,0 +1,294 @@
+use core::arch::x86_64::*;
+
+use crate::convolution::optimisations::CoefficientsI16Chunk;
+use crate::convolution::{optimisations, Coefficients};
+use crate::image_view::{FourRows, FourRowsMut, TypedImageView, TypedImageViewMut};
+use crate::pixels::U8x4;
+use crate::simd_utils;
+
+use super::avx2;
+
+#[inline]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+    let dst_height = dst_image.height().get();
+
+    let src_iter = src_image.iter_4_rows(offset, dst_height + offset);
+    let dst_iter = dst_image.iter_4_rows_mut();
+    for (src_rows, dst_rows) in src_iter.zip(dst_iter) {
+        unsafe {
+            horiz_convolution_8u4x(src_rows, dst_rows, &coefficients_chunks, precision);
+        }
+    }
+
+    // Remaining rows are processed by AVX2 implementation
+    // because they can't fill whole AVX-512 register.
+    let mut yy = dst_height - dst_height % 4;
+    while yy < dst_height {
+        unsafe {
+            avx2::horiz_convolution_8u(
+                src_image.get_row(yy + offset).unwrap(),
+                dst_image.get_row_mut(yy).unwrap(),
+                &coefficients_chunks,
+                precision,
+            );
+        }
+        yy += 1;
+    }
+}
+
+#[inline]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, coeffs_chunk) in dst_rows.zip(coefficients_chunks) {
+        unsafe {
+            vert_convolution_8u(&src_image, dst_row, coeffs_chunk, precision);
+        }
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - length of all rows in src_rows must be equal
+/// - length of all rows in dst_rows must be equal
+/// - coefficients_chunks.len() == dst_rows.0.len()
+/// - max(chunk.start + chunk.values.len() for chunk in coefficients_chunks) <= src_row.0.len()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "avx512f,avx512bw,avx512vl")]
+unsafe fn horiz_convolution_8u4x(
+    src_rows: FourRows<U8x4>,
+    dst_rows: FourRowsMut<U8x4>,
+    coefficients_chunks: &[CoefficientsI16Chunk],
+    precision: u8,
+) {
+    let (s_row0, s_row1, s_row2, s_row3) = src_rows;
+    let (d_row0, d_row1, d_row2, d_row3) = dst_rows;
+    let zero = _mm512_setzero_si512();
+    let initial = _mm512_set1_epi32(1 << (precision - 1));
+    let shift = _mm_cvtsi32_si128(precision as i32);
+
+    // Every 128-bit lane of register contains pixels from own row.
+    #[rustfmt::skip]
+    let sh1 = _mm512_set_epi8(
+        -1, 7, -1, 3, -1, 6, -1, 2, -1, 5, -1, 1, -1, 4, -1, 0,
+        -1, 7, -1, 3, -1, 6, -1, 2, -1, 5, -1, 1, -1, 4, -1, 0,
+        -1, 7, -1, 3, -1, 6, -1, 2, -1, 5, -1, 1, -1, 4, -1, 0,
+        -1, 7, -1, 3, -1, 6, -1, 2, -1, 5, -1, 1, -1, 4, -1, 0,
+    );
+    #[rustfmt::skip]
+    let sh2 = _mm512_set_epi8(
+        -1, 15, -1, 11, -1, 14, -1, 10, -1, 13, -1, 9, -1, 12, -1, 8,
+        -1, 15, -1, 11, -1, 14, -1, 10, -1, 13, -1, 9, -1, 12, -1, 8,
+        -1, 15, -1, 11, -1, 14, -1, 10, -1, 13, -1, 9, -1, 12, -1, 8,
+        -1, 15, -1, 11, -1, 14, -1, 10, -1, 13, -1, 9, -1, 12, -1, 8,
+    );
+
+    for (dst_x, coeffs_chunk) in coefficients_chunks.iter().enumerate() {
+        let mut x = coeffs_chunk.start as usize;
+        let mut sss = initial;
+        let coeffs = coeffs_chunk.values;
+
+        let coeffs_by_4 = coeffs.chunks_exact(4);
+        let reminder4 = coeffs_by_4.remainder();
+
+        for k in coeffs_by_4 {
+            let mmk0 = coeffs_pair(k, 0);
+            let mmk1 = coeffs_pair(k, 2);
+
+            let source = join_4_lanes(
+                simd_utils::loadu_si128(s_row0, x),
+                simd_utils::loadu_si128(s_row1, x),
+                simd_utils::loadu_si128(s_row2, x),
+                simd_utils::loadu_si128(s_row3, x),
+            );
+            let pix = _mm512_shuffle_epi8(source, sh1);
+            sss = _mm512_add_epi32(sss, _mm512_madd_epi16(pix, mmk0));
+            let pix = _mm512_shuffle_epi8(source, sh2);
+            sss = _mm512_add_epi32(sss, _mm512_madd_epi16(pix, mmk1));
+
+            x += 4;
+        }
+
+        let coeffs_by_2 = reminder4.chunks_exact(2);
+        let reminder2 = coeffs_by_2.remainder();
+
+        for k in coeffs_by_2 {
+            let mmk = coeffs_pair(k, 0);
+
+            let source = join_4_lanes(
+                simd_utils::loadl_epi64(s_row0, x),
+                simd_utils::loadl_epi64(s_row1, x),
+                simd_utils::loadl_epi64(s_row2, x),
+                simd_utils::loadl_epi64(s_row3, x),
+            );
+            let pix = _mm512_shuffle_epi8(source, sh1);
+            sss = _mm512_add_epi32(sss, _mm512_madd_epi16(pix, mmk));
+
+            x += 2;
+        }
+
+        if let Some(&k) = reminder2.first() {
+            // [16] xx k0 xx k0 xx k0 xx k0 xx k0 xx k0 xx k0 xx k0
+            let mmk = _mm512_set1_epi32(k as i32);
+
+            // [16] xx a0 xx b0 xx g0 xx r0 xx a0 xx b0 xx g0 xx r0
+            let pix = join_4_lanes(
+                simd_utils::mm_cvtepu8_epi32(s_row0, x),
+                simd_utils::mm_cvtepu8_epi32(s_row1, x),
+                simd_utils::mm_cvtepu8_epi32(s_row2, x),
+                simd_utils::mm_cvtepu8_epi32(s_row3, x),
+            );
+            sss = _mm512_add_epi32(sss, _mm512_madd_epi16(pix, mmk));
+        }
+
+        sss = _mm512_sra_epi32(sss, shift);
+        sss = _mm512_packs_epi32(sss, zero);
+        sss = _mm512_packus_epi16(sss, zero);
+        *d_row0.get_unchecked_mut(dst_x) =
+            U8x4(_mm_cvtsi128_si32(_mm512_castsi512_si128(sss)) as u32);
+        *d_row1.get_unchecked_mut(dst_x) =
+            U8x4(_mm_cvtsi128_si32(_mm512_extracti32x4_epi32::<1>(sss)) as u32);
+        *d_row2.get_unchecked_mut(dst_x) =
+            U8x4(_mm_cvtsi128_si32(_mm512_extracti32x4_epi32::<2>(sss)) as u32);
+        *d_row3.get_unchecked_mut(dst_x) =
+            U8x4(_mm_cvtsi128_si32(_mm512_extracti32x4_epi32::<3>(sss)) as u32);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == src_img.width()
+/// - coeffs_chunk.start + coeffs_chunk.values.len() <= src_img.height()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "avx512f,avx512bw,avx512vl")]
+unsafe fn vert_convolution_8u(
+    src_img: &TypedImageView<U8x4>,
+    dst_row: &mut [U8x4],
+    coeffs_chunk: CoefficientsI16Chunk,
+    precision: u8,
+) {
+    let mut xx: usize = 0;
+    let src_width = src_img.width().get() as usize;
+    let y_start = coeffs_chunk.start;
+    let coeffs = coeffs_chunk.values;
+    let max_y = y_start + coeffs.len() as u32;
+
+    let zero = _mm512_setzero_si512();
+    let initial = _mm512_set1_epi32(1 << (precision - 1));
+    let shift = _mm_cvtsi32_si128(precision as i32);
+
+    while xx < src_width {
+        // Last pixels of row are processed with help of masked
+        // loads and stores to avoid going beyond the row bounds.
+        let mask: __mmask16 = match src_width - xx {
+            n if n >= 16 => 0xffff,
+            n => (1 << n) - 1,
+        };
+
+        let mut sss0 = initial;
+        let mut sss1 = initial;
+        let mut sss2 = initial;
+        let mut sss3 = initial;
+
+        let mut y: u32 = 0;
+        for (s_row1, s_row2) in src_img.iter_2_rows(y_start, max_y) {
+            // Two coefficients packed into one i32
+            let mmk = coeffs_pair(coeffs, y as usize);
+
+            let source1 = load_masked(s_row1, xx, mask);
+            let source2 = load_masked(s_row2, xx, mask);
+
+            let source = _mm512_unpacklo_epi8(source1, source2);
+            let pix = _mm512_unpacklo_epi8(source, zero);
+            sss0 = _mm512_add_epi32(sss0, _mm512_madd_epi16(pix, mmk));
+            let pix = _mm512_unpackhi_epi8(source, zero);
+            sss1 = _mm512_add_epi32(sss1, _mm512_madd_epi16(pix, mmk));
+
+            let source = _mm512_unpackhi_epi8(source1, source2);
+            let pix = _mm512_unpacklo_epi8(source, zero);
+            sss2 = _mm512_add_epi32(sss2, _mm512_madd_epi16(pix, mmk));
+            let pix = _mm512_unpackhi_epi8(source, zero);
+            sss3 = _mm512_add_epi32(sss3, _mm512_madd_epi16(pix, mmk));
+
+            y += 2;
+        }
+
+        if let Some(&k) = coeffs.get(y as usize) {
+            let s_row = src_img.get_row(y_start + y).unwrap();
+            let mmk = _mm512_set1_epi32(k as u16 as i32);
+
+            let source1 = load_masked(s_row, xx, mask);
+
+            let source = _mm512_unpacklo_epi8(source1, zero);
+            let pix = _mm512_unpacklo_epi8(source, zero);
+            sss0 = _mm512_add_epi32(sss0, _mm512_madd_epi16(pix, mmk));
+            let pix = _mm512_unpackhi_epi8(source, zero);
+            sss1 = _mm512_add_epi32(sss1, _mm512_madd_epi16(pix, mmk));
+
+            let source = _mm512_unpackhi_epi8(source1, zero);
+            let pix = _mm512_unpacklo_epi8(source, zero);
+            sss2 = _mm512_add_epi32(sss2, _mm512_madd_epi16(pix, mmk));
+            let pix = _mm512_unpackhi_epi8(source, zero);
+            sss3 = _mm512_add_epi32(sss3, _mm512_madd_epi16(pix, mmk));
+        }
+
+        sss0 = _mm512_sra_epi32(sss0, shift);
+        sss1 = _mm512_sra_epi32(sss1, shift);
+        sss2 = _mm512_sra_epi32(sss2, shift);
+        sss3 = _mm512_sra_epi32(sss3, shift);
+
+        sss0 = _mm512_packs_epi32(sss0, sss1);
+        sss2 = _mm512_packs_epi32(sss2, sss3);
+        let res = _mm512_packus_epi16(sss0, sss2);
+
+        _mm512_mask_storeu_epi32(
+            dst_row.get_unchecked_mut(xx..).as_mut_ptr() as *mut i32,
+            mask,
+            res,
+        );
+
+        xx += 16;
+    }
+}
+
+/// Joins four 128-bit vectors into one 512-bit vector.
+#[inline(always)]
+unsafe fn join_4_lanes(a0: __m128i, a1: __m128i, a2: __m128i, a3: __m128i) -> __m512i {
+    let res = _mm512_castsi128_si512(a0);
+    let res = _mm512_inserti32x4::<1>(res, a1);
+    let res = _mm512_inserti32x4::<2>(res, a2);
+    _mm512_inserti32x4::<3>(res, a3)
+}
+
+/// Loads up to 16 pixels selected by `mask`, other pixels are zeroed.
+#[inline(always)]
+unsafe fn load_masked(buf: &[U8x4], index: usize, mask: __mmask16) -> __m512i {
+    _mm512_maskz_loadu_epi32(mask, buf.get_unchecked(index..).as_ptr() as *const i32)
+}
+
+/// Returns two adjacent coefficients packed into every `i32` of vector.
+#[inline(always)]
+unsafe fn coeffs_pair(coeffs: &[i16], index: usize) -> __m512i {
+    let k0 = *coeffs.get_unchecked(index) as u16 as i32;
+    let k1 = *coeffs.get_unchecked(index + 1) as i32;
+    _mm512_set1_epi32(k0 | (k1 << 16))
+}
@@ -5,7 +5,13 @@ use crate::CpuExtensions;
 
 #[cfg(target_arch = "x86_64")]
 mod avx2;
+#[cfg(target_arch = "x86_64")]
+mod avx512;
 mod native;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+#[cfg(target_arch = "wasm32")]
+mod simd128;
 #[cfg(target_arch = "x86_64")]
 mod sse4;
 
@@ -18,10 +24,20 @@ impl Convolution for U8x4 {
         cpu_extensions: CpuExtensions,
     ) {
         match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx512 => {
+                avx512::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Avx2 => avx2::horiz_convolution(src_image, dst_image, offset, coeffs),
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => sse4::horiz_convolution(src_image, dst_image, offset, coeffs),
+            #[cfg(target_arch = "aarch64")]
+            CpuExtensions::Neon => neon::horiz_convolution(src_image, dst_image, offset, coeffs),
+            #[cfg(target_arch = "wasm32")]
+            CpuExtensions::Simd128 => {
+                simd128::horiz_convolution(src_image, dst_image, offset, coeffs)
+            }
             _ => native::horiz_convolution(src_image, dst_image, offset, coeffs),
         }
     }
@@ -33,11 +49,21 @@ impl Convolution for U8x4 {
         cpu_extensions: CpuExtensions,
     ) {
         match cpu_extensions {
+            #[cfg(target_arch = "x86_64")]
+            CpuExtensions::Avx512 => avx512::vert_convolution(src_image, dst_image, coeffs),
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Avx2 => avx2::vert_convolution(src_image, dst_image, coeffs),
             #[cfg(target_arch = "x86_64")]
             CpuExtensions::Sse4_1 => sse4::vert_convolution(src_image, dst_image, coeffs),
+            #[cfg(target_arch = "aarch64")]
+            CpuExtensions::Neon => neon::vert_convolution(src_image, dst_image, coeffs),
+            #[cfg(target_arch = "wasm32")]
+            CpuExtensions::Simd128 => simd128::vert_convolution(src_image, dst_image, coeffs),
             _ => native::vert_convolution(src_image, dst_image, coeffs),
         }
     }
+
+    fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+        cpu_extensions
+    }
 }
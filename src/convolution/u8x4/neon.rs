@@ -0,0 +1,197 @@
+use core::arch::aarch64::*;
+
+use crate::convolution::optimisations::CoefficientsI16Chunk;
+use crate::convolution::{optimisations, Coefficients};
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::U8x4;
+
+#[inline]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        unsafe {
+            horiz_convolution_8u(src_row, dst_row, &coefficients_chunks, precision);
+        }
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - coefficients_chunks.len() == dst_row.len()
+/// - max(chunk.start + chunk.values.len() for chunk in coefficients_chunks) <= src_row.len()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "neon")]
+unsafe fn horiz_convolution_8u(
+    src_row: &[U8x4],
+    dst_row: &mut [U8x4],
+    coefficients_chunks: &[CoefficientsI16Chunk],
+    precision: u8,
+) {
+    let initial = vdupq_n_s32(1 << (precision - 1));
+    // Negative value of shift is used to shift right
+    let shift = vdupq_n_s32(-(precision as i32));
+
+    for (dst_x, &coeffs_chunk) in coefficients_chunks.iter().enumerate() {
+        let mut x: usize = coeffs_chunk.start as usize;
+        let mut sss = initial;
+
+        let coeffs_by_4 = coeffs_chunk.values.chunks_exact(4);
+        let reminder4 = coeffs_by_4.remainder();
+
+        for k in coeffs_by_4 {
+            // [8] a3 b3 g3 r3 a2 b2 g2 r2 a1 b1 g1 r1 a0 b0 g0 r0
+            let source = load_u8x16(src_row, x);
+            // [16] a1 b1 g1 r1 a0 b0 g0 r0
+            let pix = vreinterpretq_s16_u16(vmovl_u8(vget_low_u8(source)));
+            sss = vmlal_n_s16(sss, vget_low_s16(pix), k[0]);
+            sss = vmlal_n_s16(sss, vget_high_s16(pix), k[1]);
+            // [16] a3 b3 g3 r3 a2 b2 g2 r2
+            let pix = vreinterpretq_s16_u16(vmovl_u8(vget_high_u8(source)));
+            sss = vmlal_n_s16(sss, vget_low_s16(pix), k[2]);
+            sss = vmlal_n_s16(sss, vget_high_s16(pix), k[3]);
+            x += 4;
+        }
+
+        let coeffs_by_2 = reminder4.chunks_exact(2);
+        let reminder2 = coeffs_by_2.remainder();
+
+        for k in coeffs_by_2 {
+            // [8] a1 b1 g1 r1 a0 b0 g0 r0
+            let source = load_u8x8(src_row, x);
+            // [16] a1 b1 g1 r1 a0 b0 g0 r0
+            let pix = vreinterpretq_s16_u16(vmovl_u8(source));
+            sss = vmlal_n_s16(sss, vget_low_s16(pix), k[0]);
+            sss = vmlal_n_s16(sss, vget_high_s16(pix), k[1]);
+            x += 2;
+        }
+
+        if let Some(&k) = reminder2.first() {
+            let pix = load_pixel_i16x4(src_row, x);
+            sss = vmlal_n_s16(sss, pix, k);
+        }
+
+        sss = vshlq_s32(sss, shift);
+        *dst_row.get_unchecked_mut(dst_x) = store_pixel(sss);
+    }
+}
+
+#[inline]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, coeffs_chunk) in dst_rows.zip(coefficients_chunks) {
+        unsafe {
+            vert_convolution_8u(&src_image, dst_row, coeffs_chunk, precision);
+        }
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == src_img.width()
+/// - coeffs_chunk.start + coeffs_chunk.values.len() <= src_img.height()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "neon")]
+unsafe fn vert_convolution_8u(
+    src_img: &TypedImageView<U8x4>,
+    dst_row: &mut [U8x4],
+    coeffs_chunk: CoefficientsI16Chunk,
+    precision: u8,
+) {
+    let mut xx: usize = 0;
+    let src_width = src_img.width().get() as usize;
+    let y_start = coeffs_chunk.start;
+    let coeffs = coeffs_chunk.values;
+
+    let initial = vdupq_n_s32(1 << (precision - 1));
+    // Negative value of shift is used to shift right
+    let shift = vdupq_n_s32(-(precision as i32));
+
+    while xx < src_width.saturating_sub(3) {
+        let mut sss0 = initial;
+        let mut sss1 = initial;
+        let mut sss2 = initial;
+        let mut sss3 = initial;
+
+        let src_rows = src_img.iter_rows(y_start);
+        for (&k, s_row) in coeffs.iter().zip(src_rows) {
+            // [8] a3 b3 g3 r3 a2 b2 g2 r2 a1 b1 g1 r1 a0 b0 g0 r0
+            let source = load_u8x16(s_row, xx);
+            // [16] a1 b1 g1 r1 a0 b0 g0 r0
+            let pix = vreinterpretq_s16_u16(vmovl_u8(vget_low_u8(source)));
+            sss0 = vmlal_n_s16(sss0, vget_low_s16(pix), k);
+            sss1 = vmlal_n_s16(sss1, vget_high_s16(pix), k);
+            // [16] a3 b3 g3 r3 a2 b2 g2 r2
+            let pix = vreinterpretq_s16_u16(vmovl_u8(vget_high_u8(source)));
+            sss2 = vmlal_n_s16(sss2, vget_low_s16(pix), k);
+            sss3 = vmlal_n_s16(sss3, vget_high_s16(pix), k);
+        }
+
+        *dst_row.get_unchecked_mut(xx) = store_pixel(vshlq_s32(sss0, shift));
+        *dst_row.get_unchecked_mut(xx + 1) = store_pixel(vshlq_s32(sss1, shift));
+        *dst_row.get_unchecked_mut(xx + 2) = store_pixel(vshlq_s32(sss2, shift));
+        *dst_row.get_unchecked_mut(xx + 3) = store_pixel(vshlq_s32(sss3, shift));
+
+        xx += 4;
+    }
+
+    while xx < src_width {
+        let mut sss = initial;
+
+        let src_rows = src_img.iter_rows(y_start);
+        for (&k, s_row) in coeffs.iter().zip(src_rows) {
+            let pix = load_pixel_i16x4(s_row, xx);
+            sss = vmlal_n_s16(sss, pix, k);
+        }
+
+        *dst_row.get_unchecked_mut(xx) = store_pixel(vshlq_s32(sss, shift));
+
+        xx += 1;
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn load_u8x16(buf: &[U8x4], index: usize) -> uint8x16_t {
+    vld1q_u8(buf.get_unchecked(index..).as_ptr() as *const u8)
+}
+
+#[inline(always)]
+pub(crate) unsafe fn load_u8x8(buf: &[U8x4], index: usize) -> uint8x8_t {
+    vld1_u8(buf.get_unchecked(index..).as_ptr() as *const u8)
+}
+
+/// Loads one pixel and extends its components into `i16`.
+#[inline(always)]
+pub(crate) unsafe fn load_pixel_i16x4(buf: &[U8x4], index: usize) -> int16x4_t {
+    let pixel = vreinterpret_u8_u32(vdup_n_u32(buf.get_unchecked(index).0));
+    vget_low_s16(vreinterpretq_s16_u16(vmovl_u8(pixel)))
+}
+
+/// Saturates components of pixel into `u8` and packs them into `U8x4`.
+#[inline(always)]
+pub(crate) unsafe fn store_pixel(sss: int32x4_t) -> U8x4 {
+    let res_i16 = vqmovn_s32(sss);
+    let res_u8 = vqmovun_s16(vcombine_s16(res_i16, res_i16));
+    U8x4(vget_lane_u32::<0>(vreinterpret_u32_u8(res_u8)))
+}
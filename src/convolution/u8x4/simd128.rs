@@ -0,0 +1,190 @@
+use core::arch::wasm32::*;
+
+use crate::convolution::optimisations::CoefficientsI16Chunk;
+use crate::convolution::{optimisations, Coefficients};
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::U8x4;
+
+#[inline]
+pub(crate) fn horiz_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    offset: u32,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+
+    let src_rows = src_image.iter_rows(offset);
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, src_row) in dst_rows.zip(src_rows) {
+        unsafe {
+            horiz_convolution_8u(src_row, dst_row, &coefficients_chunks, precision);
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn vert_convolution(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+    coeffs: Coefficients,
+) {
+    let (values, window_size, bounds_per_pixel) =
+        (coeffs.values, coeffs.window_size, coeffs.bounds);
+
+    let normalizer_guard = optimisations::NormalizerGuard16::new(values);
+    let precision = normalizer_guard.precision();
+    let coefficients_chunks = normalizer_guard.normalized_chunks(window_size, &bounds_per_pixel);
+
+    let dst_rows = dst_image.iter_rows_mut();
+    for (dst_row, coeffs_chunk) in dst_rows.zip(coefficients_chunks) {
+        unsafe {
+            vert_convolution_8u(&src_image, dst_row, coeffs_chunk, precision);
+        }
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - coefficients_chunks.len() == dst_row.len()
+/// - max(chunk.start + chunk.values.len() for chunk in coefficients_chunks) <= src_row.len()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "simd128")]
+unsafe fn horiz_convolution_8u(
+    src_row: &[U8x4],
+    dst_row: &mut [U8x4],
+    coefficients_chunks: &[CoefficientsI16Chunk],
+    precision: u8,
+) {
+    let initial = i32x4_splat(1 << (precision - 1));
+
+    for (dst_x, &coeffs_chunk) in coefficients_chunks.iter().enumerate() {
+        let mut x: usize = coeffs_chunk.start as usize;
+        let mut sss = initial;
+
+        let coeffs_by_4 = coeffs_chunk.values.chunks_exact(4);
+        let reminder4 = coeffs_by_4.remainder();
+
+        for k in coeffs_by_4 {
+            // [8] a3 b3 g3 r3 a2 b2 g2 r2 a1 b1 g1 r1 a0 b0 g0 r0
+            let source = load_v128(src_row, x);
+            // [16] a1 b1 g1 r1 a0 b0 g0 r0
+            let pix = u16x8_extend_low_u8x16(source);
+            sss = i32x4_add(
+                sss,
+                i32x4_mul(u32x4_extend_low_u16x8(pix), i32x4_splat(k[0] as i32)),
+            );
+            sss = i32x4_add(
+                sss,
+                i32x4_mul(u32x4_extend_high_u16x8(pix), i32x4_splat(k[1] as i32)),
+            );
+            // [16] a3 b3 g3 r3 a2 b2 g2 r2
+            let pix = u16x8_extend_high_u8x16(source);
+            sss = i32x4_add(
+                sss,
+                i32x4_mul(u32x4_extend_low_u16x8(pix), i32x4_splat(k[2] as i32)),
+            );
+            sss = i32x4_add(
+                sss,
+                i32x4_mul(u32x4_extend_high_u16x8(pix), i32x4_splat(k[3] as i32)),
+            );
+            x += 4;
+        }
+
+        for &k in reminder4 {
+            let pix = load_pixel_i32x4(src_row, x);
+            sss = i32x4_add(sss, i32x4_mul(pix, i32x4_splat(k as i32)));
+            x += 1;
+        }
+
+        *dst_row.get_unchecked_mut(dst_x) = store_pixel(sss, precision);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - dst_row.len() == src_img.width()
+/// - coeffs_chunk.start + coeffs_chunk.values.len() <= src_img.height()
+/// - precision <= MAX_COEFS_PRECISION
+#[target_feature(enable = "simd128")]
+unsafe fn vert_convolution_8u(
+    src_img: &TypedImageView<U8x4>,
+    dst_row: &mut [U8x4],
+    coeffs_chunk: CoefficientsI16Chunk,
+    precision: u8,
+) {
+    let mut xx: usize = 0;
+    let src_width = src_img.width().get() as usize;
+    let y_start = coeffs_chunk.start;
+    let coeffs = coeffs_chunk.values;
+
+    let initial = i32x4_splat(1 << (precision - 1));
+
+    while xx < src_width.saturating_sub(3) {
+        let mut sss0 = initial;
+        let mut sss1 = initial;
+        let mut sss2 = initial;
+        let mut sss3 = initial;
+
+        let src_rows = src_img.iter_rows(y_start);
+        for (&k, s_row) in coeffs.iter().zip(src_rows) {
+            let mmk = i32x4_splat(k as i32);
+            // [8] a3 b3 g3 r3 a2 b2 g2 r2 a1 b1 g1 r1 a0 b0 g0 r0
+            let source = load_v128(s_row, xx);
+            // [16] a1 b1 g1 r1 a0 b0 g0 r0
+            let pix = u16x8_extend_low_u8x16(source);
+            sss0 = i32x4_add(sss0, i32x4_mul(u32x4_extend_low_u16x8(pix), mmk));
+            sss1 = i32x4_add(sss1, i32x4_mul(u32x4_extend_high_u16x8(pix), mmk));
+            // [16] a3 b3 g3 r3 a2 b2 g2 r2
+            let pix = u16x8_extend_high_u8x16(source);
+            sss2 = i32x4_add(sss2, i32x4_mul(u32x4_extend_low_u16x8(pix), mmk));
+            sss3 = i32x4_add(sss3, i32x4_mul(u32x4_extend_high_u16x8(pix), mmk));
+        }
+
+        *dst_row.get_unchecked_mut(xx) = store_pixel(sss0, precision);
+        *dst_row.get_unchecked_mut(xx + 1) = store_pixel(sss1, precision);
+        *dst_row.get_unchecked_mut(xx + 2) = store_pixel(sss2, precision);
+        *dst_row.get_unchecked_mut(xx + 3) = store_pixel(sss3, precision);
+
+        xx += 4;
+    }
+
+    while xx < src_width {
+        let mut sss = initial;
+
+        let src_rows = src_img.iter_rows(y_start);
+        for (&k, s_row) in coeffs.iter().zip(src_rows) {
+            let pix = load_pixel_i32x4(s_row, xx);
+            sss = i32x4_add(sss, i32x4_mul(pix, i32x4_splat(k as i32)));
+        }
+
+        *dst_row.get_unchecked_mut(xx) = store_pixel(sss, precision);
+
+        xx += 1;
+    }
+}
+
+#[inline(always)]
+unsafe fn load_v128(buf: &[U8x4], index: usize) -> v128 {
+    v128_load(buf.get_unchecked(index..).as_ptr() as *const v128)
+}
+
+/// Loads one pixel and extends its components into `i32`.
+#[inline(always)]
+unsafe fn load_pixel_i32x4(buf: &[U8x4], index: usize) -> v128 {
+    let pixel = u32x4_splat(buf.get_unchecked(index).0);
+    u32x4_extend_low_u16x8(u16x8_extend_low_u8x16(pixel))
+}
+
+/// Shifts components of pixel by `precision`, saturates them
+/// into `u8` and packs into `U8x4`.
+#[inline(always)]
+unsafe fn store_pixel(sss: v128, precision: u8) -> U8x4 {
+    let sss = i32x4_shr(sss, precision as u32);
+    let res_i16 = i16x8_narrow_i32x4(sss, sss);
+    let res_u8 = u8x16_narrow_i16x8(res_i16, res_i16);
+    U8x4(u32x4_extract_lane::<0>(res_u8))
+}
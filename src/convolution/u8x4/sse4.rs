@@ -1,5 +1,5 @@
-use std::arch::x86_64::*;
-use std::intrinsics::transmute;
+use core::arch::x86_64::*;
+use core::intrinsics::transmute;
 
 use crate::convolution::optimisations::{CoefficientsI16Chunk, NormalizerGuard16};
 use crate::convolution::{optimisations, Coefficients};
@@ -0,0 +1,223 @@
+//! Convolution with non-separable filter in one pass.
+use alloc::vec::Vec;
+
+#[cfg(feature = "half")]
+use half::f16;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::convolution::CustomFilter2D;
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+#[cfg(feature = "half")]
+use crate::pixels::F16;
+use crate::pixels::{F32x4, Pixel, U16x2, U16x3, U16x4, U8x2, U8x3, U8x4, F32, I16, I32, U8};
+
+/// Pixel which components may be converted into `f64` values and back.
+pub(crate) trait Components: Pixel {
+    /// `true` if components are floating-point numbers,
+    /// which aren't rounded.
+    const IS_FLOAT: bool = false;
+
+    /// Returns components of the pixel, unused items are zero.
+    fn to_f64(self) -> [f64; 4];
+
+    /// Creates the pixel from components, values out of range of
+    /// components are rounded and saturated.
+    fn from_f64(values: [f64; 4]) -> Self;
+}
+
+macro_rules! int_components {
+    ($pixel:ident, $type:ty, $count:literal) => {
+        impl Components for $pixel {
+            fn to_f64(self) -> [f64; 4] {
+                let mut values = [0.; 4];
+                for (v, &c) in values.iter_mut().zip(self.0.iter()) {
+                    *v = c as f64;
+                }
+                values
+            }
+
+            fn from_f64(values: [f64; 4]) -> Self {
+                // Conversion of float into integer saturates the value.
+                $pixel(core::array::from_fn::<$type, $count, _>(|i| {
+                    values[i].round() as $type
+                }))
+            }
+        }
+    };
+}
+
+int_components!(U8x2, u8, 2);
+int_components!(U8x3, u8, 3);
+int_components!(U16x2, u16, 2);
+int_components!(U16x3, u16, 3);
+int_components!(U16x4, u16, 4);
+
+impl Components for U8 {
+    fn to_f64(self) -> [f64; 4] {
+        [self.0 as f64, 0., 0., 0.]
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        U8(values[0].round() as u8)
+    }
+}
+
+impl Components for U8x4 {
+    fn to_f64(self) -> [f64; 4] {
+        self.0.to_ne_bytes().map(|c| c as f64)
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        U8x4(u32::from_ne_bytes(values.map(|v| v.round() as u8)))
+    }
+}
+
+impl Components for I16 {
+    fn to_f64(self) -> [f64; 4] {
+        [self.0 as f64, 0., 0., 0.]
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        I16(values[0].round() as i16)
+    }
+}
+
+impl Components for I32 {
+    fn to_f64(self) -> [f64; 4] {
+        [self.0 as f64, 0., 0., 0.]
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        I32(values[0].round() as i32)
+    }
+}
+
+impl Components for F32 {
+    const IS_FLOAT: bool = true;
+
+    fn to_f64(self) -> [f64; 4] {
+        [self.0 as f64, 0., 0., 0.]
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        F32(values[0] as f32)
+    }
+}
+
+#[cfg(feature = "half")]
+impl Components for F16 {
+    const IS_FLOAT: bool = true;
+
+    fn to_f64(self) -> [f64; 4] {
+        [self.0.to_f64(), 0., 0., 0.]
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        F16(f16::from_f64(values[0]))
+    }
+}
+
+impl Components for F32x4 {
+    const IS_FLOAT: bool = true;
+
+    fn to_f64(self) -> [f64; 4] {
+        self.0.map(|c| c as f64)
+    }
+
+    fn from_f64(values: [f64; 4]) -> Self {
+        F32x4(values.map(|v| v as f32))
+    }
+}
+
+/// Source pixels used to calculate one destination pixel along one axis:
+/// index of the first pixel and distances of pixels from the center
+/// of the destination pixel scaled into units of the filter.
+struct Window {
+    start: usize,
+    distances: Vec<f64>,
+}
+
+/// Returns windows of source pixels for every destination pixel
+/// along one axis like [precompute_coefficients](crate::convolution::precompute_coefficients).
+fn windows(
+    src_size: u32,
+    crop_start: f64,
+    crop_size: f64,
+    dst_size: u32,
+    support: f64,
+) -> Vec<Window> {
+    let scale = crop_size / dst_size as f64;
+    let filter_scale = scale.max(1.0);
+    let radius = support * filter_scale;
+    (0..dst_size)
+        .map(|i| {
+            let center = crop_start + (i as f64 + 0.5) * scale;
+            let start = (center - radius).floor().max(0.) as u32;
+            let end = (center + radius).ceil().min(src_size as f64) as u32;
+            Window {
+                start: start as usize,
+                distances: (start..end)
+                    .map(|x| (x as f64 + 0.5 - center) / filter_scale)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Resizes the crop box of source image by convolution with
+/// non-separable filter. Every destination pixel is calculated
+/// in one pass from the 2D window of source pixels, so the time
+/// of resizing is proportional to the area of the window.
+pub(crate) fn resample_convolution_2d<P: Components>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    filter: CustomFilter2D,
+    normalize: bool,
+) {
+    let crop_box = src_image.crop_box();
+    let func = filter.func();
+    let x_windows = windows(
+        src_image.width().get(),
+        crop_box.left,
+        crop_box.width,
+        dst_image.width().get(),
+        filter.support(),
+    );
+    let y_windows = windows(
+        src_image.height().get(),
+        crop_box.top,
+        crop_box.height,
+        dst_image.height().get(),
+        filter.support(),
+    );
+
+    let mut weights: Vec<f64> = Vec::new();
+    for (dst_row, y_window) in dst_image.iter_rows_mut().zip(&y_windows) {
+        let src_rows: Vec<&[P]> = src_image
+            .iter_rows(y_window.start as u32)
+            .take(y_window.distances.len())
+            .collect();
+        for (dst_pixel, x_window) in dst_row.iter_mut().zip(&x_windows) {
+            weights.clear();
+            for &dy in y_window.distances.iter() {
+                weights.extend(x_window.distances.iter().map(|&dx| func(dx, dy)));
+            }
+            let sum: f64 = weights.iter().sum();
+            let norm = if normalize && sum != 0. { 1. / sum } else { 1. };
+
+            let mut values = [0f64; 4];
+            let x_range = x_window.start..x_window.start + x_window.distances.len();
+            let row_weights = weights.chunks_exact(x_window.distances.len());
+            for (src_row, row_weights) in src_rows.iter().zip(row_weights) {
+                for (&src_pixel, &w) in src_row[x_range.clone()].iter().zip(row_weights) {
+                    for (v, c) in values.iter_mut().zip(src_pixel.to_f64()) {
+                        *v += c * w;
+                    }
+                }
+            }
+            *dst_pixel = P::from_f64(values.map(|v| v * norm));
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Conversions between [Image] and `DynamicImage` from
+//! the [image](https://crates.io/crates/image) crate.
+use core::any::TypeId;
+use core::convert::TryFrom;
+use core::num::NonZeroU32;
+use core::ops::{Deref, DerefMut};
+
+use image::{ColorType, DynamicImage, ImageBuffer, Pixel};
+
+use crate::{
+    FromDynamicImageError, Image, ImageBufferError, ImageView, ImageViewMut, PixelType,
+    ResizeImageBufferError, Resizer, UnsupportedPixelTypeError,
+};
+
+impl<'a> TryFrom<&DynamicImage> for Image<'a> {
+    type Error = FromDynamicImageError;
+
+    /// Copies pixels of `DynamicImage` into a new image.
+    ///
+    /// Supported color types: `Luma8`, `LumaA8`, `Rgb8`, `Rgba8`, `LumaA16`,
+    /// `Rgb16` and `Rgba16`.
+    fn try_from(src_image: &DynamicImage) -> Result<Self, Self::Error> {
+        let (pixel_type, width, height) = match src_image {
+            DynamicImage::ImageLuma8(img) => (PixelType::U8, img.width(), img.height()),
+            DynamicImage::ImageLumaA8(img) => (PixelType::U8x2, img.width(), img.height()),
+            DynamicImage::ImageRgb8(img) => (PixelType::U8x3, img.width(), img.height()),
+            DynamicImage::ImageRgba8(img) => (PixelType::U8x4, img.width(), img.height()),
+            DynamicImage::ImageLumaA16(img) => (PixelType::U16x2, img.width(), img.height()),
+            DynamicImage::ImageRgb16(img) => (PixelType::U16x3, img.width(), img.height()),
+            DynamicImage::ImageRgba16(img) => (PixelType::U16x4, img.width(), img.height()),
+            _ => return Err(FromDynamicImageError::UnsupportedColorType),
+        };
+        let width = NonZeroU32::new(width).ok_or(FromDynamicImageError::ZeroSize)?;
+        let height = NonZeroU32::new(height).ok_or(FromDynamicImageError::ZeroSize)?;
+        let mut dst_image = Image::new(width, height, pixel_type);
+        let dst_buffer = dst_image.buffer_mut();
+        match src_image {
+            DynamicImage::ImageLuma8(img) => dst_buffer.copy_from_slice(img.as_raw()),
+            DynamicImage::ImageLumaA8(img) => dst_buffer.copy_from_slice(img.as_raw()),
+            DynamicImage::ImageRgb8(img) => dst_buffer.copy_from_slice(img.as_raw()),
+            DynamicImage::ImageRgba8(img) => dst_buffer.copy_from_slice(img.as_raw()),
+            DynamicImage::ImageLumaA16(img) => copy_u16_into_bytes(img.as_raw(), dst_buffer),
+            DynamicImage::ImageRgb16(img) => copy_u16_into_bytes(img.as_raw(), dst_buffer),
+            DynamicImage::ImageRgba16(img) => copy_u16_into_bytes(img.as_raw(), dst_buffer),
+            _ => unreachable!(),
+        }
+        Ok(dst_image)
+    }
+}
+
+impl<'a> Image<'a> {
+    /// Copies pixels of the image into a new `DynamicImage`
+    /// from the [image](https://crates.io/crates/image) crate.
+    ///
+    /// Images with `I16`, `I32` and `F32` pixels are not supported.
+    pub fn to_dynamic_image(&self) -> Result<DynamicImage, UnsupportedPixelTypeError> {
+        let width = self.width().get();
+        let height = self.height().get();
+        let buffer = self.buffer();
+        // Size of buffer always matches to dimensions of image,
+        // so creating of ImageBuffer can't fail.
+        let dyn_image = match self.pixel_type() {
+            PixelType::U8 => DynamicImage::ImageLuma8(
+                ImageBuffer::from_raw(width, height, buffer.to_vec()).unwrap(),
+            ),
+            PixelType::U8x2 => DynamicImage::ImageLumaA8(
+                ImageBuffer::from_raw(width, height, buffer.to_vec()).unwrap(),
+            ),
+            PixelType::U8x3 => DynamicImage::ImageRgb8(
+                ImageBuffer::from_raw(width, height, buffer.to_vec()).unwrap(),
+            ),
+            PixelType::U8x4 => DynamicImage::ImageRgba8(
+                ImageBuffer::from_raw(width, height, buffer.to_vec()).unwrap(),
+            ),
+            PixelType::U16x2 => DynamicImage::ImageLumaA16(
+                ImageBuffer::from_raw(width, height, bytes_into_u16(buffer)).unwrap(),
+            ),
+            PixelType::U16x3 => DynamicImage::ImageRgb16(
+                ImageBuffer::from_raw(width, height, bytes_into_u16(buffer)).unwrap(),
+            ),
+            PixelType::U16x4 => DynamicImage::ImageRgba16(
+                ImageBuffer::from_raw(width, height, bytes_into_u16(buffer)).unwrap(),
+            ),
+            PixelType::I16 | PixelType::I32 | PixelType::F32 | PixelType::F32x4 => {
+                return Err(UnsupportedPixelTypeError)
+            }
+            #[cfg(feature = "half")]
+            PixelType::F16 => return Err(UnsupportedPixelTypeError),
+        };
+        Ok(dyn_image)
+    }
+}
+
+impl Resizer {
+    /// Resizes `ImageBuffer` from the [image](https://crates.io/crates/image)
+    /// crate into another `ImageBuffer` with the same type of pixels.
+    ///
+    /// Views of images are created directly over sample buffers of
+    /// `ImageBuffer`, so pixels aren't copied into intermediate [Image].
+    /// Samples of containers beyond `width * height` pixels are ignored.
+    ///
+    /// Supported color types: `L8`, `La8`, `Rgb8`, `Rgba8`, `Bgr8`, `Bgra8`,
+    /// `La16`, `Rgb16` and `Rgba16`.
+    pub fn resize_image_buffer<P, S, D>(
+        &mut self,
+        src_image: &ImageBuffer<P, S>,
+        dst_image: &mut ImageBuffer<P, D>,
+    ) -> Result<(), ResizeImageBufferError>
+    where
+        P: Pixel + 'static,
+        P::Subpixel: 'static,
+        S: Deref<Target = [P::Subpixel]>,
+        D: DerefMut<Target = [P::Subpixel]>,
+    {
+        let pixel_type =
+            pixel_type_of::<P>().ok_or(ResizeImageBufferError::UnsupportedColorType)?;
+        let (src_width, src_height) = non_zero_size(src_image.dimensions())?;
+        let (dst_width, dst_height) = non_zero_size(dst_image.dimensions())?;
+
+        // Containers of `ImageBuffer` hold at least `width * height` pixels.
+        let src_samples = &(**src_image)[..samples_count::<P>(src_width, src_height)];
+        // Safety: subpixels of supported color types are u8 or u16,
+        // which don't have padding bytes.
+        let src_buffer = unsafe { src_samples.align_to::<u8>().1 };
+        let src_view = ImageView::from_buffer(src_width, src_height, src_buffer, pixel_type)
+            .map_err(buffer_error)?;
+
+        let samples_count = samples_count::<P>(dst_width, dst_height);
+        let dst_samples = &mut (**dst_image)[..samples_count];
+        let dst_buffer = unsafe { dst_samples.align_to_mut::<u8>().1 };
+        let mut dst_view = ImageViewMut::from_buffer(dst_width, dst_height, dst_buffer, pixel_type)
+            .map_err(buffer_error)?;
+
+        // Types of pixels are the same, so resizing can't fail.
+        self.resize(&src_view, &mut dst_view).unwrap();
+        Ok(())
+    }
+}
+
+/// Returns type of pixels of [Image] with the same layout as
+/// pixels of `ImageBuffer`.
+fn pixel_type_of<P>() -> Option<PixelType>
+where
+    P: Pixel + 'static,
+    P::Subpixel: 'static,
+{
+    // Color type of pixels with other subpixels (e.g. f32)
+    // is the same as for 16-bit pixels, so type of subpixels
+    // must be checked too.
+    let subpixel = TypeId::of::<P::Subpixel>();
+    if subpixel == TypeId::of::<u8>() {
+        match P::COLOR_TYPE {
+            ColorType::L8 => Some(PixelType::U8),
+            ColorType::La8 => Some(PixelType::U8x2),
+            ColorType::Rgb8 | ColorType::Bgr8 => Some(PixelType::U8x3),
+            ColorType::Rgba8 | ColorType::Bgra8 => Some(PixelType::U8x4),
+            _ => None,
+        }
+    } else if subpixel == TypeId::of::<u16>() {
+        match P::COLOR_TYPE {
+            ColorType::La16 => Some(PixelType::U16x2),
+            ColorType::Rgb16 => Some(PixelType::U16x3),
+            ColorType::Rgba16 => Some(PixelType::U16x4),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Size of buffer always matches to dimensions of image,
+/// so only alignment of buffer may be invalid.
+fn buffer_error(_: ImageBufferError) -> ResizeImageBufferError {
+    ResizeImageBufferError::InvalidBufferAlignment
+}
+
+fn non_zero_size(
+    (width, height): (u32, u32),
+) -> Result<(NonZeroU32, NonZeroU32), ResizeImageBufferError> {
+    let width = NonZeroU32::new(width).ok_or(ResizeImageBufferError::ZeroSize)?;
+    let height = NonZeroU32::new(height).ok_or(ResizeImageBufferError::ZeroSize)?;
+    Ok((width, height))
+}
+
+fn samples_count<P: Pixel>(width: NonZeroU32, height: NonZeroU32) -> usize {
+    width.get() as usize * height.get() as usize * P::CHANNEL_COUNT as usize
+}
+
+fn copy_u16_into_bytes(src: &[u16], dst: &mut [u8]) {
+    for (&s, d) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        d.copy_from_slice(&s.to_ne_bytes());
+    }
+}
+
+fn bytes_into_u16(buffer: &[u8]) -> Vec<u16> {
+    buffer
+        .chunks_exact(2)
+        .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+        .collect()
+}
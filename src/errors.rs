@@ -18,6 +18,16 @@ pub enum ImageBufferError {
     InvalidBufferSize,
     #[error("Alignment of buffer don't match to alignment of u32")]
     InvalidBufferAlignment,
+    #[error("Row stride is less than size of a row of the image")]
+    InvalidRowStride,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum PixelAccessError {
+    #[error("Type of the pixel is not equal to pixel type of the image")]
+    DifferentTypesOfPixels,
+    #[error("Position of the pixel is out of the image boundaries")]
+    PositionIsOutOfImageBoundaries,
 }
 
 #[derive(Error, Debug, Clone, Copy)]
@@ -26,8 +36,142 @@ pub enum CropBoxError {
     PositionIsOutOfImageBoundaries,
     #[error("Size of the crop box is out of the image boundaries")]
     SizeIsOutOfImageBoundaries,
+    #[error("Width or height of the crop box is less than or equal to zero")]
+    WidthOrHeightLessOrEqualToZero,
 }
 
 #[derive(Error, Debug, Clone, Copy)]
 #[error("Type of pixels of the source image is not equal to pixel type of the destination image.")]
 pub struct DifferentTypesOfPixelsError;
+
+#[derive(Error, Debug, Clone, Copy)]
+#[error("Support of filter must be positive and finite number")]
+pub struct InvalidFilterSupportError;
+
+#[derive(Error, Debug, Clone, Copy)]
+#[error("Parameter of filter must be positive and finite number")]
+pub struct InvalidFilterParameterError;
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizePreparedError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error("Sizes of images, crop box of the source image or options of the resizer don't match to parameters of prepared resize")]
+    DifferentParams,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizeIntoAllocatedError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error("Size of the destination image is not equal to the required size")]
+    DifferentSize,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizeRegionError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error(transparent)]
+    InvalidRegion(#[from] CropBoxError),
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizePremultipliedError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error("Pixel type of image is not supported")]
+    UnsupportedPixelType,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizeWithProgressError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error("Resizing was cancelled by the progress callback")]
+    Cancelled,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[error("Only convolution algorithms are supported for resizing by one pass")]
+pub struct UnsupportedAlgorithmError;
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizeByStripsError {
+    #[error("Only convolution algorithms are supported for resizing by strips")]
+    UnsupportedAlgorithm,
+    #[error("Wrap boundary mode is not supported for resizing by strips")]
+    UnsupportedBoundaryMode,
+    #[error("Resizing was cancelled by the callback")]
+    Cancelled,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ConvolutionError {
+    #[error(
+        "Type of pixels of the source image is not equal to pixel type of the destination image."
+    )]
+    DifferentTypesOfPixels,
+    #[error("Count of coefficients chunks is not equal to the size of the destination image")]
+    InvalidCountOfChunks,
+    #[error("Coefficients use pixels out of the source image boundaries")]
+    CoefficientsAreOutOfImageBoundaries,
+    #[error("Rows used by the convolution are out of the source image boundaries")]
+    OffsetIsOutOfImageBoundaries,
+    #[error("Width of the destination image is not equal to width of the source image")]
+    DifferentWidths,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ResizeError {
+    #[error("Width or height of the destination image is zero")]
+    ZeroDimension,
+    #[error("Scale factor must be positive and finite")]
+    InvalidScaleFactor,
+    #[error("Size of fill pixel is not equal to size of pixels of the image")]
+    InvalidFillPixel,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum FromDynamicImageError {
+    #[error("Color type of the image is not supported")]
+    UnsupportedColorType,
+    #[error("Width or height of the image is zero")]
+    ZeroSize,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum ResizeImageBufferError {
+    #[error("Color type of the image is not supported")]
+    UnsupportedColorType,
+    #[error("Width or height of the image is zero")]
+    ZeroSize,
+    #[error("Alignment of samples buffer don't match to alignment of pixels")]
+    InvalidBufferAlignment,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+#[error("Type of pixels of the image is not supported")]
+pub struct UnsupportedPixelTypeError;
+
+#[derive(Error, Debug, Clone, Copy)]
+#[error("Conversion between these types of pixels is not supported")]
+pub struct UnsupportedConversionError;
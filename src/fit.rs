@@ -0,0 +1,65 @@
+use core::num::NonZeroU32;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Returns the size of destination image that fits into the box with
+/// size `max_width`x`max_height` and has the same aspect ratio as the
+/// source image.
+///
+/// Source image is never upscaled if `upscale` is `false`, in this case
+/// the size of source image is returned if it already fits into the box.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use fast_image_resize::fit_size;
+///
+/// let nz = |v| NonZeroU32::new(v).unwrap();
+/// let (width, height) = fit_size(nz(1920), nz(1080), nz(400), nz(400), false);
+/// assert_eq!((width.get(), height.get()), (400, 225));
+/// ```
+pub fn fit_size(
+    src_width: NonZeroU32,
+    src_height: NonZeroU32,
+    max_width: NonZeroU32,
+    max_height: NonZeroU32,
+    upscale: bool,
+) -> (NonZeroU32, NonZeroU32) {
+    if !upscale && src_width <= max_width && src_height <= max_height {
+        return (src_width, src_height);
+    }
+    let (src_w, src_h) = (src_width.get() as u64, src_height.get() as u64);
+    let (max_w, max_h) = (max_width.get() as u64, max_height.get() as u64);
+    if src_w * max_h >= src_h * max_w {
+        // Width of source image is the limiting side
+        let height = (2 * src_h * max_w + src_w) / (2 * src_w);
+        (max_width, to_non_zero(height))
+    } else {
+        let width = (2 * src_w * max_h + src_h) / (2 * src_h);
+        (to_non_zero(width), max_height)
+    }
+}
+
+/// Returns the size of source image with both dimensions multiplied
+/// by `factor`. Dimensions are rounded to the nearest integer and are
+/// never less than 1.
+pub(crate) fn scale_size(
+    src_width: NonZeroU32,
+    src_height: NonZeroU32,
+    factor: f64,
+) -> (NonZeroU32, NonZeroU32) {
+    let scale = |size: NonZeroU32| {
+        let scaled = (size.get() as f64 * factor).round().min(u32::MAX as f64);
+        to_non_zero(scaled as u64)
+    };
+    (scale(src_width), scale(src_height))
+}
+
+#[inline]
+fn to_non_zero(value: u64) -> NonZeroU32 {
+    // Rounded size is never greater than the size of the box,
+    // so it always fits into u32.
+    NonZeroU32::new(value.max(1) as u32).unwrap()
+}
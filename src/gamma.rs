@@ -0,0 +1,221 @@
+//! Conversion of sRGB-encoded images into linear light and back.
+//! It is used by [Resizer](crate::Resizer) to resize images with
+//! enabled gamma correction.
+use core::num::NonZeroU32;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::image_view::{CropBox, TypedImageView, TypedImageViewMut};
+use crate::pixels::{U16x3, U16x4, U8x3, U8x4};
+use crate::{Image, PixelType, UnsupportedConversionError};
+
+/// Converts sRGB-encoded pixels of the image into linear light inplace.
+///
+/// Supported pixel types: `U8x3`, `U8x4` (alpha-channel is not changed)
+/// and `F32` (values in range `[0, 1]`). Returns an error for other types.
+///
+/// Note: 8 bits are not enough to store dark tones in linear light, so
+/// sRGB -> linear -> sRGB round-trip of `U8x3` and `U8x4` images changes
+/// dark pixels noticeably. Use `F32` images to keep precision.
+pub fn srgb_to_linear(image: &mut Image) -> Result<(), UnsupportedConversionError> {
+    convert_components(image, decode_u8, decode_f32)
+}
+
+/// Converts pixels of the image from linear light into sRGB inplace.
+///
+/// Supported pixel types: `U8x3`, `U8x4` (alpha-channel is not changed)
+/// and `F32` (values in range `[0, 1]`). Returns an error for other types.
+pub fn linear_to_srgb(image: &mut Image) -> Result<(), UnsupportedConversionError> {
+    convert_components(image, encode_u8, encode_f32)
+}
+
+fn convert_components(
+    image: &mut Image,
+    convert_u8: fn(u8) -> u8,
+    convert_f32: fn(f32) -> f32,
+) -> Result<(), UnsupportedConversionError> {
+    let pixel_type = image.pixel_type();
+    let buffer = image.buffer_mut();
+    match pixel_type {
+        PixelType::U8x3 => buffer.iter_mut().for_each(|c| *c = convert_u8(*c)),
+        PixelType::U8x4 => {
+            for pixel in buffer.chunks_exact_mut(4) {
+                pixel[..3].iter_mut().for_each(|c| *c = convert_u8(*c));
+            }
+        }
+        PixelType::F32 => {
+            for c in buffer.chunks_exact_mut(4) {
+                let value = f32::from_ne_bytes([c[0], c[1], c[2], c[3]]);
+                c.copy_from_slice(&convert_f32(value).to_ne_bytes());
+            }
+        }
+        _ => return Err(UnsupportedConversionError),
+    }
+    Ok(())
+}
+
+/// Returns the size of the image that holds whole pixels covered by
+/// the given crop box, and the crop box relative to this image.
+pub(crate) fn linear_crop_box(crop_box: CropBox) -> (NonZeroU32, NonZeroU32, CropBox) {
+    let left = crop_box.left.floor();
+    let top = crop_box.top.floor();
+    let right = (crop_box.left + crop_box.width).ceil();
+    let bottom = (crop_box.top + crop_box.height).ceil();
+    let width = NonZeroU32::new((right - left) as u32).unwrap();
+    let height = NonZeroU32::new((bottom - top) as u32).unwrap();
+    let linear_crop_box = CropBox {
+        left: crop_box.left - left,
+        top: crop_box.top - top,
+        ..crop_box
+    };
+    (width, height, linear_crop_box)
+}
+
+/// Converts pixels from the crop box of sRGB-encoded source image into
+/// linear light.
+pub(crate) fn u8x3_into_linear(
+    src_image: TypedImageView<U8x3>,
+    mut dst_image: TypedImageViewMut<U16x3>,
+) {
+    let crop_box = src_image.crop_box();
+    let left = crop_box.left as usize;
+    let right = (crop_box.left + crop_box.width).ceil() as usize;
+    let src_rows = src_image.iter_rows(crop_box.top as u32);
+    for (src_row, dst_row) in src_rows.zip(dst_image.iter_rows_mut()) {
+        let src_row = &src_row[left..right];
+        for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row.iter_mut()) {
+            dst_pixel.0 = src_pixel.0.map(decode);
+        }
+    }
+}
+
+/// Converts pixels from the crop box of sRGB-encoded source image into
+/// linear light. Alpha channel is not gamma encoded, so it is only scaled.
+pub(crate) fn u8x4_into_linear(
+    src_image: TypedImageView<U8x4>,
+    mut dst_image: TypedImageViewMut<U16x4>,
+) {
+    let crop_box = src_image.crop_box();
+    let left = crop_box.left as usize;
+    let right = (crop_box.left + crop_box.width).ceil() as usize;
+    let src_rows = src_image.iter_rows(crop_box.top as u32);
+    for (src_row, dst_row) in src_rows.zip(dst_image.iter_rows_mut()) {
+        let src_row = &src_row[left..right];
+        for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row.iter_mut()) {
+            let [r, g, b, a] = src_pixel.0.to_le_bytes();
+            dst_pixel.0 = [decode(r), decode(g), decode(b), a as u16 * 257];
+        }
+    }
+}
+
+pub(crate) fn linear_into_u8x3(
+    src_image: TypedImageView<U16x3>,
+    mut dst_image: TypedImageViewMut<U8x3>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    for (src_row, dst_row) in src_rows.zip(dst_image.iter_rows_mut()) {
+        for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row.iter_mut()) {
+            dst_pixel.0 = src_pixel.0.map(encode);
+        }
+    }
+}
+
+pub(crate) fn linear_into_u8x4(
+    src_image: TypedImageView<U16x4>,
+    mut dst_image: TypedImageViewMut<U8x4>,
+) {
+    let src_rows = src_image.iter_rows(0);
+    for (src_row, dst_row) in src_rows.zip(dst_image.iter_rows_mut()) {
+        for (src_pixel, dst_pixel) in src_row.iter().zip(dst_row.iter_mut()) {
+            let [r, g, b, a] = src_pixel.0;
+            // Rounded division by 257
+            let a = ((a as u32 + 128) / 257) as u8;
+            dst_pixel.0 = u32::from_le_bytes([encode(r), encode(g), encode(b), a]);
+        }
+    }
+}
+
+fn decode_u8(value: u8) -> u8 {
+    // Rounded division by 257
+    ((decode(value) as u32 + 128) / 257) as u8
+}
+
+fn encode_u8(value: u8) -> u8 {
+    encode(value as u16 * 257)
+}
+
+fn decode_f32(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn encode_f32(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1. / 2.4) - 0.055
+    }
+}
+
+#[inline(always)]
+fn decode(value: u8) -> u16 {
+    SRGB_TO_LINEAR[value as usize]
+}
+
+#[inline(always)]
+fn encode(value: u16) -> u8 {
+    // Index of first threshold greater than value is a rounded
+    // sRGB-encoded value.
+    LINEAR_TO_SRGB_THRESHOLDS.partition_point(|&t| t <= value) as u8
+}
+
+/// Linear light values (scaled to `u16`) of sRGB-encoded `u8` values.
+#[rustfmt::skip]
+const SRGB_TO_LINEAR: [u16; 256] = [
+    0, 20, 40, 60, 80, 99, 119, 139, 159, 179, 199, 219, 241, 264, 288, 313, 340, 367, 396, 427,
+    458, 491, 526, 562, 599, 637, 677, 718, 761, 805, 851, 898, 947, 997, 1048, 1101, 1156, 1212,
+    1270, 1330, 1391, 1453, 1517, 1583, 1651, 1720, 1790, 1863, 1937, 2013, 2090, 2170, 2250, 2333,
+    2418, 2504, 2592, 2681, 2773, 2866, 2961, 3058, 3157, 3258, 3360, 3464, 3570, 3678, 3788, 3900,
+    4014, 4129, 4247, 4366, 4488, 4611, 4736, 4864, 4993, 5124, 5257, 5392, 5530, 5669, 5810, 5953,
+    6099, 6246, 6395, 6547, 6700, 6856, 7014, 7174, 7335, 7500, 7666, 7834, 8004, 8177, 8352, 8528,
+    8708, 8889, 9072, 9258, 9445, 9635, 9828, 10022, 10219, 10417, 10619, 10822, 11028, 11235,
+    11446, 11658, 11873, 12090, 12309, 12530, 12754, 12980, 13209, 13440, 13673, 13909, 14146,
+    14387, 14629, 14874, 15122, 15371, 15623, 15878, 16135, 16394, 16656, 16920, 17187, 17456,
+    17727, 18001, 18277, 18556, 18837, 19121, 19407, 19696, 19987, 20281, 20577, 20876, 21177,
+    21481, 21787, 22096, 22407, 22721, 23038, 23357, 23678, 24002, 24329, 24658, 24990, 25325,
+    25662, 26001, 26344, 26688, 27036, 27386, 27739, 28094, 28452, 28813, 29176, 29542, 29911,
+    30282, 30656, 31033, 31412, 31794, 32179, 32567, 32957, 33350, 33745, 34143, 34544, 34948,
+    35355, 35764, 36176, 36591, 37008, 37429, 37852, 38278, 38706, 39138, 39572, 40009, 40449,
+    40891, 41337, 41785, 42236, 42690, 43147, 43606, 44069, 44534, 45002, 45473, 45947, 46423,
+    46903, 47385, 47871, 48359, 48850, 49344, 49841, 50341, 50844, 51349, 51858, 52369, 52884,
+    53401, 53921, 54445, 54971, 55500, 56032, 56567, 57105, 57646, 58190, 58737, 59287, 59840,
+    60396, 60955, 61517, 62082, 62650, 63221, 63795, 64372, 64952, 65535,
+];
+
+/// Linear light values (scaled to `u16`) of middles between neighbouring
+/// sRGB-encoded `u8` values.
+#[rustfmt::skip]
+const LINEAR_TO_SRGB_THRESHOLDS: [u16; 255] = [
+    10, 30, 50, 70, 90, 110, 130, 150, 170, 189, 209, 230, 253, 276, 301, 327, 354, 382, 412, 443,
+    475, 509, 544, 580, 618, 657, 698, 740, 783, 828, 875, 923, 972, 1023, 1075, 1129, 1185, 1242,
+    1300, 1360, 1422, 1486, 1551, 1617, 1685, 1755, 1827, 1900, 1975, 2052, 2130, 2210, 2292, 2376,
+    2461, 2548, 2637, 2727, 2820, 2914, 3010, 3108, 3208, 3309, 3412, 3518, 3625, 3734, 3844, 3957,
+    4072, 4188, 4307, 4427, 4550, 4674, 4800, 4928, 5059, 5191, 5325, 5461, 5599, 5740, 5882, 6026,
+    6173, 6321, 6471, 6624, 6778, 6935, 7094, 7255, 7418, 7583, 7750, 7919, 8091, 8265, 8440, 8618,
+    8798, 8981, 9165, 9352, 9541, 9732, 9925, 10121, 10318, 10518, 10720, 10925, 11132, 11341,
+    11552, 11765, 11981, 12199, 12420, 12643, 12868, 13095, 13325, 13557, 13791, 14028, 14267,
+    14508, 14752, 14998, 15247, 15498, 15751, 16007, 16265, 16525, 16788, 17054, 17321, 17592,
+    17864, 18139, 18417, 18697, 18980, 19264, 19552, 19842, 20134, 20429, 20727, 21027, 21329,
+    21634, 21942, 22252, 22564, 22880, 23197, 23518, 23840, 24166, 24494, 24824, 25158, 25493,
+    25832, 26173, 26516, 26862, 27211, 27563, 27917, 28273, 28633, 28995, 29359, 29727, 30097,
+    30469, 30845, 31223, 31603, 31987, 32373, 32762, 33153, 33547, 33944, 34344, 34747, 35152,
+    35560, 35970, 36384, 36800, 37219, 37640, 38065, 38492, 38922, 39355, 39790, 40229, 40670,
+    41114, 41561, 42011, 42463, 42918, 43377, 43838, 44301, 44768, 45238, 45710, 46185, 46663,
+    47144, 47628, 48115, 48605, 49097, 49593, 50091, 50592, 51096, 51604, 52114, 52627, 53142,
+    53661, 54183, 54708, 55235, 55766, 56300, 56836, 57376, 57918, 58464, 59012, 59564, 60118,
+    60675, 61236, 61799, 62366, 62935, 63508, 64083, 64662, 65244,
+];
@@ -1,8 +1,20 @@
-use std::num::NonZeroU32;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use crate::image_view::{ImageRows, ImageRowsMut, TypedImageView, TypedImageViewMut};
-use crate::pixels::{Pixel, PixelType, U16x3, U8x3, U8x4, F32, I32, U8};
-use crate::{ImageBufferError, ImageView, ImageViewMut, InvalidBufferSizeError};
+#[cfg(feature = "half")]
+use crate::pixels::F16;
+use crate::pixels::{
+    F32x4, Pixel, PixelType, U16x2, U16x3, U16x4, U8x2, U8x3, U8x4, F32, I16, I32, U8,
+};
+use crate::{
+    DifferentTypesOfPixelsError, ImageBufferError, ImageView, ImageViewMut, InvalidBufferSizeError,
+    MulDiv, MulDivImageError, PixelAccessError, UnsupportedConversionError,
+};
 
 #[derive(Debug)]
 enum PixelsContainer<'a> {
@@ -23,22 +35,40 @@ pub struct Image<'a> {
 
 impl<'a> Image<'a> {
     /// Create empty image with given dimensions and pixel type.
+    ///
+    /// # Panics
+    /// Panics if size of image buffer in bytes overflows `usize`,
+    /// use [Image::try_new] to get an error instead.
     pub fn new(width: NonZeroU32, height: NonZeroU32, pixel_type: PixelType) -> Self {
-        let pixels_count = (width.get() * height.get()) as usize;
+        Self::try_new(width, height, pixel_type).expect("Size of image buffer overflows usize")
+    }
+
+    /// Create empty image with given dimensions and pixel type.
+    ///
+    /// Returns [ImageBufferError::InvalidBufferSize] if size of image buffer
+    /// in bytes overflows `usize`.
+    pub fn try_new(
+        width: NonZeroU32,
+        height: NonZeroU32,
+        pixel_type: PixelType,
+    ) -> Result<Self, ImageBufferError> {
+        let buffer_size = pixel_type
+            .buffer_len(width, height)
+            .ok_or(ImageBufferError::InvalidBufferSize)?;
         let pixels = match pixel_type {
-            PixelType::U8x3 => PixelsContainer::VecU8(vec![0; pixels_count * U8x3::size()]),
-            PixelType::U16x3 => PixelsContainer::VecU8(vec![0; pixels_count * U16x3::size()]),
-            PixelType::U8x4 | PixelType::I32 | PixelType::F32 => {
-                PixelsContainer::VecU32(vec![0; pixels_count])
-            }
-            PixelType::U8 => PixelsContainer::VecU8(vec![0; pixels_count]),
+            PixelType::U8x4
+            | PixelType::U16x2
+            | PixelType::I32
+            | PixelType::F32
+            | PixelType::F32x4 => PixelsContainer::VecU32(vec![0; buffer_size / 4]),
+            _ => PixelsContainer::VecU8(vec![0; buffer_size]),
         };
-        Self {
+        Ok(Self {
             width,
             height,
             pixels,
             pixel_type,
-        }
+        })
     }
 
     pub fn from_vec_u32(
@@ -47,8 +77,7 @@ impl<'a> Image<'a> {
         buffer: Vec<u32>,
         pixel_type: PixelType,
     ) -> Result<Self, InvalidBufferSizeError> {
-        let size = (width.get() * height.get()) as usize;
-        if buffer.len() != size {
+        if pixel_type.u32_buffer_len(width, height) != Some(buffer.len()) {
             return Err(InvalidBufferSizeError);
         }
         Ok(Self {
@@ -65,8 +94,7 @@ impl<'a> Image<'a> {
         buffer: Vec<u8>,
         pixel_type: PixelType,
     ) -> Result<Self, ImageBufferError> {
-        let size = (width.get() * height.get()) as usize * pixel_type.size();
-        if buffer.len() != size {
+        if pixel_type.buffer_len(width, height) != Some(buffer.len()) {
             return Err(ImageBufferError::InvalidBufferSize);
         }
         if !pixel_type.is_aligned(&buffer) {
@@ -86,8 +114,7 @@ impl<'a> Image<'a> {
         buffer: &'a mut [u32],
         pixel_type: PixelType,
     ) -> Result<Self, InvalidBufferSizeError> {
-        let size = (width.get() * height.get()) as usize;
-        if buffer.len() != size {
+        if pixel_type.u32_buffer_len(width, height) != Some(buffer.len()) {
             return Err(InvalidBufferSizeError);
         }
         Ok(Self {
@@ -104,8 +131,7 @@ impl<'a> Image<'a> {
         buffer: &'a mut [u8],
         pixel_type: PixelType,
     ) -> Result<Self, ImageBufferError> {
-        let size = (width.get() * height.get()) as usize * pixel_type.size();
-        if buffer.len() != size {
+        if pixel_type.buffer_len(width, height) != Some(buffer.len()) {
             return Err(ImageBufferError::InvalidBufferSize);
         }
         if !pixel_type.is_aligned(buffer) {
@@ -119,6 +145,30 @@ impl<'a> Image<'a> {
         })
     }
 
+    /// Creates an image that borrows pixels from a buffer given by a raw
+    /// pointer and its length in bytes, e.g. a buffer received through FFI.
+    /// Pixels are not copied.
+    ///
+    /// Length and alignment of the buffer are checked like in
+    /// [Image::from_slice_u8].
+    ///
+    /// # Safety
+    /// The same requirements as for [core::slice::from_raw_parts_mut]:
+    /// - `ptr` must be non-null and valid for reads and writes of `len` bytes;
+    /// - the memory must not be accessed through any other pointer
+    ///   while the returned image (lifetime `'a`) is alive;
+    /// - `len` must not be greater than `isize::MAX`.
+    pub unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len: usize,
+        width: NonZeroU32,
+        height: NonZeroU32,
+        pixel_type: PixelType,
+    ) -> Result<Self, ImageBufferError> {
+        let buffer = core::slice::from_raw_parts_mut(ptr, len);
+        Self::from_slice_u8(width, height, buffer, pixel_type)
+    }
+
     #[inline(always)]
     pub fn pixel_type(&self) -> PixelType {
         self.pixel_type
@@ -146,7 +196,7 @@ impl<'a> Image<'a> {
     }
 
     #[inline(always)]
-    fn buffer_mut(&mut self) -> &mut [u8] {
+    pub(crate) fn buffer_mut(&mut self) -> &mut [u8] {
         match &mut self.pixels {
             PixelsContainer::MutU32(p) => unsafe { p.align_to_mut::<u8>().1 },
             PixelsContainer::MutU8(p) => p,
@@ -171,6 +221,14 @@ impl<'a> Image<'a> {
                 let pixels = unsafe { buffer.align_to::<U16x3>().1 };
                 ImageRows::U16x3(pixels.chunks_exact(self.width.get() as usize).collect())
             }
+            PixelType::U16x4 => {
+                let pixels = unsafe { buffer.align_to::<U16x4>().1 };
+                ImageRows::U16x4(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
+            PixelType::I16 => {
+                let pixels = unsafe { buffer.align_to::<I16>().1 };
+                ImageRows::I16(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
             PixelType::I32 => {
                 let pixels = unsafe { buffer.align_to::<I32>().1 };
                 ImageRows::I32(pixels.chunks_exact(self.width.get() as usize).collect())
@@ -179,6 +237,23 @@ impl<'a> Image<'a> {
                 let pixels = unsafe { buffer.align_to::<F32>().1 };
                 ImageRows::F32(pixels.chunks_exact(self.width.get() as usize).collect())
             }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                let pixels = unsafe { buffer.align_to::<F16>().1 };
+                ImageRows::F16(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
+            PixelType::F32x4 => {
+                let pixels = unsafe { buffer.align_to::<F32x4>().1 };
+                ImageRows::F32x4(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
+            PixelType::U8x2 => {
+                let pixels = unsafe { buffer.align_to::<U8x2>().1 };
+                ImageRows::U8x2(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
+            PixelType::U16x2 => {
+                let pixels = unsafe { buffer.align_to::<U16x2>().1 };
+                ImageRows::U16x2(pixels.chunks_exact(self.width.get() as usize).collect())
+            }
             PixelType::U8 => {
                 let pixels = unsafe { buffer.align_to::<U8>().1 };
                 ImageRows::U8(pixels.chunks_exact(self.width.get() as usize).collect())
@@ -206,6 +281,14 @@ impl<'a> Image<'a> {
                 let pixels = unsafe { buffer.align_to_mut::<U16x3>().1 };
                 ImageRowsMut::U16x3(pixels.chunks_exact_mut(width.get() as usize).collect())
             }
+            PixelType::U16x4 => {
+                let pixels = unsafe { buffer.align_to_mut::<U16x4>().1 };
+                ImageRowsMut::U16x4(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::I16 => {
+                let pixels = unsafe { buffer.align_to_mut::<I16>().1 };
+                ImageRowsMut::I16(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
             PixelType::I32 => {
                 let pixels = unsafe { buffer.align_to_mut::<I32>().1 };
                 ImageRowsMut::I32(pixels.chunks_exact_mut(width.get() as usize).collect())
@@ -214,6 +297,23 @@ impl<'a> Image<'a> {
                 let pixels = unsafe { buffer.align_to_mut::<F32>().1 };
                 ImageRowsMut::F32(pixels.chunks_exact_mut(width.get() as usize).collect())
             }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                let pixels = unsafe { buffer.align_to_mut::<F16>().1 };
+                ImageRowsMut::F16(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::F32x4 => {
+                let pixels = unsafe { buffer.align_to_mut::<F32x4>().1 };
+                ImageRowsMut::F32x4(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::U8x2 => {
+                let pixels = unsafe { buffer.align_to_mut::<U8x2>().1 };
+                ImageRowsMut::U8x2(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::U16x2 => {
+                let pixels = unsafe { buffer.align_to_mut::<U16x2>().1 };
+                ImageRowsMut::U16x2(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
             PixelType::U8 => {
                 let pixels = unsafe { buffer.align_to_mut::<U8>().1 };
                 ImageRowsMut::U8(pixels.chunks_exact_mut(width.get() as usize).collect())
@@ -221,6 +321,264 @@ impl<'a> Image<'a> {
         };
         ImageViewMut::new(width, height, rows).unwrap()
     }
+
+    /// Multiplies RGB-channels of the image by alpha-channel inplace
+    /// with help of [MulDiv] with the best CPU-extensions provided by your CPU.
+    ///
+    /// Returns an error if pixels of the image don't have alpha-channel
+    /// (supported pixel types: `U8x2`, `U8x4`, `U16x2`, `U16x4` and `F32x4`).
+    pub fn multiply_alpha_in_place(&mut self) -> Result<(), MulDivImageError> {
+        MulDiv::default().multiply_alpha_inplace(&mut self.view_mut())
+    }
+
+    /// Divides RGB-channels of the image by alpha-channel inplace
+    /// with help of [MulDiv] with the best CPU-extensions provided by your CPU.
+    ///
+    /// Returns an error if pixels of the image don't have alpha-channel
+    /// (supported pixel types: `U8x2`, `U8x4`, `U16x2`, `U16x4` and `F32x4`).
+    pub fn divide_alpha_in_place(&mut self) -> Result<(), MulDivImageError> {
+        MulDiv::default().divide_alpha_inplace(&mut self.view_mut())
+    }
+
+    /// Creates a copy of the image with pixels converted into given type.
+    ///
+    /// Supported conversions:
+    /// - `U8x4` into `U8x3` - alpha-channel is dropped;
+    /// - `U8x3` into `U8x4` - alpha-channel is filled by 255 (opaque);
+    /// - `U8x3` into `U8` - luma is calculated with coefficients of Rec.709;
+    /// - `U16x3` into `U8x3` - channels are scaled into range `[0, 255]`;
+    /// - `U8x3` into `U16x3` - channels are multiplied by 257, so `255`
+    ///   becomes `65535`;
+    /// - any type into the same type - pixels are copied as is.
+    ///
+    /// Returns an error for other pairs of pixel types.
+    pub fn convert(
+        &self,
+        pixel_type: PixelType,
+    ) -> Result<Image<'static>, UnsupportedConversionError> {
+        let convert =
+            pixels_converter(self.pixel_type, pixel_type).ok_or(UnsupportedConversionError)?;
+        let mut dst_image = Image::new(self.width, self.height, pixel_type);
+        convert(self.buffer(), dst_image.buffer_mut());
+        Ok(dst_image)
+    }
+
+    /// Creates a copy of the image rotated by 90 degrees clockwise.
+    pub fn rotate_90(&self) -> Image<'static> {
+        let height = self.height.get();
+        self.transform(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Creates a copy of the image rotated by 180 degrees.
+    pub fn rotate_180(&self) -> Image<'static> {
+        let (width, height) = (self.width.get(), self.height.get());
+        self.transform(self.width, self.height, |x, y| {
+            (width - 1 - x, height - 1 - y)
+        })
+    }
+
+    /// Creates a copy of the image rotated by 270 degrees clockwise
+    /// (90 degrees counterclockwise).
+    pub fn rotate_270(&self) -> Image<'static> {
+        let width = self.width.get();
+        self.transform(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    /// Creates a copy of the image mirrored from left to right.
+    pub fn flip_horizontally(&self) -> Image<'static> {
+        let width = self.width.get();
+        self.transform(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Creates a copy of the image mirrored from top to bottom.
+    pub fn flip_vertically(&self) -> Image<'static> {
+        let height = self.height.get();
+        self.transform(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    /// Creates a new image with given dimensions, every pixel `(x, y)` of
+    /// it is copied from the pixel of this image with coordinates returned
+    /// by `src_position(x, y)`.
+    fn transform(
+        &self,
+        width: NonZeroU32,
+        height: NonZeroU32,
+        src_position: impl Fn(u32, u32) -> (u32, u32),
+    ) -> Image<'static> {
+        let mut dst_image = Image::new(width, height, self.pixel_type);
+        let pixel_size = self.pixel_type.size();
+        let src_width = self.width.get() as usize;
+        let src_buffer = self.buffer();
+        let dst_rows = dst_image
+            .buffer_mut()
+            .chunks_exact_mut(width.get() as usize * pixel_size);
+        for (y, dst_row) in dst_rows.enumerate() {
+            for (x, dst_pixel) in dst_row.chunks_exact_mut(pixel_size).enumerate() {
+                let (src_x, src_y) = src_position(x as u32, y as u32);
+                let offset = (src_y as usize * src_width + src_x as usize) * pixel_size;
+                dst_pixel.copy_from_slice(&src_buffer[offset..offset + pixel_size]);
+            }
+        }
+        dst_image
+    }
+
+    /// Returns index of the pixel with given coordinates in the buffer
+    /// of pixels with type `P`.
+    fn pixel_index<P: Pixel>(&self, x: u32, y: u32) -> Result<usize, PixelAccessError> {
+        if P::pixel_type() != self.pixel_type {
+            return Err(PixelAccessError::DifferentTypesOfPixels);
+        }
+        if x >= self.width.get() || y >= self.height.get() {
+            return Err(PixelAccessError::PositionIsOutOfImageBoundaries);
+        }
+        Ok(y as usize * self.width.get() as usize + x as usize)
+    }
+
+    fn get_pixel<P: Pixel>(&self, x: u32, y: u32) -> Option<P> {
+        let index = self.pixel_index::<P>(x, y).ok()?;
+        let pixels = unsafe { self.buffer().align_to::<P>().1 };
+        pixels.get(index).copied()
+    }
+
+    fn set_pixel<P: Pixel>(&mut self, x: u32, y: u32, pixel: P) -> Result<(), PixelAccessError> {
+        let index = self.pixel_index::<P>(x, y)?;
+        let pixels = unsafe { self.buffer_mut().align_to_mut::<P>().1 };
+        pixels[index] = pixel;
+        Ok(())
+    }
+
+    fn fill_pixels<P: Pixel>(&mut self, color: P) -> Result<(), DifferentTypesOfPixelsError> {
+        if P::pixel_type() != self.pixel_type {
+            return Err(DifferentTypesOfPixelsError);
+        }
+        // Pixels are stored as values of `P`, so the compiler
+        // writes them by wide stores instead of byte by byte.
+        let pixels = unsafe { self.buffer_mut().align_to_mut::<P>().1 };
+        pixels.fill(color);
+        Ok(())
+    }
+}
+
+macro_rules! pixel_accessors {
+    ($get:ident, $set:ident, $fill:ident, $pixel:ident) => {
+        /// Returns pixel with given coordinates or `None` if the image has
+        /// pixels of another type or the position is out of the image.
+        pub fn $get(&self, x: u32, y: u32) -> Option<$pixel> {
+            self.get_pixel(x, y)
+        }
+
+        /// Replaces pixel with given coordinates. Returns an error if the
+        /// image has pixels of another type or the position is out of
+        /// the image.
+        pub fn $set(&mut self, x: u32, y: u32, pixel: $pixel) -> Result<(), PixelAccessError> {
+            self.set_pixel(x, y, pixel)
+        }
+
+        /// Fills all pixels of the image by given color. Returns an error
+        /// if the image has pixels of another type.
+        pub fn $fill(&mut self, color: $pixel) -> Result<(), DifferentTypesOfPixelsError> {
+            self.fill_pixels(color)
+        }
+    };
+}
+
+impl<'a> Image<'a> {
+    pixel_accessors!(get_pixel_u8, set_pixel_u8, fill_u8, U8);
+    pixel_accessors!(get_pixel_u8x2, set_pixel_u8x2, fill_u8x2, U8x2);
+    pixel_accessors!(get_pixel_u8x3, set_pixel_u8x3, fill_u8x3, U8x3);
+    pixel_accessors!(get_pixel_u8x4, set_pixel_u8x4, fill_u8x4, U8x4);
+    pixel_accessors!(get_pixel_u16x2, set_pixel_u16x2, fill_u16x2, U16x2);
+    pixel_accessors!(get_pixel_u16x3, set_pixel_u16x3, fill_u16x3, U16x3);
+    pixel_accessors!(get_pixel_u16x4, set_pixel_u16x4, fill_u16x4, U16x4);
+    pixel_accessors!(get_pixel_i16, set_pixel_i16, fill_i16, I16);
+    pixel_accessors!(get_pixel_i32, set_pixel_i32, fill_i32, I32);
+    pixel_accessors!(get_pixel_f32, set_pixel_f32, fill_f32, F32);
+    pixel_accessors!(get_pixel_f32x4, set_pixel_f32x4, fill_f32x4, F32x4);
+    #[cfg(feature = "half")]
+    pixel_accessors!(get_pixel_f16, set_pixel_f16, fill_f16, F16);
+}
+
+/// Converts bytes of pixels of one type into bytes of pixels of another type.
+pub(crate) type PixelsConverter = fn(&[u8], &mut [u8]);
+
+/// Returns function that converts pixels of `src` type into pixels
+/// of `dst` type, or `None` if the conversion is not supported.
+/// See [Image::convert] for the list of supported conversions.
+pub(crate) fn pixels_converter(src: PixelType, dst: PixelType) -> Option<PixelsConverter> {
+    let converter: PixelsConverter = match (src, dst) {
+        (src, dst) if src == dst => |src, dst| dst.copy_from_slice(src),
+        (PixelType::U8x4, PixelType::U8x3) => {
+            |src, dst| convert_pixels(src, dst, |[r, g, b, _]: [u8; 4]| [r, g, b])
+        }
+        (PixelType::U8x3, PixelType::U8x4) => {
+            |src, dst| convert_pixels(src, dst, |[r, g, b]: [u8; 3]| [r, g, b, 255])
+        }
+        (PixelType::U8x3, PixelType::U8) => {
+            |src, dst| convert_pixels(src, dst, |[r, g, b]: [u8; 3]| [rec709_luma(r, g, b)])
+        }
+        (PixelType::U16x3, PixelType::U8x3) => |src, dst| {
+            convert_pixels(src, dst, |c: [u8; 6]| {
+                [0, 2, 4].map(|i| u16_to_u8(u16::from_ne_bytes([c[i], c[i + 1]])))
+            })
+        },
+        (PixelType::U8x3, PixelType::U16x3) => |src, dst| {
+            convert_pixels(src, dst, |[r, g, b]: [u8; 3]| {
+                let [r0, r1] = (r as u16 * 257).to_ne_bytes();
+                let [g0, g1] = (g as u16 * 257).to_ne_bytes();
+                let [b0, b1] = (b as u16 * 257).to_ne_bytes();
+                [r0, r1, g0, g1, b0, b1]
+            })
+        },
+        _ => return None,
+    };
+    Some(converter)
+}
+
+/// Fills bytes of destination pixels by bytes of source pixels
+/// converted with help of `convert` function.
+fn convert_pixels<const S: usize, const D: usize>(
+    src: &[u8],
+    dst: &mut [u8],
+    convert: impl Fn([u8; S]) -> [u8; D],
+) {
+    let src_pixels = src.chunks_exact(S);
+    let dst_pixels = dst.chunks_exact_mut(D);
+    for (src_pixel, dst_pixel) in src_pixels.zip(dst_pixels) {
+        let src_pixel: [u8; S] = src_pixel.try_into().unwrap();
+        dst_pixel.copy_from_slice(&convert(src_pixel));
+    }
+}
+
+#[inline(always)]
+pub(crate) fn rec709_luma(r: u8, g: u8, b: u8) -> u8 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as u8
+}
+
+/// Thresholds of ordered dithering (4x4 Bayer matrix).
+const BAYER_MATRIX_4X4: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Converts row `y` of `U16x3` pixels into `U8x3` pixels with ordered
+/// dithering: every component is rounded up or down depending on
+/// the threshold for the position of the pixel.
+pub(crate) fn dither_u16x3_into_u8x3(src: &[u8], dst: &mut [u8], y: usize) {
+    let thresholds = &BAYER_MATRIX_4X4[y % 4];
+    let src_pixels = src.chunks_exact(6);
+    let dst_pixels = dst.chunks_exact_mut(3);
+    for (x, (src_pixel, dst_pixel)) in src_pixels.zip(dst_pixels).enumerate() {
+        let threshold = (thresholds[x % 4] as f32 + 0.5) / 16.;
+        for (src, dst) in src_pixel.chunks_exact(2).zip(dst_pixel) {
+            let v = u16::from_ne_bytes([src[0], src[1]]);
+            // Maximal value is 255 + threshold, it is less than 256.
+            *dst = (v as f32 / 257. + threshold) as u8;
+        }
+    }
+}
+
+/// Returns `round(v * 255 / 65535)`.
+#[inline(always)]
+fn u16_to_u8(v: u16) -> u8 {
+    ((v as u32 + 128) / 257) as u8
 }
 
 /// Generic image container for internal purposes.
@@ -249,7 +607,7 @@ where
     #[inline(always)]
     pub fn src_view<'s>(&'s self) -> TypedImageView<'s, 'a, P> {
         let rows = self.rows.as_slice();
-        let rows: &[&[P]] = unsafe { std::mem::transmute(rows) };
+        let rows: &[&[P]] = unsafe { core::mem::transmute(rows) };
         TypedImageView::new(self.width, self.height, rows)
     }
 
@@ -1,8 +1,16 @@
-use std::num::NonZeroU32;
-use std::slice;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+use core::slice;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use crate::errors::{CropBoxError, ImageBufferError, ImageRowsError};
-use crate::pixels::{Pixel, PixelType, U16x3, U8x3, U8x4, F32, I32, U8};
+#[cfg(feature = "half")]
+use crate::pixels::F16;
+use crate::pixels::{
+    F32x4, Pixel, PixelType, U16x2, U16x3, U16x4, U8x2, U8x3, U8x4, F32, I16, I32, U8,
+};
 
 pub(crate) type RowMut<'a, 'b, T> = &'a mut &'b mut [T];
 pub(crate) type TwoRows<'a, T> = (&'a [T], &'a [T]);
@@ -15,12 +23,33 @@ pub(crate) type FourRowsMut<'a, 'b, T> = (
 );
 
 /// Parameters of crop box that may be used with [`ImageView`]
-#[derive(Debug, Clone, Copy)]
+///
+/// Coordinates may be fractional to crop a subpixel region of
+/// the source image (e.g. for smooth panning and zooming).
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CropBox {
-    pub left: u32,
-    pub top: u32,
-    pub width: NonZeroU32,
-    pub height: NonZeroU32,
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl CropBox {
+    fn from_size(width: NonZeroU32, height: NonZeroU32) -> Self {
+        Self {
+            left: 0.,
+            top: 0.,
+            width: width.get() as f64,
+            height: height.get() as f64,
+        }
+    }
+
+    /// Returns `true` if all bounds of the crop box are whole numbers of pixels.
+    pub(crate) fn is_integral(&self) -> bool {
+        [self.left, self.top, self.width, self.height]
+            .iter()
+            .all(|v| v.fract() == 0.)
+    }
 }
 
 /// An immutable rows of image.
@@ -29,8 +58,15 @@ pub enum ImageRows<'a> {
     U8x3(Vec<&'a [U8x3]>),
     U8x4(Vec<&'a [U8x4]>),
     U16x3(Vec<&'a [U16x3]>),
+    U16x4(Vec<&'a [U16x4]>),
+    I16(Vec<&'a [I16]>),
     I32(Vec<&'a [I32]>),
     F32(Vec<&'a [F32]>),
+    #[cfg(feature = "half")]
+    F16(Vec<&'a [F16]>),
+    F32x4(Vec<&'a [F32x4]>),
+    U8x2(Vec<&'a [U8x2]>),
+    U16x2(Vec<&'a [U16x2]>),
     U8(Vec<&'a [U8]>),
 }
 
@@ -44,8 +80,15 @@ impl<'a> ImageRows<'a> {
             ImageRows::U8x3(rows) => check_rows_count_and_size(width, height, rows),
             ImageRows::U8x4(rows) => check_rows_count_and_size(width, height, rows),
             ImageRows::U16x3(rows) => check_rows_count_and_size(width, height, rows),
+            ImageRows::U16x4(rows) => check_rows_count_and_size(width, height, rows),
+            ImageRows::I16(rows) => check_rows_count_and_size(width, height, rows),
             ImageRows::I32(rows) => check_rows_count_and_size(width, height, rows),
             ImageRows::F32(rows) => check_rows_count_and_size(width, height, rows),
+            #[cfg(feature = "half")]
+            ImageRows::F16(rows) => check_rows_count_and_size(width, height, rows),
+            ImageRows::F32x4(rows) => check_rows_count_and_size(width, height, rows),
+            ImageRows::U8x2(rows) => check_rows_count_and_size(width, height, rows),
+            ImageRows::U16x2(rows) => check_rows_count_and_size(width, height, rows),
             ImageRows::U8(rows) => check_rows_count_and_size(width, height, rows),
         }
     }
@@ -55,11 +98,51 @@ impl<'a> ImageRows<'a> {
             Self::U8x3(_) => PixelType::U8x3,
             Self::U8x4(_) => PixelType::U8x4,
             Self::U16x3(_) => PixelType::U16x3,
+            Self::U16x4(_) => PixelType::U16x4,
+            Self::I16(_) => PixelType::I16,
             Self::I32(_) => PixelType::I32,
             Self::F32(_) => PixelType::F32,
+            #[cfg(feature = "half")]
+            Self::F16(_) => PixelType::F16,
+            Self::F32x4(_) => PixelType::F32x4,
+            Self::U8x2(_) => PixelType::U8x2,
+            Self::U16x2(_) => PixelType::U16x2,
             Self::U8(_) => PixelType::U8,
         }
     }
+
+    /// Returns rows of the rectangular part of the image
+    /// without copying of pixels.
+    fn sub_rows(&self, left: usize, top: usize, width: usize, height: usize) -> Self {
+        match self {
+            Self::U8x3(rows) => Self::U8x3(sub_rows(rows, left, top, width, height)),
+            Self::U8x4(rows) => Self::U8x4(sub_rows(rows, left, top, width, height)),
+            Self::U16x3(rows) => Self::U16x3(sub_rows(rows, left, top, width, height)),
+            Self::U16x4(rows) => Self::U16x4(sub_rows(rows, left, top, width, height)),
+            Self::I16(rows) => Self::I16(sub_rows(rows, left, top, width, height)),
+            Self::I32(rows) => Self::I32(sub_rows(rows, left, top, width, height)),
+            Self::F32(rows) => Self::F32(sub_rows(rows, left, top, width, height)),
+            #[cfg(feature = "half")]
+            Self::F16(rows) => Self::F16(sub_rows(rows, left, top, width, height)),
+            Self::F32x4(rows) => Self::F32x4(sub_rows(rows, left, top, width, height)),
+            Self::U8x2(rows) => Self::U8x2(sub_rows(rows, left, top, width, height)),
+            Self::U16x2(rows) => Self::U16x2(sub_rows(rows, left, top, width, height)),
+            Self::U8(rows) => Self::U8(sub_rows(rows, left, top, width, height)),
+        }
+    }
+}
+
+fn sub_rows<'a, P>(
+    rows: &[&'a [P]],
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+) -> Vec<&'a [P]> {
+    rows[top..top + height]
+        .iter()
+        .map(|row| &row[left..left + width])
+        .collect()
 }
 
 /// A mutable rows of image.
@@ -68,8 +151,15 @@ pub enum ImageRowsMut<'a> {
     U8x3(Vec<&'a mut [U8x3]>),
     U8x4(Vec<&'a mut [U8x4]>),
     U16x3(Vec<&'a mut [U16x3]>),
+    U16x4(Vec<&'a mut [U16x4]>),
+    I16(Vec<&'a mut [I16]>),
     I32(Vec<&'a mut [I32]>),
     F32(Vec<&'a mut [F32]>),
+    #[cfg(feature = "half")]
+    F16(Vec<&'a mut [F16]>),
+    F32x4(Vec<&'a mut [F32x4]>),
+    U8x2(Vec<&'a mut [U8x2]>),
+    U16x2(Vec<&'a mut [U16x2]>),
     U8(Vec<&'a mut [U8]>),
 }
 
@@ -83,8 +173,15 @@ impl<'a> ImageRowsMut<'a> {
             Self::U8x3(rows) => check_rows_count_and_size(width, height, rows),
             Self::U8x4(rows) => check_rows_count_and_size(width, height, rows),
             Self::U16x3(rows) => check_rows_count_and_size(width, height, rows),
+            Self::U16x4(rows) => check_rows_count_and_size(width, height, rows),
+            Self::I16(rows) => check_rows_count_and_size(width, height, rows),
             Self::I32(rows) => check_rows_count_and_size(width, height, rows),
             Self::F32(rows) => check_rows_count_and_size(width, height, rows),
+            #[cfg(feature = "half")]
+            Self::F16(rows) => check_rows_count_and_size(width, height, rows),
+            Self::F32x4(rows) => check_rows_count_and_size(width, height, rows),
+            Self::U8x2(rows) => check_rows_count_and_size(width, height, rows),
+            Self::U16x2(rows) => check_rows_count_and_size(width, height, rows),
             Self::U8(rows) => check_rows_count_and_size(width, height, rows),
         }
     }
@@ -94,8 +191,15 @@ impl<'a> ImageRowsMut<'a> {
             Self::U8x3(_) => PixelType::U8x3,
             Self::U8x4(_) => PixelType::U8x4,
             Self::U16x3(_) => PixelType::U16x3,
+            Self::U16x4(_) => PixelType::U16x4,
+            Self::I16(_) => PixelType::I16,
             Self::I32(_) => PixelType::I32,
             Self::F32(_) => PixelType::F32,
+            #[cfg(feature = "half")]
+            Self::F16(_) => PixelType::F16,
+            Self::F32x4(_) => PixelType::F32x4,
+            Self::U8x2(_) => PixelType::U8x2,
+            Self::U16x2(_) => PixelType::U16x2,
             Self::U8(_) => PixelType::U8,
         }
     }
@@ -120,12 +224,7 @@ impl<'a> ImageView<'a> {
         Ok(Self {
             width,
             height,
-            crop_box: CropBox {
-                left: 0,
-                top: 0,
-                width,
-                height,
-            },
+            crop_box: CropBox::from_size(width, height),
             rows,
         })
     }
@@ -136,8 +235,7 @@ impl<'a> ImageView<'a> {
         buffer: &'a [u8],
         pixel_type: PixelType,
     ) -> Result<Self, ImageBufferError> {
-        let size = (width.get() * height.get()) as usize * pixel_type.size();
-        if buffer.len() != size {
+        if pixel_type.buffer_len(width, height) != Some(buffer.len()) {
             return Err(ImageBufferError::InvalidBufferSize);
         }
         let rows = match pixel_type {
@@ -153,6 +251,14 @@ impl<'a> ImageView<'a> {
                 let pixels = align_buffer_to(buffer)?;
                 ImageRows::U16x3(pixels.chunks_exact(width.get() as usize).collect())
             }
+            PixelType::U16x4 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::U16x4(pixels.chunks_exact(width.get() as usize).collect())
+            }
+            PixelType::I16 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::I16(pixels.chunks_exact(width.get() as usize).collect())
+            }
             PixelType::I32 => {
                 let pixels = align_buffer_to(buffer)?;
                 ImageRows::I32(pixels.chunks_exact(width.get() as usize).collect())
@@ -161,6 +267,23 @@ impl<'a> ImageView<'a> {
                 let pixels = align_buffer_to(buffer)?;
                 ImageRows::F32(pixels.chunks_exact(width.get() as usize).collect())
             }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::F16(pixels.chunks_exact(width.get() as usize).collect())
+            }
+            PixelType::F32x4 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::F32x4(pixels.chunks_exact(width.get() as usize).collect())
+            }
+            PixelType::U8x2 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::U8x2(pixels.chunks_exact(width.get() as usize).collect())
+            }
+            PixelType::U16x2 => {
+                let pixels = align_buffer_to(buffer)?;
+                ImageRows::U16x2(pixels.chunks_exact(width.get() as usize).collect())
+            }
             PixelType::U8 => {
                 let pixels = align_buffer_to(buffer)?;
                 ImageRows::U8(pixels.chunks_exact(width.get() as usize).collect())
@@ -169,12 +292,45 @@ impl<'a> ImageView<'a> {
         Ok(Self {
             width,
             height,
-            crop_box: CropBox {
-                left: 0,
-                top: 0,
-                width,
-                height,
-            },
+            crop_box: CropBox::from_size(width, height),
+            rows,
+        })
+    }
+
+    /// Creates a view of the image which rows are stored in the buffer
+    /// with the given stride, i.e. every row starts `row_stride` bytes
+    /// after the beginning of the previous one. Bytes between the end of
+    /// a row and the beginning of the next one (padding) are ignored.
+    ///
+    /// `row_stride` must not be less than size of a row in bytes.
+    /// Padding after the last row may be absent.
+    pub fn from_buffer_with_stride(
+        width: NonZeroU32,
+        height: NonZeroU32,
+        buffer: &'a [u8],
+        row_stride: usize,
+        pixel_type: PixelType,
+    ) -> Result<Self, ImageBufferError> {
+        check_strided_buffer_size(width, height, buffer.len(), row_stride, pixel_type)?;
+        let rows = match pixel_type {
+            PixelType::U8x3 => ImageRows::U8x3(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U8x4 => ImageRows::U8x4(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U16x3 => ImageRows::U16x3(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U16x4 => ImageRows::U16x4(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::I16 => ImageRows::I16(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::I32 => ImageRows::I32(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::F32 => ImageRows::F32(strided_rows(buffer, width, height, row_stride)?),
+            #[cfg(feature = "half")]
+            PixelType::F16 => ImageRows::F16(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::F32x4 => ImageRows::F32x4(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U8x2 => ImageRows::U8x2(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U16x2 => ImageRows::U16x2(strided_rows(buffer, width, height, row_stride)?),
+            PixelType::U8 => ImageRows::U8(strided_rows(buffer, width, height, row_stride)?),
+        };
+        Ok(Self {
+            width,
+            height,
+            crop_box: CropBox::from_size(width, height),
             rows,
         })
     }
@@ -200,18 +356,58 @@ impl<'a> ImageView<'a> {
     }
 
     pub fn set_crop_box(&mut self, crop_box: CropBox) -> Result<(), CropBoxError> {
-        if crop_box.left >= self.width.get() || crop_box.top >= self.height.get() {
+        let width = self.width.get() as f64;
+        let height = self.height.get() as f64;
+        if !(0. ..width).contains(&crop_box.left) || !(0. ..height).contains(&crop_box.top) {
             return Err(CropBoxError::PositionIsOutOfImageBoundaries);
         }
-        let right = crop_box.left + crop_box.width.get();
-        let bottom = crop_box.top + crop_box.height.get();
-        if right > self.width.get() || bottom > self.height.get() {
+        if !(crop_box.width > 0. && crop_box.height > 0.) {
+            return Err(CropBoxError::WidthOrHeightLessOrEqualToZero);
+        }
+        let right = crop_box.left + crop_box.width;
+        let bottom = crop_box.top + crop_box.height;
+        if !(right <= width && bottom <= height) {
             return Err(CropBoxError::SizeIsOutOfImageBoundaries);
         }
         self.crop_box = crop_box;
         Ok(())
     }
 
+    /// Returns a view of the part of the image covered by `region`.
+    ///
+    /// Unlike a view with the crop box, pixels outside the region are
+    /// not visible for the returned view, so they don't affect the result
+    /// of resizing. Integral bounding box of the region becomes the size of
+    /// the returned view and its fractional part becomes the crop box.
+    pub(crate) fn region(&self, region: CropBox) -> Result<ImageView<'a>, CropBoxError> {
+        // Checks that region is inside the image.
+        self.clone().set_crop_box(region)?;
+        let left = region.left.floor();
+        let top = region.top.floor();
+        let right = (region.left + region.width).ceil();
+        let bottom = (region.top + region.height).ceil();
+        // Region has non-zero size, so its bounding box has at least one pixel.
+        let width = NonZeroU32::new((right - left) as u32).unwrap();
+        let height = NonZeroU32::new((bottom - top) as u32).unwrap();
+        let rows = self.rows.sub_rows(
+            left as usize,
+            top as usize,
+            width.get() as usize,
+            height.get() as usize,
+        );
+        Ok(Self {
+            width,
+            height,
+            crop_box: CropBox {
+                left: region.left - left,
+                top: region.top - top,
+                width: region.width,
+                height: region.height,
+            },
+            rows,
+        })
+    }
+
     /// Set a crop box to resize the source image into the
     /// aspect ratio of destination image without distortions.
     ///
@@ -265,15 +461,139 @@ impl<'a> ImageView<'a> {
         let crop_left = (width - crop_width) * centering.0;
         let crop_top = (height - crop_height) * centering.1;
 
+        // Crop box of an image with extreme aspect ratio may be rounded
+        // to zero size, and rounding of both position and size may move
+        // the crop box out of the image boundaries.
+        let crop_width = crop_width.round().max(1.);
+        let crop_height = crop_height.round().max(1.);
         self.set_crop_box(CropBox {
-            left: crop_left.round() as u32,
-            top: crop_top.round() as u32,
-            width: NonZeroU32::new(crop_width.round() as u32).unwrap(),
-            height: NonZeroU32::new(crop_height.round() as u32).unwrap(),
+            left: crop_left.round().min(width - crop_width) as f64,
+            top: crop_top.round().min(height - crop_height) as f64,
+            width: crop_width as f64,
+            height: crop_height as f64,
         })
         .unwrap();
     }
 
+    /// Returns iterator over all rows of the image if it has `U8x3` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u8x3(&self) -> Option<impl Iterator<Item = &'a [U8x3]> + '_> {
+        if let ImageRows::U8x3(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `U8x4` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u8x4(&self) -> Option<impl Iterator<Item = &'a [U8x4]> + '_> {
+        if let ImageRows::U8x4(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `U16x3` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u16x3(&self) -> Option<impl Iterator<Item = &'a [U16x3]> + '_> {
+        if let ImageRows::U16x3(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `U16x4` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u16x4(&self) -> Option<impl Iterator<Item = &'a [U16x4]> + '_> {
+        if let ImageRows::U16x4(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `I16` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_i16(&self) -> Option<impl Iterator<Item = &'a [I16]> + '_> {
+        if let ImageRows::I16(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `I32` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_i32(&self) -> Option<impl Iterator<Item = &'a [I32]> + '_> {
+        if let ImageRows::I32(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `F32` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_f32(&self) -> Option<impl Iterator<Item = &'a [F32]> + '_> {
+        if let ImageRows::F32(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `F16` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    #[cfg(feature = "half")]
+    pub fn rows_f16(&self) -> Option<impl Iterator<Item = &'a [F16]> + '_> {
+        if let ImageRows::F16(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `F32x4` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_f32x4(&self) -> Option<impl Iterator<Item = &'a [F32x4]> + '_> {
+        if let ImageRows::F32x4(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+    /// Returns iterator over all rows of the image if it has `U8x2` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u8x2(&self) -> Option<impl Iterator<Item = &'a [U8x2]> + '_> {
+        if let ImageRows::U8x2(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+    /// Returns iterator over all rows of the image if it has `U16x2` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u16x2(&self) -> Option<impl Iterator<Item = &'a [U16x2]> + '_> {
+        if let ImageRows::U16x2(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over all rows of the image if it has `U8` pixels,
+    /// otherwise `None`. Crop box is ignored.
+    pub fn rows_u8(&self) -> Option<impl Iterator<Item = &'a [U8]> + '_> {
+        if let ImageRows::U8(ref rows) = self.rows {
+            Some(rows.iter().copied())
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn u8x3_image(&self) -> Option<TypedImageView<U8x3>> {
         if let ImageRows::U8x3(ref rows) = self.rows {
             Some(TypedImageView {
@@ -313,6 +633,32 @@ impl<'a> ImageView<'a> {
         }
     }
 
+    pub(crate) fn u16x4_image(&self) -> Option<TypedImageView<'_, '_, U16x4>> {
+        if let ImageRows::U16x4(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn i16_image(&self) -> Option<TypedImageView<'_, '_, I16>> {
+        if let ImageRows::I16(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn i32_image(&self) -> Option<TypedImageView<I32>> {
         if let ImageRows::I32(ref rows) = self.rows {
             Some(TypedImageView {
@@ -339,6 +685,59 @@ impl<'a> ImageView<'a> {
         }
     }
 
+    #[cfg(feature = "half")]
+    pub(crate) fn f16_image(&self) -> Option<TypedImageView<F16>> {
+        if let ImageRows::F16(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn f32x4_image(&self) -> Option<TypedImageView<'_, '_, F32x4>> {
+        if let ImageRows::F32x4(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn u8x2_image(&self) -> Option<TypedImageView<'_, '_, U8x2>> {
+        if let ImageRows::U8x2(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn u16x2_image(&self) -> Option<TypedImageView<'_, '_, U16x2>> {
+        if let ImageRows::U16x2(ref rows) = self.rows {
+            Some(TypedImageView {
+                width: self.width,
+                height: self.height,
+                crop_box: self.crop_box,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn u8_image(&self) -> Option<TypedImageView<U8>> {
         if let ImageRows::U8(ref rows) = self.rows {
             Some(TypedImageView {
@@ -354,6 +753,7 @@ impl<'a> ImageView<'a> {
 }
 
 /// Generic immutable image view.
+#[derive(Clone, Copy)]
 pub(crate) struct TypedImageView<'a, 'b, P>
 where
     P: Pixel,
@@ -372,12 +772,7 @@ where
         Self {
             width,
             height,
-            crop_box: CropBox {
-                left: 0,
-                top: 0,
-                width,
-                height,
-            },
+            crop_box: CropBox::from_size(width, height),
             rows,
         }
     }
@@ -397,6 +792,12 @@ where
         self.crop_box
     }
 
+    /// Sets the crop box without checking of its boundaries.
+    #[inline(always)]
+    pub(crate) fn set_crop_box_unchecked(&mut self, crop_box: CropBox) {
+        self.crop_box = crop_box;
+    }
+
     #[inline]
     pub(crate) fn get_pixel(&self, x: u32, y: u32) -> P {
         self.rows[y as usize][x as usize]
@@ -443,23 +844,6 @@ where
     pub(crate) fn get_row(&self, y: u32) -> Option<&'b [P]> {
         self.rows.get(y as usize).copied()
     }
-
-    #[inline(always)]
-    pub(crate) fn iter_rows_with_step<'s>(
-        &'s self,
-        mut y: f64,
-        step: f64,
-        max_count: usize,
-    ) -> impl Iterator<Item = &'b [P]> + 's {
-        let steps = (self.height.get() as f64 - y) / step;
-        let steps = (steps.max(0.).ceil() as usize).min(max_count);
-        (0..steps).map(move |_| {
-            // Safety of value of y guaranteed by calculation of steps count
-            let row = unsafe { *self.rows.get_unchecked(y as usize) };
-            y += step;
-            row
-        })
-    }
 }
 
 /// A mutable view of image data used by resizer as destination image.
@@ -490,8 +874,7 @@ impl<'a> ImageViewMut<'a> {
         buffer: &'a mut [u8],
         pixel_type: PixelType,
     ) -> Result<Self, ImageBufferError> {
-        let size = (width.get() * height.get()) as usize * pixel_type.size();
-        if buffer.len() != size {
+        if pixel_type.buffer_len(width, height) != Some(buffer.len()) {
             return Err(ImageBufferError::InvalidBufferSize);
         }
         let rows = match pixel_type {
@@ -507,6 +890,14 @@ impl<'a> ImageViewMut<'a> {
                 let pixels = align_buffer_to_mut(buffer)?;
                 ImageRowsMut::U16x3(pixels.chunks_exact_mut(width.get() as usize).collect())
             }
+            PixelType::U16x4 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::U16x4(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::I16 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::I16(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
             PixelType::I32 => {
                 let pixels = align_buffer_to_mut(buffer)?;
                 ImageRowsMut::I32(pixels.chunks_exact_mut(width.get() as usize).collect())
@@ -515,6 +906,23 @@ impl<'a> ImageViewMut<'a> {
                 let pixels = align_buffer_to_mut(buffer)?;
                 ImageRowsMut::F32(pixels.chunks_exact_mut(width.get() as usize).collect())
             }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::F16(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::F32x4 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::F32x4(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::U8x2 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::U8x2(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
+            PixelType::U16x2 => {
+                let pixels = align_buffer_to_mut(buffer)?;
+                ImageRowsMut::U16x2(pixels.chunks_exact_mut(width.get() as usize).collect())
+            }
             PixelType::U8 => {
                 let pixels = align_buffer_to_mut(buffer)?;
                 ImageRowsMut::U8(pixels.chunks_exact_mut(width.get() as usize).collect())
@@ -527,6 +935,65 @@ impl<'a> ImageViewMut<'a> {
         })
     }
 
+    /// Creates a view of the image which rows are stored in the buffer
+    /// with the given stride, i.e. every row starts `row_stride` bytes
+    /// after the beginning of the previous one. Bytes between the end of
+    /// a row and the beginning of the next one (padding) are ignored.
+    ///
+    /// `row_stride` must not be less than size of a row in bytes.
+    /// Padding after the last row may be absent.
+    pub fn from_buffer_with_stride(
+        width: NonZeroU32,
+        height: NonZeroU32,
+        buffer: &'a mut [u8],
+        row_stride: usize,
+        pixel_type: PixelType,
+    ) -> Result<Self, ImageBufferError> {
+        check_strided_buffer_size(width, height, buffer.len(), row_stride, pixel_type)?;
+        let rows = match pixel_type {
+            PixelType::U8x3 => {
+                ImageRowsMut::U8x3(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U8x4 => {
+                ImageRowsMut::U8x4(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U16x3 => {
+                ImageRowsMut::U16x3(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U16x4 => {
+                ImageRowsMut::U16x4(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::I16 => {
+                ImageRowsMut::I16(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::I32 => {
+                ImageRowsMut::I32(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::F32 => {
+                ImageRowsMut::F32(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                ImageRowsMut::F16(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::F32x4 => {
+                ImageRowsMut::F32x4(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U8x2 => {
+                ImageRowsMut::U8x2(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U16x2 => {
+                ImageRowsMut::U16x2(strided_rows_mut(buffer, width, height, row_stride)?)
+            }
+            PixelType::U8 => ImageRowsMut::U8(strided_rows_mut(buffer, width, height, row_stride)?),
+        };
+        Ok(Self {
+            width,
+            height,
+            rows,
+        })
+    }
+
     #[inline(always)]
     pub fn pixel_type(&self) -> PixelType {
         self.rows.pixel_type()
@@ -578,6 +1045,30 @@ impl<'a> ImageViewMut<'a> {
         }
     }
 
+    pub(crate) fn u16x4_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, U16x4>> {
+        if let ImageRowsMut::U16x4(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn i16_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, I16>> {
+        if let ImageRowsMut::I16(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn i32_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, I32>> {
         if let ImageRowsMut::I32(rows) = &mut self.rows {
             Some(TypedImageViewMut {
@@ -602,6 +1093,55 @@ impl<'a> ImageViewMut<'a> {
         }
     }
 
+    #[cfg(feature = "half")]
+    pub(crate) fn f16_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, F16>> {
+        if let ImageRowsMut::F16(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn f32x4_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, F32x4>> {
+        if let ImageRowsMut::F32x4(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn u8x2_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, U8x2>> {
+        if let ImageRowsMut::U8x2(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn u16x2_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, U16x2>> {
+        if let ImageRowsMut::U16x2(rows) = &mut self.rows {
+            Some(TypedImageViewMut {
+                width: self.width,
+                height: self.height,
+                rows,
+            })
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn u8_image<'s>(&'s mut self) -> Option<TypedImageViewMut<'s, 'a, U8>> {
         if let ImageRowsMut::U8(rows) = &mut self.rows {
             Some(TypedImageViewMut {
@@ -613,6 +1153,30 @@ impl<'a> ImageViewMut<'a> {
             None
         }
     }
+
+    /// Returns rows of the image as slices of bytes.
+    pub(crate) fn rows_bytes_mut(&mut self) -> Vec<&mut [u8]> {
+        fn bytes<'s, P>(rows: &'s mut [&mut [P]]) -> Vec<&'s mut [u8]> {
+            rows.iter_mut()
+                .map(|row| unsafe { row.align_to_mut::<u8>().1 })
+                .collect()
+        }
+        match &mut self.rows {
+            ImageRowsMut::U8x3(rows) => bytes(rows),
+            ImageRowsMut::U8x4(rows) => bytes(rows),
+            ImageRowsMut::U16x3(rows) => bytes(rows),
+            ImageRowsMut::U16x4(rows) => bytes(rows),
+            ImageRowsMut::I16(rows) => bytes(rows),
+            ImageRowsMut::I32(rows) => bytes(rows),
+            ImageRowsMut::F32(rows) => bytes(rows),
+            #[cfg(feature = "half")]
+            ImageRowsMut::F16(rows) => bytes(rows),
+            ImageRowsMut::F32x4(rows) => bytes(rows),
+            ImageRowsMut::U8x2(rows) => bytes(rows),
+            ImageRowsMut::U16x2(rows) => bytes(rows),
+            ImageRowsMut::U8(rows) => bytes(rows),
+        }
+    }
 }
 
 /// Generic mutable image view.
@@ -664,6 +1228,26 @@ where
     pub fn get_row_mut<'s>(&'s mut self, y: u32) -> Option<RowMut<'s, 'b, P>> {
         self.rows.get_mut(y as usize)
     }
+
+    /// Returns a view of the same rows that borrows this view.
+    pub fn reborrow(&mut self) -> TypedImageViewMut<'_, 'b, P> {
+        TypedImageViewMut::new(self.width, self.height, self.rows)
+    }
+
+    /// Splits the image into parts by rows. Every part, except the last
+    /// one, has the given height.
+    pub fn split_by_height<'s>(
+        &'s mut self,
+        part_height: NonZeroU32,
+    ) -> impl Iterator<Item = TypedImageViewMut<'s, 'b, P>> {
+        let width = self.width;
+        self.rows
+            .chunks_mut(part_height.get() as usize)
+            .map(move |rows| {
+                let height = NonZeroU32::new(rows.len() as u32).unwrap();
+                TypedImageViewMut::new(width, height, rows)
+            })
+    }
 }
 
 fn check_rows_count_and_size<T>(
@@ -681,6 +1265,59 @@ fn check_rows_count_and_size<T>(
     Ok(())
 }
 
+fn check_strided_buffer_size(
+    width: NonZeroU32,
+    height: NonZeroU32,
+    buffer_len: usize,
+    row_stride: usize,
+    pixel_type: PixelType,
+) -> Result<(), ImageBufferError> {
+    let row_size = pixel_type
+        .buffer_len(width, NonZeroU32::new(1).unwrap())
+        .ok_or(ImageBufferError::InvalidBufferSize)?;
+    if row_stride < row_size {
+        return Err(ImageBufferError::InvalidRowStride);
+    }
+    let required_len = (height.get() as usize - 1)
+        .checked_mul(row_stride)
+        .and_then(|len| len.checked_add(row_size));
+    match required_len {
+        Some(len) if len <= buffer_len => Ok(()),
+        _ => Err(ImageBufferError::InvalidBufferSize),
+    }
+}
+
+/// Splits the buffer into rows that begin every `row_stride` bytes
+/// and have `width` pixels. Size of the buffer must be checked by
+/// [check_strided_buffer_size] before.
+fn strided_rows<T>(
+    buffer: &[u8],
+    width: NonZeroU32,
+    height: NonZeroU32,
+    row_stride: usize,
+) -> Result<Vec<&[T]>, ImageBufferError> {
+    let row_size = width.get() as usize * core::mem::size_of::<T>();
+    buffer
+        .chunks(row_stride)
+        .take(height.get() as usize)
+        .map(|row| align_buffer_to(&row[..row_size]))
+        .collect()
+}
+
+fn strided_rows_mut<T>(
+    buffer: &mut [u8],
+    width: NonZeroU32,
+    height: NonZeroU32,
+    row_stride: usize,
+) -> Result<Vec<&mut [T]>, ImageBufferError> {
+    let row_size = width.get() as usize * core::mem::size_of::<T>();
+    buffer
+        .chunks_mut(row_stride)
+        .take(height.get() as usize)
+        .map(|row| align_buffer_to_mut(&mut row[..row_size]))
+        .collect()
+}
+
 fn align_buffer_to<T>(buffer: &[u8]) -> Result<&[T], ImageBufferError> {
     let (head, pixels, _) = unsafe { buffer.align_to::<T>() };
     if !head.is_empty() {
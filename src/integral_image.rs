@@ -0,0 +1,104 @@
+//! Downscaling by box averaging with help of summed-area table
+//! (integral image).
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::{Pixel, U8x3, U8x4};
+
+/// Pixel which consists of `CHANNELS` one-byte components.
+pub(crate) trait IntegralPixel: Pixel {
+    const CHANNELS: usize;
+}
+
+impl IntegralPixel for U8x3 {
+    const CHANNELS: usize = 3;
+}
+
+impl IntegralPixel for U8x4 {
+    const CHANNELS: usize = 4;
+}
+
+/// Max number of rows which sum of bytes fits into `u16`.
+const MAX_SHORT_ROWS: usize = u16::MAX as usize / u8::MAX as usize;
+
+/// Resizes the crop box of source image by averaging of source pixels
+/// covered by each destination pixel.
+///
+/// Summed-area table is built for the band of source rows covered by
+/// one row of destination image at a time, so sum of any rectangle
+/// of source pixels is calculated in O(1) and memory usage doesn't
+/// depend on the height of source image.
+pub(crate) fn resample_integral_image<P: IntegralPixel>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+) {
+    let crop_box = src_image.crop_box();
+    let channels = P::CHANNELS;
+    let src_width = crop_box.width as usize;
+    let row_start = crop_box.left as usize * channels;
+    let row_end = row_start + src_width * channels;
+    let dst_width = dst_image.width().get() as u64;
+    let dst_height = dst_image.height().get() as u64;
+
+    // Bounds of source pixels covered by destination pixels.
+    let bounds = |dst_size: u64, src_size: u64| -> Vec<(usize, usize)> {
+        (0..dst_size)
+            .map(|i| {
+                let start = i * src_size / dst_size;
+                let end = ((i + 1) * src_size / dst_size).max(start + 1);
+                (start as usize, end as usize)
+            })
+            .collect()
+    };
+    let x_bounds = bounds(dst_width, src_width as u64);
+    let y_bounds = bounds(dst_height, crop_box.height as u64);
+
+    // Sums of components by columns of the current band of source rows.
+    let mut column_sums = vec![0u64; src_width * channels];
+    // Sums of components by columns of up to `MAX_SHORT_ROWS` rows.
+    // Summing of bytes into `u16` is much faster than into `u64`.
+    let mut short_sums = vec![0u16; src_width * channels];
+    // Summed-area table of the current band of source rows.
+    // Components of the first item are always zero.
+    let mut table = vec![0u64; (src_width + 1) * channels];
+
+    for (dst_row, &(y_start, y_end)) in dst_image.iter_rows_mut().zip(&y_bounds) {
+        column_sums.fill(0);
+        let band_rows = src_image
+            .iter_rows(crop_box.top as u32 + y_start as u32)
+            .take(y_end - y_start);
+        let mut short_rows = 0;
+        for (i, src_row) in band_rows.enumerate() {
+            // Pixels consist of bytes only, so the slice is always aligned.
+            let src_bytes = unsafe { src_row.align_to::<u8>().1 };
+            for (sum, &c) in short_sums.iter_mut().zip(&src_bytes[row_start..row_end]) {
+                *sum += c as u16;
+            }
+            short_rows += 1;
+            if short_rows == MAX_SHORT_ROWS || i + 1 == y_end - y_start {
+                for (sum, short_sum) in column_sums.iter_mut().zip(short_sums.iter_mut()) {
+                    *sum += *short_sum as u64;
+                    *short_sum = 0;
+                }
+                short_rows = 0;
+            }
+        }
+        for i in channels..table.len() {
+            table[i] = table[i - channels] + column_sums[i - channels];
+        }
+
+        let rows_count = (y_end - y_start) as u64;
+        // Pixels consist of bytes only, so the slice is always aligned.
+        let dst_bytes = unsafe { dst_row.align_to_mut::<u8>().1 };
+        let dst_pixels = dst_bytes.chunks_exact_mut(channels);
+        for (dst_pixel, &(x_start, x_end)) in dst_pixels.zip(&x_bounds) {
+            let area = rows_count * (x_end - x_start) as u64;
+            let start = &table[x_start * channels..];
+            let end = &table[x_end * channels..];
+            for (i, c) in dst_pixel.iter_mut().enumerate() {
+                *c = ((end[i] - start[i] + area / 2) / area) as u8;
+            }
+        }
+    }
+}
@@ -1,20 +1,43 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub use alpha::{MulDiv, MulDivImageError, MulDivImagesError};
-pub use convolution::FilterType;
+pub use convolution::{
+    BoundaryMode, Coefficients, CoefficientsChunk, CustomFilter, CustomFilter2D, FilterType,
+    RoundingMode,
+};
 pub use errors::*;
+pub use fit::fit_size;
+pub use gamma::{linear_to_srgb, srgb_to_linear};
 pub use image_view::{CropBox, ImageRows, ImageRowsMut, ImageView, ImageViewMut};
 pub use pixels::PixelType;
-pub use resizer::{CpuExtensions, ResizeAlg, Resizer};
+pub use resizer::{
+    build_mipmaps, resize, resize_by_factor, resize_letterbox, AlphaMode, ContentRect,
+    CpuExtensions, PreparedResize, ResizeAlg, Resizer,
+};
+pub use sharpen::UnsharpMask;
 
 pub use crate::image::Image;
 
 mod alpha;
+mod anti_ringing;
+mod bilinear;
 mod convolution;
+mod convolution_2d;
+#[cfg(feature = "image")]
+mod dynamic_image;
 mod errors;
+mod fit;
+mod gamma;
 mod image;
 mod image_view;
+mod integral_image;
+mod nearest;
 pub mod pixels;
 mod resizer;
+mod sharpen;
 #[cfg(target_arch = "x86_64")]
 mod simd_utils;
+mod strips;
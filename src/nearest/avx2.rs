@@ -0,0 +1,26 @@
+use core::arch::x86_64::*;
+
+use super::native;
+use crate::pixels::U8x4;
+
+pub(super) fn gather_row(src_row: &[U8x4], dst_row: &mut [U8x4], x_indexes: &[u32]) {
+    unsafe {
+        gather_row_avx2(src_row, dst_row, x_indexes);
+    }
+}
+
+/// For safety, it is necessary to ensure the following conditions:
+/// - indexes are less than src_row.len() and i32::MAX
+#[target_feature(enable = "avx2")]
+unsafe fn gather_row_avx2(src_row: &[U8x4], dst_row: &mut [U8x4], x_indexes: &[u32]) {
+    let src_ptr = src_row.as_ptr() as *const i32;
+    let indexes_chunks = x_indexes.chunks_exact(8);
+    let indexes_reminder = indexes_chunks.remainder();
+    let (dst_chunks, dst_reminder) = dst_row.split_at_mut(indexes_chunks.len() * 8);
+    for (dst, indexes) in dst_chunks.chunks_exact_mut(8).zip(indexes_chunks) {
+        let indexes = _mm256_loadu_si256(indexes.as_ptr() as *const __m256i);
+        let pixels = _mm256_i32gather_epi32::<4>(src_ptr, indexes);
+        _mm256_storeu_si256(dst.as_mut_ptr() as *mut __m256i, pixels);
+    }
+    native::gather_row(src_row, dst_reminder, indexes_reminder);
+}
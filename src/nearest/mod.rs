@@ -0,0 +1,111 @@
+//! Resizing by nearest neighbor.
+//!
+//! Indexes of source pixels are calculated once for all columns of
+//! destination image, so every row of destination image is gathered from
+//! a row of source image by these indexes. Rows which are gathered from
+//! the same source row as the previous one are copied from it.
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::image_view::{TypedImageView, TypedImageViewMut};
+use crate::pixels::{Pixel, U8x4};
+use crate::CpuExtensions;
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+mod native;
+
+/// Returns CPU-extensions that are actually used by [resample_nearest_u8x4]
+/// if `cpu_extensions` are requested.
+pub(crate) fn used_cpu_extensions(cpu_extensions: CpuExtensions) -> CpuExtensions {
+    match cpu_extensions {
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx2 | CpuExtensions::Avx512 => CpuExtensions::Avx2,
+        _ => CpuExtensions::None,
+    }
+}
+
+/// Resizes the crop box of source image by nearest neighbor,
+/// `offset` shifts positions of source pixels.
+pub(crate) fn resample_nearest<P: Pixel>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    offset: (f32, f32),
+) {
+    resample_with(src_image, dst_image, offset, native::gather_row);
+}
+
+/// Resizes `U8x4` image like [resample_nearest], pixels are gathered
+/// with help of `AVX2` instructions if they are enabled by `cpu_extensions`.
+pub(crate) fn resample_nearest_u8x4(
+    src_image: TypedImageView<U8x4>,
+    dst_image: TypedImageViewMut<U8x4>,
+    offset: (f32, f32),
+    cpu_extensions: CpuExtensions,
+) {
+    let gather_row = match cpu_extensions {
+        // Indexes of pixels are passed into AVX2 instructions as `i32`.
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx2 | CpuExtensions::Avx512
+            if src_image.width().get() <= i32::MAX as u32 =>
+        {
+            avx2::gather_row
+        }
+        _ => native::gather_row,
+    };
+    resample_with(src_image, dst_image, offset, gather_row);
+}
+
+/// Resizes the image with help of `gather_row` which fills destination row
+/// by pixels of source row with given indexes. Indexes passed into
+/// `gather_row` are always less than length of the source row.
+fn resample_with<P: Pixel>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: (f32, f32),
+    gather_row: fn(&[P], &mut [P], &[u32]),
+) {
+    let crop_box = src_image.crop_box();
+    let dst_width = dst_image.width().get();
+    let dst_height = dst_image.height().get();
+    let x_scale = crop_box.width / dst_width as f64;
+    let y_scale = crop_box.height / dst_height as f64;
+    let (x_offset, y_offset) = (offset.0 as f64, offset.1 as f64);
+
+    // Pretabulate horizontal pixel positions.
+    // Positions are clamped by bounds of crop box.
+    let x_in_start = crop_box.left + x_scale * 0.5 + x_offset;
+    let min_src_x = crop_box.left;
+    let max_src_x = (crop_box.left + crop_box.width).ceil() as u32 - 1;
+    let x_in_tab: Vec<u32> = (0..dst_width)
+        .map(|x| ((x_in_start + x_scale * x as f64).max(min_src_x) as u32).min(max_src_x))
+        .collect();
+
+    // Vertical pixel positions are calculated by the same way
+    let mut y_in = crop_box.top + y_scale * 0.5 + y_offset;
+    let min_src_y = crop_box.top;
+    let max_src_y = (crop_box.top + crop_box.height).ceil() as u32 - 1;
+    let y_in_tab = (0..dst_height).map(|_| {
+        let y = (y_in.max(min_src_y) as u32).min(max_src_y);
+        y_in += y_scale;
+        y
+    });
+
+    let mut prev_row: Option<(u32, &[P])> = None;
+    for (out_row, y) in dst_image.iter_rows_mut().zip(y_in_tab) {
+        let out_row: &mut [P] = out_row;
+        match prev_row {
+            Some((prev_y, prev)) if prev_y == y => out_row.copy_from_slice(prev),
+            _ => {
+                let in_row = match src_image.get_row(y) {
+                    Some(row) => row,
+                    None => return,
+                };
+                gather_row(in_row, out_row, &x_in_tab);
+            }
+        }
+        prev_row = Some((y, out_row));
+    }
+}
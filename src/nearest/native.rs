@@ -0,0 +1,6 @@
+pub(super) fn gather_row<P: Copy>(src_row: &[P], dst_row: &mut [P], x_indexes: &[u32]) {
+    for (&x, dst_pixel) in x_indexes.iter().zip(dst_row.iter_mut()) {
+        // Safety of value of x guaranteed by algorithm of creating of x_indexes
+        *dst_pixel = unsafe { *src_row.get_unchecked(x as usize) };
+    }
+}
@@ -1,14 +1,25 @@
 //! Contains types of pixels.
-use std::mem::size_of;
+use core::mem::{align_of, size_of};
+use core::num::NonZeroU32;
+
+#[cfg(feature = "half")]
+use half::f16;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PixelType {
     U8x3,
     U8x4,
     U16x3,
+    U16x4,
     I32,
     F32,
+    F32x4,
     U8,
+    U8x2,
+    U16x2,
+    I16,
+    #[cfg(feature = "half")]
+    F16,
 }
 
 impl PixelType {
@@ -16,22 +27,98 @@ impl PixelType {
         match self {
             Self::U8x3 => 3,
             Self::U16x3 => 6,
+            Self::U16x4 => 8,
+            Self::F32x4 => 16,
             Self::U8 => 1,
+            Self::U8x2 => 2,
+            Self::I16 => 2,
+            #[cfg(feature = "half")]
+            Self::F16 => 2,
             _ => 4,
         }
     }
 
-    /// Returns `true` is given buffer is aligned by the alignment of pixel.
-    pub(crate) fn is_aligned(&self, buffer: &[u8]) -> bool {
+    /// Returns `true` if pixels have alpha channel.
+    pub(crate) fn has_alpha(&self) -> bool {
+        matches!(
+            self,
+            Self::U8x2 | Self::U8x4 | Self::U16x2 | Self::U16x4 | Self::F32x4
+        )
+    }
+
+    /// Returns `true` if components of pixels are floating point numbers
+    /// or `i32` values, which can hold unnormalized sums of convolution.
+    pub(crate) fn has_unbounded_components(&self) -> bool {
         match self {
-            Self::U8x3 => unsafe { buffer.align_to::<U8x3>().0.is_empty() },
-            Self::U8x4 => unsafe { buffer.align_to::<U8x4>().0.is_empty() },
-            Self::U16x3 => unsafe { buffer.align_to::<U16x3>().0.is_empty() },
-            Self::I32 => unsafe { buffer.align_to::<I32>().0.is_empty() },
-            Self::F32 => unsafe { buffer.align_to::<F32>().0.is_empty() },
-            Self::U8 => true,
+            Self::I32 | Self::F32 | Self::F32x4 => true,
+            #[cfg(feature = "half")]
+            Self::F16 => true,
+            _ => false,
         }
     }
+
+    /// Returns size in bytes of buffer for image with given dimensions
+    /// or `None` if it doesn't fit into `usize`.
+    ///
+    /// It is the length of buffer required by [Image::from_slice_u8] and
+    /// [ImageView::from_buffer]. Besides the length, the buffer must be
+    /// aligned by the alignment of pixel components (e.g. `u16` for `U16x3`).
+    ///
+    /// [Image::from_slice_u8]: crate::Image::from_slice_u8
+    /// [ImageView::from_buffer]: crate::ImageView::from_buffer
+    pub fn buffer_len(&self, width: NonZeroU32, height: NonZeroU32) -> Option<usize> {
+        pixels_count(width, height)?.checked_mul(self.size())
+    }
+
+    /// Returns length of buffer of `u32` values for image with given
+    /// dimensions or `None` if it doesn't fit into `usize`. Pixels smaller
+    /// than `u32` use one `u32` value per pixel.
+    pub(crate) fn u32_buffer_len(&self, width: NonZeroU32, height: NonZeroU32) -> Option<usize> {
+        pixels_count(width, height)?.checked_mul((self.size() / 4).max(1))
+    }
+
+    /// Returns alignment in bytes of buffer with pixels of this type
+    /// (the alignment of pixel components, e.g. 2 for `U16x3`).
+    ///
+    /// Buffers passed into [Image::from_slice_u8] and [Image::from_vec_u8]
+    /// must be aligned by this value, otherwise
+    /// [ImageBufferError::InvalidBufferAlignment] is returned.
+    /// The alignment is never greater than 4 bytes, so buffers
+    /// of `u32` values are aligned for all types of pixels.
+    ///
+    /// [Image::from_slice_u8]: crate::Image::from_slice_u8
+    /// [Image::from_vec_u8]: crate::Image::from_vec_u8
+    /// [ImageBufferError::InvalidBufferAlignment]: crate::ImageBufferError::InvalidBufferAlignment
+    pub fn required_alignment(&self) -> usize {
+        match self {
+            Self::U8x3 => align_of::<U8x3>(),
+            Self::U8x4 => align_of::<U8x4>(),
+            Self::U16x3 => align_of::<U16x3>(),
+            Self::U16x4 => align_of::<U16x4>(),
+            Self::I32 => align_of::<I32>(),
+            Self::F32 => align_of::<F32>(),
+            Self::F32x4 => align_of::<F32x4>(),
+            Self::U8 => align_of::<U8>(),
+            Self::U8x2 => align_of::<U8x2>(),
+            Self::U16x2 => align_of::<U16x2>(),
+            Self::I16 => align_of::<I16>(),
+            #[cfg(feature = "half")]
+            Self::F16 => align_of::<F16>(),
+        }
+    }
+
+    /// Returns `true` is given buffer is aligned by the alignment of pixel.
+    pub(crate) fn is_aligned(&self, buffer: &[u8]) -> bool {
+        // Alignment is always a power of two.
+        buffer.as_ptr() as usize & (self.required_alignment() - 1) == 0
+    }
+}
+
+/// Returns count of pixels in image with given dimensions
+/// or `None` if it doesn't fit into `usize`.
+#[inline]
+pub(crate) fn pixels_count(width: NonZeroU32, height: NonZeroU32) -> Option<usize> {
+    (width.get() as usize).checked_mul(height.get() as usize)
 }
 
 /// Additional information about pixel type.
@@ -70,6 +157,12 @@ macro_rules! pixel_struct {
 }
 
 pixel_struct!(U8, u8, PixelType::U8, "One byte per pixel");
+pixel_struct!(
+    U8x2,
+    [u8; 2],
+    PixelType::U8x2,
+    "Two bytes per pixel (e.g. luma with alpha)"
+);
 pixel_struct!(
     U8x3,
     [u8; 3],
@@ -82,11 +175,32 @@ pixel_struct!(
     PixelType::U8x4,
     "Four bytes per pixel (RGBA, RGBx, CMYK and other)"
 );
+pixel_struct!(
+    U16x2,
+    [u16; 2],
+    PixelType::U16x2,
+    "Two `u16` components per pixel (e.g. luma with alpha)"
+);
 pixel_struct!(
     U16x3,
     [u16; 3],
     PixelType::U16x3,
     "Three `u16` components per pixel (e.g. RGB)"
 );
+pixel_struct!(
+    U16x4,
+    [u16; 4],
+    PixelType::U16x4,
+    "Four `u16` components per pixel (e.g. RGBA)"
+);
+pixel_struct!(I16, i16, PixelType::I16, "One `i16` component per pixel");
 pixel_struct!(I32, i32, PixelType::I32, "One `i32` component per pixel");
 pixel_struct!(F32, f32, PixelType::F32, "One `f32` component per pixel");
+#[cfg(feature = "half")]
+pixel_struct!(F16, f16, PixelType::F16, "One `f16` component per pixel");
+pixel_struct!(
+    F32x4,
+    [f32; 4],
+    PixelType::F32x4,
+    "Four `f32` components per pixel (e.g. RGBA)"
+);
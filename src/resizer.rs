@@ -1,44 +1,246 @@
-use std::num::NonZeroU32;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
 
-use crate::convolution::{self, Convolution, FilterType};
-use crate::errors::DifferentTypesOfPixelsError;
-use crate::image::InnerImage;
-use crate::image_view::{ImageView, ImageViewMut, TypedImageView, TypedImageViewMut};
-use crate::pixels::{Pixel, PixelType};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
+use crate::alpha::{clamp_by_alpha, MulDiv};
+use crate::anti_ringing;
+use crate::bilinear;
+use crate::convolution::{
+    self, BoundaryMode, CoefficientsOptions, Convolution, CustomFilter2D, FilterType, RoundingMode,
+};
+use crate::convolution_2d::{self, Components};
+use crate::errors::{
+    ConvolutionError, DifferentTypesOfPixelsError, ResizeError, ResizeIntoAllocatedError,
+    ResizePremultipliedError, ResizePreparedError, ResizeRegionError, ResizeWithProgressError,
+    UnsupportedAlgorithmError, UnsupportedConversionError, UnsupportedPixelTypeError,
+};
+use crate::fit::{fit_size, scale_size};
+use crate::gamma;
+use crate::image::{dither_u16x3_into_u8x3, pixels_converter, rec709_luma, Image, InnerImage};
+use crate::image_view::{CropBox, ImageView, ImageViewMut, TypedImageView, TypedImageViewMut};
+use crate::integral_image;
+use crate::nearest;
+use crate::pixels::{Pixel, PixelType, U16x3, U16x4, U8x3, U8x4};
+use crate::sharpen::UnsharpMask;
+
+/// SIMD extensions of CPU used to speed up resizing and
+/// multiplying/dividing by alpha channel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CpuExtensions {
+    /// Disables all SIMD optimisations. Every operation is performed by
+    /// native Rust-code, so results are the same on any CPU.
     None,
     #[cfg(target_arch = "x86_64")]
     Sse4_1,
     #[cfg(target_arch = "x86_64")]
     Avx2,
+    #[cfg(target_arch = "x86_64")]
+    Avx512,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+    #[cfg(target_arch = "wasm32")]
+    Simd128,
 }
 
-impl Default for CpuExtensions {
-    #[cfg(target_arch = "x86_64")]
-    fn default() -> Self {
-        if is_x86_feature_detected!("avx2") {
-            Self::Avx2
-        } else if is_x86_feature_detected!("sse4.1") {
-            Self::Sse4_1
-        } else {
-            Self::None
+impl CpuExtensions {
+    /// Returns the best variant of CPU-extensions that is supported
+    /// by the current CPU.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            for cpu_extensions in [Self::Avx512, Self::Avx2, Self::Sse4_1] {
+                if cpu_extensions.is_supported() {
+                    return cpu_extensions;
+                }
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if Self::Neon.is_supported() {
+                return Self::Neon;
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            if Self::Simd128.is_supported() {
+                return Self::Simd128;
+            }
+        }
+        Self::None
+    }
+
+    /// Returns this variant if it is supported by the current CPU,
+    /// otherwise the best supported variant that is weaker than this one.
+    fn supported_fallback(self) -> Self {
+        let variants = [
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx512,
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2,
+            #[cfg(target_arch = "x86_64")]
+            Self::Sse4_1,
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon,
+            #[cfg(target_arch = "wasm32")]
+            Self::Simd128,
+            Self::None,
+        ];
+        variants
+            .into_iter()
+            .skip_while(|&variant| variant != self)
+            .find(|variant| variant.is_supported())
+            .unwrap_or(Self::None)
+    }
+
+    /// Returns `true` if the current CPU supports this variant of
+    /// CPU-extensions.
+    ///
+    /// Without feature `std` CPU-extensions can't be detected in runtime,
+    /// so only extensions enabled during compilation are supported.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::None => true,
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            Self::Sse4_1 => is_x86_feature_detected!("sse4.1"),
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            Self::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            Self::Avx512 => {
+                is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512bw")
+                    && is_x86_feature_detected!("avx512vl")
+            }
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            Self::Sse4_1 => cfg!(target_feature = "sse4.1"),
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            Self::Avx2 => cfg!(target_feature = "avx2"),
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            Self::Avx512 => {
+                cfg!(target_feature = "avx512f")
+                    && cfg!(target_feature = "avx512bw")
+                    && cfg!(target_feature = "avx512vl")
+            }
+            #[cfg(all(target_arch = "aarch64", feature = "std"))]
+            Self::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+            Self::Neon => cfg!(target_feature = "neon"),
+            // WebAssembly doesn't support runtime detection of features,
+            // so SIMD is used only if it was enabled during compilation.
+            #[cfg(target_arch = "wasm32")]
+            Self::Simd128 => cfg!(target_feature = "simd128"),
         }
     }
+}
 
-    #[cfg(not(target_arch = "x86_64"))]
+impl Default for CpuExtensions {
+    /// Returns result of [CpuExtensions::detect].
     fn default() -> Self {
-        Self::None
+        Self::detect()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[non_exhaustive]
 pub enum ResizeAlg {
     Nearest,
     Convolution(FilterType),
+    /// Convolution with separate filters for horizontal and vertical
+    /// passes, e.g. to use a sharper filter horizontally than vertically
+    /// for anamorphic or interlaced content.
+    ConvolutionPerAxis {
+        horizontal: FilterType,
+        vertical: FilterType,
+    },
     SuperSampling(FilterType, u8),
+    /// Averages source pixels covered by each destination pixel with
+    /// help of summed-area table (integral image). It is fast for large
+    /// downscale ratios. On upscaling, it works like `Nearest`.
+    /// Only `U8x3` and `U8x4` pixels are supported,
+    /// images with other types of pixels or with fractional bounds of
+    /// the crop box are resized by `Convolution(FilterType::Box)`.
+    IntegralImage,
+    /// Bilinear interpolation between 4 nearest source pixels computed
+    /// directly, without generic convolution. Results equal to results of
+    /// `Convolution(FilterType::Bilinear)` up to rounding. It is used only
+    /// for `U8x4` images if the crop box of source image has integer bounds
+    /// and is not larger than destination image in both dimensions;
+    /// in other cases images are resized by `Convolution(FilterType::Bilinear)`.
+    Bilinear,
+    /// Convolution with a filter selected for each axis by the ratio of
+    /// reduction of the image along this axis: `FilterType::Box` if size of
+    /// the crop box of source image is more than `threshold` times larger
+    /// than size of destination image, otherwise `FilterType::Lanczos3`.
+    /// Box filter is much faster on large reductions, where the difference
+    /// in quality is negligible.
+    ///
+    /// Use [ResizeAlg::adaptive()] to create it with the default threshold
+    /// [ResizeAlg::ADAPTIVE_THRESHOLD].
+    Adaptive {
+        threshold: f64,
+    },
+    /// Convolution with non-separable filter in one pass instead of
+    /// horizontal and vertical passes. It is much slower than `Convolution`,
+    /// because time of resizing is proportional to the area of the filter
+    /// instead of its width and height, so use it only for filters that
+    /// can't be split into passes (e.g. a radial blur). Pixels are
+    /// processed in one thread without SIMD and rounded to nearest values
+    /// regardless of rounding mode of the resizer.
+    ///
+    /// This variant can't be serialized or deserialized
+    /// with help of `serde`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Convolution2D(CustomFilter2D),
+}
+
+impl ResizeAlg {
+    /// Default threshold of reduction ratio of [ResizeAlg::Adaptive].
+    pub const ADAPTIVE_THRESHOLD: f64 = 4.;
+
+    /// Returns [ResizeAlg::Adaptive] with the default threshold.
+    pub fn adaptive() -> Self {
+        Self::Adaptive {
+            threshold: Self::ADAPTIVE_THRESHOLD,
+        }
+    }
+
+    /// Returns filter used by [ResizeAlg::Adaptive] with the given
+    /// `threshold` for the axis with the given reduction `ratio`
+    /// (size of source image divided by size of destination image).
+    pub fn adaptive_filter(threshold: f64, ratio: f64) -> FilterType {
+        if ratio > threshold {
+            FilterType::Box
+        } else {
+            FilterType::Lanczos3
+        }
+    }
+
+    /// Returns filters of horizontal and vertical passes if the algorithm
+    /// is convolution, `crop_size` is size of crop box of source image.
+    pub(crate) fn convolution_filters(
+        &self,
+        crop_size: (f64, f64),
+        dst_size: (NonZeroU32, NonZeroU32),
+    ) -> Option<(FilterType, FilterType)> {
+        match *self {
+            Self::Convolution(filter_type) => Some((filter_type, filter_type)),
+            Self::ConvolutionPerAxis {
+                horizontal,
+                vertical,
+            } => Some((horizontal, vertical)),
+            Self::Adaptive { threshold } => Some((
+                Self::adaptive_filter(threshold, crop_size.0 / dst_size.0.get() as f64),
+                Self::adaptive_filter(threshold, crop_size.1 / dst_size.1.get() as f64),
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl Default for ResizeAlg {
@@ -47,13 +249,96 @@ impl Default for ResizeAlg {
     }
 }
 
+/// Defines how [Resizer] handles the alpha channel of images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum AlphaMode {
+    /// Alpha channel is resized as any other channel.
+    ///
+    /// Use this mode for images with four channels of color without
+    /// alpha channel, e.g. CMYK images stored as `U8x4` pixels (with
+    /// disabled gamma correction, which treats the last channel
+    /// as alpha channel too).
+    #[default]
+    None,
+    /// Source image is multiplied by alpha channel before resizing and
+    /// destination image is divided by alpha channel after it. It prevents
+    /// bleeding of color of transparent pixels into opaque ones.
+    ///
+    /// It is applied only to images with pixel types supported by
+    /// [MulDiv], other images are resized as with [AlphaMode::None].
+    Premultiplied,
+    /// Source image is already multiplied by alpha channel and destination
+    /// image stays premultiplied. Convolution is linear, so premultiplied
+    /// pixels are resized as is, without rounding loss of dividing and
+    /// multiplying by alpha channel. Color channels of destination image
+    /// are clamped by its alpha channel, because sharp filters may
+    /// overshoot near edges.
+    ///
+    /// It is applied only to images with pixel types supported by
+    /// [MulDiv], other images are resized as with [AlphaMode::None].
+    AlreadyPremultiplied,
+}
+
 /// Methods of this structure used to resize images.
-#[derive(Default, Debug, Clone)]
+///
+/// `Resizer` is `Send` and `Sync`: it doesn't have an interior mutability
+/// and its internal buffers are owned by it. So an instance of `Resizer`
+/// may be moved into another thread, e.g. to keep a pool of resizers
+/// with one instance per worker thread.
+///
+/// Clone of `Resizer` has the same settings as the original instance,
+/// but doesn't copy its internal buffers, they are allocated anew
+/// on demand.
+#[derive(Default, Debug)]
 pub struct Resizer {
     pub algorithm: ResizeAlg,
     cpu_extensions: CpuExtensions,
+    gamma_correction: bool,
+    nearest_offset: (f32, f32),
+    num_threads: usize,
+    alpha_mode: AlphaMode,
+    rounding_mode: RoundingMode,
+    skip_normalization: bool,
+    dithering: bool,
+    max_filter_radius: Option<NonZeroU32>,
+    boundary_mode: BoundaryMode,
+    anti_ringing: bool,
+    unsharp_mask: Option<UnsharpMask>,
+    reference_mode: bool,
+    used_cpu_extensions: Option<CpuExtensions>,
     convolution_buffer: Vec<u8>,
     super_sampling_buffer: Vec<u8>,
+    linear_src_buffer: Vec<u8>,
+    linear_dst_buffer: Vec<u8>,
+    alpha_buffer: Vec<u32>,
+    luma_buffer: Vec<u8>,
+    conversion_buffer: Vec<u32>,
+    sharpen_buffer: Vec<u32>,
+}
+
+impl Clone for Resizer {
+    fn clone(&self) -> Self {
+        Self {
+            algorithm: self.algorithm,
+            cpu_extensions: self.cpu_extensions,
+            gamma_correction: self.gamma_correction,
+            nearest_offset: self.nearest_offset,
+            num_threads: self.num_threads,
+            alpha_mode: self.alpha_mode,
+            rounding_mode: self.rounding_mode,
+            skip_normalization: self.skip_normalization,
+            dithering: self.dithering,
+            max_filter_radius: self.max_filter_radius,
+            boundary_mode: self.boundary_mode,
+            anti_ringing: self.anti_ringing,
+            unsharp_mask: self.unsharp_mask,
+            reference_mode: self.reference_mode,
+            ..Default::default()
+        }
+    }
 }
 
 impl Resizer {
@@ -72,8 +357,9 @@ impl Resizer {
     /// the result to the latter's pixel buffer.
     ///
     /// This method doesn't multiply source image and doesn't divide
-    /// destination image by alpha channel.
-    /// You must use [MulDiv](crate::MulDiv) for these actions.
+    /// destination image by alpha channel unless it is enabled by
+    /// [Resizer::set_alpha_mode].
+    /// Otherwise you must use [MulDiv](crate::MulDiv) for these actions.
     pub fn resize(
         &mut self,
         src_image: &ImageView,
@@ -82,280 +368,2465 @@ impl Resizer {
         if src_image.pixel_type() != dst_image.pixel_type() {
             return Err(DifferentTypesOfPixelsError);
         }
-        match src_image.pixel_type() {
-            PixelType::U8x3 => {
-                if let Some(src_rows) = src_image.u8x3_image() {
-                    if let Some(dst_rows) = dst_image.u8x3_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-            PixelType::U8x4 => {
-                if let Some(src_rows) = src_image.u8x4_image() {
-                    if let Some(dst_rows) = dst_image.u8x4_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-            PixelType::U16x3 => {
-                if let Some(src_rows) = src_image.u16x3_image() {
-                    if let Some(dst_rows) = dst_image.u16x3_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-            PixelType::I32 => {
-                if let Some(src_rows) = src_image.i32_image() {
-                    if let Some(dst_rows) = dst_image.i32_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-            PixelType::F32 => {
-                if let Some(src_rows) = src_image.f32_image() {
-                    if let Some(dst_rows) = dst_image.f32_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-            PixelType::U8 => {
-                if let Some(src_rows) = src_image.u8_image() {
-                    if let Some(dst_rows) = dst_image.u8_image() {
-                        self.resize_inner(src_rows, dst_rows);
-                    }
-                }
-            }
-        }
+        self.resize_with(src_image, dst_image, None);
         Ok(())
     }
 
-    fn resize_inner<P>(&mut self, src_image: TypedImageView<P>, dst_image: TypedImageViewMut<P>)
-    where
-        P: Convolution,
-    {
-        match self.algorithm {
-            ResizeAlg::Nearest => resample_nearest(src_image, dst_image),
-            ResizeAlg::Convolution(filter_type) => {
-                let convolution_buffer = &mut self.convolution_buffer;
-                resample_convolution(
-                    src_image,
-                    dst_image,
-                    filter_type,
-                    self.cpu_extensions,
-                    convolution_buffer,
-                )
-            }
-            ResizeAlg::SuperSampling(filter_type, multiplicity) => {
-                let convolution_buffer = &mut self.convolution_buffer;
-                let super_sampling_buffer = &mut self.super_sampling_buffer;
-                resample_super_sampling(
-                    src_image,
-                    dst_image,
-                    filter_type,
-                    multiplicity,
-                    self.cpu_extensions,
-                    super_sampling_buffer,
-                    convolution_buffer,
-                )
-            }
+    /// Resize source image to the size of destination image like
+    /// [Resizer::resize] with multiplication of color channels of the source
+    /// image by alpha channel and division of the result by alpha channel,
+    /// regardless of [Resizer::alpha_mode].
+    ///
+    /// It replaces manual [MulDiv::multiply_alpha], resizing and
+    /// [MulDiv::divide_alpha_inplace] for borrowed views without
+    /// allocation of an intermediate image: the premultiplied copy of
+    /// the source image is stored in the internal buffer of the resizer,
+    /// which is reused by following calls.
+    ///
+    /// Supported pixel types: `U8x2`, `U8x4`, `U16x2`, `U16x4` and `F32x4`.
+    pub fn resize_premultiplied(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+    ) -> Result<(), ResizePremultipliedError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ResizePremultipliedError::DifferentTypesOfPixels);
         }
+        if !src_image.pixel_type().has_alpha() {
+            return Err(ResizePremultipliedError::UnsupportedPixelType);
+        }
+        let alpha_mode = self.alpha_mode;
+        self.alpha_mode = AlphaMode::Premultiplied;
+        self.resize_with(src_image, dst_image, None);
+        self.alpha_mode = alpha_mode;
+        Ok(())
     }
 
-    /// Returns the size of internal buffers used to store the results of
-    /// intermediate resizing steps.
-    pub fn size_of_internal_buffers(&self) -> usize {
-        (self.convolution_buffer.capacity() + self.super_sampling_buffer.capacity())
-            * std::mem::size_of::<u8>()
-    }
-
-    /// Deallocates the internal buffers used to store the results of
-    /// intermediate resizing steps.
-    pub fn reset_internal_buffers(&mut self) {
-        if self.convolution_buffer.capacity() > 0 {
-            self.convolution_buffer = Vec::new();
+    /// Resize source image to the size of destination image like
+    /// [Resizer::resize] and report progress of resizing into `callback`.
+    ///
+    /// `callback` receives the fraction of done work (from 0 to 1) after
+    /// every processed part of rows of the image. Convolution reports
+    /// progress after each of 16 parts of rows of every pass, other
+    /// algorithms report only the end of resizing. If `callback` returns
+    /// `false`, resizing is stopped and [ResizeWithProgressError::Cancelled]
+    /// is returned. Content of the destination image is unspecified in
+    /// this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use fast_image_resize as fr;
+    ///
+    /// let width = NonZeroU32::new(256).unwrap();
+    /// let height = NonZeroU32::new(256).unwrap();
+    /// let src_image = fr::Image::new(width, height, fr::PixelType::U8);
+    /// let mut dst_image = fr::Image::new(
+    ///     NonZeroU32::new(100).unwrap(),
+    ///     NonZeroU32::new(100).unwrap(),
+    ///     fr::PixelType::U8,
+    /// );
+    /// let mut resizer = fr::Resizer::default();
+    /// let mut last_fraction = 0.;
+    /// resizer
+    ///     .resize_with_progress(&src_image.view(), &mut dst_image.view_mut(), &mut |fraction| {
+    ///         last_fraction = fraction;
+    ///         true
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(last_fraction, 1.);
+    /// ```
+    pub fn resize_with_progress(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        callback: &mut dyn FnMut(f32) -> bool,
+    ) -> Result<(), ResizeWithProgressError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ResizeWithProgressError::DifferentTypesOfPixels);
         }
-        if self.super_sampling_buffer.capacity() > 0 {
-            self.super_sampling_buffer = Vec::new();
+        let mut progress = Progress::new(callback);
+        self.resize_with_tracking(src_image, dst_image, None, &mut progress);
+        progress.finish();
+        if progress.cancelled {
+            return Err(ResizeWithProgressError::Cancelled);
         }
+        Ok(())
     }
 
-    #[inline(always)]
-    pub fn cpu_extensions(&self) -> CpuExtensions {
-        self.cpu_extensions
-    }
-
-    /// # Safety
-    /// This is unsafe because this method allows you to set a CPU-extensions
-    /// that is not actually supported by your CPU.
-    pub unsafe fn set_cpu_extensions(&mut self, extensions: CpuExtensions) {
-        self.cpu_extensions = extensions;
+    /// Resize source image to the size of destination image with help of
+    /// coefficients precomputed by [PreparedResize] and save the result to
+    /// the latter's pixel buffer.
+    ///
+    /// Convolution with filter of `prepared` is used regardless of
+    /// algorithm of the resizer. Returns an error if options of the resizer
+    /// that affect coefficients (boundary mode, maximal radius of filter
+    /// and normalization of weights) are not equal to ones used to
+    /// create `prepared` (see [PreparedResize::with_resizer]).
+    pub fn resize_prepared(
+        &mut self,
+        prepared: &PreparedResize,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+    ) -> Result<(), ResizePreparedError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ResizePreparedError::DifferentTypesOfPixels);
+        }
+        let params = ResizeParams::new(src_image, dst_image.width(), dst_image.height());
+        if params != prepared.params
+            || self.coefficients_options(src_image.pixel_type()) != prepared.options
+        {
+            return Err(ResizePreparedError::DifferentParams);
+        }
+        self.resize_with(src_image, dst_image, Some(&prepared.coefficients));
+        Ok(())
     }
-}
 
-fn get_temp_image_from_buffer<P: Pixel>(
-    buffer: &mut Vec<u8>,
-    width: NonZeroU32,
-    height: NonZeroU32,
-) -> InnerImage<P> {
-    let pixels_count = (width.get() * height.get()) as usize;
-    // Add pixel size as gap for alignment of resulted buffer.
-    let buf_size = pixels_count * P::size() + P::size();
-    if buffer.len() < buf_size {
-        buffer.resize(buf_size, 0);
+    /// Resize source image into the given size and save the result to
+    /// pixel buffer of pre-allocated destination image.
+    ///
+    /// Unlike [resize()], this method never allocates a new destination
+    /// image, so the same image may be reused to resize many frames.
+    /// Returns an error if size of `dst_image` is not equal to the given
+    /// size or its pixel type is not equal to pixel type of source image.
+    pub fn resize_into_allocated(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut Image,
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+    ) -> Result<(), ResizeIntoAllocatedError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ResizeIntoAllocatedError::DifferentTypesOfPixels);
+        }
+        if dst_image.width() != dst_width || dst_image.height() != dst_height {
+            return Err(ResizeIntoAllocatedError::DifferentSize);
+        }
+        self.resize_with(src_image, &mut dst_image.view_mut(), None);
+        Ok(())
     }
-    let pixels = unsafe { buffer.align_to_mut::<P>().1 };
-    InnerImage::new(width, height, &mut pixels[0..pixels_count])
-}
 
-fn resample_nearest<P>(src_image: TypedImageView<P>, mut dst_image: TypedImageViewMut<P>)
-where
-    P: Pixel,
-{
-    let crop_box = src_image.crop_box();
-    let dst_width = dst_image.width().get();
-    let x_scale = crop_box.width.get() as f64 / dst_width as f64;
-    let y_scale = crop_box.height.get() as f64 / dst_image.height().get() as f64;
+    /// Resizes every source image into a new image with the given size
+    /// and the same type of pixels.
+    ///
+    /// If the algorithm of the resizer is convolution, coefficients of
+    /// convolution are computed once for all source images with the same
+    /// size and crop box, and internal buffers of the resizer are reused
+    /// for all images. Results are the same as results of [Resizer::resize]
+    /// of every image.
+    ///
+    /// If feature `rayon` is enabled and the number of threads of
+    /// the resizer is `0` (see [Resizer::set_num_threads]), images are
+    /// resized in parallel by threads of the current rayon thread pool
+    /// with help of clones of the resizer.
+    pub fn resize_batch(
+        &mut self,
+        src_images: &[Image],
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+    ) -> Vec<Image<'static>> {
+        // Unique coefficients and index of coefficients for every image.
+        let mut coefficients: Vec<(ResizeParams, bool, ConvolutionCoefficients)> = Vec::new();
+        let mut indexes: Vec<Option<usize>> = Vec::with_capacity(src_images.len());
+        for src_image in src_images {
+            let src_view = src_image.view();
+            let crop_box = src_view.crop_box();
+            let filters = self
+                .algorithm
+                .convolution_filters((crop_box.width, crop_box.height), (dst_width, dst_height));
+            let index = filters.map(|filters| {
+                let params = ResizeParams::new(&src_view, dst_width, dst_height);
+                let normalize = self.normalizes_weights(src_image.pixel_type());
+                coefficients
+                    .iter()
+                    .position(|(p, n, _)| *p == params && *n == normalize)
+                    .unwrap_or_else(|| {
+                        let options = self.coefficients_options(src_image.pixel_type());
+                        let coeffs = ConvolutionCoefficients::new(&params, filters, options);
+                        coefficients.push((params, normalize, coeffs));
+                        coefficients.len() - 1
+                    })
+            });
+            indexes.push(index);
+        }
 
-    // Pretabulate horizontal pixel positions
-    let x_in_start = crop_box.left as f64 + x_scale * 0.5;
-    let max_src_x = src_image.width().get() as usize;
-    let x_in_tab: Vec<usize> = (0..dst_width)
-        .map(|x| ((x_in_start + x_scale * x as f64) as usize).min(max_src_x))
-        .collect();
+        let resize = |resizer: &mut Resizer, src_image: &Image, index: &Option<usize>| {
+            let mut dst_image = Image::new(dst_width, dst_height, src_image.pixel_type());
+            let coeffs = index.map(|i| &coefficients[i].2);
+            resizer.resize_with(&src_image.view(), &mut dst_image.view_mut(), coeffs);
+            dst_image
+        };
 
-    let y_in_start = crop_box.top as f64 + y_scale * 0.5;
+        #[cfg(feature = "rayon")]
+        if self.num_threads == 0 {
+            use rayon::prelude::*;
 
-    let src_rows =
-        src_image.iter_rows_with_step(y_in_start, y_scale, dst_image.height().get() as usize);
-    let dst_rows = dst_image.iter_rows_mut();
-    for (out_row, in_row) in dst_rows.zip(src_rows) {
-        for (&x_in, out_pixel) in x_in_tab.iter().zip(out_row.iter_mut()) {
-            // Safety of value of x_in guaranteed by algorithm of creating of x_in_tab
-            *out_pixel = unsafe { *in_row.get_unchecked(x_in) };
+            return src_images
+                .par_iter()
+                .zip(&indexes)
+                .map_init(
+                    || self.clone(),
+                    |resizer, (src_image, index)| resize(resizer, src_image, index),
+                )
+                .collect();
         }
+        src_images
+            .iter()
+            .zip(&indexes)
+            .map(|(src_image, index)| resize(self, src_image, index))
+            .collect()
     }
-}
-
-fn resample_convolution<P>(
-    src_image: TypedImageView<P>,
-    dst_image: TypedImageViewMut<P>,
-    filter_type: FilterType,
-    cpu_extensions: CpuExtensions,
-    temp_buffer: &mut Vec<u8>,
-) where
-    P: Convolution,
-{
-    let crop_box = src_image.crop_box();
-    let dst_width = dst_image.width();
-    let dst_height = dst_image.height();
-    let (filter_fn, filter_support) = convolution::get_filter_func(filter_type);
-
-    let need_horizontal = dst_width != src_image.width() || crop_box.width != src_image.width();
-    let need_vertical = dst_height != src_image.height() || crop_box.height != src_image.height();
-
-    let mut vert_coeffs = convolution::precompute_coefficients(
-        src_image.height(),
-        crop_box.top as f64,
-        crop_box.top as f64 + crop_box.height.get() as f64,
-        dst_height,
-        filter_fn,
-        filter_support,
-    );
 
-    if need_horizontal {
-        let horiz_coeffs = convolution::precompute_coefficients(
-            src_image.width(),
-            crop_box.left as f64,
-            crop_box.left as f64 + crop_box.width.get() as f64,
-            dst_width,
-            filter_fn,
-            filter_support,
-        );
+    /// Runs only the horizontal pass of convolution and returns
+    /// the intermediate image with width `dst_width` and height of
+    /// the source image.
+    ///
+    /// Left and width of the crop box of source image are used, its top
+    /// and height are ignored. They should be set into the crop box of
+    /// the intermediate image before [Resizer::resize_vertically].
+    /// The intermediate image may be cached and used to resize
+    /// the source image into several heights.
+    ///
+    /// Alpha mode and gamma correction of the resizer are applied to each
+    /// pass separately. With [AlphaMode::None] and disabled gamma
+    /// correction, the result of both passes is equal to the result of
+    /// [Resizer::resize].
+    ///
+    /// Only [ResizeAlg::Convolution], [ResizeAlg::ConvolutionPerAxis]
+    /// and [ResizeAlg::Adaptive] algorithms are supported.
+    pub fn resize_horizontally(
+        &mut self,
+        src_image: &ImageView,
+        dst_width: NonZeroU32,
+    ) -> Result<Image<'static>, UnsupportedAlgorithmError> {
+        let crop_box = CropBox {
+            top: 0.,
+            height: src_image.height().get() as f64,
+            ..src_image.crop_box()
+        };
+        self.resize_one_pass(src_image, crop_box, dst_width, src_image.height())
+    }
 
-        // First used row in the source image
-        let y_first = vert_coeffs.bounds[0].start;
+    /// Runs only the vertical pass of convolution and returns
+    /// the image with height `dst_height` and width of the source image.
+    ///
+    /// Top and height of the crop box of source image are used, its left
+    /// and width are ignored. See [Resizer::resize_horizontally].
+    pub fn resize_vertically(
+        &mut self,
+        src_image: &ImageView,
+        dst_height: NonZeroU32,
+    ) -> Result<Image<'static>, UnsupportedAlgorithmError> {
+        let crop_box = CropBox {
+            left: 0.,
+            width: src_image.width().get() as f64,
+            ..src_image.crop_box()
+        };
+        self.resize_one_pass(src_image, crop_box, src_image.width(), dst_height)
+    }
 
-        if need_vertical {
-            // Last used row in the source image
-            let last_y_bound = vert_coeffs.bounds.last().unwrap();
-            let y_last = last_y_bound.start + last_y_bound.size;
+    fn resize_one_pass(
+        &mut self,
+        src_image: &ImageView,
+        crop_box: CropBox,
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+    ) -> Result<Image<'static>, UnsupportedAlgorithmError> {
+        let filters = self
+            .algorithm
+            .convolution_filters((crop_box.width, crop_box.height), (dst_width, dst_height))
+            .ok_or(UnsupportedAlgorithmError)?;
+        let mut src_view = src_image.clone();
+        // Bounds of the crop box are taken from the valid crop box.
+        src_view.set_crop_box(crop_box).unwrap();
+        let params = ResizeParams::new(&src_view, dst_width, dst_height);
+        let options = self.coefficients_options(src_image.pixel_type());
+        let coeffs = ConvolutionCoefficients::new(&params, filters, options);
+        let mut dst_image = Image::new(dst_width, dst_height, src_image.pixel_type());
+        self.resize_with(&src_view, &mut dst_image.view_mut(), Some(&coeffs));
+        Ok(dst_image)
+    }
 
-            let temp_height = NonZeroU32::new(y_last - y_first).unwrap();
-            let mut temp_image = get_temp_image_from_buffer(temp_buffer, dst_width, temp_height);
-            P::horiz_convolution(
-                src_image,
-                temp_image.dst_view(),
-                y_first,
-                horiz_coeffs,
-                cpu_extensions,
-            );
+    /// Resize the region of source image to the size of destination image
+    /// and save the result to the latter's pixel buffer.
+    ///
+    /// Pixels of source image aren't copied. Unlike resizing with help of
+    /// [ImageView::set_crop_box], pixels outside the region don't affect
+    /// the result, so it is the same as the result of resizing of the
+    /// region copied into a separate image (e.g. a sprite from an atlas).
+    /// Crop box of source image is ignored.
+    pub fn resize_region(
+        &mut self,
+        src_image: &ImageView,
+        region: CropBox,
+        dst_image: &mut ImageViewMut,
+    ) -> Result<(), ResizeRegionError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ResizeRegionError::DifferentTypesOfPixels);
+        }
+        let src_region = src_image.region(region)?;
+        self.resize_with(&src_region, dst_image, None);
+        Ok(())
+    }
 
-            // Shift bounds for vertical pass
-            vert_coeffs
-                .bounds
-                .iter_mut()
-                .for_each(|b| b.start -= y_first);
-            P::vert_convolution(
-                temp_image.src_view(),
-                dst_image,
-                vert_coeffs,
-                cpu_extensions,
-            );
+    /// Resize `U8x3` or `U8x4` source image to the size of `U8` destination
+    /// image and save luma of the result to the latter's pixel buffer.
+    ///
+    /// Luma is calculated with coefficients of Rec.709 (alpha-channel of
+    /// `U8x4` image is ignored) before convolution, so the result is equal
+    /// to the result of [Image::convert] into `U8` and resizing of the
+    /// converted image, but the converted image is stored in the internal
+    /// buffer of resizer. Convolution of one channel instead of three is
+    /// faster than conversion of the resized image. Luma is calculated from
+    /// gamma-encoded values, so [Resizer::set_gamma_correction] doesn't
+    /// affect this method.
+    ///
+    /// Returns an error if source image is not `U8x3` or `U8x4` or
+    /// destination image is not `U8`.
+    pub fn resize_to_luma(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+    ) -> Result<(), UnsupportedConversionError> {
+        if dst_image.pixel_type() != PixelType::U8 {
+            return Err(UnsupportedConversionError);
+        }
+        let (width, height) = (src_image.width(), src_image.height());
+        let mut buffer = core::mem::take(&mut self.luma_buffer);
+        buffer.resize(width.get() as usize * height.get() as usize, 0);
+        let dst_rows = buffer.chunks_exact_mut(width.get() as usize);
+        if let Some(src_rows) = src_image.u8x3_image() {
+            for (src_row, dst_row) in src_rows.iter_rows(0).zip(dst_rows) {
+                for (src, dst) in src_row.iter().zip(dst_row) {
+                    let [r, g, b] = src.0;
+                    *dst = rec709_luma(r, g, b);
+                }
+            }
+        } else if let Some(src_rows) = src_image.u8x4_image() {
+            for (src_row, dst_row) in src_rows.iter_rows(0).zip(dst_rows) {
+                for (src, dst) in src_row.iter().zip(dst_row) {
+                    let [r, g, b, _] = src.0.to_ne_bytes();
+                    *dst = rec709_luma(r, g, b);
+                }
+            }
         } else {
-            P::horiz_convolution(src_image, dst_image, y_first, horiz_coeffs, cpu_extensions);
+            self.luma_buffer = buffer;
+            return Err(UnsupportedConversionError);
         }
-    } else if need_vertical {
-        P::vert_convolution(src_image, dst_image, vert_coeffs, cpu_extensions);
+
+        let mut luma_image = ImageView::from_buffer(width, height, &buffer, PixelType::U8).unwrap();
+        luma_image.set_crop_box(src_image.crop_box()).unwrap();
+        self.resize_with(&luma_image, dst_image, None);
+        self.luma_buffer = buffer;
+        Ok(())
     }
-}
 
-fn resample_super_sampling<P>(
+    /// Resize source image to the size of destination image with another
+    /// type of pixels and save the result converted into this type to the
+    /// latter's pixel buffer.
+    ///
+    /// Source image is resized once into the internal buffer of resizer
+    /// with pixels of the source type, then pixels of the result are
+    /// converted as by [Image::convert]:
+    /// - `U8x4` into `U8x3` - alpha-channel is dropped;
+    /// - `U8x3` into `U8x4` - alpha-channel is filled by 255 (opaque);
+    /// - `U8x3` into `U8` - luma is calculated with coefficients of Rec.709;
+    /// - `U16x3` into `U8x3` - channels are scaled into range `[0, 255]`
+    ///   (with optional dithering, see [Resizer::set_dithering]);
+    /// - `U8x3` into `U16x3` - channels are multiplied by 257;
+    /// - any type into the same type - the same as [Resizer::resize].
+    ///
+    /// So the result is equal to the result of resizing into an image with
+    /// pixels of the source type and converting of it, without allocation
+    /// of the intermediate image. Unlike [Resizer::resize_to_luma], luma of
+    /// `U8x3` image is calculated after resizing.
+    ///
+    /// Returns an error for other pairs of pixel types.
+    pub fn resize_with_conversion(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+    ) -> Result<(), UnsupportedConversionError> {
+        let src_pixel_type = src_image.pixel_type();
+        let convert = pixels_converter(src_pixel_type, dst_image.pixel_type())
+            .ok_or(UnsupportedConversionError)?;
+        if src_pixel_type == dst_image.pixel_type() {
+            self.resize_with(src_image, dst_image, None);
+            return Ok(());
+        }
+        let (width, height) = (dst_image.width(), dst_image.height());
+        let mut buffer = core::mem::take(&mut self.conversion_buffer);
+        let bytes_len = src_pixel_type
+            .buffer_len(width, height)
+            .expect("Size of image buffer overflows usize");
+        let buffer_len = bytes_len.div_ceil(4);
+        if buffer.len() < buffer_len {
+            buffer.resize(buffer_len, 0);
+        }
+        let bytes = &mut unsafe { buffer.align_to_mut::<u8>() }.1[..bytes_len];
+
+        let mut resized = ImageViewMut::from_buffer(width, height, bytes, src_pixel_type).unwrap();
+        self.resize_with(src_image, &mut resized, None);
+        let src_rows = bytes.chunks_exact(width.get() as usize * src_pixel_type.size());
+        let dither = self.dithering
+            && (src_pixel_type, dst_image.pixel_type()) == (PixelType::U16x3, PixelType::U8x3);
+        for (y, (src_row, dst_row)) in src_rows.zip(dst_image.rows_bytes_mut()).enumerate() {
+            if dither {
+                dither_u16x3_into_u8x3(src_row, dst_row, y);
+            } else {
+                convert(src_row, dst_row);
+            }
+        }
+        self.conversion_buffer = buffer;
+        Ok(())
+    }
+
+    /// Convolves rows of source image with help of the given coefficients
+    /// and saves the result to the destination image. It is the horizontal
+    /// pass of resizing without the vertical one.
+    ///
+    /// Row `y` of destination image is calculated from row `offset + y` of
+    /// source image. Pixel `x` of the row is calculated with help of the
+    /// chunk of `coeffs` with index `x`, so count of chunks must be equal to
+    /// width of destination image. Crop box of source image is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use fast_image_resize as fr;
+    ///
+    /// let width = NonZeroU32::new(32).unwrap();
+    /// let height = NonZeroU32::new(16).unwrap();
+    /// let src_image = fr::Image::new(width, height, fr::PixelType::U8x4);
+    /// // Horizontal-only 2x stretch
+    /// let dst_width = NonZeroU32::new(64).unwrap();
+    /// let mut dst_image = fr::Image::new(dst_width, height, fr::PixelType::U8x4);
+    /// let coeffs = fr::Coefficients::new(fr::FilterType::Lanczos3, width, dst_width);
+    /// let mut resizer = fr::Resizer::default();
+    /// resizer
+    ///     .horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs)
+    ///     .unwrap();
+    /// ```
+    pub fn horiz_convolution(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        offset: u32,
+        coeffs: &convolution::Coefficients,
+    ) -> Result<(), ConvolutionError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ConvolutionError::DifferentTypesOfPixels);
+        }
+        if coeffs.bounds.len() != dst_image.width().get() as usize {
+            return Err(ConvolutionError::InvalidCountOfChunks);
+        }
+        if !coeffs.is_in_bounds(src_image.width()) {
+            return Err(ConvolutionError::CoefficientsAreOutOfImageBoundaries);
+        }
+        if offset as u64 + dst_image.height().get() as u64 > src_image.height().get() as u64 {
+            return Err(ConvolutionError::OffsetIsOutOfImageBoundaries);
+        }
+        self.convolve_in_direction(src_image, dst_image, Direction::Horizontal(offset), coeffs);
+        Ok(())
+    }
+
+    /// Convolves columns of source image with help of the given coefficients
+    /// and saves the result to the destination image. It is the vertical
+    /// pass of resizing without the horizontal one.
+    ///
+    /// Row `y` of destination image is calculated with help of the chunk
+    /// of `coeffs` with index `y`, so count of chunks must be equal to
+    /// height of destination image. Both images must have the same width.
+    /// Crop box of source image is ignored.
+    pub fn vert_convolution(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        coeffs: &convolution::Coefficients,
+    ) -> Result<(), ConvolutionError> {
+        if src_image.pixel_type() != dst_image.pixel_type() {
+            return Err(ConvolutionError::DifferentTypesOfPixels);
+        }
+        if src_image.width() != dst_image.width() {
+            return Err(ConvolutionError::DifferentWidths);
+        }
+        if coeffs.bounds.len() != dst_image.height().get() as usize {
+            return Err(ConvolutionError::InvalidCountOfChunks);
+        }
+        if !coeffs.is_in_bounds(src_image.height()) {
+            return Err(ConvolutionError::CoefficientsAreOutOfImageBoundaries);
+        }
+        self.convolve_in_direction(src_image, dst_image, Direction::Vertical, coeffs);
+        Ok(())
+    }
+
+    /// Returns CPU-extensions supported by the current CPU that are used
+    /// instead of requested ones. SIMD isn't used in the reference mode.
+    fn supported_cpu_extensions(&self) -> CpuExtensions {
+        if self.reference_mode {
+            CpuExtensions::None
+        } else {
+            self.cpu_extensions.supported_fallback()
+        }
+    }
+
+    /// Returns CPU-extensions used for convolution. SIMD implementations
+    /// support only the default rounding mode.
+    fn convolution_cpu_extensions(&self) -> CpuExtensions {
+        if self.rounding_mode == RoundingMode::default() {
+            self.supported_cpu_extensions()
+        } else {
+            CpuExtensions::None
+        }
+    }
+
+    fn convolution_settings<P: Pixel>(&self) -> ConvolutionSettings {
+        ConvolutionSettings {
+            cpu_extensions: self.convolution_cpu_extensions(),
+            rounding_mode: self.rounding_mode,
+            num_threads: self.num_threads,
+            coefficients_options: self.coefficients_options(P::pixel_type()),
+            anti_ringing: self.anti_ringing,
+            reference: self.reference_mode,
+        }
+    }
+
+    /// Returns options of precomputing of coefficients of convolution
+    /// of images with the given type of pixels.
+    pub(crate) fn coefficients_options(&self, pixel_type: PixelType) -> CoefficientsOptions {
+        CoefficientsOptions {
+            normalize: self.normalizes_weights(pixel_type),
+            max_filter_radius: self.max_filter_radius,
+            boundary_mode: self.boundary_mode,
+        }
+    }
+
+    /// Returns `true` if weights of convolution of images with
+    /// the given type of pixels are normalized.
+    pub(crate) fn normalizes_weights(&self, pixel_type: PixelType) -> bool {
+        !self.skip_normalization || !pixel_type.has_unbounded_components()
+    }
+
+    fn convolve_in_direction(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        direction: Direction,
+        coeffs: &convolution::Coefficients,
+    ) {
+        let cpu_extensions = self.convolution_cpu_extensions();
+        let num_threads = self.num_threads;
+        let mut coeffs = coeffs.clone();
+        coeffs.rounding_mode = self.rounding_mode;
+        coeffs.reference = self.reference_mode;
+        macro_rules! convolve_typed {
+            ($typed_image:ident) => {
+                if let Some(src_rows) = src_image.$typed_image() {
+                    if let Some(dst_rows) = dst_image.$typed_image() {
+                        self.used_cpu_extensions = Some(convolve_one_pass(
+                            src_rows,
+                            dst_rows,
+                            direction,
+                            coeffs.clone(),
+                            cpu_extensions,
+                            num_threads,
+                        ));
+                    }
+                }
+            };
+        }
+        match src_image.pixel_type() {
+            PixelType::U8x3 => convolve_typed!(u8x3_image),
+            PixelType::U8x4 => convolve_typed!(u8x4_image),
+            PixelType::U16x3 => convolve_typed!(u16x3_image),
+            PixelType::U16x4 => convolve_typed!(u16x4_image),
+            PixelType::I16 => convolve_typed!(i16_image),
+            PixelType::I32 => convolve_typed!(i32_image),
+            PixelType::F32 => convolve_typed!(f32_image),
+            #[cfg(feature = "half")]
+            PixelType::F16 => convolve_typed!(f16_image),
+            PixelType::F32x4 => convolve_typed!(f32x4_image),
+            PixelType::U8x2 => convolve_typed!(u8x2_image),
+            PixelType::U16x2 => convolve_typed!(u16x2_image),
+            PixelType::U8 => convolve_typed!(u8_image),
+        }
+    }
+
+    /// Resize source image to cover the whole destination image without
+    /// distortions and save the result to the latter's pixel buffer.
+    ///
+    /// Source image is scaled to cover the destination image, and the
+    /// overflow is cropped (like `object-fit: cover` in CSS). Crop box
+    /// of source image is ignored. `centering` is used to control
+    /// the cropping position, see [ImageView::set_crop_box_to_fit_dst_size].
+    pub fn resize_to_cover(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        centering: Option<(f32, f32)>,
+    ) -> Result<(), DifferentTypesOfPixelsError> {
+        let mut src_image = src_image.clone();
+        src_image.set_crop_box_to_fit_dst_size(dst_image.width(), dst_image.height(), centering);
+        self.resize(&src_image, dst_image)
+    }
+
+    fn resize_with(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        coefficients: Option<&ConvolutionCoefficients>,
+    ) {
+        self.resize_with_tracking(src_image, dst_image, coefficients, &mut Progress::default());
+    }
+
+    fn resize_with_tracking(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) {
+        // Nearest with non-zero offset shifts pixels and non-separable
+        // filter blurs them even without resizing.
+        let shifts_pixels = match self.algorithm {
+            ResizeAlg::Nearest => self.nearest_offset != (0., 0.),
+            ResizeAlg::Convolution2D(_) => true,
+            _ => false,
+        };
+        let has_alpha = src_image.pixel_type().has_alpha();
+        if !shifts_pixels && copy_same_size(src_image, dst_image) {
+            self.used_cpu_extensions = Some(CpuExtensions::None);
+        } else if has_alpha && self.alpha_mode == AlphaMode::Premultiplied {
+            self.resize_with_alpha_multiplication(src_image, dst_image, coefficients, progress);
+        } else {
+            self.resize_image(src_image, dst_image, coefficients, progress);
+            if has_alpha && self.alpha_mode == AlphaMode::AlreadyPremultiplied {
+                clamp_by_alpha(dst_image);
+            }
+        }
+        if let Some(mask) = self.unsharp_mask {
+            if !progress.cancelled {
+                // Images with other types of pixels are not sharpened.
+                self.sharpen(dst_image, mask).ok();
+            }
+        }
+    }
+
+    /// Sharpens the image by unsharp mask: the difference between
+    /// the image and its Gaussian blur multiplied by `mask.amount()`
+    /// is added to the image.
+    ///
+    /// Supported pixel types: `U8x3` and `U8x4`. The fourth component
+    /// (alpha channel) of `U8x4` pixels isn't changed.
+    pub fn sharpen(
+        &mut self,
+        image: &mut ImageViewMut,
+        mask: UnsharpMask,
+    ) -> Result<(), UnsupportedPixelTypeError> {
+        let pixel_type = image.pixel_type();
+        if !matches!(pixel_type, PixelType::U8x3 | PixelType::U8x4) {
+            return Err(UnsupportedPixelTypeError);
+        }
+        let (width, height) = (image.width(), image.height());
+        // Size fits into usize because the image exists.
+        let bytes_len = pixel_type.buffer_len(width, height).unwrap();
+        let buffer_len = bytes_len.div_ceil(4);
+        let mut buffer = core::mem::take(&mut self.sharpen_buffer);
+        if buffer.len() < 2 * buffer_len {
+            buffer.resize(2 * buffer_len, 0);
+        }
+        let (src_buffer, tmp_buffer) = buffer[..2 * buffer_len].split_at_mut(buffer_len);
+        let src_bytes = &mut unsafe { src_buffer.align_to_mut::<u8>() }.1[..bytes_len];
+        let tmp_bytes = &mut unsafe { tmp_buffer.align_to_mut::<u8>() }.1[..bytes_len];
+
+        let row_size = width.get() as usize * pixel_type.size();
+        for (src_row, row) in src_bytes
+            .chunks_exact_mut(row_size)
+            .zip(image.rows_bytes_mut())
+        {
+            src_row.copy_from_slice(row);
+        }
+        // Blur of the image is stored into the image itself.
+        let filter_type = FilterType::Gaussian {
+            sigma: mask.radius(),
+        };
+        let src_view = ImageView::from_buffer(width, height, src_bytes, pixel_type).unwrap();
+        let mut tmp_image =
+            ImageViewMut::from_buffer(width, height, tmp_bytes, pixel_type).unwrap();
+        let coeffs = convolution::Coefficients::new(filter_type, width, width);
+        self.convolve_in_direction(&src_view, &mut tmp_image, Direction::Horizontal(0), &coeffs);
+        let tmp_view = ImageView::from_buffer(width, height, tmp_bytes, pixel_type).unwrap();
+        let coeffs = convolution::Coefficients::new(filter_type, height, height);
+        self.convolve_in_direction(&tmp_view, image, Direction::Vertical, &coeffs);
+
+        let src_rows = src_bytes.chunks_exact(row_size);
+        for (src_row, row) in src_rows.zip(image.rows_bytes_mut()) {
+            mask.sharpen_row(src_row, row, pixel_type.size());
+        }
+        self.sharpen_buffer = buffer;
+        Ok(())
+    }
+
+    fn resize_with_alpha_multiplication(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) {
+        let mul_div = self.mul_div();
+        let pixel_type = src_image.pixel_type();
+        let (width, height) = (src_image.width(), src_image.height());
+        let mut buffer = core::mem::take(&mut self.alpha_buffer);
+        // Both sizes fit into usize because the source image exists.
+        let buffer_len = pixel_type.u32_buffer_len(width, height).unwrap();
+        let bytes_len = pixel_type.buffer_len(width, height).unwrap();
+        if buffer.len() < buffer_len {
+            buffer.resize(buffer_len, 0);
+        }
+        let bytes = &mut unsafe { buffer.align_to_mut::<u8>() }.1[..bytes_len];
+
+        let mut premultiplied =
+            ImageViewMut::from_buffer(width, height, bytes, pixel_type).unwrap();
+        mul_div
+            .multiply_alpha(src_image, &mut premultiplied)
+            .unwrap();
+        let mut premultiplied = ImageView::from_buffer(width, height, bytes, pixel_type).unwrap();
+        premultiplied.set_crop_box(src_image.crop_box()).unwrap();
+        self.resize_image(&premultiplied, dst_image, coefficients, progress);
+        mul_div.divide_alpha_inplace(dst_image).unwrap();
+        self.alpha_buffer = buffer;
+    }
+
+    /// Returns [MulDiv] with CPU-extensions of the resizer.
+    pub(crate) fn mul_div(&self) -> MulDiv {
+        let mut mul_div = MulDiv::default();
+        // Safety: supported_cpu_extensions() always returns extensions
+        // supported by the current CPU.
+        unsafe { mul_div.set_cpu_extensions(self.supported_cpu_extensions()) };
+        mul_div
+    }
+
+    fn resize_image(
+        &mut self,
+        src_image: &ImageView,
+        dst_image: &mut ImageViewMut,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) {
+        // Overwritten below by the branches that can use SIMD.
+        self.used_cpu_extensions = Some(CpuExtensions::None);
+        let use_linear_light = self.gamma_correction
+            && (coefficients.is_some() || !matches!(self.algorithm, ResizeAlg::Nearest));
+        // Fast paths for integral image and bilinear interpolation
+        // support only the default rounding mode and aren't used
+        // in the reference mode.
+        let use_fast_paths = self.rounding_mode == RoundingMode::default() && !self.reference_mode;
+        match src_image.pixel_type() {
+            PixelType::U8x3 => {
+                if let Some(src_rows) = src_image.u8x3_image() {
+                    if let Some(dst_rows) = dst_image.u8x3_image() {
+                        if use_linear_light {
+                            self.resize_u8x3_in_linear_light(
+                                src_rows,
+                                dst_rows,
+                                coefficients,
+                                progress,
+                            );
+                        } else if coefficients.is_none()
+                            && use_fast_paths
+                            && matches!(self.algorithm, ResizeAlg::IntegralImage)
+                            && src_rows.crop_box().is_integral()
+                        {
+                            integral_image::resample_integral_image(src_rows, dst_rows);
+                        } else {
+                            self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                        }
+                    }
+                }
+            }
+            PixelType::U8x4 => {
+                if let Some(src_rows) = src_image.u8x4_image() {
+                    if let Some(dst_rows) = dst_image.u8x4_image() {
+                        if use_linear_light {
+                            self.resize_u8x4_in_linear_light(
+                                src_rows,
+                                dst_rows,
+                                coefficients,
+                                progress,
+                            );
+                        } else if coefficients.is_none()
+                            && use_fast_paths
+                            && matches!(self.algorithm, ResizeAlg::IntegralImage)
+                            && src_rows.crop_box().is_integral()
+                        {
+                            integral_image::resample_integral_image(src_rows, dst_rows);
+                        } else if coefficients.is_none()
+                            && matches!(self.algorithm, ResizeAlg::Nearest)
+                        {
+                            let cpu_extensions = self.supported_cpu_extensions();
+                            self.used_cpu_extensions =
+                                Some(nearest::used_cpu_extensions(cpu_extensions));
+                            nearest::resample_nearest_u8x4(
+                                src_rows,
+                                dst_rows,
+                                self.nearest_offset,
+                                cpu_extensions,
+                            );
+                        } else if coefficients.is_none()
+                            && use_fast_paths
+                            && matches!(self.algorithm, ResizeAlg::Bilinear)
+                            && bilinear::is_applicable(&src_rows, &dst_rows)
+                        {
+                            let cpu_extensions = self.supported_cpu_extensions();
+                            self.used_cpu_extensions =
+                                Some(bilinear::used_cpu_extensions(cpu_extensions));
+                            bilinear::resample_bilinear(src_rows, dst_rows, cpu_extensions);
+                        } else {
+                            self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                        }
+                    }
+                }
+            }
+            PixelType::U16x3 => {
+                if let Some(src_rows) = src_image.u16x3_image() {
+                    if let Some(dst_rows) = dst_image.u16x3_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::U16x4 => {
+                if let Some(src_rows) = src_image.u16x4_image() {
+                    if let Some(dst_rows) = dst_image.u16x4_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::I16 => {
+                if let Some(src_rows) = src_image.i16_image() {
+                    if let Some(dst_rows) = dst_image.i16_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::I32 => {
+                if let Some(src_rows) = src_image.i32_image() {
+                    if let Some(dst_rows) = dst_image.i32_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::F32 => {
+                if let Some(src_rows) = src_image.f32_image() {
+                    if let Some(dst_rows) = dst_image.f32_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            #[cfg(feature = "half")]
+            PixelType::F16 => {
+                if let Some(src_rows) = src_image.f16_image() {
+                    if let Some(dst_rows) = dst_image.f16_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::F32x4 => {
+                if let Some(src_rows) = src_image.f32x4_image() {
+                    if let Some(dst_rows) = dst_image.f32x4_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::U8x2 => {
+                if let Some(src_rows) = src_image.u8x2_image() {
+                    if let Some(dst_rows) = dst_image.u8x2_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::U16x2 => {
+                if let Some(src_rows) = src_image.u16x2_image() {
+                    if let Some(dst_rows) = dst_image.u16x2_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+            PixelType::U8 => {
+                if let Some(src_rows) = src_image.u8_image() {
+                    if let Some(dst_rows) = dst_image.u8_image() {
+                        self.resize_inner(src_rows, dst_rows, coefficients, progress);
+                    }
+                }
+            }
+        }
+    }
+
+    fn resize_inner<P>(
+        &mut self,
+        src_image: TypedImageView<P>,
+        dst_image: TypedImageViewMut<P>,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) where
+        P: Convolution + Components,
+    {
+        let settings = self.convolution_settings::<P>();
+        let cpu_extensions = settings.cpu_extensions;
+        let without_simd = matches!(
+            self.algorithm,
+            ResizeAlg::Nearest | ResizeAlg::Convolution2D(_)
+        );
+        if coefficients.is_some() || !without_simd {
+            self.used_cpu_extensions = Some(P::used_cpu_extensions(cpu_extensions));
+        }
+        if let Some(coefficients) = coefficients {
+            convolve(
+                src_image,
+                dst_image,
+                coefficients.clone(),
+                settings,
+                &mut self.convolution_buffer,
+                progress,
+            );
+            return;
+        }
+        match self.algorithm {
+            ResizeAlg::Nearest => {
+                nearest::resample_nearest(src_image, dst_image, self.nearest_offset)
+            }
+            ResizeAlg::Convolution(filter_type) => {
+                let convolution_buffer = &mut self.convolution_buffer;
+                resample_convolution(
+                    src_image,
+                    dst_image,
+                    (filter_type, filter_type),
+                    settings,
+                    convolution_buffer,
+                    progress,
+                )
+            }
+            ResizeAlg::ConvolutionPerAxis {
+                horizontal,
+                vertical,
+            } => {
+                let convolution_buffer = &mut self.convolution_buffer;
+                resample_convolution(
+                    src_image,
+                    dst_image,
+                    (horizontal, vertical),
+                    settings,
+                    convolution_buffer,
+                    progress,
+                )
+            }
+            ResizeAlg::Adaptive { .. } => {
+                let crop_box = src_image.crop_box();
+                let filters = self
+                    .algorithm
+                    .convolution_filters(
+                        (crop_box.width, crop_box.height),
+                        (dst_image.width(), dst_image.height()),
+                    )
+                    .unwrap();
+                let convolution_buffer = &mut self.convolution_buffer;
+                resample_convolution(
+                    src_image,
+                    dst_image,
+                    filters,
+                    settings,
+                    convolution_buffer,
+                    progress,
+                )
+            }
+            ResizeAlg::IntegralImage => {
+                // Only U8x3 and U8x4 images are resized with help of
+                // integral image, other types fall back to box filter.
+                let convolution_buffer = &mut self.convolution_buffer;
+                resample_convolution(
+                    src_image,
+                    dst_image,
+                    (FilterType::Box, FilterType::Box),
+                    settings,
+                    convolution_buffer,
+                    progress,
+                )
+            }
+            ResizeAlg::Bilinear => {
+                let convolution_buffer = &mut self.convolution_buffer;
+                resample_convolution(
+                    src_image,
+                    dst_image,
+                    (FilterType::Bilinear, FilterType::Bilinear),
+                    settings,
+                    convolution_buffer,
+                    progress,
+                )
+            }
+            ResizeAlg::Convolution2D(filter) => convolution_2d::resample_convolution_2d(
+                src_image,
+                dst_image,
+                filter,
+                settings.coefficients_options.normalize,
+            ),
+            ResizeAlg::SuperSampling(filter_type, multiplicity) => self.resample_super_sampling(
+                src_image,
+                dst_image,
+                filter_type,
+                multiplicity,
+                progress,
+            ),
+        }
+    }
+
+    fn resample_super_sampling<P>(
+        &mut self,
+        src_image: TypedImageView<P>,
+        dst_image: TypedImageViewMut<P>,
+        filter_type: FilterType,
+        multiplicity: u8,
+        progress: &mut Progress,
+    ) where
+        P: Convolution + Components,
+    {
+        let settings = self.convolution_settings::<P>();
+        let crop_box = src_image.crop_box();
+        let dst_width = dst_image.width().get();
+        let dst_height = dst_image.height().get();
+        let width_scale = crop_box.width as f32 / dst_width as f32;
+        let height_scale = crop_box.height as f32 / dst_height as f32;
+        // It makes sense to resize the image in two steps only if the image
+        // size is greater than the required size by multiplicity times.
+        let factor = width_scale.min(height_scale) / multiplicity as f32;
+        if factor > 1.2 {
+            // First step is resizing the source image by fastest algorithm.
+            // The temporary image will be about ``multiplicity`` times larger
+            // than required.
+            let tmp_width =
+                NonZeroU32::new((crop_box.width as f32 / factor).round() as u32).unwrap();
+            let tmp_height =
+                NonZeroU32::new((crop_box.height as f32 / factor).round() as u32).unwrap();
+
+            let mut tmp_img =
+                get_temp_image_from_buffer(&mut self.super_sampling_buffer, tmp_width, tmp_height);
+            nearest::resample_nearest(src_image, tmp_img.dst_view(), (0., 0.));
+            // Second step is resizing the temporary image with a convolution.
+            resample_convolution(
+                tmp_img.src_view(),
+                dst_image,
+                (filter_type, filter_type),
+                settings,
+                &mut self.convolution_buffer,
+                progress,
+            );
+        } else {
+            // There is no point in doing the resizing in two steps.
+            // We immediately resize the original image with a convolution.
+            resample_convolution(
+                src_image,
+                dst_image,
+                (filter_type, filter_type),
+                settings,
+                &mut self.convolution_buffer,
+                progress,
+            );
+        }
+    }
+
+    fn resize_u8x3_in_linear_light(
+        &mut self,
+        src_image: TypedImageView<U8x3>,
+        dst_image: TypedImageViewMut<U8x3>,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) {
+        let mut src_buffer = core::mem::take(&mut self.linear_src_buffer);
+        let mut dst_buffer = core::mem::take(&mut self.linear_dst_buffer);
+        let (width, height, linear_crop_box) = gamma::linear_crop_box(src_image.crop_box());
+        let mut linear_src = get_temp_image_from_buffer::<U16x3>(&mut src_buffer, width, height);
+        let mut linear_dst = get_temp_image_from_buffer::<U16x3>(
+            &mut dst_buffer,
+            dst_image.width(),
+            dst_image.height(),
+        );
+        gamma::u8x3_into_linear(src_image, linear_src.dst_view());
+        let mut linear_src_view = linear_src.src_view();
+        linear_src_view.set_crop_box_unchecked(linear_crop_box);
+        self.resize_inner(
+            linear_src_view,
+            linear_dst.dst_view(),
+            coefficients,
+            progress,
+        );
+        gamma::linear_into_u8x3(linear_dst.src_view(), dst_image);
+        self.linear_src_buffer = src_buffer;
+        self.linear_dst_buffer = dst_buffer;
+    }
+
+    fn resize_u8x4_in_linear_light(
+        &mut self,
+        src_image: TypedImageView<U8x4>,
+        dst_image: TypedImageViewMut<U8x4>,
+        coefficients: Option<&ConvolutionCoefficients>,
+        progress: &mut Progress,
+    ) {
+        let mut src_buffer = core::mem::take(&mut self.linear_src_buffer);
+        let mut dst_buffer = core::mem::take(&mut self.linear_dst_buffer);
+        let (width, height, linear_crop_box) = gamma::linear_crop_box(src_image.crop_box());
+        let mut linear_src = get_temp_image_from_buffer::<U16x4>(&mut src_buffer, width, height);
+        let mut linear_dst = get_temp_image_from_buffer::<U16x4>(
+            &mut dst_buffer,
+            dst_image.width(),
+            dst_image.height(),
+        );
+        gamma::u8x4_into_linear(src_image, linear_src.dst_view());
+        let mut linear_src_view = linear_src.src_view();
+        linear_src_view.set_crop_box_unchecked(linear_crop_box);
+        self.resize_inner(
+            linear_src_view,
+            linear_dst.dst_view(),
+            coefficients,
+            progress,
+        );
+        gamma::linear_into_u8x4(linear_dst.src_view(), dst_image);
+        self.linear_src_buffer = src_buffer;
+        self.linear_dst_buffer = dst_buffer;
+    }
+
+    /// Returns the size of internal buffers used to store the results of
+    /// intermediate resizing steps.
+    pub fn size_of_internal_buffers(&self) -> usize {
+        (self.convolution_buffer.capacity()
+            + self.super_sampling_buffer.capacity()
+            + self.linear_src_buffer.capacity()
+            + self.linear_dst_buffer.capacity()
+            + self.luma_buffer.capacity())
+            * core::mem::size_of::<u8>()
+            + (self.alpha_buffer.capacity()
+                + self.conversion_buffer.capacity()
+                + self.sharpen_buffer.capacity())
+                * core::mem::size_of::<u32>()
+    }
+
+    /// Deallocates the internal buffers used to store the results of
+    /// intermediate resizing steps.
+    pub fn reset_internal_buffers(&mut self) {
+        if self.convolution_buffer.capacity() > 0 {
+            self.convolution_buffer = Vec::new();
+        }
+        if self.super_sampling_buffer.capacity() > 0 {
+            self.super_sampling_buffer = Vec::new();
+        }
+        if self.linear_src_buffer.capacity() > 0 {
+            self.linear_src_buffer = Vec::new();
+        }
+        if self.linear_dst_buffer.capacity() > 0 {
+            self.linear_dst_buffer = Vec::new();
+        }
+        if self.alpha_buffer.capacity() > 0 {
+            self.alpha_buffer = Vec::new();
+        }
+        if self.luma_buffer.capacity() > 0 {
+            self.luma_buffer = Vec::new();
+        }
+        if self.conversion_buffer.capacity() > 0 {
+            self.conversion_buffer = Vec::new();
+        }
+        if self.sharpen_buffer.capacity() > 0 {
+            self.sharpen_buffer = Vec::new();
+        }
+    }
+
+    #[inline(always)]
+    pub fn cpu_extensions(&self) -> CpuExtensions {
+        self.cpu_extensions
+    }
+
+    /// Returns CPU-extensions that were actually used by the last
+    /// call of resizing, or `None` if nothing was resized yet.
+    ///
+    /// It may differ from [Resizer::cpu_extensions] if the requested
+    /// extensions are not supported by the current CPU or there is no
+    /// SIMD implementation for the type of pixels or the algorithm
+    /// that was used.
+    #[inline(always)]
+    pub fn used_cpu_extensions(&self) -> Option<CpuExtensions> {
+        self.used_cpu_extensions
+    }
+
+    /// Sets CPU-extensions used by resizer. Use [CpuExtensions::None]
+    /// to force native implementation without SIMD.
+    ///
+    /// If the CPU doesn't support the given extensions, the best supported
+    /// extensions that are weaker than the given ones are used instead.
+    pub fn set_cpu_extensions(&mut self, extensions: CpuExtensions) {
+        self.cpu_extensions = extensions;
+    }
+
+    #[inline(always)]
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Sets the mode of handling of the alpha channel, see [AlphaMode].
+    ///
+    /// It is [AlphaMode::None] by default, so images are resized
+    /// as is and you must use [MulDiv] to multiply and divide them
+    /// by alpha channel.
+    pub fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    #[inline(always)]
+    pub fn rounding_mode(&self) -> RoundingMode {
+        self.rounding_mode
+    }
+
+    /// Sets the mode of rounding of results of convolution of images
+    /// with integer pixels (`U8`, `U16`, `I16` and `I32` components).
+    /// It is [RoundingMode::NearestAway] by default.
+    ///
+    /// SIMD implementations support only the default rounding mode,
+    /// so images are processed by native Rust-code with other modes.
+    pub fn set_rounding_mode(&mut self, rounding_mode: RoundingMode) {
+        self.rounding_mode = rounding_mode;
+    }
+
+    #[inline(always)]
+    pub fn normalization(&self) -> bool {
+        !self.skip_normalization
+    }
+
+    /// Enables or disables normalization of weights of convolution
+    /// (enabled by default).
+    ///
+    /// By default, weights of source pixels used to calculate every
+    /// destination pixel are divided by their sum, so the result is a
+    /// weighted average of source pixels. With disabled normalization
+    /// raw values of the filter are used as weights, so the result is a
+    /// weighted sum (e.g. `FilterType::Box` sums source pixels instead of
+    /// averaging them on downscaling).
+    ///
+    /// The option affects only images with `I32`, `F32`, `F32x4` and `F16`
+    /// pixels. Other images are always resized with normalized weights,
+    /// because unnormalized sums don't fit into range of their components.
+    pub fn set_normalization(&mut self, enabled: bool) {
+        self.skip_normalization = !enabled;
+    }
+
+    #[inline(always)]
+    pub fn dithering(&self) -> bool {
+        self.dithering
+    }
+
+    /// Enables or disables dithering of the result of
+    /// [Resizer::resize_with_conversion] from `U16x3` into `U8x3` pixels
+    /// (disabled by default).
+    ///
+    /// Without dithering components are rounded to the nearest 8-bit value,
+    /// so smooth gradients of 16-bit image turn into visible bands. With
+    /// dithering components are rounded up or down by ordered dithering with
+    /// 4x4 Bayer matrix, so the average value of every area of the image is
+    /// kept. The result is deterministic and depends only on the position
+    /// of the pixel in the destination image.
+    pub fn set_dithering(&mut self, enabled: bool) {
+        self.dithering = enabled;
+    }
+
+    #[inline(always)]
+    pub fn max_filter_radius(&self) -> Option<NonZeroU32> {
+        self.max_filter_radius
+    }
+
+    /// Sets the maximal radius (half-width) of filter of convolution
+    /// in source pixels for every axis (not limited by default).
+    ///
+    /// On downscaling the filter is stretched by the ratio of sizes,
+    /// so a time of resizing grows with the ratio (e.g. `Lanczos3` uses
+    /// 6000 source pixels for every destination pixel on 1000x reduction).
+    /// The limit bounds the time of resizing of untrusted images with
+    /// extreme ratios. Radius of the filter isn't limited below support
+    /// of the filter itself.
+    ///
+    /// The filter with limited radius doesn't cover all source pixels,
+    /// so fine details of the source image produce aliasing (moire)
+    /// in the result. Smooth images are resized almost the same as without
+    /// the limit.
+    pub fn set_max_filter_radius(&mut self, radius: Option<NonZeroU32>) {
+        self.max_filter_radius = radius;
+    }
+
+    #[inline(always)]
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundary_mode
+    }
+
+    /// Sets handling of pixels outside the source image, which are covered
+    /// by filters of convolution near borders of the image
+    /// ([BoundaryMode::Clamp] by default).
+    ///
+    /// Use [BoundaryMode::Wrap] to resize tileable textures, so borders of
+    /// the result stay seamless. [BoundaryMode::Mirror] reduces artifacts
+    /// of some filters near borders. The mode affects only convolution of
+    /// the whole image, pixels outside the crop box but inside the image
+    /// are used as is.
+    pub fn set_boundary_mode(&mut self, boundary_mode: BoundaryMode) {
+        self.boundary_mode = boundary_mode;
+    }
+
+    #[inline(always)]
+    pub fn unsharp_mask(&self) -> Option<UnsharpMask> {
+        self.unsharp_mask
+    }
+
+    /// Sets unsharp mask that sharpens every result of resizing of images
+    /// with `U8x3` and `U8x4` pixels (disabled by default).
+    ///
+    /// Downscaling softens images, so sharpening of the result
+    /// restores their crispness. See [Resizer::sharpen] for details.
+    pub fn set_unsharp_mask(&mut self, mask: Option<UnsharpMask>) {
+        self.unsharp_mask = mask;
+    }
+
+    #[inline(always)]
+    pub fn reference_mode(&self) -> bool {
+        self.reference_mode
+    }
+
+    /// Enables or disables the reference mode (disabled by default).
+    ///
+    /// In the reference mode convolution of images with any type of pixels
+    /// is calculated by the same scalar code: components of source pixels
+    /// are multiplied by weights and summed up in `f64` in order of source
+    /// pixels. Sums of integer components are rounded by the rounding mode
+    /// of resizer ([RoundingMode::NearestAway] by default, i.e. half-way
+    /// cases are rounded away from zero) and saturated into the range of
+    /// the type of components, sums of float components are converted into
+    /// the nearest float value. Results of the horizontal pass are stored
+    /// into an image with the same type of pixels, so they are rounded too.
+    ///
+    /// SIMD, fixed-point arithmetic and fast paths of resizing (e.g. for
+    /// [ResizeAlg::Bilinear] and [ResizeAlg::IntegralImage]) are not used,
+    /// so results don't depend on architecture, CPU-extensions and number
+    /// of threads, which is useful for comparison with golden images.
+    /// But resizing is much slower.
+    ///
+    /// Weights of filters are calculated with help of functions of `std`
+    /// (e.g. `f64::sin()`), so results may differ if these functions give
+    /// different results on different platforms.
+    pub fn set_reference_mode(&mut self, enabled: bool) {
+        self.reference_mode = enabled;
+    }
+
+    #[inline(always)]
+    pub fn anti_ringing(&self) -> bool {
+        self.anti_ringing
+    }
+
+    /// Enables or disables clamping of results of convolution by the range
+    /// of source pixels used to calculate them (disabled by default).
+    ///
+    /// Sharp filters (e.g. `Lanczos3` or `CatmullRom`) produce halos
+    /// (ringing) near sharp edges, because their negative lobes make pixels
+    /// darker than the darkest source pixel or brighter than the brightest
+    /// one. With enabled clamping, every component of every pixel after each
+    /// pass of convolution is clamped into the range of this component of
+    /// source pixels covered by the filter. It removes halos, but makes edges
+    /// a bit softer and resizing slower, because clamping doesn't use SIMD.
+    ///
+    /// It is not applied by [Resizer::resize_by_strips] and methods
+    /// [Resizer::horiz_convolution] and [Resizer::vert_convolution].
+    pub fn set_anti_ringing(&mut self, enabled: bool) {
+        self.anti_ringing = enabled;
+    }
+
+    #[inline(always)]
+    pub fn gamma_correction(&self) -> bool {
+        self.gamma_correction
+    }
+
+    /// Enables or disables gamma correction (disabled by default).
+    ///
+    /// With enabled gamma correction images with `U8x3` and `U8x4` pixels
+    /// are treated as sRGB-encoded. Source image is converted into linear
+    /// light before convolution and result is converted back into sRGB.
+    /// Alpha channel of `U8x4` images is not gamma-encoded, so it is
+    /// resized as is. Other types of pixels and `ResizeAlg::Nearest`
+    /// algorithm are not affected by this option.
+    pub fn set_gamma_correction(&mut self, enabled: bool) {
+        self.gamma_correction = enabled;
+    }
+
+    #[inline(always)]
+    pub fn nearest_offset(&self) -> (f32, f32) {
+        self.nearest_offset
+    }
+
+    /// Sets offset (in pixels of source image) of sampling grid
+    /// used by `ResizeAlg::Nearest` algorithm (`(0.0, 0.0)` by default).
+    ///
+    /// The offset is added to positions of centers of destination pixels
+    /// projected into source image before selection of nearest source
+    /// pixels. Selected pixels are clamped by bounds of the crop box
+    /// of source image, so sampling never goes out of the crop box.
+    pub fn set_nearest_offset(&mut self, x_offset: f32, y_offset: f32) {
+        self.nearest_offset = (x_offset, y_offset);
+    }
+
+    #[inline(always)]
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Sets the number of threads used by passes of convolution
+    /// (`0` by default).
+    ///
+    /// If `num_threads` is greater than 1, rows of destination image are
+    /// split into `num_threads` strips that are processed in parallel by
+    /// scoped threads of the standard library. Value `1` forces resizing
+    /// in the current thread. With value `0` images are resized by threads
+    /// of the current rayon thread pool if feature `rayon` is enabled,
+    /// otherwise in the current thread. Without feature `std` values
+    /// greater than 1 work like `1`.
+    ///
+    /// Results don't depend on the number of threads.
+    pub fn set_num_threads(&mut self, num_threads: usize) {
+        self.num_threads = num_threads;
+    }
+}
+
+/// Resizes source image into a new image with given size and the same
+/// type of pixels.
+///
+/// New instance of [Resizer] with the best CPU-extensions supported by
+/// your CPU is used. Use [ResizeAlg::default()] if you don't know what
+/// algorithm to choose.
+///
+/// Returns [ResizeError::ZeroDimension] if width or height of destination
+/// image is zero (e.g. if it was calculated from a too small scale factor).
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use fast_image_resize as fr;
+///
+/// let src_image = fr::Image::new(
+///     NonZeroU32::new(640).unwrap(),
+///     NonZeroU32::new(480).unwrap(),
+///     fr::PixelType::U8x4,
+/// );
+/// let dst_image = fr::resize(&src_image, 320, 240, fr::ResizeAlg::default()).unwrap();
+/// assert_eq!(dst_image.width().get(), 320);
+/// assert_eq!(dst_image.pixel_type(), fr::PixelType::U8x4);
+///
+/// let result = fr::resize(&src_image, 0, 240, fr::ResizeAlg::default());
+/// assert!(matches!(result, Err(fr::ResizeError::ZeroDimension)));
+/// ```
+pub fn resize(
+    src_image: &Image,
+    dst_width: u32,
+    dst_height: u32,
+    algorithm: ResizeAlg,
+) -> Result<Image<'static>, ResizeError> {
+    let (dst_width, dst_height) = match (NonZeroU32::new(dst_width), NonZeroU32::new(dst_height)) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return Err(ResizeError::ZeroDimension),
+    };
+    let mut dst_image = Image::new(dst_width, dst_height, src_image.pixel_type());
+    let mut resizer = Resizer::new(algorithm);
+    resizer.resize_with(&src_image.view(), &mut dst_image.view_mut(), None);
+    Ok(dst_image)
+}
+
+/// Resizes source image into a new image with both dimensions multiplied
+/// by `factor` and the same type of pixels.
+///
+/// Dimensions of destination image are rounded to the nearest integer
+/// and are never less than 1. Factor greater than 1 upscales the image.
+///
+/// Returns [ResizeError::InvalidScaleFactor] if `factor` is not positive
+/// and finite.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use fast_image_resize as fr;
+///
+/// let src_image = fr::Image::new(
+///     NonZeroU32::new(640).unwrap(),
+///     NonZeroU32::new(480).unwrap(),
+///     fr::PixelType::U8x4,
+/// );
+/// let dst_image = fr::resize_by_factor(&src_image, 0.5, fr::ResizeAlg::default()).unwrap();
+/// assert_eq!(dst_image.width().get(), 320);
+/// assert_eq!(dst_image.height().get(), 240);
+/// ```
+pub fn resize_by_factor(
+    src_image: &Image,
+    factor: f64,
+    algorithm: ResizeAlg,
+) -> Result<Image<'static>, ResizeError> {
+    if !factor.is_finite() || factor <= 0. {
+        return Err(ResizeError::InvalidScaleFactor);
+    }
+    let (dst_width, dst_height) = scale_size(src_image.width(), src_image.height(), factor);
+    resize(src_image, dst_width.get(), dst_height.get(), algorithm)
+}
+
+/// Position and size of the resized source image inside of the
+/// destination image returned by [resize_letterbox].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRect {
+    pub left: u32,
+    pub top: u32,
+    pub width: NonZeroU32,
+    pub height: NonZeroU32,
+}
+
+/// Resizes source image to fit into a new image with size
+/// `dst_width`x`dst_height` without changing of aspect ratio and pads
+/// the rest of the new image by `fill_pixel` (letterboxing).
+///
+/// `fill_pixel` contains bytes of one pixel with the same type as pixels
+/// of source image. `centering` is used to control the position of
+/// the resized image inside of the new one, `None` is equal to
+/// `Some((0.5, 0.5))` that centers the image. Returns the new image and
+/// the position of the resized image inside of it.
+///
+/// Returns [ResizeError::ZeroDimension] if width or height of the new
+/// image is zero and [ResizeError::InvalidFillPixel] if size of
+/// `fill_pixel` is not equal to size of pixels of source image.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use fast_image_resize as fr;
+///
+/// let src_image = fr::Image::new(
+///     NonZeroU32::new(1920).unwrap(),
+///     NonZeroU32::new(1080).unwrap(),
+///     fr::PixelType::U8x3,
+/// );
+/// let (dst_image, rect) = fr::resize_letterbox(
+///     &src_image,
+///     640,
+///     640,
+///     &[114, 114, 114],
+///     None,
+///     fr::ResizeAlg::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(dst_image.width().get(), 640);
+/// assert_eq!(dst_image.height().get(), 640);
+/// assert_eq!((rect.left, rect.top), (0, 140));
+/// assert_eq!((rect.width.get(), rect.height.get()), (640, 360));
+/// ```
+pub fn resize_letterbox(
+    src_image: &Image,
+    dst_width: u32,
+    dst_height: u32,
+    fill_pixel: &[u8],
+    centering: Option<(f32, f32)>,
+    algorithm: ResizeAlg,
+) -> Result<(Image<'static>, ContentRect), ResizeError> {
+    let (dst_width, dst_height) = match (NonZeroU32::new(dst_width), NonZeroU32::new(dst_height)) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return Err(ResizeError::ZeroDimension),
+    };
+    let pixel_type = src_image.pixel_type();
+    if fill_pixel.len() != pixel_type.size() {
+        return Err(ResizeError::InvalidFillPixel);
+    }
+    let (width, height) = fit_size(
+        src_image.width(),
+        src_image.height(),
+        dst_width,
+        dst_height,
+        true,
+    );
+    let (center_x, center_y) = match centering {
+        Some((x, y)) => (x.clamp(0., 1.), y.clamp(0., 1.)),
+        None => (0.5, 0.5),
+    };
+    let rect = ContentRect {
+        left: ((dst_width.get() - width.get()) as f32 * center_x).round() as u32,
+        top: ((dst_height.get() - height.get()) as f32 * center_y).round() as u32,
+        width,
+        height,
+    };
+
+    let mut dst_image = Image::new(dst_width, dst_height, pixel_type);
+    let buffer = dst_image.buffer_mut();
+    for pixel in buffer.chunks_exact_mut(fill_pixel.len()) {
+        pixel.copy_from_slice(fill_pixel);
+    }
+    let row_stride = pixel_type.size() * dst_width.get() as usize;
+    let content_offset = rect.top as usize * row_stride + rect.left as usize * pixel_type.size();
+    // Offset is a multiple of the size of pixel, so the content
+    // is aligned as well as the buffer of the image.
+    let mut content = ImageViewMut::from_buffer_with_stride(
+        width,
+        height,
+        &mut buffer[content_offset..],
+        row_stride,
+        pixel_type,
+    )
+    .unwrap();
+    let mut resizer = Resizer::new(algorithm);
+    resizer.resize_with(&src_image.view(), &mut content, None);
+    Ok((dst_image, rect))
+}
+
+/// Builds a pyramid of images (mipmaps) from source image.
+///
+/// The first level is a copy of source image. Every next level is resized
+/// from the previous one by halving of its dimensions (odd dimensions are
+/// rounded down) until the size of the level is 1x1. Dimensions that are
+/// already equal to 1 are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroU32;
+/// use fast_image_resize as fr;
+///
+/// let src_image = fr::Image::new(
+///     NonZeroU32::new(640).unwrap(),
+///     NonZeroU32::new(480).unwrap(),
+///     fr::PixelType::U8x4,
+/// );
+/// let levels = fr::build_mipmaps(&src_image, fr::ResizeAlg::default());
+/// assert_eq!(levels.len(), 10);
+/// assert_eq!(levels[1].width().get(), 320);
+/// assert_eq!(levels[9].height().get(), 1);
+/// ```
+pub fn build_mipmaps(src_image: &Image, algorithm: ResizeAlg) -> Vec<Image<'static>> {
+    let mut first_level = Image::new(
+        src_image.width(),
+        src_image.height(),
+        src_image.pixel_type(),
+    );
+    first_level.buffer_mut().copy_from_slice(src_image.buffer());
+    let mut levels = vec![first_level];
+
+    let mut resizer = Resizer::new(algorithm);
+    loop {
+        let prev_level = &levels[levels.len() - 1];
+        let (width, height) = (prev_level.width().get(), prev_level.height().get());
+        if width == 1 && height == 1 {
+            break;
+        }
+        let dst_width = NonZeroU32::new((width / 2).max(1)).unwrap();
+        let dst_height = NonZeroU32::new((height / 2).max(1)).unwrap();
+        let mut level = Image::new(dst_width, dst_height, prev_level.pixel_type());
+        resizer.resize_with(&prev_level.view(), &mut level.view_mut(), None);
+        levels.push(level);
+    }
+    levels
+}
+
+fn get_temp_image_from_buffer<P: Pixel>(
+    buffer: &mut Vec<u8>,
+    width: NonZeroU32,
+    height: NonZeroU32,
+) -> InnerImage<P> {
+    let pixels_count = width.get() as usize * height.get() as usize;
+    // Add pixel size as gap for alignment of resulted buffer.
+    let buf_size = pixels_count * P::size() + P::size();
+    if buffer.len() < buf_size {
+        buffer.resize(buf_size, 0);
+    }
+    let pixels = unsafe { buffer.align_to_mut::<P>().1 };
+    InnerImage::new(width, height, &mut pixels[0..pixels_count])
+}
+
+/// Parameters of resizing that define coefficients of convolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ResizeParams {
+    src_width: NonZeroU32,
+    src_height: NonZeroU32,
+    crop_box: CropBox,
+    dst_width: NonZeroU32,
+    dst_height: NonZeroU32,
+}
+
+impl ResizeParams {
+    fn new(src_image: &ImageView, dst_width: NonZeroU32, dst_height: NonZeroU32) -> Self {
+        Self {
+            src_width: src_image.width(),
+            src_height: src_image.height(),
+            crop_box: src_image.crop_box(),
+            dst_width,
+            dst_height,
+        }
+    }
+}
+
+/// Precomputed coefficients of convolution that may be reused to resize
+/// many images of the same size.
+///
+/// ```
+/// # use std::num::NonZeroU32;
+/// # use fast_image_resize::{FilterType, Image, PixelType, PreparedResize, ResizeAlg, Resizer};
+/// let size = NonZeroU32::new(64).unwrap();
+/// let new_size = NonZeroU32::new(16).unwrap();
+/// let images: Vec<Image> = (0..3)
+///     .map(|_| Image::new(size, size, PixelType::U8x4))
+///     .collect();
+/// let prepared = PreparedResize::new(FilterType::Lanczos3, &images[0].view(), new_size, new_size);
+///
+/// let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+/// let mut dst_image = Image::new(new_size, new_size, PixelType::U8x4);
+/// for image in images.iter() {
+///     resizer
+///         .resize_prepared(&prepared, &image.view(), &mut dst_image.view_mut())
+///         .unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreparedResize {
+    filter_type: FilterType,
+    params: ResizeParams,
+    options: CoefficientsOptions,
+    coefficients: ConvolutionCoefficients,
+}
+
+impl PreparedResize {
+    /// Precomputes coefficients to resize images that have the same size
+    /// and crop box as the given source image into the given size.
+    ///
+    /// Coefficients are computed with default options of [Resizer],
+    /// use [PreparedResize::with_resizer] to take options of the resizer
+    /// into account.
+    pub fn new(
+        filter_type: FilterType,
+        src_image: &ImageView,
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+    ) -> Self {
+        Self::with_options(
+            filter_type,
+            src_image,
+            dst_width,
+            dst_height,
+            CoefficientsOptions::normalized(),
+        )
+    }
+
+    /// Precomputes coefficients to resize images that have the same size,
+    /// crop box and type of pixels as the given source image into
+    /// the given size with help of the given resizer.
+    ///
+    /// Boundary mode, maximal radius of filter and normalization of weights
+    /// are taken from the resizer.
+    pub fn with_resizer(
+        resizer: &Resizer,
+        filter_type: FilterType,
+        src_image: &ImageView,
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+    ) -> Self {
+        let options = resizer.coefficients_options(src_image.pixel_type());
+        Self::with_options(filter_type, src_image, dst_width, dst_height, options)
+    }
+
+    fn with_options(
+        filter_type: FilterType,
+        src_image: &ImageView,
+        dst_width: NonZeroU32,
+        dst_height: NonZeroU32,
+        options: CoefficientsOptions,
+    ) -> Self {
+        let params = ResizeParams::new(src_image, dst_width, dst_height);
+        Self {
+            filter_type,
+            params,
+            options,
+            coefficients: ConvolutionCoefficients::new(
+                &params,
+                (filter_type, filter_type),
+                options,
+            ),
+        }
+    }
+
+    #[inline(always)]
+    pub fn filter_type(&self) -> FilterType {
+        self.filter_type
+    }
+
+    /// Returns coefficients of horizontal pass of convolution.
+    ///
+    /// Returns `None` if the pass is not needed because the width of
+    /// the image is not changed.
+    pub fn horiz_coefficients(&self) -> Option<&convolution::Coefficients> {
+        self.coefficients.horiz.as_ref()
+    }
+
+    /// Returns coefficients of vertical pass of convolution.
+    ///
+    /// Returns `None` if the pass is not needed because the height of
+    /// the image is not changed.
+    pub fn vert_coefficients(&self) -> Option<&convolution::Coefficients> {
+        self.coefficients
+            .need_vertical
+            .then_some(&self.coefficients.vert)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ConvolutionCoefficients {
+    /// Coefficients for horizontal pass, `None` if it is not needed.
+    horiz: Option<convolution::Coefficients>,
+    vert: convolution::Coefficients,
+    need_vertical: bool,
+}
+
+impl ConvolutionCoefficients {
+    /// Creates coefficients of horizontal and vertical passes
+    /// with help of the given pair of filters.
+    fn new(
+        params: &ResizeParams,
+        (horiz_filter, vert_filter): (FilterType, FilterType),
+        options: CoefficientsOptions,
+    ) -> Self {
+        let crop_box = params.crop_box;
+
+        let need_horizontal =
+            params.dst_width != params.src_width || crop_box.width != params.src_width.get() as f64;
+        let need_vertical = params.dst_height != params.src_height
+            || crop_box.height != params.src_height.get() as f64;
+
+        // Without vertical pass, bounds of vertical coefficients define
+        // only the range of rows of source image, which isn't padded.
+        let vert_options = if need_vertical {
+            options
+        } else {
+            CoefficientsOptions {
+                boundary_mode: BoundaryMode::Clamp,
+                ..options
+            }
+        };
+        let vert = convolution::precompute_coefficients(
+            params.src_height,
+            crop_box.top,
+            crop_box.top + crop_box.height,
+            params.dst_height,
+            vert_filter,
+            vert_options,
+        );
+        let horiz = need_horizontal.then(|| {
+            convolution::precompute_coefficients(
+                params.src_width,
+                crop_box.left,
+                crop_box.left + crop_box.width,
+                params.dst_width,
+                horiz_filter,
+                options,
+            )
+        });
+        Self {
+            horiz,
+            vert,
+            need_vertical,
+        }
+    }
+}
+
+/// Settings of [Resizer] that are used by convolution.
+#[derive(Debug, Clone, Copy)]
+struct ConvolutionSettings {
+    cpu_extensions: CpuExtensions,
+    rounding_mode: RoundingMode,
+    num_threads: usize,
+    coefficients_options: CoefficientsOptions,
+    /// Clamp results by the range of used source pixels.
+    anti_ringing: bool,
+    /// Use the reference implementation of convolution.
+    reference: bool,
+}
+
+/// Tracks progress of resizing and passes it into the user callback.
+#[derive(Default)]
+struct Progress<'a> {
+    callback: Option<&'a mut dyn FnMut(f32) -> bool>,
+    total_rows: u64,
+    done_rows: u64,
+    fraction: f32,
+    cancelled: bool,
+}
+
+impl<'a> Progress<'a> {
+    fn new(callback: &'a mut dyn FnMut(f32) -> bool) -> Self {
+        Self {
+            callback: Some(callback),
+            ..Default::default()
+        }
+    }
+
+    fn is_tracked(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    /// Starts tracking of work that consists of processing of
+    /// the given count of rows.
+    fn start(&mut self, total_rows: u32) {
+        self.total_rows = total_rows as u64;
+        self.done_rows = 0;
+    }
+
+    /// Reports processing of the given count of rows.
+    /// Returns `false` if resizing was cancelled.
+    fn advance(&mut self, rows: u32) -> bool {
+        self.done_rows += rows as u64;
+        let fraction = self.done_rows as f32 / self.total_rows.max(1) as f32;
+        self.report(fraction.min(1.))
+    }
+
+    /// Reports the end of resizing if it wasn't reported yet.
+    fn finish(&mut self) {
+        if self.fraction < 1. {
+            self.report(1.);
+        }
+    }
+
+    fn report(&mut self, fraction: f32) -> bool {
+        if !self.cancelled {
+            if let Some(callback) = self.callback.as_mut() {
+                self.fraction = fraction;
+                self.cancelled = !callback(fraction);
+            }
+        }
+        !self.cancelled
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    /// Horizontal convolution that starts from the row with given index.
+    Horizontal(u32),
+    Vertical,
+}
+
+/// Makes one pass of convolution and returns CPU-extensions
+/// that were used.
+fn convolve_one_pass<P>(
     src_image: TypedImageView<P>,
     dst_image: TypedImageViewMut<P>,
-    filter_type: FilterType,
-    multiplicity: u8,
+    direction: Direction,
+    coeffs: convolution::Coefficients,
     cpu_extensions: CpuExtensions,
-    temp_buffer: &mut Vec<u8>,
-    convolution_temp_buffer: &mut Vec<u8>,
-) where
+    num_threads: usize,
+) -> CpuExtensions
+where
     P: Convolution,
 {
-    let crop_box = src_image.crop_box();
-    let dst_width = dst_image.width().get();
-    let dst_height = dst_image.height().get();
-    let width_scale = crop_box.width.get() as f32 / dst_width as f32;
-    let height_scale = crop_box.height.get() as f32 / dst_height as f32;
-    // It makes sense to resize the image in two steps only if the image
-    // size is greater than the required size by multiplicity times.
-    let factor = width_scale.min(height_scale) / multiplicity as f32;
-    if factor > 1.2 {
-        // First step is resizing the source image by fastest algorithm.
-        // The temporary image will be about ``multiplicity`` times larger
-        // than required.
-        let tmp_width =
-            NonZeroU32::new((crop_box.width.get() as f32 / factor).round() as u32).unwrap();
-        let tmp_height =
-            NonZeroU32::new((crop_box.height.get() as f32 / factor).round() as u32).unwrap();
-
-        let mut tmp_img = get_temp_image_from_buffer(temp_buffer, tmp_width, tmp_height);
-        resample_nearest(src_image, tmp_img.dst_view());
-        // Second step is resizing the temporary image with a convolution.
-        resample_convolution(
-            tmp_img.src_view(),
+    match direction {
+        Direction::Horizontal(offset) => horiz_convolution(
+            src_image,
             dst_image,
-            filter_type,
+            offset,
+            coeffs,
             cpu_extensions,
-            convolution_temp_buffer,
+            num_threads,
+        ),
+        Direction::Vertical => {
+            vert_convolution(src_image, dst_image, coeffs, cpu_extensions, num_threads)
+        }
+    }
+    P::used_cpu_extensions(cpu_extensions)
+}
+
+/// Copies pixels of the crop box of source image into the destination
+/// image if they have the same size, so resizing isn't needed.
+/// Returns `false` if the crop box isn't aligned to whole pixels or
+/// its size differs from the size of destination image.
+fn copy_same_size(src_image: &ImageView, dst_image: &mut ImageViewMut) -> bool {
+    let crop_box = src_image.crop_box();
+    if !crop_box.is_integral()
+        || crop_box.width != dst_image.width().get() as f64
+        || crop_box.height != dst_image.height().get() as f64
+    {
+        return false;
+    }
+    macro_rules! copy_typed {
+        ($typed_image:ident) => {
+            if let Some(src_rows) = src_image.$typed_image() {
+                if let Some(dst_rows) = dst_image.$typed_image() {
+                    copy_crop_box(src_rows, dst_rows);
+                }
+            }
+        };
+    }
+    match src_image.pixel_type() {
+        PixelType::U8x3 => copy_typed!(u8x3_image),
+        PixelType::U8x4 => copy_typed!(u8x4_image),
+        PixelType::U16x3 => copy_typed!(u16x3_image),
+        PixelType::U16x4 => copy_typed!(u16x4_image),
+        PixelType::I16 => copy_typed!(i16_image),
+        PixelType::I32 => copy_typed!(i32_image),
+        PixelType::F32 => copy_typed!(f32_image),
+        #[cfg(feature = "half")]
+        PixelType::F16 => copy_typed!(f16_image),
+        PixelType::F32x4 => copy_typed!(f32x4_image),
+        PixelType::U8x2 => copy_typed!(u8x2_image),
+        PixelType::U16x2 => copy_typed!(u16x2_image),
+        PixelType::U8 => copy_typed!(u8_image),
+    }
+    true
+}
+
+fn copy_crop_box<P: Pixel>(src_image: TypedImageView<P>, mut dst_image: TypedImageViewMut<P>) {
+    let crop_box = src_image.crop_box();
+    let left = crop_box.left as usize;
+    let right = left + dst_image.width().get() as usize;
+    let src_rows = src_image.iter_rows(crop_box.top as u32);
+    for (dst_row, src_row) in dst_image.iter_rows_mut().zip(src_rows) {
+        dst_row.copy_from_slice(&src_row[left..right]);
+    }
+}
+
+fn resample_convolution<P>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    filters: (FilterType, FilterType),
+    settings: ConvolutionSettings,
+    temp_buffer: &mut Vec<u8>,
+    progress: &mut Progress,
+) where
+    P: Convolution + Components,
+{
+    let params = ResizeParams {
+        src_width: src_image.width(),
+        src_height: src_image.height(),
+        crop_box: src_image.crop_box(),
+        dst_width: dst_image.width(),
+        dst_height: dst_image.height(),
+    };
+    let coefficients =
+        ConvolutionCoefficients::new(&params, filters, settings.coefficients_options);
+    convolve(
+        src_image,
+        dst_image,
+        coefficients,
+        settings,
+        temp_buffer,
+        progress,
+    );
+}
+
+fn convolve<P>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    coefficients: ConvolutionCoefficients,
+    settings: ConvolutionSettings,
+    temp_buffer: &mut Vec<u8>,
+    progress: &mut Progress,
+) where
+    P: Convolution + Components,
+{
+    let ConvolutionSettings {
+        cpu_extensions,
+        rounding_mode,
+        num_threads,
+        anti_ringing,
+        reference,
+        ..
+    } = settings;
+    let dst_width = dst_image.width();
+    let dst_height = dst_image.height().get();
+    let ConvolutionCoefficients {
+        horiz,
+        vert: mut vert_coeffs,
+        need_vertical,
+    } = coefficients;
+
+    // Coefficients for `BoundaryMode::Wrap` use pixels of source image
+    // padded by wrapped pixels.
+    let horiz_padding = horiz.as_ref().map_or(0, |c| c.padding);
+    let vert_padding = vert_coeffs.padding;
+    let mut padded_pixels: Vec<P>;
+    let padded_image: InnerImage<P>;
+    let src_image = if horiz_padding > 0 || vert_padding > 0 {
+        padded_pixels = pad_by_wrapping(src_image, horiz_padding, vert_padding);
+        let width = src_image.width().get() + 2 * horiz_padding;
+        let height = src_image.height().get() + 2 * vert_padding;
+        padded_image = InnerImage::new(
+            NonZeroU32::new(width).unwrap(),
+            NonZeroU32::new(height).unwrap(),
+            &mut padded_pixels,
         );
+        padded_image.src_view()
     } else {
-        // There is no point in doing the resizing in two steps.
-        // We immediately resize the original image with a convolution.
-        resample_convolution(
+        src_image
+    };
+
+    vert_coeffs.rounding_mode = rounding_mode;
+    vert_coeffs.reference = reference;
+
+    if let Some(mut horiz_coeffs) = horiz {
+        horiz_coeffs.rounding_mode = rounding_mode;
+        horiz_coeffs.reference = reference;
+        // First used row in the source image
+        let y_first = vert_coeffs.bounds[0].start;
+
+        if need_vertical {
+            // Last used row in the source image
+            let last_y_bound = vert_coeffs.bounds.last().unwrap();
+            let y_last = last_y_bound.start + last_y_bound.size;
+
+            let temp_height = NonZeroU32::new(y_last - y_first).unwrap();
+            progress.start(temp_height.get() + dst_height);
+            let mut temp_image = get_temp_image_from_buffer(temp_buffer, dst_width, temp_height);
+            let horiz_bounds = anti_ringing.then(|| horiz_coeffs.bounds.clone());
+            if !horiz_convolution_with_progress(
+                src_image,
+                temp_image.dst_view(),
+                y_first,
+                horiz_coeffs,
+                cpu_extensions,
+                num_threads,
+                progress,
+            ) {
+                return;
+            }
+            if let Some(bounds) = horiz_bounds {
+                anti_ringing::clamp_horiz(src_image, temp_image.dst_view(), y_first, &bounds);
+            }
+
+            // Shift bounds for vertical pass
+            vert_coeffs
+                .bounds
+                .iter_mut()
+                .for_each(|b| b.start -= y_first);
+            let vert_bounds = anti_ringing.then(|| vert_coeffs.bounds.clone());
+            if vert_convolution_with_progress(
+                temp_image.src_view(),
+                dst_image.reborrow(),
+                vert_coeffs,
+                cpu_extensions,
+                num_threads,
+                progress,
+            ) {
+                if let Some(bounds) = vert_bounds {
+                    anti_ringing::clamp_vert(temp_image.src_view(), dst_image, &bounds);
+                }
+            }
+        } else {
+            progress.start(dst_height);
+            let horiz_bounds = anti_ringing.then(|| horiz_coeffs.bounds.clone());
+            if horiz_convolution_with_progress(
+                src_image,
+                dst_image.reborrow(),
+                y_first,
+                horiz_coeffs,
+                cpu_extensions,
+                num_threads,
+                progress,
+            ) {
+                if let Some(bounds) = horiz_bounds {
+                    anti_ringing::clamp_horiz(src_image, dst_image, y_first, &bounds);
+                }
+            }
+        }
+    } else if need_vertical {
+        progress.start(dst_height);
+        let vert_bounds = anti_ringing.then(|| vert_coeffs.bounds.clone());
+        if vert_convolution_with_progress(
+            src_image,
+            dst_image.reborrow(),
+            vert_coeffs,
+            cpu_extensions,
+            num_threads,
+            progress,
+        ) {
+            if let Some(bounds) = vert_bounds {
+                anti_ringing::clamp_vert(src_image, dst_image, &bounds);
+            }
+        }
+    }
+}
+
+/// Count of parts which rows of the destination image are split into
+/// by every pass of convolution to report progress of resizing.
+const PROGRESS_PARTS: usize = 16;
+
+/// Returns pixels of the image padded on the left and right sides
+/// by `horiz_padding` pixels and on the top and bottom sides by
+/// `vert_padding` pixels taken from the opposite side of the image.
+fn pad_by_wrapping<P: Pixel>(
+    src_image: TypedImageView<P>,
+    horiz_padding: u32,
+    vert_padding: u32,
+) -> Vec<P> {
+    let width = src_image.width().get() as i64;
+    let height = src_image.height().get() as i64;
+    let (horiz_padding, vert_padding) = (horiz_padding as i64, vert_padding as i64);
+    let padded_width = width + 2 * horiz_padding;
+    let padded_height = height + 2 * vert_padding;
+    let mut pixels = Vec::with_capacity((padded_width * padded_height) as usize);
+    for y in -vert_padding..height + vert_padding {
+        let row = src_image.get_row(y.rem_euclid(height) as u32).unwrap();
+        pixels.extend(
+            (-horiz_padding..width + horiz_padding).map(|x| row[x.rem_euclid(width) as usize]),
+        );
+    }
+    pixels
+}
+
+/// Makes horizontal pass of convolution. If progress is tracked, rows of
+/// the destination image are processed by parts and progress is reported
+/// after every part. Returns `false` if resizing was cancelled.
+fn horiz_convolution_with_progress<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+    progress: &mut Progress,
+) -> bool {
+    if !progress.is_tracked() {
+        horiz_convolution(
             src_image,
             dst_image,
-            filter_type,
+            offset,
+            coeffs,
+            cpu_extensions,
+            num_threads,
+        );
+        return true;
+    }
+    let part_height = get_part_height(dst_image.height(), PROGRESS_PARTS);
+    for (i, dst_part) in dst_image.split_by_height(part_height).enumerate() {
+        let part_offset = offset + i as u32 * part_height.get();
+        let rows = dst_part.height().get();
+        horiz_convolution(
+            src_image,
+            dst_part,
+            part_offset,
+            coeffs.clone(),
             cpu_extensions,
-            convolution_temp_buffer,
+            num_threads,
         );
+        if !progress.advance(rows) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Makes vertical pass of convolution. If progress is tracked, rows of
+/// the destination image are processed by parts and progress is reported
+/// after every part. Returns `false` if resizing was cancelled.
+fn vert_convolution_with_progress<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+    progress: &mut Progress,
+) -> bool {
+    if !progress.is_tracked() {
+        vert_convolution(src_image, dst_image, coeffs, cpu_extensions, num_threads);
+        return true;
+    }
+    let part_height = get_part_height(dst_image.height(), PROGRESS_PARTS);
+    for (i, dst_part) in dst_image.split_by_height(part_height).enumerate() {
+        let start = i * part_height.get() as usize;
+        let rows = dst_part.height().get();
+        let coeffs = coeffs.slice(start..start + rows as usize);
+        vert_convolution(src_image, dst_part, coeffs, cpu_extensions, num_threads);
+        if !progress.advance(rows) {
+            return false;
+        }
+    }
+    true
+}
+
+#[inline]
+fn horiz_convolution<P: Convolution>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+) {
+    match num_threads {
+        #[cfg(feature = "std")]
+        n if n > 1 => {
+            horiz_convolution_in_threads(src_image, dst_image, offset, coeffs, cpu_extensions, n)
+        }
+        #[cfg(feature = "rayon")]
+        0 => horiz_convolution_in_rayon(src_image, dst_image, offset, coeffs, cpu_extensions),
+        _ => horiz_convolution_part(src_image, dst_image, offset, coeffs, cpu_extensions),
+    }
+}
+
+#[inline]
+fn vert_convolution<P: Convolution>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+) {
+    match num_threads {
+        #[cfg(feature = "std")]
+        n if n > 1 => vert_convolution_in_threads(src_image, dst_image, coeffs, cpu_extensions, n),
+        #[cfg(feature = "rayon")]
+        0 => vert_convolution_in_rayon(src_image, dst_image, coeffs, cpu_extensions),
+        _ => vert_convolution_part(src_image, dst_image, coeffs, cpu_extensions),
+    }
+}
+
+/// Convolves rows of one part of the destination image
+/// in the current thread.
+#[inline]
+fn horiz_convolution_part<P: Convolution>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+) {
+    if coeffs.reference {
+        convolution::reference::horiz_convolution(src_image, dst_image, offset, coeffs);
+    } else {
+        P::horiz_convolution(src_image, dst_image, offset, coeffs, cpu_extensions);
+    }
+}
+
+/// Convolves rows of one part of the destination image
+/// in the current thread.
+#[inline]
+fn vert_convolution_part<P: Convolution>(
+    src_image: TypedImageView<P>,
+    dst_image: TypedImageViewMut<P>,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+) {
+    if coeffs.reference {
+        convolution::reference::vert_convolution(src_image, dst_image, coeffs);
+    } else {
+        P::vert_convolution(src_image, dst_image, coeffs, cpu_extensions);
     }
 }
+
+/// Splits rows of the destination image into `num_threads` parts that
+/// are processed in parallel by scoped threads.
+#[cfg(feature = "std")]
+fn horiz_convolution_in_threads<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+) {
+    let part_height = get_part_height(dst_image.height(), num_threads);
+    std::thread::scope(|scope| {
+        for (i, dst_part) in dst_image.split_by_height(part_height).enumerate() {
+            let part_offset = offset + i as u32 * part_height.get();
+            let coeffs = coeffs.clone();
+            scope.spawn(move || {
+                horiz_convolution_part(src_image, dst_part, part_offset, coeffs, cpu_extensions);
+            });
+        }
+    });
+}
+
+/// Splits rows of the destination image into `num_threads` parts that
+/// are processed in parallel by scoped threads.
+#[cfg(feature = "std")]
+fn vert_convolution_in_threads<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+    num_threads: usize,
+) {
+    let part_height = get_part_height(dst_image.height(), num_threads);
+    std::thread::scope(|scope| {
+        for (i, dst_part) in dst_image.split_by_height(part_height).enumerate() {
+            let start = i * part_height.get() as usize;
+            let end = start + dst_part.height().get() as usize;
+            let coeffs = coeffs.slice(start..end);
+            scope.spawn(move || {
+                vert_convolution_part(src_image, dst_part, coeffs, cpu_extensions);
+            });
+        }
+    });
+}
+
+/// Splits rows of the destination image into parts that are
+/// processed in parallel by threads of the current rayon thread pool.
+#[cfg(feature = "rayon")]
+fn horiz_convolution_in_rayon<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    offset: u32,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+) {
+    use rayon::prelude::*;
+
+    let part_height = get_part_height(dst_image.height(), rayon::current_num_threads());
+    let dst_parts: Vec<_> = dst_image.split_by_height(part_height).collect();
+    dst_parts
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(i, dst_part)| {
+            let part_offset = offset + i as u32 * part_height.get();
+            horiz_convolution_part(
+                src_image,
+                dst_part,
+                part_offset,
+                coeffs.clone(),
+                cpu_extensions,
+            );
+        });
+}
+
+/// Splits rows of the destination image into parts that are
+/// processed in parallel by threads of the current rayon thread pool.
+#[cfg(feature = "rayon")]
+fn vert_convolution_in_rayon<P: Convolution>(
+    src_image: TypedImageView<P>,
+    mut dst_image: TypedImageViewMut<P>,
+    coeffs: convolution::Coefficients,
+    cpu_extensions: CpuExtensions,
+) {
+    use rayon::prelude::*;
+
+    let part_height = get_part_height(dst_image.height(), rayon::current_num_threads());
+    let dst_parts: Vec<_> = dst_image.split_by_height(part_height).collect();
+    dst_parts
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(i, dst_part)| {
+            let start = i * part_height.get() as usize;
+            let end = start + dst_part.height().get() as usize;
+            vert_convolution_part(
+                src_image,
+                dst_part,
+                coeffs.slice(start..end),
+                cpu_extensions,
+            );
+        });
+}
+
+/// Returns height of image part processed by one thread.
+/// It is a multiple of 4 because some SIMD implementations
+/// process four rows at once.
+fn get_part_height(height: NonZeroU32, num_threads: usize) -> NonZeroU32 {
+    let threads = num_threads.max(1) as u32;
+    let part_height = height.get().div_ceil(threads);
+    NonZeroU32::new((part_height + 3) & !3).unwrap()
+}
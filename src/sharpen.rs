@@ -0,0 +1,87 @@
+//! Sharpening of images by unsharp mask.
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::errors::InvalidFilterParameterError;
+
+/// Parameters of unsharp mask, which sharpens an image by adding
+/// the difference between the image and its Gaussian blur.
+///
+/// # Examples
+///
+/// ```
+/// use fast_image_resize::{UnsharpMask, Resizer};
+///
+/// let mut resizer = Resizer::default();
+/// resizer.set_unsharp_mask(Some(UnsharpMask::new(1.0, 0.5, 2).unwrap()));
+/// assert!(UnsharpMask::new(0.0, 0.5, 2).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnsharpMask {
+    radius: f32,
+    amount: f32,
+    threshold: u8,
+}
+
+impl UnsharpMask {
+    /// Creates unsharp mask.
+    ///
+    /// - `radius` is standard deviation (sigma) of Gaussian blur in pixels;
+    /// - `amount` is multiplier of the difference between the image and
+    ///   its blur that is added to the image (e.g. 0.5 adds 50% of it);
+    /// - components which differ from the blur less than `threshold`
+    ///   are not changed, so noise in flat areas isn't amplified.
+    ///
+    /// Returns an error if `radius` or `amount` is not positive
+    /// and finite number.
+    pub fn new(
+        radius: f32,
+        amount: f32,
+        threshold: u8,
+    ) -> Result<Self, InvalidFilterParameterError> {
+        if !radius.is_finite() || radius <= 0. || !amount.is_finite() || amount <= 0. {
+            return Err(InvalidFilterParameterError);
+        }
+        Ok(Self {
+            radius,
+            amount,
+            threshold,
+        })
+    }
+
+    #[inline(always)]
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    #[inline(always)]
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    #[inline(always)]
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Sharpens the row of `U8x3` or `U8x4` pixels. `row` contains
+    /// blurred pixels and receives the result. The fourth component
+    /// (alpha channel) of `U8x4` pixels is copied from `src_row`.
+    pub(crate) fn sharpen_row(&self, src_row: &[u8], row: &mut [u8], pixel_size: usize) {
+        let src_pixels = src_row.chunks_exact(pixel_size);
+        for (src_pixel, pixel) in src_pixels.zip(row.chunks_exact_mut(pixel_size)) {
+            for (&src, dst) in src_pixel.iter().zip(pixel.iter_mut()).take(3) {
+                let diff = src as f32 - *dst as f32;
+                *dst = if diff.abs() < self.threshold as f32 {
+                    src
+                } else {
+                    // Conversion of float into integer saturates the value.
+                    (src as f32 + diff * self.amount).round() as u8
+                };
+            }
+            if let (Some(dst), Some(&src)) = (pixel.get_mut(3), src_pixel.get(3)) {
+                *dst = src;
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use std::arch::x86_64::*;
-use std::intrinsics::transmute;
+use core::arch::x86_64::*;
+use core::intrinsics::transmute;
 
 use crate::pixels::{U8x3, U8x4, U8};
 
@@ -46,13 +46,13 @@ pub unsafe fn mm_cvtepu8_epi32_u8x3(buf: &[U8x3], index: usize) -> __m128i {
 #[inline(always)]
 pub unsafe fn mm_cvtepu8_epi32_from_u8(buf: &[U8], index: usize) -> __m128i {
     let ptr = buf.get_unchecked(index..).as_ptr() as *const i32;
-    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(*ptr))
+    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(ptr.read_unaligned()))
 }
 
 #[inline(always)]
 pub unsafe fn mm_cvtepu8_epi32_from_raw<T>(buf: &[T], offset: usize) -> __m128i {
     let ptr = (buf.as_ptr() as *const u8).add(offset) as *const i32;
-    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(*ptr))
+    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(ptr.read_unaligned()))
 }
 
 #[inline(always)]
@@ -64,21 +64,23 @@ pub unsafe fn mm_cvtsi32_si128_from_u32(buf: &[U8x4], index: usize) -> __m128i {
 #[inline(always)]
 pub unsafe fn mm_cvtsi32_si128_from_u8(buf: &[U8], index: usize) -> __m128i {
     let ptr = buf.get_unchecked(index..).as_ptr() as *const i32;
-    _mm_cvtsi32_si128(*ptr)
+    _mm_cvtsi32_si128(ptr.read_unaligned())
 }
 
 #[inline(always)]
 pub unsafe fn mm_cvtsi32_si128_from_raw<T>(buf: &[T], offset: usize) -> __m128i {
     let ptr = (buf.as_ptr() as *const u8).add(offset) as *const i32;
-    _mm_cvtsi32_si128(*ptr)
+    _mm_cvtsi32_si128(ptr.read_unaligned())
 }
 
 #[inline(always)]
 pub unsafe fn ptr_i16_to_set1_epi32(buf: &[i16], index: usize) -> __m128i {
-    _mm_set1_epi32(*(buf.get_unchecked(index..).as_ptr() as *const i32))
+    let ptr = buf.get_unchecked(index..).as_ptr() as *const i32;
+    _mm_set1_epi32(ptr.read_unaligned())
 }
 
 #[inline(always)]
 pub unsafe fn ptr_i16_to_256set1_epi32(buf: &[i16], index: usize) -> __m256i {
-    _mm256_set1_epi32(*(buf.get_unchecked(index..).as_ptr() as *const i32))
+    let ptr = buf.get_unchecked(index..).as_ptr() as *const i32;
+    _mm256_set1_epi32(ptr.read_unaligned())
 }
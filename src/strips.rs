@@ -0,0 +1,333 @@
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+use crate::alpha::{clamp_by_alpha, MulDiv};
+use crate::convolution::{precompute_coefficients, BoundaryMode};
+use crate::errors::ResizeByStripsError;
+use crate::image_view::{ImageView, ImageViewMut};
+use crate::pixels::PixelType;
+use crate::resizer::{AlphaMode, Resizer};
+
+impl Resizer {
+    /// Resizes an image that is read and written by horizontal strips,
+    /// so neither the whole source image nor the whole destination image
+    /// has to be stored in memory (e.g. for images larger than RAM).
+    ///
+    /// Rows of the destination image with size `dst_size` are produced by
+    /// strips of `strip_height` rows (the last strip may be shorter) and
+    /// passed into `write_strip` together with index of the first row
+    /// of the strip.
+    ///
+    /// Rows of the source image with size `src_size` are requested from
+    /// `read_rows` in order, every row exactly once. The callback receives
+    /// index of the first requested row and a buffer which must be filled
+    /// by pixels of the requested rows. Only source rows used by the filter
+    /// for the current strip are stored in internal buffers, the overlap
+    /// between strips is kept without reading the rows again.
+    ///
+    /// Buffers contain rows without padding, pixels are stored in the same
+    /// format as in [Image::buffer](crate::Image::buffer). If any of the
+    /// callbacks returns `false`, resizing is stopped and
+    /// [ResizeByStripsError::Cancelled] is returned.
+    ///
+    /// Only [ResizeAlg::Convolution](crate::ResizeAlg::Convolution),
+    /// [ResizeAlg::ConvolutionPerAxis](crate::ResizeAlg::ConvolutionPerAxis)
+    /// and [ResizeAlg::Adaptive](crate::ResizeAlg::Adaptive) algorithms
+    /// are supported. Gamma correction is not applied.
+    /// [BoundaryMode::Wrap] is not supported, because rows near the top
+    /// edge of the result need rows from the bottom of the source image.
+    /// Otherwise, the result is equal to the result of [Resizer::resize]
+    /// of the whole image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use fast_image_resize as fr;
+    ///
+    /// let src_size = (NonZeroU32::new(64).unwrap(), NonZeroU32::new(4096).unwrap());
+    /// let dst_size = (NonZeroU32::new(32).unwrap(), NonZeroU32::new(2048).unwrap());
+    /// let mut dst_buffer = vec![0u8; 32 * 2048];
+    ///
+    /// let mut resizer = fr::Resizer::default();
+    /// resizer
+    ///     .resize_by_strips(
+    ///         src_size,
+    ///         dst_size,
+    ///         fr::PixelType::U8,
+    ///         NonZeroU32::new(256).unwrap(),
+    ///         &mut |first_row, rows| {
+    ///             // Read rows of the source image starting from `first_row`
+    ///             rows.fill(first_row as u8);
+    ///             true
+    ///         },
+    ///         &mut |first_row, strip| {
+    ///             let start = first_row as usize * 32;
+    ///             dst_buffer[start..start + strip.len()].copy_from_slice(strip);
+    ///             true
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn resize_by_strips(
+        &mut self,
+        src_size: (NonZeroU32, NonZeroU32),
+        dst_size: (NonZeroU32, NonZeroU32),
+        pixel_type: PixelType,
+        strip_height: NonZeroU32,
+        read_rows: &mut dyn FnMut(u32, &mut [u8]) -> bool,
+        write_strip: &mut dyn FnMut(u32, &[u8]) -> bool,
+    ) -> Result<(), ResizeByStripsError> {
+        let (src_width, src_height) = src_size;
+        let (dst_width, dst_height) = dst_size;
+        let crop_size = (src_width.get() as f64, src_height.get() as f64);
+        let (horiz_filter, vert_filter) = self
+            .algorithm
+            .convolution_filters(crop_size, dst_size)
+            .ok_or(ResizeByStripsError::UnsupportedAlgorithm)?;
+        if self.boundary_mode() == BoundaryMode::Wrap {
+            return Err(ResizeByStripsError::UnsupportedBoundaryMode);
+        }
+        let need_horizontal = dst_width != src_width;
+        let need_vertical = dst_height != src_height;
+        let options = self.coefficients_options(pixel_type);
+        let new_coeffs = |filter_type, src_size: NonZeroU32, dst_size| {
+            precompute_coefficients(
+                src_size,
+                0.,
+                src_size.get() as f64,
+                dst_size,
+                filter_type,
+                options,
+            )
+        };
+        let horiz_coeffs = need_horizontal.then(|| new_coeffs(horiz_filter, src_width, dst_width));
+        let vert_coeffs = need_vertical.then(|| new_coeffs(vert_filter, src_height, dst_height));
+        // The first source row used by every row of the result and rows
+        // after it. With `BoundaryMode::Mirror` rows near the bottom edge
+        // may use source rows before rows used by previous ones.
+        let first_used_rows: Vec<u32> = match &vert_coeffs {
+            Some(coeffs) => {
+                let mut rows: Vec<u32> = (coeffs.bounds.iter().rev())
+                    .scan(u32::MAX, |first_row, bound| {
+                        *first_row = bound.start.min(*first_row);
+                        Some(*first_row)
+                    })
+                    .collect();
+                rows.reverse();
+                rows
+            }
+            None => Vec::new(),
+        };
+        // Like `Resizer::resize`, images of the same size are copied
+        // without multiplying by alpha channel.
+        let mul_div = (pixel_type.has_alpha()
+            && self.alpha_mode() == AlphaMode::Premultiplied
+            && (need_horizontal || need_vertical))
+            .then(|| self.mul_div());
+        let clamp = pixel_type.has_alpha()
+            && self.alpha_mode() == AlphaMode::AlreadyPremultiplied
+            && (need_horizontal || need_vertical);
+
+        // Source rows that have passed through the horizontal pass.
+        let mut rows = RowsBuffer::new(dst_width, pixel_type);
+        let mut src_buffer: Vec<u32> = Vec::new();
+        let mut dst_buffer: Vec<u32> = Vec::new();
+
+        let strip_height = strip_height.get();
+        for strip_start in (0..dst_height.get()).step_by(strip_height as usize) {
+            let strip_end = (strip_start + strip_height).min(dst_height.get());
+            let height = NonZeroU32::new(strip_end - strip_start).unwrap();
+            // Range of source rows used by rows of the strip
+            let (src_start, src_end) = match &vert_coeffs {
+                Some(coeffs) => {
+                    let bounds = &coeffs.bounds[strip_start as usize..strip_end as usize];
+                    let end = bounds.iter().map(|b| b.start + b.size).max().unwrap();
+                    (first_used_rows[strip_start as usize], end)
+                }
+                None => (strip_start, strip_end),
+            };
+            rows.drop_before(src_start);
+
+            if let Some(count) = NonZeroU32::new(src_end.saturating_sub(rows.end)) {
+                let first_row = rows.end;
+                let mut new_rows = rows.push(count);
+                match &horiz_coeffs {
+                    Some(coeffs) => {
+                        let src_bytes =
+                            bytes_of_buffer(&mut src_buffer, pixel_type, src_width, count);
+                        if !read_rows(first_row, src_bytes) {
+                            return Err(ResizeByStripsError::Cancelled);
+                        }
+                        let mut src_image =
+                            ImageViewMut::from_buffer(src_width, count, src_bytes, pixel_type)
+                                .unwrap();
+                        multiply_alpha(&mul_div, &mut src_image);
+                        let src_image =
+                            ImageView::from_buffer(src_width, count, src_bytes, pixel_type)
+                                .unwrap();
+                        let mut dst_image = new_rows.view_mut();
+                        // Sizes of images match to coefficients by construction.
+                        self.horiz_convolution(&src_image, &mut dst_image, 0, coeffs)
+                            .unwrap();
+                    }
+                    None => {
+                        if !read_rows(first_row, new_rows.bytes) {
+                            return Err(ResizeByStripsError::Cancelled);
+                        }
+                        multiply_alpha(&mul_div, &mut new_rows.view_mut());
+                    }
+                }
+                // Rows between strips that aren't used by any strip
+                // (e.g. on downscaling with a short filter).
+                rows.drop_before(src_start);
+            }
+
+            let strip_bytes = match &vert_coeffs {
+                Some(coeffs) => {
+                    let mut coeffs = coeffs.slice(strip_start as usize..strip_end as usize);
+                    coeffs.bounds.iter_mut().for_each(|b| b.start -= rows.start);
+                    let dst_bytes = bytes_of_buffer(&mut dst_buffer, pixel_type, dst_width, height);
+                    let mut dst_image =
+                        ImageViewMut::from_buffer(dst_width, height, dst_bytes, pixel_type)
+                            .unwrap();
+                    self.vert_convolution(&rows.view(), &mut dst_image, &coeffs)
+                        .unwrap();
+                    divide_alpha(&mul_div, &mut dst_image);
+                    if clamp {
+                        clamp_by_alpha(&mut dst_image);
+                    }
+                    &*dst_bytes
+                }
+                None => {
+                    // Rows of the strip are stored in the buffer as is
+                    // and aren't used by the next strips.
+                    divide_alpha(&mul_div, &mut rows.view_mut());
+                    if clamp {
+                        clamp_by_alpha(&mut rows.view_mut());
+                    }
+                    &*rows.bytes()
+                }
+            };
+            if !write_strip(strip_start, strip_bytes) {
+                return Err(ResizeByStripsError::Cancelled);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn multiply_alpha(mul_div: &Option<MulDiv>, image: &mut ImageViewMut) {
+    if let Some(mul_div) = mul_div {
+        // Only pixel types supported by `MulDiv` have alpha channel.
+        mul_div.multiply_alpha_inplace(image).unwrap();
+    }
+}
+
+fn divide_alpha(mul_div: &Option<MulDiv>, image: &mut ImageViewMut) {
+    if let Some(mul_div) = mul_div {
+        mul_div.divide_alpha_inplace(image).unwrap();
+    }
+}
+
+/// Returns bytes of the buffer for image with given dimensions.
+/// The buffer of `u32` values guarantees alignment of pixels.
+fn bytes_of_buffer(
+    buffer: &mut Vec<u32>,
+    pixel_type: PixelType,
+    width: NonZeroU32,
+    height: NonZeroU32,
+) -> &mut [u8] {
+    let bytes_len = pixel_type
+        .buffer_len(width, height)
+        .expect("Size of image buffer overflows usize");
+    let buffer_len = bytes_len.div_ceil(4);
+    if buffer.len() < buffer_len {
+        buffer.resize(buffer_len, 0);
+    }
+    &mut unsafe { buffer.align_to_mut::<u8>() }.1[..bytes_len]
+}
+
+/// Rows of an image with indexes from `start` to `end`.
+struct RowsBuffer {
+    buffer: Vec<u32>,
+    width: NonZeroU32,
+    pixel_type: PixelType,
+    row_size: usize,
+    start: u32,
+    end: u32,
+}
+
+/// Rows appended into [RowsBuffer].
+struct NewRows<'a> {
+    bytes: &'a mut [u8],
+    width: NonZeroU32,
+    height: NonZeroU32,
+    pixel_type: PixelType,
+}
+
+impl<'a> NewRows<'a> {
+    fn view_mut(&mut self) -> ImageViewMut<'_> {
+        ImageViewMut::from_buffer(self.width, self.height, self.bytes, self.pixel_type).unwrap()
+    }
+}
+
+impl RowsBuffer {
+    fn new(width: NonZeroU32, pixel_type: PixelType) -> Self {
+        Self {
+            buffer: Vec::new(),
+            width,
+            pixel_type,
+            row_size: width.get() as usize * pixel_type.size(),
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn bytes(&mut self) -> &mut [u8] {
+        let len = (self.end - self.start) as usize * self.row_size;
+        &mut unsafe { self.buffer.align_to_mut::<u8>() }.1[..len]
+    }
+
+    /// Removes rows with indexes less than `y`. Index of the next
+    /// appended row is not changed.
+    fn drop_before(&mut self, y: u32) {
+        let y = y.clamp(self.start, self.end);
+        let dropped = (y - self.start) as usize * self.row_size;
+        let len = (self.end - self.start) as usize * self.row_size;
+        let bytes = unsafe { self.buffer.align_to_mut::<u8>() }.1;
+        bytes.copy_within(dropped..len, 0);
+        self.start = y;
+    }
+
+    /// Appends `count` rows after the last one and returns them.
+    fn push(&mut self, count: NonZeroU32) -> NewRows<'_> {
+        let offset = (self.end - self.start) as usize * self.row_size;
+        self.end += count.get();
+        let len = (self.end - self.start) as usize * self.row_size;
+        let buffer_len = len.div_ceil(4);
+        if self.buffer.len() < buffer_len {
+            self.buffer.resize(buffer_len, 0);
+        }
+        let bytes = unsafe { self.buffer.align_to_mut::<u8>() }.1;
+        NewRows {
+            bytes: &mut bytes[offset..len],
+            width: self.width,
+            height: count,
+            pixel_type: self.pixel_type,
+        }
+    }
+
+    fn view(&self) -> ImageView<'_> {
+        let height = NonZeroU32::new(self.end - self.start).unwrap();
+        let len = height.get() as usize * self.row_size;
+        let bytes = unsafe { self.buffer.align_to::<u8>() }.1;
+        ImageView::from_buffer(self.width, height, &bytes[..len], self.pixel_type).unwrap()
+    }
+
+    fn view_mut(&mut self) -> ImageViewMut<'_> {
+        let height = NonZeroU32::new(self.end - self.start).unwrap();
+        let (width, pixel_type) = (self.width, self.pixel_type);
+        ImageViewMut::from_buffer(width, height, self.bytes(), pixel_type).unwrap()
+    }
+}
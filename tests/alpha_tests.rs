@@ -1,8 +1,9 @@
 use std::num::NonZeroU32;
 
-use fast_image_resize::pixels::U8x4;
+use fast_image_resize::pixels::{F32x4, U16x2, U16x4, U8x2, U8x4};
 use fast_image_resize::{
-    CpuExtensions, Image, ImageRows, ImageRowsMut, ImageView, ImageViewMut, MulDiv, PixelType,
+    CpuExtensions, Image, ImageRows, ImageRowsMut, ImageView, ImageViewMut, MulDiv,
+    MulDivImageError, PixelType,
 };
 use utils::{cpu_ext_into_str, image_checksum};
 
@@ -254,3 +255,408 @@ fn divide_alpha_real_image_test() {
         assert_eq!(checksum, [8292504, 8292504, 8292504, 8355840]);
     }
 }
+
+#[test]
+fn multiply_alpha_u16x4_test() {
+    let width: u32 = 5;
+    let height: u32 = 3;
+
+    let src_pixels = [
+        U16x4([65535, 32768, 0, 32768]),
+        U16x4([65535, 32768, 0, 65535]),
+        U16x4([65535, 32768, 0, 0]),
+    ];
+    let res_pixels = [
+        U16x4([32768, 16384, 0, 32768]),
+        U16x4([65535, 32768, 0, 65535]),
+        U16x4([0, 0, 0, 0]),
+    ];
+
+    let mut src_rows: [Vec<U16x4>; 3] = [
+        vec![src_pixels[0]; width as usize],
+        vec![src_pixels[1]; width as usize],
+        vec![src_pixels[2]; width as usize],
+    ];
+
+    let rows: Vec<&[U16x4]> = src_rows.iter().map(|r| r.as_ref()).collect();
+    let src_image_view = ImageView::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        ImageRows::U16x4(rows),
+    )
+    .unwrap();
+
+    let mut dst_image = Image::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        PixelType::U16x4,
+    );
+
+    let alpha_mul_div: MulDiv = Default::default();
+    alpha_mul_div
+        .multiply_alpha(&src_image_view, &mut dst_image.view_mut())
+        .unwrap();
+
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[u16; 4]>().1 };
+    let dst_rows = dst_pixels.chunks_exact(width as usize);
+    for (row, &valid_pixel) in dst_rows.zip(res_pixels.iter()) {
+        for &pixel in row.iter() {
+            assert_eq!(pixel, valid_pixel.0);
+        }
+    }
+
+    // Inplace
+    let rows: Vec<&mut [U16x4]> = src_rows.iter_mut().map(|r| r.as_mut()).collect();
+    let mut image_view = ImageViewMut::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        ImageRowsMut::U16x4(rows),
+    )
+    .unwrap();
+    alpha_mul_div
+        .multiply_alpha_inplace(&mut image_view)
+        .unwrap();
+
+    for (row, &valid_pixel) in src_rows.iter().zip(res_pixels.iter()) {
+        for &pixel in row.iter() {
+            assert_eq!(pixel, valid_pixel);
+        }
+    }
+}
+
+#[test]
+fn divide_alpha_u16x4_test() {
+    let width: u32 = 5;
+    let height: u32 = 3;
+
+    let src_pixels = [
+        U16x4([32768, 16384, 0, 32768]),
+        U16x4([65535, 32768, 0, 65535]),
+        U16x4([65535, 32768, 0, 0]),
+    ];
+    let res_pixels = [
+        U16x4([65535, 32768, 0, 32768]),
+        U16x4([65535, 32768, 0, 65535]),
+        U16x4([0, 0, 0, 0]),
+    ];
+
+    let mut src_rows: [Vec<U16x4>; 3] = [
+        vec![src_pixels[0]; width as usize],
+        vec![src_pixels[1]; width as usize],
+        vec![src_pixels[2]; width as usize],
+    ];
+
+    let rows: Vec<&[U16x4]> = src_rows.iter().map(|r| r.as_ref()).collect();
+    let src_image_view = ImageView::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        ImageRows::U16x4(rows),
+    )
+    .unwrap();
+
+    let mut dst_image = Image::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        PixelType::U16x4,
+    );
+
+    let alpha_mul_div: MulDiv = Default::default();
+    alpha_mul_div
+        .divide_alpha(&src_image_view, &mut dst_image.view_mut())
+        .unwrap();
+
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[u16; 4]>().1 };
+    let dst_rows = dst_pixels.chunks_exact(width as usize);
+    for (row, &valid_pixel) in dst_rows.zip(res_pixels.iter()) {
+        for &pixel in row.iter() {
+            assert_eq!(pixel, valid_pixel.0);
+        }
+    }
+
+    // Inplace
+    let rows: Vec<&mut [U16x4]> = src_rows.iter_mut().map(|r| r.as_mut()).collect();
+    let mut image_view = ImageViewMut::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        ImageRowsMut::U16x4(rows),
+    )
+    .unwrap();
+    alpha_mul_div.divide_alpha_inplace(&mut image_view).unwrap();
+
+    for (row, &valid_pixel) in src_rows.iter().zip(res_pixels.iter()) {
+        for &pixel in row.iter() {
+            assert_eq!(pixel, valid_pixel);
+        }
+    }
+}
+
+#[test]
+fn multiply_and_divide_alpha_u16x4_roundtrip_test() {
+    let size = NonZeroU32::new(256).unwrap();
+    let mut pixels = Vec::with_capacity(256 * 256);
+    for alpha in 0..=255u16 {
+        let alpha = alpha * 257;
+        for color in 0..=255u16 {
+            let color = color * 257;
+            pixels.push(U16x4([color, color / 2, 65535 - color, alpha]));
+        }
+    }
+    let rows: Vec<&[U16x4]> = pixels.chunks_exact(256).collect();
+    let src_image = ImageView::new(size, size, ImageRows::U16x4(rows)).unwrap();
+    let mut multiplied_image = Image::new(size, size, PixelType::U16x4);
+    let mut dst_image = Image::new(size, size, PixelType::U16x4);
+
+    let alpha_mul_div: MulDiv = Default::default();
+    alpha_mul_div
+        .multiply_alpha(&src_image, &mut multiplied_image.view_mut())
+        .unwrap();
+    alpha_mul_div
+        .divide_alpha(&multiplied_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[u16; 4]>().1 };
+    for (src_pixel, dst_pixel) in pixels.iter().zip(dst_pixels) {
+        let alpha = src_pixel.0[3];
+        assert_eq!(dst_pixel[3], alpha);
+        if alpha == 0 {
+            assert_eq!(dst_pixel, &[0, 0, 0, 0]);
+            continue;
+        }
+        // Multiplication looses precision for small values of alpha.
+        let tolerance = 65535 / (2 * alpha as i32) + 1;
+        for i in 0..3 {
+            let diff = (dst_pixel[i] as i32 - src_pixel.0[i] as i32).abs();
+            assert!(diff <= tolerance, "{:?} => {:?}", src_pixel.0, dst_pixel);
+        }
+    }
+}
+
+fn f32x4_pixels() -> Vec<F32x4> {
+    let mut pixels = Vec::with_capacity(64 * 64);
+    for a in 0..64 {
+        let alpha = a as f32 / 63.;
+        for c in 0..64 {
+            let color = c as f32 / 63.;
+            pixels.push(F32x4([color, color / 2., 1. - color, alpha]));
+        }
+    }
+    pixels
+}
+
+#[test]
+fn multiply_and_divide_alpha_f32x4_roundtrip_test() {
+    let size = NonZeroU32::new(64).unwrap();
+    let pixels = f32x4_pixels();
+    let rows: Vec<&[F32x4]> = pixels.chunks_exact(64).collect();
+    let src_image = ImageView::new(size, size, ImageRows::F32x4(rows)).unwrap();
+    let mut multiplied_image = Image::new(size, size, PixelType::F32x4);
+    let mut dst_image = Image::new(size, size, PixelType::F32x4);
+
+    let alpha_mul_div: MulDiv = Default::default();
+    alpha_mul_div
+        .multiply_alpha(&src_image, &mut multiplied_image.view_mut())
+        .unwrap();
+    let multiplied_pixels = unsafe { multiplied_image.buffer().align_to::<[f32; 4]>().1 };
+    for (src_pixel, mul_pixel) in pixels.iter().zip(multiplied_pixels) {
+        let [r, g, b, a] = src_pixel.0;
+        assert_eq!(mul_pixel, &[r * a, g * a, b * a, a]);
+    }
+
+    alpha_mul_div
+        .divide_alpha(&multiplied_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[f32; 4]>().1 };
+    for (src_pixel, dst_pixel) in pixels.iter().zip(dst_pixels) {
+        let alpha = src_pixel.0[3];
+        assert_eq!(dst_pixel[3], alpha);
+        if alpha == 0. {
+            continue;
+        }
+        for i in 0..3 {
+            let diff = (dst_pixel[i] - src_pixel.0[i]).abs();
+            assert!(diff <= 1e-6, "{:?} => {:?}", src_pixel.0, dst_pixel);
+        }
+    }
+
+    // Inplace
+    let buffer: Vec<u32> = pixels.iter().flat_map(|p| p.0.map(f32::to_bits)).collect();
+    let mut image = Image::from_vec_u32(size, size, buffer, PixelType::F32x4).unwrap();
+    alpha_mul_div
+        .multiply_alpha_inplace(&mut image.view_mut())
+        .unwrap();
+    assert!(image.buffer() == multiplied_image.buffer());
+    alpha_mul_div
+        .divide_alpha_inplace(&mut image.view_mut())
+        .unwrap();
+    assert!(image.buffer() == dst_image.buffer());
+}
+
+#[test]
+fn divide_alpha_f32x4_with_zero_alpha_test() {
+    let width = NonZeroU32::new(3).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let mut pixels = [
+        F32x4([0.25, 0.5, 0.75, 0.]),
+        F32x4([0.25, 0.5, 0.75, 0.5]),
+        F32x4([-1., 2., 0., 0.]),
+    ];
+    let expected = [
+        [0.25, 0.5, 0.75, 0.],
+        [0.5, 1., 1.5, 0.5],
+        [-1., 2., 0., 0.],
+    ];
+
+    let alpha_mul_div: MulDiv = Default::default();
+    let src_image = ImageView::new(width, height, ImageRows::F32x4(vec![&pixels])).unwrap();
+    let mut dst_image = Image::new(width, height, PixelType::F32x4);
+    alpha_mul_div
+        .divide_alpha(&src_image, &mut dst_image.view_mut())
+        .unwrap();
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[f32; 4]>().1 };
+    assert_eq!(dst_pixels, expected);
+
+    let mut image =
+        ImageViewMut::new(width, height, ImageRowsMut::F32x4(vec![&mut pixels])).unwrap();
+    alpha_mul_div.divide_alpha_inplace(&mut image).unwrap();
+    assert_eq!(pixels.map(|p| p.0), expected);
+}
+
+#[test]
+fn image_multiply_and_divide_alpha_in_place_test() {
+    let size = NonZeroU32::new(4).unwrap();
+    let pixels: Vec<u32> = [
+        p(255, 128, 0, 255),
+        p(255, 128, 0, 128),
+        p(200, 100, 50, 0),
+        p(0, 255, 255, 64),
+    ]
+    .iter()
+    .map(|pixel| pixel.0)
+    .cycle()
+    .take(16)
+    .collect();
+    let src_image = Image::from_vec_u32(size, size, pixels, PixelType::U8x4).unwrap();
+
+    let mut expected = Image::new(size, size, PixelType::U8x4);
+    let mul_div = MulDiv::default();
+    mul_div
+        .multiply_alpha(&src_image.view(), &mut expected.view_mut())
+        .unwrap();
+
+    let mut image =
+        Image::from_vec_u8(size, size, src_image.buffer().to_vec(), PixelType::U8x4).unwrap();
+    image.multiply_alpha_in_place().unwrap();
+    assert_eq!(image.buffer(), expected.buffer());
+    assert_eq!(&image.buffer()[4..8], &[128, 64, 0, 128]);
+
+    mul_div
+        .divide_alpha_inplace(&mut expected.view_mut())
+        .unwrap();
+    image.divide_alpha_in_place().unwrap();
+    assert_eq!(image.buffer(), expected.buffer());
+    assert_eq!(&image.buffer()[0..4], &[255, 128, 0, 255]);
+}
+
+#[test]
+fn image_alpha_in_place_unsupported_pixel_type_test() {
+    let size = NonZeroU32::new(4).unwrap();
+    let mut image = Image::new(size, size, PixelType::U8x3);
+    assert!(matches!(
+        image.multiply_alpha_in_place(),
+        Err(MulDivImageError::UnsupportedPixelType)
+    ));
+    assert!(matches!(
+        image.divide_alpha_in_place(),
+        Err(MulDivImageError::UnsupportedPixelType)
+    ));
+}
+
+#[test]
+fn multiply_and_divide_alpha_u8x2_test() {
+    let width = NonZeroU32::new(4).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let src_pixels = [
+        U8x2([255, 255]),
+        U8x2([255, 128]),
+        U8x2([100, 0]),
+        U8x2([200, 51]),
+    ];
+    let buffer: Vec<u8> = src_pixels.iter().flat_map(|p| p.0).collect();
+    let mut image = Image::from_vec_u8(width, height, buffer, PixelType::U8x2).unwrap();
+    let mul_div = MulDiv::default();
+
+    let mut multiplied = Image::new(width, height, PixelType::U8x2);
+    mul_div
+        .multiply_alpha(&image.view(), &mut multiplied.view_mut())
+        .unwrap();
+    assert_eq!(multiplied.buffer(), &[255, 255, 128, 128, 0, 0, 40, 51]);
+
+    mul_div
+        .multiply_alpha_inplace(&mut image.view_mut())
+        .unwrap();
+    assert_eq!(image.buffer(), multiplied.buffer());
+
+    let mut divided = Image::new(width, height, PixelType::U8x2);
+    mul_div
+        .divide_alpha(&multiplied.view(), &mut divided.view_mut())
+        .unwrap();
+    assert_eq!(divided.buffer(), &[255, 255, 255, 128, 0, 0, 200, 51]);
+
+    mul_div.divide_alpha_inplace(&mut image.view_mut()).unwrap();
+    assert_eq!(image.buffer(), divided.buffer());
+}
+
+fn u16x2_bytes(pixels: &[U16x2]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|p| p.0)
+        .flat_map(|c| c.to_le_bytes())
+        .collect()
+}
+
+#[test]
+fn multiply_and_divide_alpha_u16x2_test() {
+    let width = NonZeroU32::new(4).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let src_pixels = [
+        U16x2([65535, 65535]),
+        U16x2([65535, 32768]),
+        U16x2([1000, 0]),
+        U16x2([40000, 13107]),
+    ];
+    let mut image =
+        Image::from_vec_u8(width, height, u16x2_bytes(&src_pixels), PixelType::U16x2).unwrap();
+    let mul_div = MulDiv::default();
+
+    let mut multiplied = Image::new(width, height, PixelType::U16x2);
+    mul_div
+        .multiply_alpha(&image.view(), &mut multiplied.view_mut())
+        .unwrap();
+    let expected = [
+        U16x2([65535, 65535]),
+        U16x2([32768, 32768]),
+        U16x2([0, 0]),
+        U16x2([8000, 13107]),
+    ];
+    assert_eq!(multiplied.buffer(), u16x2_bytes(&expected));
+
+    mul_div
+        .multiply_alpha_inplace(&mut image.view_mut())
+        .unwrap();
+    assert_eq!(image.buffer(), multiplied.buffer());
+
+    let mut divided = Image::new(width, height, PixelType::U16x2);
+    mul_div
+        .divide_alpha(&multiplied.view(), &mut divided.view_mut())
+        .unwrap();
+    let expected = [
+        U16x2([65535, 65535]),
+        U16x2([65535, 32768]),
+        U16x2([0, 0]),
+        U16x2([40000, 13107]),
+    ];
+    assert_eq!(divided.buffer(), u16x2_bytes(&expected));
+
+    mul_div.divide_alpha_inplace(&mut image.view_mut()).unwrap();
+    assert_eq!(image.buffer(), divided.buffer());
+}
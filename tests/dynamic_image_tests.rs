@@ -0,0 +1,184 @@
+#![cfg(feature = "image")]
+use std::convert::TryFrom;
+use std::num::NonZeroU32;
+
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb, Rgba, RgbaImage};
+
+use fast_image_resize::{FromDynamicImageError, Image, PixelType, ResizeImageBufferError, Resizer};
+
+fn assert_round_trip(src_image: DynamicImage, pixel_type: PixelType) {
+    let image = Image::try_from(&src_image).unwrap();
+    assert_eq!(image.pixel_type(), pixel_type);
+    assert_eq!(image.width().get(), src_image.width());
+    assert_eq!(image.height().get(), src_image.height());
+
+    let dst_image = image.to_dynamic_image().unwrap();
+    assert_eq!(dst_image.color(), src_image.color());
+    assert_eq!(dst_image.as_bytes(), src_image.as_bytes());
+}
+
+fn u8_values(count: usize) -> Vec<u8> {
+    (0..count).map(|i| (i * 7 % 256) as u8).collect()
+}
+
+fn u16_values(count: usize) -> Vec<u16> {
+    (0..count).map(|i| (i * 2999 % 65536) as u16).collect()
+}
+
+#[test]
+fn round_trip_luma8() {
+    let img = ImageBuffer::from_raw(7, 5, u8_values(7 * 5)).unwrap();
+    assert_round_trip(DynamicImage::ImageLuma8(img), PixelType::U8);
+}
+
+#[test]
+fn round_trip_luma_a8() {
+    let img = ImageBuffer::from_raw(7, 5, u8_values(7 * 5 * 2)).unwrap();
+    assert_round_trip(DynamicImage::ImageLumaA8(img), PixelType::U8x2);
+}
+
+#[test]
+fn round_trip_luma_a16() {
+    let img = ImageBuffer::from_raw(7, 5, u16_values(7 * 5 * 2)).unwrap();
+    assert_round_trip(DynamicImage::ImageLumaA16(img), PixelType::U16x2);
+}
+
+#[test]
+fn round_trip_rgb8() {
+    let img = ImageBuffer::from_raw(7, 5, u8_values(7 * 5 * 3)).unwrap();
+    assert_round_trip(DynamicImage::ImageRgb8(img), PixelType::U8x3);
+}
+
+#[test]
+fn round_trip_rgba8() {
+    let img = ImageBuffer::from_raw(7, 5, u8_values(7 * 5 * 4)).unwrap();
+    assert_round_trip(DynamicImage::ImageRgba8(img), PixelType::U8x4);
+}
+
+#[test]
+fn round_trip_rgb16() {
+    let img = ImageBuffer::from_raw(7, 5, u16_values(7 * 5 * 3)).unwrap();
+    assert_round_trip(DynamicImage::ImageRgb16(img), PixelType::U16x3);
+}
+
+#[test]
+fn round_trip_rgba16() {
+    let img = ImageBuffer::from_raw(7, 5, u16_values(7 * 5 * 4)).unwrap();
+    assert_round_trip(DynamicImage::ImageRgba16(img), PixelType::U16x4);
+}
+
+#[test]
+fn unsupported_color_type() {
+    let img = ImageBuffer::from_raw(7, 5, u16_values(7 * 5)).unwrap();
+    assert!(matches!(
+        Image::try_from(&DynamicImage::ImageLuma16(img)),
+        Err(FromDynamicImageError::UnsupportedColorType)
+    ));
+}
+
+#[test]
+fn zero_size() {
+    let img = ImageBuffer::from_raw(0, 5, vec![]).unwrap();
+    assert!(matches!(
+        Image::try_from(&DynamicImage::ImageLuma8(img)),
+        Err(FromDynamicImageError::ZeroSize)
+    ));
+}
+
+#[test]
+fn unsupported_pixel_type() {
+    let size = std::num::NonZeroU32::new(3).unwrap();
+    let image = Image::new(size, size, PixelType::F32);
+    assert!(image.to_dynamic_image().is_err());
+}
+
+fn resize_dynamic_image(src_image: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    let src_image = Image::try_from(src_image).unwrap();
+    let mut dst_image = Image::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        src_image.pixel_type(),
+    );
+    Resizer::default()
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image.to_dynamic_image().unwrap()
+}
+
+#[test]
+fn resize_rgba_image_buffer() {
+    let src_image: RgbaImage = ImageBuffer::from_raw(71, 53, u8_values(71 * 53 * 4)).unwrap();
+    let mut dst_image = RgbaImage::new(23, 41);
+    Resizer::default()
+        .resize_image_buffer(&src_image, &mut dst_image)
+        .unwrap();
+    let expected = resize_dynamic_image(&DynamicImage::ImageRgba8(src_image), 23, 41);
+    assert_eq!(dst_image.as_raw(), expected.as_bytes());
+}
+
+#[test]
+fn resize_rgb16_image_buffer() {
+    let src_image: ImageBuffer<Rgb<u16>, _> =
+        ImageBuffer::from_raw(71, 53, u16_values(71 * 53 * 3)).unwrap();
+    let mut dst_image = ImageBuffer::new(23, 41);
+    Resizer::default()
+        .resize_image_buffer(&src_image, &mut dst_image)
+        .unwrap();
+    let expected = resize_dynamic_image(&DynamicImage::ImageRgb16(src_image), 23, 41);
+    assert_eq!(dst_image.as_raw(), expected.as_rgb16().unwrap().as_raw());
+}
+
+#[test]
+fn resize_image_buffer_with_excess_samples() {
+    let mut samples = u8_values(71 * 53 * 4);
+    let src_image: RgbaImage = ImageBuffer::from_raw(71, 53, samples.clone()).unwrap();
+    // Containers are longer than required for their dimensions
+    samples.extend([1, 2, 3]);
+    let long_src_image: RgbaImage = ImageBuffer::from_raw(71, 53, samples).unwrap();
+    let mut dst_image = RgbaImage::new(23, 41);
+    let mut long_dst_image: RgbaImage =
+        ImageBuffer::from_raw(23, 41, vec![7; 23 * 41 * 4 + 5]).unwrap();
+
+    let mut resizer = Resizer::default();
+    resizer
+        .resize_image_buffer(&src_image, &mut dst_image)
+        .unwrap();
+    resizer
+        .resize_image_buffer(&long_src_image, &mut long_dst_image)
+        .unwrap();
+    let long_samples = long_dst_image.into_raw();
+    assert_eq!(
+        &long_samples[..dst_image.len()],
+        dst_image.as_raw().as_slice()
+    );
+    assert_eq!(&long_samples[dst_image.len()..], &[7; 5]);
+}
+
+#[test]
+fn resize_image_buffer_errors() {
+    let mut resizer = Resizer::default();
+
+    let src_image: ImageBuffer<Rgba<f32>, _> = ImageBuffer::new(8, 8);
+    let mut dst_image = ImageBuffer::new(4, 4);
+    assert!(matches!(
+        resizer.resize_image_buffer(&src_image, &mut dst_image),
+        Err(ResizeImageBufferError::UnsupportedColorType)
+    ));
+
+    let src_image = RgbaImage::new(8, 8);
+    let mut dst_image = RgbaImage::new(0, 4);
+    assert!(matches!(
+        resizer.resize_image_buffer(&src_image, &mut dst_image),
+        Err(ResizeImageBufferError::ZeroSize)
+    ));
+
+    // Container that starts at odd address can't be aligned to u32
+    let samples = vec![0u32; 8 * 8 + 1];
+    let bytes = unsafe { samples.align_to::<u8>().1 };
+    let src_image: ImageBuffer<Rgba<u8>, _> = ImageBuffer::from_raw(8, 8, &bytes[1..]).unwrap();
+    let mut dst_image = RgbaImage::new(4, 4);
+    assert!(matches!(
+        resizer.resize_image_buffer(&src_image, &mut dst_image),
+        Err(ResizeImageBufferError::InvalidBufferAlignment)
+    ));
+}
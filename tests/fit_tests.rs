@@ -0,0 +1,101 @@
+use std::num::NonZeroU32;
+
+use fast_image_resize::{
+    fit_size, resize_letterbox, ContentRect, Image, PixelType, ResizeAlg, ResizeError,
+};
+
+fn nz(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap()
+}
+
+fn fit(src: (u32, u32), max: (u32, u32), upscale: bool) -> (u32, u32) {
+    let (width, height) = fit_size(nz(src.0), nz(src.1), nz(max.0), nz(max.1), upscale);
+    (width.get(), height.get())
+}
+
+#[test]
+fn fit_landscape_and_portrait() {
+    assert_eq!(fit((1920, 1080), (400, 400), false), (400, 225));
+    assert_eq!(fit((1080, 1920), (400, 400), false), (225, 400));
+    assert_eq!(fit((1920, 1080), (400, 100), false), (178, 100));
+    assert_eq!(fit((1080, 1920), (100, 400), false), (100, 178));
+}
+
+#[test]
+fn fit_without_upscale() {
+    assert_eq!(fit((320, 240), (400, 400), false), (320, 240));
+    assert_eq!(fit((320, 240), (320, 240), false), (320, 240));
+    assert_eq!(fit((320, 240), (400, 400), true), (400, 300));
+    assert_eq!(fit((240, 320), (400, 400), true), (300, 400));
+}
+
+#[test]
+fn fit_never_returns_zero_size() {
+    assert_eq!(fit((10000, 1), (100, 100), false), (100, 1));
+    assert_eq!(fit((1, 10000), (100, 100), false), (1, 100));
+}
+
+#[test]
+fn letterbox_landscape_into_square() {
+    let color = [200u8, 10, 50];
+    let fill = [114u8, 114, 114];
+    let buffer = color.repeat(1920 * 1080);
+    let src_image = Image::from_vec_u8(nz(1920), nz(1080), buffer, PixelType::U8x3).unwrap();
+    let (dst_image, rect) =
+        resize_letterbox(&src_image, 640, 640, &fill, None, ResizeAlg::default()).unwrap();
+    assert_eq!((dst_image.width(), dst_image.height()), (nz(640), nz(640)));
+    assert_eq!(
+        rect,
+        ContentRect {
+            left: 0,
+            top: 140,
+            width: nz(640),
+            height: nz(360),
+        }
+    );
+
+    let rows: Vec<&[u8]> = dst_image.buffer().chunks_exact(640 * 3).collect();
+    let is_filled_by = |row: &[u8], pixel: [u8; 3]| row.chunks_exact(3).all(|p| p == pixel);
+    // Pad regions above and below the content have equal size.
+    assert!(rows[..140].iter().all(|row| is_filled_by(row, fill)));
+    assert!(rows[140..500].iter().all(|row| is_filled_by(row, color)));
+    assert!(rows[500..].iter().all(|row| is_filled_by(row, fill)));
+}
+
+#[test]
+fn letterbox_with_centering() {
+    let src_image = Image::from_vec_u8(nz(10), nz(40), vec![255; 10 * 40], PixelType::U8).unwrap();
+    let cases = [
+        (Some((0., 0.)), 0),
+        (Some((1., 0.)), 15),
+        (Some((0.5, 0.5)), 8),
+        (None, 8),
+    ];
+    for (centering, left) in cases {
+        let (dst_image, rect) =
+            resize_letterbox(&src_image, 20, 20, &[0], centering, ResizeAlg::Nearest).unwrap();
+        assert_eq!((rect.left, rect.top), (left, 0), "{:?}", centering);
+        assert_eq!((rect.width.get(), rect.height.get()), (5, 20));
+        for row in dst_image.buffer().chunks_exact(20) {
+            let expected: Vec<u8> = (0..20)
+                .map(|x| {
+                    if (left..left + 5).contains(&x) {
+                        255
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+            assert_eq!(row, expected.as_slice());
+        }
+    }
+}
+
+#[test]
+fn letterbox_with_invalid_params() {
+    let src_image = Image::new(nz(16), nz(9), PixelType::U8x4);
+    let result = resize_letterbox(&src_image, 0, 64, &[0; 4], None, ResizeAlg::default());
+    assert!(matches!(result, Err(ResizeError::ZeroDimension)));
+    let result = resize_letterbox(&src_image, 64, 64, &[0; 3], None, ResizeAlg::default());
+    assert!(matches!(result, Err(ResizeError::InvalidFillPixel)));
+}
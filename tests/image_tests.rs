@@ -0,0 +1,602 @@
+use std::num::NonZeroU32;
+
+use fast_image_resize::pixels::{U16x3, U8x4};
+use fast_image_resize::{
+    linear_to_srgb, srgb_to_linear, DifferentTypesOfPixelsError, FilterType, Image,
+    ImageBufferError, ImageView, ImageViewMut, InvalidBufferSizeError, PixelAccessError, PixelType,
+    ResizeAlg, Resizer, UnsupportedConversionError,
+};
+
+// Product of these dimensions is 2^32 + 2^16, it overflows `u32`
+// and becomes equal to 2^16.
+const WIDTH: u32 = 65536;
+const HEIGHT: u32 = 65537;
+const WRAPPED_PIXELS_COUNT: usize = 65536;
+
+fn size() -> (NonZeroU32, NonZeroU32) {
+    (
+        NonZeroU32::new(WIDTH).unwrap(),
+        NonZeroU32::new(HEIGHT).unwrap(),
+    )
+}
+
+#[test]
+fn try_new_with_overflowed_size() {
+    // Size of buffer of this image in bytes overflows `usize`.
+    let size = NonZeroU32::new(u32::MAX).unwrap();
+    assert!(matches!(
+        Image::try_new(size, size, PixelType::F32x4),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+    assert!(matches!(
+        Image::from_vec_u8(size, size, Vec::new(), PixelType::F32x4),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+    assert!(matches!(
+        Image::from_slice_u8(size, size, &mut [], PixelType::F32x4),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+
+    let width = NonZeroU32::new(WIDTH).unwrap();
+    let image = Image::try_new(width, NonZeroU32::new(1).unwrap(), PixelType::U8).unwrap();
+    assert_eq!(image.buffer().len(), WIDTH as usize);
+}
+
+#[test]
+fn from_vec_with_overflowed_size() {
+    let (width, height) = size();
+    let buffer = vec![0u32; WRAPPED_PIXELS_COUNT];
+    assert!(matches!(
+        Image::from_vec_u32(width, height, buffer, PixelType::U8x4),
+        Err(InvalidBufferSizeError)
+    ));
+
+    let buffer = vec![0u8; WRAPPED_PIXELS_COUNT];
+    assert!(matches!(
+        Image::from_vec_u8(width, height, buffer, PixelType::U8),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+}
+
+#[test]
+fn from_slice_with_overflowed_size() {
+    let (width, height) = size();
+    let mut buffer = vec![0u32; WRAPPED_PIXELS_COUNT];
+    assert!(matches!(
+        Image::from_slice_u32(width, height, &mut buffer, PixelType::U8x4),
+        Err(InvalidBufferSizeError)
+    ));
+
+    let mut buffer = vec![0u8; WRAPPED_PIXELS_COUNT];
+    assert!(matches!(
+        Image::from_slice_u8(width, height, &mut buffer, PixelType::U8),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+}
+
+#[test]
+fn view_from_buffer_with_overflowed_size() {
+    let (width, height) = size();
+    let mut buffer = vec![0u8; WRAPPED_PIXELS_COUNT];
+    assert!(matches!(
+        ImageView::from_buffer(width, height, &buffer, PixelType::U8),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+    assert!(matches!(
+        ImageViewMut::from_buffer(width, height, &mut buffer, PixelType::U8),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Size of image buffer overflows usize")]
+fn new_image_with_overflowed_size() {
+    let max = NonZeroU32::new(u32::MAX).unwrap();
+    Image::new(max, max, PixelType::U16x4);
+}
+
+#[test]
+fn iterate_rows_of_u8x4_image() {
+    let width = NonZeroU32::new(3).unwrap();
+    let height = NonZeroU32::new(2).unwrap();
+    let buffer: Vec<u8> = (0..24).collect();
+    let image = Image::from_vec_u8(width, height, buffer, PixelType::U8x4).unwrap();
+    let view = image.view();
+
+    let rows: Vec<_> = view.rows_u8x4().unwrap().collect();
+    assert_eq!(rows.len(), 2);
+    let mut sums = [0u32; 4];
+    for row in rows {
+        assert_eq!(row.len(), 3);
+        for pixel in row {
+            for (sum, c) in sums.iter_mut().zip(pixel.0.to_le_bytes()) {
+                *sum += c as u32;
+            }
+        }
+    }
+    // Sum of components 0, 4, 8, ..., 20 plus offset of channel.
+    assert_eq!(sums, [60, 66, 72, 78]);
+
+    assert!(view.rows_u8x3().is_none());
+    assert!(view.rows_u8().is_none());
+}
+
+/// Returns buffer with rows of the given tightly packed buffer
+/// followed by `padding` bytes with value 0xAA.
+fn padded_buffer(buffer: &[u8], row_size: usize, padding: usize) -> Vec<u8> {
+    let mut result = Vec::with_capacity(buffer.len() / row_size * (row_size + padding));
+    for row in buffer.chunks_exact(row_size) {
+        result.extend_from_slice(row);
+        result.resize(result.len() + padding, 0xaa);
+    }
+    result
+}
+
+#[test]
+fn view_from_buffer_with_stride() {
+    let width = NonZeroU32::new(3).unwrap();
+    let height = NonZeroU32::new(2).unwrap();
+    let pixels: Vec<u8> = (0..18).collect();
+    let buffer = padded_buffer(&pixels, 9, 7);
+    let view =
+        ImageView::from_buffer_with_stride(width, height, &buffer, 16, PixelType::U8x3).unwrap();
+    let rows: Vec<_> = view.rows_u8x3().unwrap().collect();
+    assert_eq!(rows.len(), 2);
+    for (y, row) in rows.into_iter().enumerate() {
+        assert_eq!(row.len(), 3);
+        for (x, pixel) in row.iter().enumerate() {
+            let i = (y * 9 + x * 3) as u8;
+            assert_eq!(pixel.0, [i, i + 1, i + 2]);
+        }
+    }
+
+    // Padding after the last row may be absent
+    let view =
+        ImageView::from_buffer_with_stride(width, height, &buffer[..25], 16, PixelType::U8x3);
+    assert!(view.is_ok());
+}
+
+#[test]
+fn view_from_buffer_with_invalid_stride() {
+    let width = NonZeroU32::new(3).unwrap();
+    let height = NonZeroU32::new(2).unwrap();
+    let mut buffer = vec![0u8; 32];
+    assert!(matches!(
+        ImageView::from_buffer_with_stride(width, height, &buffer, 8, PixelType::U8x3),
+        Err(ImageBufferError::InvalidRowStride)
+    ));
+    assert!(matches!(
+        ImageViewMut::from_buffer_with_stride(width, height, &mut buffer, 8, PixelType::U8x3),
+        Err(ImageBufferError::InvalidRowStride)
+    ));
+    assert!(matches!(
+        ImageView::from_buffer_with_stride(width, height, &buffer[..24], 16, PixelType::U8x3),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+    assert!(matches!(
+        ImageViewMut::from_buffer_with_stride(width, height, &mut buffer, 24, PixelType::U8x3),
+        Err(ImageBufferError::InvalidBufferSize)
+    ));
+}
+
+#[test]
+fn resize_of_padded_buffer_ignores_padding() {
+    let width = NonZeroU32::new(7).unwrap();
+    let height = NonZeroU32::new(5).unwrap();
+    let dst_width = NonZeroU32::new(4).unwrap();
+    let dst_height = NonZeroU32::new(3).unwrap();
+    let pixels: Vec<u8> = (0..7 * 5 * 3).map(|v| (v * 7 % 256) as u8).collect();
+    let src_image = Image::from_vec_u8(width, height, pixels.clone(), PixelType::U8x3).unwrap();
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x3);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer
+        .resize(&src_image.view(), &mut expected.view_mut())
+        .unwrap();
+
+    let src_buffer = padded_buffer(&pixels, 21, 11);
+    let src_view =
+        ImageView::from_buffer_with_stride(width, height, &src_buffer, 32, PixelType::U8x3)
+            .unwrap();
+    let mut dst_buffer = vec![0xaa; 16 * 3];
+    let mut dst_view = ImageViewMut::from_buffer_with_stride(
+        dst_width,
+        dst_height,
+        &mut dst_buffer,
+        16,
+        PixelType::U8x3,
+    )
+    .unwrap();
+    resizer.resize(&src_view, &mut dst_view).unwrap();
+
+    assert_eq!(dst_buffer, padded_buffer(expected.buffer(), 12, 4));
+}
+
+#[test]
+fn image_from_raw_parts() {
+    let width = NonZeroU32::new(13).unwrap();
+    let height = NonZeroU32::new(9).unwrap();
+    let dst_width = NonZeroU32::new(5).unwrap();
+    let dst_height = NonZeroU32::new(4).unwrap();
+    let mut buffer: Vec<u8> = (0..13 * 9 * 3).map(|v| (v * 13 % 256) as u8).collect();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x3);
+    let src_view = ImageView::from_buffer(width, height, &buffer, PixelType::U8x3).unwrap();
+    resizer.resize(&src_view, &mut expected.view_mut()).unwrap();
+
+    let (ptr, len) = (buffer.as_mut_ptr(), buffer.len());
+    let src_image =
+        unsafe { Image::from_raw_parts(ptr, len, width, height, PixelType::U8x3) }.unwrap();
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x3);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert_eq!(dst_image.buffer(), expected.buffer());
+
+    let result = unsafe { Image::from_raw_parts(ptr, len - 1, width, height, PixelType::U8x3) };
+    assert!(matches!(result, Err(ImageBufferError::InvalidBufferSize)));
+}
+
+fn one_row_image(buffer: Vec<u8>, pixel_type: PixelType) -> Image<'static> {
+    let width = buffer.len() / pixel_type_size(pixel_type);
+    Image::from_vec_u8(
+        NonZeroU32::new(width as u32).unwrap(),
+        NonZeroU32::new(1).unwrap(),
+        buffer,
+        pixel_type,
+    )
+    .unwrap()
+}
+
+fn pixel_type_size(pixel_type: PixelType) -> usize {
+    match pixel_type {
+        PixelType::U8 => 1,
+        PixelType::U8x3 => 3,
+        PixelType::U8x4 => 4,
+        PixelType::U16x3 => 6,
+        PixelType::F32 => 4,
+        _ => unimplemented!(),
+    }
+}
+
+#[test]
+fn convert_u8x4_into_u8x3() {
+    let image = one_row_image(vec![10, 20, 30, 40, 50, 60, 70, 0], PixelType::U8x4);
+    let result = image.convert(PixelType::U8x3).unwrap();
+    assert_eq!(result.pixel_type(), PixelType::U8x3);
+    assert_eq!(result.width().get(), 2);
+    assert_eq!(result.buffer(), &[10, 20, 30, 50, 60, 70]);
+}
+
+#[test]
+fn convert_u8x3_into_u8x4() {
+    let image = one_row_image(vec![10, 20, 30, 50, 60, 70], PixelType::U8x3);
+    let result = image.convert(PixelType::U8x4).unwrap();
+    assert_eq!(result.pixel_type(), PixelType::U8x4);
+    assert_eq!(result.buffer(), &[10, 20, 30, 255, 50, 60, 70, 255]);
+}
+
+#[test]
+fn convert_u8x3_into_u8() {
+    let image = one_row_image(
+        vec![255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255],
+        PixelType::U8x3,
+    );
+    let result = image.convert(PixelType::U8).unwrap();
+    assert_eq!(result.pixel_type(), PixelType::U8);
+    // 0.2126 * 255 = 54.2; 0.7152 * 255 = 182.4; 0.0722 * 255 = 18.4
+    assert_eq!(result.buffer(), &[255, 0, 54, 182, 18]);
+}
+
+#[test]
+fn convert_u16x3_into_u8x3() {
+    let buffer: Vec<u8> = [0u16, 128, 129, 257, 32896, 65535]
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let image = one_row_image(buffer, PixelType::U16x3);
+    let result = image.convert(PixelType::U8x3).unwrap();
+    assert_eq!(result.pixel_type(), PixelType::U8x3);
+    assert_eq!(result.buffer(), &[0, 0, 1, 1, 128, 255]);
+}
+
+#[test]
+fn convert_u8x3_into_u16x3() {
+    let image = one_row_image(vec![0, 1, 128, 255, 254, 2], PixelType::U8x3);
+    let result = image.convert(PixelType::U16x3).unwrap();
+    assert_eq!(result.pixel_type(), PixelType::U16x3);
+    let values: Vec<u16> = result
+        .buffer()
+        .chunks_exact(2)
+        .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+        .collect();
+    assert_eq!(values, [0, 257, 32896, 65535, 65278, 514]);
+    // Back conversion restores original values.
+    assert_eq!(
+        result.convert(PixelType::U8x3).unwrap().buffer(),
+        image.buffer()
+    );
+}
+
+#[test]
+fn convert_into_same_type_copies_pixels() {
+    let image = one_row_image(vec![1, 2, 3, 4, 5, 6], PixelType::U8x3);
+    let result = image.convert(PixelType::U8x3).unwrap();
+    assert_eq!(result.buffer(), image.buffer());
+}
+
+#[test]
+fn convert_unsupported_pixel_types() {
+    let image = one_row_image(vec![1, 2, 3, 4], PixelType::U8x4);
+    for pixel_type in [PixelType::U8, PixelType::U16x3, PixelType::F32] {
+        assert!(matches!(
+            image.convert(pixel_type),
+            Err(UnsupportedConversionError)
+        ));
+    }
+}
+
+fn f32_values(image: &Image) -> Vec<f32> {
+    image
+        .buffer()
+        .chunks_exact(4)
+        .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+#[test]
+fn srgb_to_linear_known_values() {
+    let mut image = one_row_image(vec![0, 188, 255, 188, 188, 255, 100, 0], PixelType::U8x4);
+    srgb_to_linear(&mut image).unwrap();
+    // Alpha-channel is not changed
+    assert_eq!(image.buffer(), &[0, 128, 255, 188, 128, 255, 32, 0]);
+
+    let values = [0., 188. / 255., 1.];
+    let buffer = values.iter().flat_map(|v: &f32| v.to_ne_bytes()).collect();
+    let mut image = one_row_image(buffer, PixelType::F32);
+    srgb_to_linear(&mut image).unwrap();
+    let result = f32_values(&image);
+    assert_eq!(result[0], 0.);
+    assert!((result[1] - 0.5029).abs() < 1e-4, "{}", result[1]);
+    assert!((result[2] - 1.).abs() < 1e-6, "{}", result[2]);
+}
+
+#[test]
+fn linear_to_srgb_round_trip() {
+    // Linear light -> sRGB -> linear light of 8-bit values
+    let buffer: Vec<u8> = (0..255).collect();
+    let mut image = one_row_image(buffer.clone(), PixelType::U8x3);
+    linear_to_srgb(&mut image).unwrap();
+    assert_eq!(image.buffer()[128], 188);
+    srgb_to_linear(&mut image).unwrap();
+    for (&result, &expected) in image.buffer().iter().zip(&buffer) {
+        assert!(
+            (result as i32 - expected as i32).abs() <= 1,
+            "{} != {}",
+            result,
+            expected
+        );
+    }
+
+    // sRGB -> linear light -> sRGB of f32 values
+    let values: Vec<f32> = (0..=1000).map(|i| i as f32 / 1000.).collect();
+    let buffer = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+    let mut image = one_row_image(buffer, PixelType::F32);
+    srgb_to_linear(&mut image).unwrap();
+    linear_to_srgb(&mut image).unwrap();
+    for (result, expected) in f32_values(&image).into_iter().zip(values) {
+        assert!(
+            (result - expected).abs() < 1e-5,
+            "{} != {}",
+            result,
+            expected
+        );
+    }
+}
+
+#[test]
+fn srgb_conversion_of_unsupported_pixel_types() {
+    let mut image = one_row_image(vec![1, 2, 3, 4, 5, 6], PixelType::U16x3);
+    assert!(matches!(
+        srgb_to_linear(&mut image),
+        Err(UnsupportedConversionError)
+    ));
+    assert!(matches!(
+        linear_to_srgb(&mut image),
+        Err(UnsupportedConversionError)
+    ));
+    assert_eq!(image.buffer(), &[1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn get_and_set_pixels() {
+    let (width, height) = (NonZeroU32::new(3).unwrap(), NonZeroU32::new(2).unwrap());
+    let mut image = Image::new(width, height, PixelType::U8x4);
+    for (x, y) in [(0, 0), (2, 0), (0, 1), (2, 1)] {
+        let pixel = U8x4(u32::from_le_bytes([x as u8, y as u8, 10, 255]));
+        image.set_pixel_u8x4(x, y, pixel).unwrap();
+    }
+    assert_eq!(
+        image.get_pixel_u8x4(0, 0),
+        Some(U8x4(0xff0a0000u32.to_le()))
+    );
+    assert_eq!(
+        image.get_pixel_u8x4(2, 1),
+        Some(U8x4(0xff0a0102u32.to_le()))
+    );
+    assert_eq!(image.get_pixel_u8x4(1, 1), Some(U8x4(0)));
+    assert_eq!(
+        &image.buffer()[20..],
+        &[2, 1, 10, 255],
+        "Pixel (2, 1) must be the last pixel in the buffer"
+    );
+
+    assert_eq!(image.get_pixel_u8x4(3, 0), None);
+    assert_eq!(image.get_pixel_u8x4(0, 2), None);
+    assert!(matches!(
+        image.set_pixel_u8x4(3, 1, U8x4(0)),
+        Err(PixelAccessError::PositionIsOutOfImageBoundaries)
+    ));
+
+    assert_eq!(image.get_pixel_u16x3(0, 0), None);
+    assert!(matches!(
+        image.set_pixel_u16x3(0, 0, U16x3([1, 2, 3])),
+        Err(PixelAccessError::DifferentTypesOfPixels)
+    ));
+}
+
+#[test]
+fn fill_image_by_color() {
+    let (width, height) = (NonZeroU32::new(7).unwrap(), NonZeroU32::new(5).unwrap());
+    let mut image = Image::new(width, height, PixelType::U8x4);
+    let color = U8x4(u32::from_le_bytes([10, 20, 30, 255]));
+    image.fill_u8x4(color).unwrap();
+    for (x, y) in [(0, 0), (6, 0), (3, 2), (0, 4), (6, 4)] {
+        assert_eq!(image.get_pixel_u8x4(x, y), Some(color));
+    }
+    assert_eq!(&image.buffer()[..8], &[10, 20, 30, 255, 10, 20, 30, 255]);
+
+    assert!(matches!(
+        image.fill_u16x3(U16x3([1, 2, 3])),
+        Err(DifferentTypesOfPixelsError)
+    ));
+    assert_eq!(image.get_pixel_u8x4(6, 4), Some(color));
+}
+
+#[test]
+fn misaligned_buffer_is_error() {
+    assert_eq!(PixelType::U8.required_alignment(), 1);
+    assert_eq!(PixelType::U16x3.required_alignment(), 2);
+    assert_eq!(PixelType::U8x4.required_alignment(), 4);
+    assert_eq!(PixelType::F32x4.required_alignment(), 4);
+
+    let size = NonZeroU32::new(4).unwrap();
+    let len = PixelType::U8x4.buffer_len(size, size).unwrap();
+    // Buffer of `u32` values is aligned by 4 bytes.
+    let mut buffer = vec![0u32; len / 4 + 1];
+    let bytes = unsafe { buffer.align_to_mut::<u8>().1 };
+    assert_eq!(
+        bytes.as_ptr() as usize % PixelType::U8x4.required_alignment(),
+        0
+    );
+    assert!(Image::from_slice_u8(size, size, &mut bytes[..len], PixelType::U8x4).is_ok());
+    assert!(matches!(
+        Image::from_slice_u8(size, size, &mut bytes[1..len + 1], PixelType::U8x4),
+        Err(ImageBufferError::InvalidBufferAlignment)
+    ));
+    // Pixels of `U8x3` consist of bytes, any buffer is aligned.
+    let len = PixelType::U8x3.buffer_len(size, size).unwrap();
+    assert!(Image::from_slice_u8(size, size, &mut bytes[1..len + 1], PixelType::U8x3).is_ok());
+}
+
+#[test]
+fn buffer_len_of_pixel_types() {
+    let pixel_types = [
+        (PixelType::U8, 1),
+        (PixelType::U8x2, 2),
+        (PixelType::U8x3, 3),
+        (PixelType::U8x4, 4),
+        (PixelType::U16x2, 4),
+        (PixelType::U16x3, 6),
+        (PixelType::U16x4, 8),
+        (PixelType::I16, 2),
+        (PixelType::I32, 4),
+        (PixelType::F32, 4),
+        (PixelType::F32x4, 16),
+        #[cfg(feature = "half")]
+        (PixelType::F16, 2),
+    ];
+    for (pixel_type, pixel_size) in pixel_types {
+        for (width, height) in [(1, 1), (3, 7), (1920, 1080)] {
+            let (width, height) = (
+                NonZeroU32::new(width).unwrap(),
+                NonZeroU32::new(height).unwrap(),
+            );
+            let len = pixel_type.buffer_len(width, height).unwrap();
+            assert_eq!(
+                len,
+                (width.get() * height.get()) as usize * pixel_size,
+                "{:?}",
+                pixel_type
+            );
+            // Buffer of `u32` values is aligned for any type of pixels.
+            let mut buffer = vec![0u32; len.div_ceil(4)];
+            let bytes = &mut unsafe { buffer.align_to_mut::<u8>() }.1[..len];
+            assert!(Image::from_slice_u8(width, height, bytes, pixel_type).is_ok());
+        }
+
+        // Size of the buffer doesn't fit into `usize`.
+        let max = NonZeroU32::new(u32::MAX).unwrap();
+        if pixel_size > 1 {
+            assert_eq!(pixel_type.buffer_len(max, max), None);
+        }
+        let (width, height) = size();
+        assert_eq!(
+            pixel_type.buffer_len(width, height),
+            (WIDTH as usize)
+                .checked_mul(HEIGHT as usize)
+                .and_then(|count| count.checked_mul(pixel_size))
+        );
+    }
+}
+
+#[test]
+fn rotate_and_flip_image() {
+    // 3x2 image:
+    // 1 2 3
+    // 4 5 6
+    let image = Image::from_vec_u8(
+        NonZeroU32::new(3).unwrap(),
+        NonZeroU32::new(2).unwrap(),
+        vec![1, 2, 3, 4, 5, 6],
+        PixelType::U8,
+    )
+    .unwrap();
+    let check = |image: Image, width: u32, height: u32, pixels: &[u8]| {
+        assert_eq!((image.width().get(), image.height().get()), (width, height));
+        assert_eq!(image.buffer(), pixels);
+    };
+    check(image.rotate_90(), 2, 3, &[4, 1, 5, 2, 6, 3]);
+    check(image.rotate_180(), 3, 2, &[6, 5, 4, 3, 2, 1]);
+    check(image.rotate_270(), 2, 3, &[3, 6, 2, 5, 1, 4]);
+    check(image.flip_horizontally(), 3, 2, &[3, 2, 1, 6, 5, 4]);
+    check(image.flip_vertically(), 3, 2, &[4, 5, 6, 1, 2, 3]);
+    let rotated = image.rotate_90().rotate_90().rotate_90().rotate_90();
+    check(rotated, 3, 2, image.buffer());
+}
+
+#[test]
+fn rotate_90_maps_corners() {
+    let (width, height) = (5, 3);
+    let mut image = Image::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        PixelType::U16x3,
+    );
+    let corners = [
+        (0, 0),
+        (width - 1, 0),
+        (0, height - 1),
+        (width - 1, height - 1),
+    ];
+    for (i, &(x, y)) in corners.iter().enumerate() {
+        let value = 1000 * (i as u16 + 1);
+        image
+            .set_pixel_u16x3(x, y, U16x3([value, value + 1, value + 2]))
+            .unwrap();
+    }
+    let rotated = image.rotate_90();
+    assert_eq!(rotated.width().get(), height);
+    assert_eq!(rotated.height().get(), width);
+    // Clockwise rotation: top-left corner becomes top-right one, etc.
+    let rotated_corners = [
+        (height - 1, 0),
+        (height - 1, width - 1),
+        (0, 0),
+        (0, width - 1),
+    ];
+    for (&(x, y), &(rx, ry)) in corners.iter().zip(&rotated_corners) {
+        assert_eq!(rotated.get_pixel_u16x3(rx, ry), image.get_pixel_u16x3(x, y));
+    }
+    assert_eq!(rotated.get_pixel_u16x3(1, 1), Some(U16x3([0, 0, 0])));
+}
@@ -2,8 +2,12 @@ use std::num::NonZeroU32;
 
 use fast_image_resize::pixels::*;
 use fast_image_resize::{
-    CpuExtensions, DifferentTypesOfPixelsError, FilterType, Image, ImageView, PixelType, ResizeAlg,
-    Resizer,
+    AlphaMode, BoundaryMode, Coefficients, CoefficientsChunk, ConvolutionError, CpuExtensions,
+    CropBox, CropBoxError, CustomFilter, CustomFilter2D, DifferentTypesOfPixelsError, FilterType,
+    Image, ImageView, InvalidFilterSupportError, MulDiv, PixelType, PreparedResize, ResizeAlg,
+    ResizeError, ResizeIntoAllocatedError, ResizePremultipliedError, ResizePreparedError,
+    ResizeRegionError, ResizeWithProgressError, Resizer, RoundingMode, UnsharpMask,
+    UnsupportedAlgorithmError, UnsupportedConversionError,
 };
 use utils::{cpu_ext_into_str, PixelExt};
 
@@ -17,6 +21,56 @@ fn get_new_height(src_image: &ImageView, new_width: u32) -> u32 {
 const NEW_WIDTH: u32 = 255;
 const NEW_BIG_WIDTH: u32 = 5016;
 
+#[test]
+fn mismatched_pixel_types_are_error() {
+    let size = NonZeroU32::new(16).unwrap();
+    let dst_size = NonZeroU32::new(8).unwrap();
+    let src_image =
+        Image::from_vec_u8(size, size, vec![255; 16 * 16 * 4], PixelType::U8x4).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x3);
+    let algorithms = [
+        ResizeAlg::Nearest,
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        ResizeAlg::SuperSampling(FilterType::Bilinear, 2),
+        ResizeAlg::IntegralImage,
+        ResizeAlg::Bilinear,
+    ];
+    for algorithm in algorithms {
+        let mut resizer = Resizer::new(algorithm);
+        assert!(matches!(
+            resizer.resize(&src_image.view(), &mut dst_image.view_mut()),
+            Err(DifferentTypesOfPixelsError)
+        ));
+        assert!(matches!(
+            resizer.resize_to_cover(&src_image.view(), &mut dst_image.view_mut(), None),
+            Err(DifferentTypesOfPixelsError)
+        ));
+        let region = CropBox {
+            left: 2.,
+            top: 2.,
+            width: 8.,
+            height: 8.,
+        };
+        assert!(matches!(
+            resizer.resize_region(&src_image.view(), region, &mut dst_image.view_mut()),
+            Err(ResizeRegionError::DifferentTypesOfPixels)
+        ));
+        let prepared =
+            PreparedResize::new(FilterType::Lanczos3, &src_image.view(), dst_size, dst_size);
+        assert!(matches!(
+            resizer.resize_prepared(&prepared, &src_image.view(), &mut dst_image.view_mut()),
+            Err(ResizePreparedError::DifferentTypesOfPixels)
+        ));
+        assert!(matches!(
+            resizer
+                .resize_with_progress(&src_image.view(), &mut dst_image.view_mut(), &mut |_| true),
+            Err(ResizeWithProgressError::DifferentTypesOfPixels)
+        ));
+    }
+    // Destination image is left untouched.
+    assert!(dst_image.buffer().iter().all(|&v| v == 0));
+}
+
 #[test]
 fn try_resize_to_other_pixel_type() {
     let src_image = U8x4::load_big_src_image();
@@ -37,9 +91,7 @@ fn downscale_test<P: PixelExt>(resize_alg: ResizeAlg, cpu_extensions: CpuExtensi
     assert_eq!(image.pixel_type(), P::pixel_type());
 
     let mut resizer = Resizer::new(resize_alg);
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
     let new_height = get_new_height(&image.view(), NEW_WIDTH);
     let mut result = Image::new(
         NonZeroU32::new(NEW_WIDTH).unwrap(),
@@ -55,10 +107,18 @@ fn downscale_test<P: PixelExt>(resize_alg: ResizeAlg, cpu_extensions: CpuExtensi
         ResizeAlg::Convolution(filter) => match filter {
             FilterType::Box => "box",
             FilterType::Bilinear => "bilinear",
+            FilterType::Triangle => "triangle",
             FilterType::Hamming => "hamming",
             FilterType::Mitchell => "mitchell",
+            FilterType::Hermite => "hermite",
+            FilterType::BSpline => "bspline",
             FilterType::CatmullRom => "catmullrom",
+            FilterType::Lanczos2 => "lanczos2",
             FilterType::Lanczos3 => "lanczos3",
+            FilterType::Blackman => "blackman",
+            FilterType::Welch => "welch",
+            FilterType::Bartlett => "bartlett",
+            FilterType::Hann => "hann",
             _ => "unknown",
         },
         ResizeAlg::SuperSampling(_, _) => "supersampling",
@@ -80,9 +140,7 @@ fn upscale_test<P: PixelExt>(resize_alg: ResizeAlg, cpu_extensions: CpuExtension
     assert_eq!(image.pixel_type(), P::pixel_type());
 
     let mut resizer = Resizer::new(resize_alg);
-    unsafe {
-        resizer.set_cpu_extensions(cpu_extensions);
-    }
+    resizer.set_cpu_extensions(cpu_extensions);
     let new_height = get_new_height(&image.view(), NEW_BIG_WIDTH);
     let mut result = Image::new(
         NonZeroU32::new(NEW_BIG_WIDTH).unwrap(),
@@ -98,10 +156,18 @@ fn upscale_test<P: PixelExt>(resize_alg: ResizeAlg, cpu_extensions: CpuExtension
         ResizeAlg::Convolution(filter) => match filter {
             FilterType::Box => "box",
             FilterType::Bilinear => "bilinear",
+            FilterType::Triangle => "triangle",
             FilterType::Hamming => "hamming",
             FilterType::Mitchell => "mitchell",
+            FilterType::Hermite => "hermite",
+            FilterType::BSpline => "bspline",
             FilterType::CatmullRom => "catmullrom",
+            FilterType::Lanczos2 => "lanczos2",
             FilterType::Lanczos3 => "lanczos3",
+            FilterType::Blackman => "blackman",
+            FilterType::Welch => "welch",
+            FilterType::Bartlett => "bartlett",
+            FilterType::Hann => "hann",
             _ => "unknown",
         },
         ResizeAlg::SuperSampling(_, _) => "supersampling",
@@ -214,11 +280,10 @@ fn downscale_u16x3() {
     );
 
     let mut cpu_extensions_vec = vec![CpuExtensions::None];
-    // #[cfg(target_arch = "x86_64")]
-    // {
-    //     cpu_extensions_vec.push(CpuExtensions::Sse4_1);
-    //     cpu_extensions_vec.push(CpuExtensions::Avx2);
-    // }
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
     for cpu_extensions in cpu_extensions_vec {
         let buffer =
             downscale_test::<P>(ResizeAlg::Convolution(FilterType::Lanczos3), cpu_extensions);
@@ -239,11 +304,10 @@ fn upscale_u16x3() {
     );
 
     let mut cpu_extensions_vec = vec![CpuExtensions::None];
-    // #[cfg(target_arch = "x86_64")]
-    // {
-    //     cpu_extensions_vec.push(CpuExtensions::Sse4_1);
-    //     cpu_extensions_vec.push(CpuExtensions::Avx2);
-    // }
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
     for cpu_extensions in cpu_extensions_vec {
         let buffer =
             upscale_test::<P>(ResizeAlg::Convolution(FilterType::Lanczos3), cpu_extensions);
@@ -254,6 +318,44 @@ fn upscale_u16x3() {
     }
 }
 
+#[test]
+fn downscale_u16x4() {
+    type P = U16x4;
+    let buffer = downscale_test::<P>(ResizeAlg::Nearest, CpuExtensions::None);
+    assert_eq!(
+        utils::image_u16_checksum::<4>(&buffer),
+        [755050580, 756962660, 740848503, 2840942250]
+    );
+
+    let buffer = downscale_test::<P>(
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        CpuExtensions::None,
+    );
+    assert_eq!(
+        utils::image_u16_checksum::<4>(&buffer),
+        [756269847, 757632467, 741478612, 2840942250]
+    );
+}
+
+#[test]
+fn upscale_u16x4() {
+    type P = U16x4;
+    let buffer = upscale_test::<P>(ResizeAlg::Nearest, CpuExtensions::None);
+    assert_eq!(
+        utils::image_u16_checksum::<4>(&buffer),
+        [297094122820, 297713401842, 291717497780, 1097279243280]
+    );
+
+    let buffer = upscale_test::<P>(
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        CpuExtensions::None,
+    );
+    assert_eq!(
+        utils::image_u16_checksum::<4>(&buffer),
+        [297122154090, 297723994984, 291725294637, 1097279243280]
+    );
+}
+
 #[test]
 fn downscale_u8x4() {
     type P = U8x4;
@@ -317,3 +419,4202 @@ fn _resize_i32() {
         downscale_test::<P>(ResizeAlg::Convolution(FilterType::Lanczos3), cpu_extensions);
     }
 }
+
+#[test]
+fn box_filter_downscale_u8x4() {
+    let src_width: u32 = 64;
+    let src_height: u32 = 48;
+    let factor: u32 = 4;
+
+    let mut src_pixels = Vec::with_capacity((src_width * src_height) as usize);
+    for y in 0..src_height {
+        for x in 0..src_width {
+            let components = [
+                (x * 4) as u8,
+                (y * 5) as u8,
+                ((x * 7 + y * 13) % 256) as u8,
+                ((x * y) % 256) as u8,
+            ];
+            src_pixels.push(u32::from_le_bytes(components));
+        }
+    }
+    let src_image = Image::from_vec_u32(
+        NonZeroU32::new(src_width).unwrap(),
+        NonZeroU32::new(src_height).unwrap(),
+        src_pixels.clone(),
+        PixelType::U8x4,
+    )
+    .unwrap();
+
+    let dst_width = src_width / factor;
+    let dst_height = src_height / factor;
+
+    // Average of each 4x4 block of source image
+    let mut expected = Vec::with_capacity((dst_width * dst_height) as usize);
+    for dst_y in 0..dst_height {
+        for dst_x in 0..dst_width {
+            let mut sum = [0u32; 4];
+            for y in dst_y * factor..(dst_y + 1) * factor {
+                for x in dst_x * factor..(dst_x + 1) * factor {
+                    let components = src_pixels[(y * src_width + x) as usize].to_le_bytes();
+                    sum.iter_mut()
+                        .zip(components)
+                        .for_each(|(s, c)| *s += c as u32);
+                }
+            }
+            expected.push(sum.map(|s| s as f64 / (factor * factor) as f64));
+        }
+    }
+
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Sse4_1);
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
+    for cpu_extensions in cpu_extensions_vec {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_width).unwrap(),
+            NonZeroU32::new(dst_height).unwrap(),
+            PixelType::U8x4,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Box));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+
+        let dst_pixels = dst_image.buffer().chunks_exact(4);
+        for (dst_pixel, expected_pixel) in dst_pixels.zip(&expected) {
+            for (&c, &e) in dst_pixel.iter().zip(expected_pixel) {
+                // Result of two passes of convolution may be
+                // different from exact average because of rounding.
+                assert!(
+                    (c as f64 - e).abs() <= 1.0,
+                    "{} != {} ({})",
+                    c,
+                    e,
+                    cpu_ext_into_str(cpu_extensions)
+                );
+            }
+        }
+    }
+}
+
+/// Creates U8 image with horizontal gradient from `min` to `max` values.
+fn gradient_u8_image(width: u32, height: u32, min: u8, max: u8) -> Image<'static> {
+    let row: Vec<u8> = (0..width)
+        .map(|x| {
+            let v = min as f64 + (max - min) as f64 * x as f64 / (width - 1) as f64;
+            v.round() as u8
+        })
+        .collect();
+    let buffer = row.repeat(height as usize);
+    Image::from_vec_u8(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        buffer,
+        PixelType::U8,
+    )
+    .unwrap()
+}
+
+#[test]
+fn mitchell_filter_without_overshoot() {
+    let (min, max) = (40u8, 200u8);
+    let src_image = gradient_u8_image(256, 8, min, max);
+
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
+    for cpu_extensions in cpu_extensions_vec {
+        for dst_width in [64, 199, 1024] {
+            let mut dst_image = Image::new(
+                NonZeroU32::new(dst_width).unwrap(),
+                NonZeroU32::new(8).unwrap(),
+                PixelType::U8,
+            );
+            let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Mitchell));
+            resizer.set_cpu_extensions(cpu_extensions);
+            resizer
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap();
+            for &v in dst_image.buffer() {
+                assert!(
+                    (min..=max).contains(&v),
+                    "{} is out of range [{}, {}] ({}, dst_width={})",
+                    v,
+                    min,
+                    max,
+                    cpu_ext_into_str(cpu_extensions),
+                    dst_width
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn catmull_rom_filter_overshoot_on_step_edge() {
+    let (low, high) = (50u8, 200u8);
+    let src_width = 32;
+    let row: Vec<u8> = (0..src_width)
+        .map(|x| if x < src_width / 2 { low } else { high })
+        .collect();
+    let src_image = Image::from_vec_u8(
+        NonZeroU32::new(src_width).unwrap(),
+        NonZeroU32::new(4).unwrap(),
+        row.repeat(4),
+        PixelType::U8,
+    )
+    .unwrap();
+
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
+    for cpu_extensions in cpu_extensions_vec {
+        let dst_width = src_width * 4;
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_width).unwrap(),
+            NonZeroU32::new(4).unwrap(),
+            PixelType::U8,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::CatmullRom));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+
+        let dst_row = &dst_image.buffer()[0..dst_width as usize];
+        let (left, right) = dst_row.split_at(dst_width as usize / 2);
+        // Negative lobes of the filter produce undershoot on dark side
+        // and overshoot on bright side of the edge.
+        assert!(left.iter().any(|&v| v < low));
+        assert!(right.iter().any(|&v| v > high));
+        // Far from the edge the values must stay unchanged.
+        assert_eq!(left[0], low);
+        assert_eq!(right[right.len() - 1], high);
+    }
+}
+
+#[test]
+fn gaussian_filter_keeps_constant_color() {
+    let color = u32::from_le_bytes([17, 128, 201, 255]);
+    let src_image = Image::from_vec_u32(
+        NonZeroU32::new(97).unwrap(),
+        NonZeroU32::new(61).unwrap(),
+        vec![color; 97 * 61],
+        PixelType::U8x4,
+    )
+    .unwrap();
+
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Sse4_1);
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
+    for cpu_extensions in cpu_extensions_vec {
+        for sigma in [0.0, 0.5, 1.0, 2.5] {
+            for (dst_width, dst_height) in [(23, 17), (200, 150)] {
+                let mut dst_image = Image::new(
+                    NonZeroU32::new(dst_width).unwrap(),
+                    NonZeroU32::new(dst_height).unwrap(),
+                    PixelType::U8x4,
+                );
+                let mut resizer =
+                    Resizer::new(ResizeAlg::Convolution(FilterType::Gaussian { sigma }));
+                resizer.set_cpu_extensions(cpu_extensions);
+                resizer
+                    .resize(&src_image.view(), &mut dst_image.view_mut())
+                    .unwrap();
+                let dst_pixels = unsafe { dst_image.buffer().align_to::<u32>().1 };
+                assert!(
+                    dst_pixels.iter().all(|&p| p == color),
+                    "sigma={}, {}",
+                    sigma,
+                    cpu_ext_into_str(cpu_extensions)
+                );
+            }
+        }
+    }
+}
+
+/// Kernels are truncated at the image borders, so their weights
+/// must be renormalized to avoid darkening of border pixels.
+#[test]
+fn border_pixels_of_white_image_stay_white() {
+    let filters = [
+        FilterType::Box,
+        FilterType::Bilinear,
+        FilterType::Hamming,
+        FilterType::CatmullRom,
+        FilterType::Mitchell,
+        FilterType::Lanczos3,
+        FilterType::Blackman,
+        FilterType::Gaussian { sigma: 1.5 },
+    ];
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.extend([CpuExtensions::Sse4_1, CpuExtensions::Avx2]);
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Neon);
+    }
+    let (src_width, src_height) = (37, 29);
+    for (pixel_type, pixel_size) in [
+        (PixelType::U8, 1),
+        (PixelType::U8x3, 3),
+        (PixelType::U8x4, 4),
+    ] {
+        let src_image = Image::from_vec_u8(
+            NonZeroU32::new(src_width).unwrap(),
+            NonZeroU32::new(src_height).unwrap(),
+            vec![255; (src_width * src_height) as usize * pixel_size],
+            pixel_type,
+        )
+        .unwrap();
+        for &cpu_extensions in &cpu_extensions_vec {
+            for filter_type in filters {
+                for (dst_width, dst_height) in [(5, 4), (16, 11), (80, 61)] {
+                    let mut dst_image = Image::new(
+                        NonZeroU32::new(dst_width).unwrap(),
+                        NonZeroU32::new(dst_height).unwrap(),
+                        pixel_type,
+                    );
+                    let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+                    resizer.set_cpu_extensions(cpu_extensions);
+                    resizer
+                        .resize(&src_image.view(), &mut dst_image.view_mut())
+                        .unwrap();
+                    assert!(
+                        dst_image.buffer().iter().all(|&v| v == 255),
+                        "{:?}, {:?}, {}x{}, {}",
+                        pixel_type,
+                        filter_type,
+                        dst_width,
+                        dst_height,
+                        cpu_ext_into_str(cpu_extensions)
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn custom_lanczos3(x: f64) -> f64 {
+    fn sinc(x: f64) -> f64 {
+        if x == 0.0 {
+            1.0
+        } else {
+            let x = x * std::f64::consts::PI;
+            x.sin() / x
+        }
+    }
+    if (-3.0..3.0).contains(&x) {
+        sinc(x) * sinc(x / 3.)
+    } else {
+        0.0
+    }
+}
+
+#[test]
+fn custom_filter_reproduces_lanczos3() {
+    let custom_filter = CustomFilter::new(custom_lanczos3, 3.0).unwrap();
+    let src_image = U8x4::load_small_src_image();
+
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    {
+        cpu_extensions_vec.push(CpuExtensions::Sse4_1);
+        cpu_extensions_vec.push(CpuExtensions::Avx2);
+    }
+    for cpu_extensions in cpu_extensions_vec {
+        let mut results = Vec::with_capacity(2);
+        for filter_type in [FilterType::Lanczos3, FilterType::Custom(custom_filter)] {
+            let mut dst_image = Image::new(
+                NonZeroU32::new(300).unwrap(),
+                NonZeroU32::new(700).unwrap(),
+                PixelType::U8x4,
+            );
+            let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+            resizer.set_cpu_extensions(cpu_extensions);
+            resizer
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap();
+            results.push(dst_image.buffer().to_owned());
+        }
+        assert!(results[0] == results[1]);
+    }
+}
+
+#[test]
+fn custom_filter_with_invalid_support() {
+    for support in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            CustomFilter::new(custom_lanczos3, support),
+            Err(InvalidFilterSupportError)
+        ));
+    }
+}
+
+/// Resizes the source image into given sizes with help of given
+/// CPU-extensions and checks that results are equal to results of
+/// implementation for `expected_cpu_extensions`.
+#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+fn assert_eq_with(
+    src_image: &Image,
+    expected_cpu_extensions: CpuExtensions,
+    cpu_extensions: CpuExtensions,
+    sizes: &[(u32, u32)],
+) {
+    for &(dst_width, dst_height) in sizes {
+        let mut results = Vec::with_capacity(2);
+        for cur_cpu_extensions in [expected_cpu_extensions, cpu_extensions] {
+            let mut dst_image = Image::new(
+                NonZeroU32::new(dst_width).unwrap(),
+                NonZeroU32::new(dst_height).unwrap(),
+                src_image.pixel_type(),
+            );
+            let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+            resizer.set_cpu_extensions(cur_cpu_extensions);
+            resizer
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap();
+            results.push(dst_image.buffer().to_owned());
+        }
+        assert!(
+            results[0] == results[1],
+            "Result of {} is different from {} for {}x{}",
+            cpu_ext_into_str(cpu_extensions),
+            cpu_ext_into_str(expected_cpu_extensions),
+            dst_width,
+            dst_height
+        );
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[test]
+fn neon_u8x4_eq_native() {
+    assert_eq_with(
+        &U8x4::load_small_src_image(),
+        CpuExtensions::None,
+        CpuExtensions::Neon,
+        &[
+            (213, 567),
+            (425, 283),
+            (851, 567),
+            (1704, 567),
+            (3407, 2268),
+        ],
+    );
+
+    let size = NonZeroU32::new(1000).unwrap();
+    let pixels: Vec<u32> = (0..1000 * 1000u32)
+        .map(|i| i.wrapping_mul(2654435761))
+        .collect();
+    let src_image = Image::from_vec_u32(size, size, pixels, PixelType::U8x4).unwrap();
+    assert_eq_with(
+        &src_image,
+        CpuExtensions::None,
+        CpuExtensions::Neon,
+        &[(300, 300)],
+    );
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn avx512_u8x4_eq_avx2() {
+    if !CpuExtensions::Avx512.is_supported() {
+        return;
+    }
+    assert_eq_with(
+        &U8x4::load_small_src_image(),
+        CpuExtensions::Avx2,
+        CpuExtensions::Avx512,
+        &[
+            (213, 567),
+            (425, 283),
+            (851, 567),
+            (1704, 567),
+            (3407, 2268),
+        ],
+    );
+
+    // Widths which aren't multiple of 16 pixels
+    let size = NonZeroU32::new(1000).unwrap();
+    let pixels: Vec<u32> = (0..1000 * 1000u32)
+        .map(|i| i.wrapping_mul(2654435761))
+        .collect();
+    let src_image = Image::from_vec_u32(size, size, pixels, PixelType::U8x4).unwrap();
+    assert_eq_with(
+        &src_image,
+        CpuExtensions::Avx2,
+        CpuExtensions::Avx512,
+        &[(300, 300), (1, 1), (17, 3), (999, 1001)],
+    );
+}
+
+#[test]
+fn detect_cpu_extensions() {
+    let detected = CpuExtensions::detect();
+    assert!(detected.is_supported());
+    assert_eq!(CpuExtensions::default(), detected);
+    assert!(CpuExtensions::None.is_supported());
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        // All x86_64 CI runners support SSE4.1
+        assert!(CpuExtensions::Sse4_1.is_supported());
+        assert_ne!(detected, CpuExtensions::None);
+    }
+    // Without std only extensions enabled during compilation are used.
+    #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+    if !cfg!(target_feature = "sse4.1") {
+        assert_eq!(detected, CpuExtensions::None);
+    }
+}
+
+#[test]
+fn used_cpu_extensions_are_reported() {
+    let size = NonZeroU32::new(8).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8x4);
+    let dst_size = NonZeroU32::new(3).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x4);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    assert_eq!(resizer.used_cpu_extensions(), None);
+
+    resizer.set_cpu_extensions(CpuExtensions::None);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert_eq!(resizer.used_cpu_extensions(), Some(CpuExtensions::None));
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        resizer.set_cpu_extensions(CpuExtensions::Avx2);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        let used = resizer.used_cpu_extensions().unwrap();
+        if CpuExtensions::Avx2.is_supported() {
+            assert_eq!(used, CpuExtensions::Avx2);
+        } else {
+            // Resizer falls back to the best supported extensions.
+            assert_ne!(used, CpuExtensions::Avx2);
+            assert_ne!(used, CpuExtensions::Avx512);
+            assert!(used.is_supported());
+        }
+    }
+
+    // There is no SIMD implementation for U16x4 images.
+    let src_image = Image::new(size, size, PixelType::U16x4);
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U16x4);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert_eq!(resizer.used_cpu_extensions(), Some(CpuExtensions::None));
+}
+
+/// Results of resizing with `CpuExtensions::None` must not depend
+/// on the CPU, so they are compared with fixed checksums.
+#[test]
+fn scalar_results_are_reproducible() {
+    let src_width = NonZeroU32::new(67).unwrap();
+    let src_height = NonZeroU32::new(41).unwrap();
+    let dst_width = NonZeroU32::new(29).unwrap();
+    let dst_height = NonZeroU32::new(53).unwrap();
+    let cases = [
+        (PixelType::U8, 1, 195925),
+        (PixelType::U8x3, 3, 587918),
+        (PixelType::U8x4, 4, 783816),
+    ];
+    for (pixel_type, pixel_size, expected_checksum) in cases {
+        let buf_size = (src_width.get() * src_height.get()) as usize * pixel_size;
+        let buffer: Vec<u8> = (0..buf_size as u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        let src_image = Image::from_vec_u8(src_width, src_height, buffer, pixel_type).unwrap();
+        let mut dst_image = Image::new(dst_width, dst_height, pixel_type);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_cpu_extensions(CpuExtensions::None);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        let checksum = utils::image_checksum::<1>(dst_image.buffer());
+        assert_eq!(checksum, [expected_checksum], "{:?}", pixel_type);
+    }
+}
+
+/// Results of resizing in the reference mode must not depend on
+/// architecture, CPU-extensions and number of threads, so they are
+/// compared with fixed hashes.
+#[test]
+fn reference_mode_results_are_reproducible() {
+    /// FNV-1a hash of components of pixels in little-endian byte order.
+    fn hash(buffer: &[u8], component_size: usize) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for component in buffer.chunks_exact(component_size) {
+            let mut bytes = component.to_vec();
+            if cfg!(target_endian = "big") {
+                bytes.reverse();
+            }
+            for byte in bytes {
+                hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
+
+    let src_width = NonZeroU32::new(67).unwrap();
+    let src_height = NonZeroU32::new(41).unwrap();
+    let dst_sizes = [(29, 53), (101, 17)];
+    let cases = [
+        (
+            PixelType::U8x3,
+            3,
+            1,
+            [11435533427578484973, 15242280719207769841],
+        ),
+        (
+            PixelType::U8x4,
+            4,
+            1,
+            [1597781704933601310, 5505769357868600586],
+        ),
+        (
+            PixelType::U16x3,
+            3,
+            2,
+            [11461869241567378316, 3868663994786059655],
+        ),
+        (
+            PixelType::F32,
+            1,
+            4,
+            [7272169421806033728, 5340930246494364024],
+        ),
+    ];
+    for (pixel_type, channels, component_size, expected_hashes) in cases {
+        let components = src_width.get() * src_height.get() * channels;
+        let buffer: Vec<u8> = (0..components)
+            .flat_map(|i| {
+                let v = i.wrapping_mul(2654435761) >> 16;
+                match component_size {
+                    1 => vec![(v >> 8) as u8],
+                    2 => (v as u16).to_ne_bytes().to_vec(),
+                    _ => (v as f32 / 65535.).to_ne_bytes().to_vec(),
+                }
+            })
+            .collect();
+        let src_image = Image::from_vec_u8(src_width, src_height, buffer, pixel_type).unwrap();
+        for (dst_size, expected_hash) in dst_sizes.into_iter().zip(expected_hashes) {
+            let dst_width = NonZeroU32::new(dst_size.0).unwrap();
+            let dst_height = NonZeroU32::new(dst_size.1).unwrap();
+            for cpu_extensions in [CpuExtensions::None, CpuExtensions::detect()] {
+                for num_threads in [1, 3] {
+                    let mut dst_image = Image::new(dst_width, dst_height, pixel_type);
+                    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+                    resizer.set_reference_mode(true);
+                    resizer.set_num_threads(num_threads);
+                    resizer.set_cpu_extensions(cpu_extensions);
+                    resizer
+                        .resize(&src_image.view(), &mut dst_image.view_mut())
+                        .unwrap();
+                    assert_eq!(resizer.used_cpu_extensions(), Some(CpuExtensions::None));
+                    assert_eq!(
+                        hash(dst_image.buffer(), component_size),
+                        expected_hash,
+                        "{:?} {:?} {} with {} threads",
+                        pixel_type,
+                        dst_size,
+                        cpu_ext_into_str(cpu_extensions),
+                        num_threads
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn multi_threaded_results_eq_single_threaded() {
+    fn resize_in_pool(src_image: &Image, dst_size: (u32, u32), num_threads: usize) -> Vec<u8> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_size.0).unwrap(),
+            NonZeroU32::new(dst_size.1).unwrap(),
+            src_image.pixel_type(),
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        pool.install(|| {
+            resizer
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap()
+        });
+        dst_image.buffer().to_owned()
+    }
+
+    let src_images = [
+        U8::load_small_src_image(),
+        U8x3::load_small_src_image(),
+        U8x4::load_small_src_image(),
+        U16x3::load_small_src_image(),
+        U16x4::load_small_src_image(),
+        I16::load_small_src_image(),
+        I32::load_small_src_image(),
+        F32::load_small_src_image(),
+    ];
+    for src_image in src_images.iter() {
+        for dst_size in [(213, 567), (1704, 123), (851, 5), (64, 1), (1, 1)] {
+            let expected = resize_in_pool(src_image, dst_size, 1);
+            for num_threads in [2, 3, 8] {
+                let result = resize_in_pool(src_image, dst_size, num_threads);
+                assert!(
+                    result == expected,
+                    "Result of resizing {:?} image into {:?} with {} threads is different",
+                    src_image.pixel_type(),
+                    dst_size,
+                    num_threads,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn results_with_num_threads_eq_single_threaded() {
+    fn resize_with_threads(
+        src_image: &Image,
+        dst_size: (u32, u32),
+        alg: ResizeAlg,
+        num_threads: usize,
+    ) -> Vec<u8> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_size.0).unwrap(),
+            NonZeroU32::new(dst_size.1).unwrap(),
+            src_image.pixel_type(),
+        );
+        let mut resizer = Resizer::new(alg);
+        resizer.set_num_threads(num_threads);
+        assert_eq!(resizer.num_threads(), num_threads);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_owned()
+    }
+
+    let src_images = [
+        U8::load_small_src_image(),
+        U8x4::load_small_src_image(),
+        U16x3::load_small_src_image(),
+        F32::load_small_src_image(),
+    ];
+    let algorithms = [
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        ResizeAlg::SuperSampling(FilterType::Bilinear, 2),
+    ];
+    for src_image in src_images.iter() {
+        for alg in algorithms {
+            for dst_size in [(213, 567), (1704, 123), (851, 5), (64, 1)] {
+                let expected = resize_with_threads(src_image, dst_size, alg, 1);
+                for num_threads in [0, 2, 4] {
+                    let result = resize_with_threads(src_image, dst_size, alg, num_threads);
+                    assert!(
+                        result == expected,
+                        "Result of resizing {:?} image into {:?} with {} threads is different",
+                        src_image.pixel_type(),
+                        dst_size,
+                        num_threads,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn prepared_resize_eq_resize() {
+    let src_image = U8x4::load_big_src_image();
+    let mut src_view = src_image.view();
+    src_view
+        .set_crop_box(CropBox {
+            left: 100.,
+            top: 50.,
+            width: 2000.,
+            height: 1500.,
+        })
+        .unwrap();
+    let filter_type = FilterType::Lanczos3;
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+    for (dst_width, dst_height) in [(852, 567), (2000, 300), (300, 1500), (2000, 1500)] {
+        let dst_width = NonZeroU32::new(dst_width).unwrap();
+        let dst_height = NonZeroU32::new(dst_height).unwrap();
+
+        let mut expected = Image::new(dst_width, dst_height, src_image.pixel_type());
+        resizer.resize(&src_view, &mut expected.view_mut()).unwrap();
+
+        let prepared = PreparedResize::new(filter_type, &src_view, dst_width, dst_height);
+        assert_eq!(prepared.filter_type(), filter_type);
+        let mut result = Image::new(dst_width, dst_height, src_image.pixel_type());
+        // Coefficients are reused for second call
+        for _ in 0..2 {
+            resizer
+                .resize_prepared(&prepared, &src_view, &mut result.view_mut())
+                .unwrap();
+            assert!(result.buffer() == expected.buffer());
+        }
+    }
+}
+
+#[test]
+fn prepared_resize_coefficients() {
+    let src_image = Image::new(
+        NonZeroU32::new(16).unwrap(),
+        NonZeroU32::new(8).unwrap(),
+        PixelType::U8,
+    );
+    let prepared = PreparedResize::new(
+        FilterType::Triangle,
+        &src_image.view(),
+        NonZeroU32::new(8).unwrap(),
+        NonZeroU32::new(8).unwrap(),
+    );
+    // Height of the image is not changed
+    assert!(prepared.vert_coefficients().is_none());
+
+    let chunks = prepared.horiz_coefficients().unwrap().get_chunks();
+    assert_eq!(chunks.len(), 8);
+    // Interior output pixel 3 has center 7.0 in the source row
+    // and uses 4 input pixels with symmetric weights.
+    assert_eq!(chunks[3].start, 5);
+    assert_eq!(chunks[3].values, &[0.125, 0.375, 0.375, 0.125]);
+}
+
+#[test]
+fn prepared_resize_with_other_params() {
+    let src_image = U8x4::load_small_src_image();
+    let new_size = NonZeroU32::new(100).unwrap();
+    let prepared = PreparedResize::new(FilterType::Bilinear, &src_image.view(), new_size, new_size);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+
+    let mut dst_image = Image::new(new_size, NonZeroU32::new(50).unwrap(), PixelType::U8x4);
+    assert!(matches!(
+        resizer.resize_prepared(&prepared, &src_image.view(), &mut dst_image.view_mut()),
+        Err(ResizePreparedError::DifferentParams)
+    ));
+
+    let mut src_view = src_image.view();
+    src_view.set_crop_box_to_fit_dst_size(new_size, NonZeroU32::new(50).unwrap(), None);
+    let mut dst_image = Image::new(new_size, new_size, PixelType::U8x4);
+    assert!(matches!(
+        resizer.resize_prepared(&prepared, &src_view, &mut dst_image.view_mut()),
+        Err(ResizePreparedError::DifferentParams)
+    ));
+
+    let mut dst_image = Image::new(new_size, new_size, PixelType::U8);
+    assert!(matches!(
+        resizer.resize_prepared(&prepared, &src_image.view(), &mut dst_image.view_mut()),
+        Err(ResizePreparedError::DifferentTypesOfPixels)
+    ));
+}
+
+#[test]
+fn prepared_resize_with_boundary_modes() {
+    let src_image = U8x4::load_small_src_image();
+    let filter_type = FilterType::Lanczos3;
+    for boundary_mode in [BoundaryMode::Mirror, BoundaryMode::Wrap] {
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+        resizer.set_boundary_mode(boundary_mode);
+        for (dst_width, dst_height) in [(100, 70), (300, 200)] {
+            let dst_width = NonZeroU32::new(dst_width).unwrap();
+            let dst_height = NonZeroU32::new(dst_height).unwrap();
+
+            let mut expected = Image::new(dst_width, dst_height, src_image.pixel_type());
+            resizer
+                .resize(&src_image.view(), &mut expected.view_mut())
+                .unwrap();
+
+            let mut result = Image::new(dst_width, dst_height, src_image.pixel_type());
+            let prepared =
+                PreparedResize::new(filter_type, &src_image.view(), dst_width, dst_height);
+            // Coefficients were computed with other boundary mode
+            assert!(matches!(
+                resizer.resize_prepared(&prepared, &src_image.view(), &mut result.view_mut()),
+                Err(ResizePreparedError::DifferentParams)
+            ));
+
+            let prepared = PreparedResize::with_resizer(
+                &resizer,
+                filter_type,
+                &src_image.view(),
+                dst_width,
+                dst_height,
+            );
+            resizer
+                .resize_prepared(&prepared, &src_image.view(), &mut result.view_mut())
+                .unwrap();
+            assert!(
+                result.buffer() == expected.buffer(),
+                "{:?} {}x{}",
+                boundary_mode,
+                dst_width,
+                dst_height
+            );
+        }
+    }
+}
+
+#[test]
+fn gamma_correction_of_checkerboard() {
+    let size = NonZeroU32::new(64).unwrap();
+    let dst_size = NonZeroU32::new(32).unwrap();
+    let mut pixels = Vec::with_capacity(64 * 64 * 4);
+    for y in 0..64 {
+        for x in 0..64 {
+            let c = if (x + y) % 2 == 0 { 255 } else { 0 };
+            pixels.extend_from_slice(&[c, c, c, 255]);
+        }
+    }
+    let src_u8x4 = Image::from_vec_u8(size, size, pixels, PixelType::U8x4).unwrap();
+    let rgb_pixels: Vec<u8> = src_u8x4
+        .buffer()
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let src_u8x3 = Image::from_vec_u8(size, size, rgb_pixels, PixelType::U8x3).unwrap();
+
+    for (src_image, pixel_size) in [(&src_u8x3, 3), (&src_u8x4, 4)] {
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Box));
+        let mut dst_image = Image::new(dst_size, dst_size, src_image.pixel_type());
+
+        // Naive resizing in gamma space gives middle of sRGB range.
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        for pixel in dst_image.buffer().chunks_exact(pixel_size) {
+            assert!(pixel[..3].iter().all(|&c| (127..=128).contains(&c)));
+        }
+
+        // Half of light in linear space is ~0.73 of sRGB range.
+        resizer.set_gamma_correction(true);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        for pixel in dst_image.buffer().chunks_exact(pixel_size) {
+            assert!(pixel[..3].iter().all(|&c| c == 188), "{:?}", pixel);
+            if pixel_size == 4 {
+                assert_eq!(pixel[3], 255);
+            }
+        }
+    }
+}
+
+#[test]
+fn resize_to_cover() {
+    let width = NonZeroU32::new(1000).unwrap();
+    let height = NonZeroU32::new(500).unwrap();
+    let pixels: Vec<u8> = (0..500u32)
+        .flat_map(|y| (0..1000u32).map(move |x| ((x * 7 + y * 3) % 256) as u8))
+        .collect();
+    let src_image = Image::from_vec_u8(width, height, pixels, PixelType::U8).unwrap();
+    let dst_size = NonZeroU32::new(200).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    for (centering, left) in [(None, 250.), (Some((0., 0.5)), 0.), (Some((1., 0.5)), 500.)] {
+        let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8);
+        resizer
+            .resize_to_cover(&src_image.view(), &mut dst_image.view_mut(), centering)
+            .unwrap();
+
+        let mut cropped_view = src_image.view();
+        cropped_view
+            .set_crop_box(CropBox {
+                left,
+                top: 0.,
+                width: height.get() as f64,
+                height: height.get() as f64,
+            })
+            .unwrap();
+        let mut expected_image = Image::new(dst_size, dst_size, PixelType::U8);
+        resizer
+            .resize(&cropped_view, &mut expected_image.view_mut())
+            .unwrap();
+
+        assert!(dst_image.buffer() == expected_image.buffer());
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn f32_simd_eq_native() {
+    fn resize_f32(src_image: &Image, size: (u32, u32), cpu_extensions: CpuExtensions) -> Vec<f32> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(size.0).unwrap(),
+            NonZeroU32::new(size.1).unwrap(),
+            PixelType::F32,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        unsafe { dst_image.buffer().align_to::<f32>().1.to_vec() }
+    }
+
+    let small_image = {
+        let size = NonZeroU32::new(67).unwrap();
+        let pixels: Vec<u32> = (0..67 * 67)
+            .map(|i: u32| (((i * 7919) % 2003) as f32 * 1.37 - 900.).to_bits())
+            .collect();
+        Image::from_vec_u32(size, size, pixels, PixelType::F32).unwrap()
+    };
+    let src_images = [F32::load_small_src_image(), small_image];
+    for src_image in src_images.iter() {
+        for size in [(213, 111), (1704, 123), (29, 997), (5, 3)] {
+            let expected = resize_f32(src_image, size, CpuExtensions::None);
+            for cpu_extensions in [CpuExtensions::Sse4_1, CpuExtensions::Avx2] {
+                if !cpu_extensions.is_supported() {
+                    continue;
+                }
+                let result = resize_f32(src_image, size, cpu_extensions);
+                for (&a, &b) in result.iter().zip(&expected) {
+                    // Results are rounded, but the order of summation of
+                    // products is different, so a half-way value may
+                    // be rounded into other side.
+                    let tolerance = 1.0f32.max(b.abs() * 1e-6);
+                    assert!(
+                        (a - b).abs() <= tolerance,
+                        "Result of {} is different from native for {:?}: {} != {}",
+                        cpu_ext_into_str(cpu_extensions),
+                        size,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn u16x3_simd_eq_native() {
+    fn resize_u16x3(
+        src_image: &Image,
+        size: (u32, u32),
+        filter_type: FilterType,
+        cpu_extensions: CpuExtensions,
+    ) -> Vec<u8> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(size.0).unwrap(),
+            NonZeroU32::new(size.1).unwrap(),
+            PixelType::U16x3,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_vec()
+    }
+
+    // Sharp edges between extreme values produce results out of
+    // range of `u16`, that must be saturated.
+    let extreme_image = {
+        let size = NonZeroU32::new(67).unwrap();
+        let buffer: Vec<u8> = (0..67 * 67 * 3)
+            .flat_map(|i: u32| {
+                let v = if (i / 7 + i / 201) & 1 == 0 {
+                    u16::MAX
+                } else {
+                    0
+                };
+                v.to_le_bytes()
+            })
+            .collect();
+        Image::from_vec_u8(size, size, buffer, PixelType::U16x3).unwrap()
+    };
+    let src_images = [U16x3::load_small_src_image(), extreme_image];
+    for src_image in src_images.iter() {
+        for size in [
+            (213, 67),
+            (67, 111),
+            (213, 111),
+            (1704, 123),
+            (29, 997),
+            (5, 3),
+            (1, 1),
+        ] {
+            for filter_type in [FilterType::Lanczos3, FilterType::Box, FilterType::Mitchell] {
+                let expected = resize_u16x3(src_image, size, filter_type, CpuExtensions::None);
+                for cpu_extensions in [CpuExtensions::Avx2, CpuExtensions::Avx512] {
+                    if !cpu_extensions.is_supported() {
+                        continue;
+                    }
+                    let result = resize_u16x3(src_image, size, filter_type, cpu_extensions);
+                    assert!(
+                        result == expected,
+                        "Result of {} is different from native for {:?} and {:?}",
+                        cpu_ext_into_str(cpu_extensions),
+                        size,
+                        filter_type
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn u8x3_simd_eq_native() {
+    fn resize_u8x3(
+        src_image: &Image,
+        size: (u32, u32),
+        filter_type: FilterType,
+        cpu_extensions: CpuExtensions,
+    ) -> Vec<u8> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(size.0).unwrap(),
+            NonZeroU32::new(size.1).unwrap(),
+            PixelType::U8x3,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_vec()
+    }
+
+    // Width of the image isn't multiple of count of pixels processed
+    // by one iteration of SIMD-loops, so tails of rows are checked too.
+    let extreme_image = {
+        let size = NonZeroU32::new(67).unwrap();
+        let buffer: Vec<u8> = (0..67 * 67 * 3)
+            .map(|i: u32| if (i / 7 + i / 201) & 1 == 0 { 255 } else { 0 })
+            .collect();
+        Image::from_vec_u8(size, size, buffer, PixelType::U8x3).unwrap()
+    };
+    let src_images = [U8x3::load_small_src_image(), extreme_image];
+    for src_image in src_images.iter() {
+        for size in [
+            (213, 67),
+            (67, 111),
+            (213, 111),
+            (1704, 123),
+            (29, 997),
+            (5, 3),
+            (1, 1),
+        ] {
+            for filter_type in [FilterType::Lanczos3, FilterType::Box, FilterType::Mitchell] {
+                let expected = resize_u8x3(src_image, size, filter_type, CpuExtensions::None);
+                for cpu_extensions in [CpuExtensions::Sse4_1, CpuExtensions::Avx2] {
+                    if !cpu_extensions.is_supported() {
+                        continue;
+                    }
+                    let result = resize_u8x3(src_image, size, filter_type, cpu_extensions);
+                    assert!(
+                        result == expected,
+                        "Result of {} is different from native for {:?} and {:?}",
+                        cpu_ext_into_str(cpu_extensions),
+                        size,
+                        filter_type
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Results of sharp filters overshoot near high-contrast edges, they
+/// must be saturated into range of component instead of wrapping.
+#[test]
+fn overshoot_near_sharp_edges_is_saturated() {
+    const SRC_SIZE: u32 = 64;
+    let pixel_types = [
+        (PixelType::U8, 1, 1),
+        (PixelType::U8x2, 2, 1),
+        (PixelType::U8x3, 3, 1),
+        (PixelType::U8x4, 4, 1),
+        (PixelType::U16x2, 2, 2),
+        (PixelType::U16x3, 3, 2),
+        (PixelType::U16x4, 4, 2),
+    ];
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    cpu_extensions_vec.extend([
+        CpuExtensions::Sse4_1,
+        CpuExtensions::Avx2,
+        CpuExtensions::Avx512,
+    ]);
+    #[cfg(target_arch = "aarch64")]
+    cpu_extensions_vec.push(CpuExtensions::Neon);
+    #[cfg(target_arch = "wasm32")]
+    cpu_extensions_vec.push(CpuExtensions::Simd128);
+
+    let half = SRC_SIZE as f64 / 2.;
+    // Source image has a bright quarter and a dark quarter in each half,
+    // so both passes of convolution cross the edges.
+    let is_bright = |x: f64, y: f64| (x >= half) != (y >= half);
+
+    for (pixel_type, components, component_size) in pixel_types {
+        let max_value: u32 = if component_size == 1 {
+            u8::MAX as u32
+        } else {
+            u16::MAX as u32
+        };
+        let src_buffer: Vec<u8> = (0..SRC_SIZE * SRC_SIZE)
+            .flat_map(|i| {
+                let (x, y) = ((i % SRC_SIZE) as f64, (i / SRC_SIZE) as f64);
+                let v = if is_bright(x, y) { max_value } else { 0 };
+                (0..components).flat_map(move |_| v.to_le_bytes()[..component_size].to_vec())
+            })
+            .collect();
+        let src_size = NonZeroU32::new(SRC_SIZE).unwrap();
+        let src_image = Image::from_vec_u8(src_size, src_size, src_buffer, pixel_type).unwrap();
+
+        for dst_size in [23u32, 150] {
+            let scale = SRC_SIZE as f64 / dst_size as f64;
+            // Distance from edges in source pixels after that filter
+            // support doesn't reach transition band of the edge.
+            let margin = scale.max(1.);
+            for &cpu_extensions in cpu_extensions_vec.iter() {
+                if !cpu_extensions.is_supported() {
+                    continue;
+                }
+                let size = NonZeroU32::new(dst_size).unwrap();
+                let mut dst_image = Image::new(size, size, pixel_type);
+                let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+                resizer.set_cpu_extensions(cpu_extensions);
+                resizer
+                    .resize(&src_image.view(), &mut dst_image.view_mut())
+                    .unwrap();
+
+                let pixel_size = components * component_size;
+                for (i, pixel) in dst_image.buffer().chunks_exact(pixel_size).enumerate() {
+                    let x = (i as u32 % dst_size) as f64 + 0.5;
+                    let y = (i as u32 / dst_size) as f64 + 0.5;
+                    let (src_x, src_y) = (x * scale, y * scale);
+                    if (src_x - half).abs() < margin || (src_y - half).abs() < margin {
+                        continue;
+                    }
+                    for component in pixel.chunks_exact(component_size) {
+                        let mut bytes = [0u8; 4];
+                        bytes[..component_size].copy_from_slice(component);
+                        let value = u32::from_le_bytes(bytes);
+                        let wrapped = if is_bright(src_x, src_y) {
+                            value < max_value * 3 / 4
+                        } else {
+                            value > max_value / 4
+                        };
+                        assert!(
+                            !wrapped,
+                            "{:?} with {}: wrong value {} of pixel ({}, {}) of {}x{} image",
+                            pixel_type,
+                            cpu_ext_into_str(cpu_extensions),
+                            value,
+                            x,
+                            y,
+                            dst_size,
+                            dst_size,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn i32_simd_eq_native() {
+    fn resize_i32(src_image: &Image, size: (u32, u32), cpu_extensions: CpuExtensions) -> Vec<i32> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(size.0).unwrap(),
+            NonZeroU32::new(size.1).unwrap(),
+            PixelType::I32,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_cpu_extensions(cpu_extensions);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        unsafe { dst_image.buffer().align_to::<i32>().1.to_vec() }
+    }
+
+    // Sharp edges between extreme values produce results out of
+    // range of `i32`, that must be saturated.
+    let extreme_image = {
+        let size = NonZeroU32::new(67).unwrap();
+        let pixels: Vec<u32> = (0..67 * 67)
+            .map(|i: u32| {
+                let v = if (i / 3 + i / 201) & 1 == 0 {
+                    i32::MAX
+                } else {
+                    i32::MIN
+                };
+                v as u32
+            })
+            .collect();
+        Image::from_vec_u32(size, size, pixels, PixelType::I32).unwrap()
+    };
+    let src_images = [I32::load_small_src_image(), extreme_image];
+    for src_image in src_images.iter() {
+        for size in [
+            (213, 67),
+            (67, 111),
+            (213, 111),
+            (1704, 123),
+            (29, 997),
+            (5, 3),
+        ] {
+            let expected = resize_i32(src_image, size, CpuExtensions::None);
+            for cpu_extensions in [CpuExtensions::Sse4_1, CpuExtensions::Avx2] {
+                if !cpu_extensions.is_supported() {
+                    continue;
+                }
+                let result = resize_i32(src_image, size, cpu_extensions);
+                for (&a, &b) in result.iter().zip(&expected) {
+                    // The order of summation of products is different,
+                    // so a half-way value may be rounded into other side.
+                    assert!(
+                        (a as i64 - b as i64).abs() <= 1,
+                        "Result of {} is different from native for {:?}: {} != {}",
+                        cpu_ext_into_str(cpu_extensions),
+                        size,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn block_average(src: &[u8], src_width: usize, channels: usize, block: (usize, usize)) -> Vec<u8> {
+    let src_height = src.len() / (src_width * channels);
+    let (block_w, block_h) = block;
+    let area = (block_w * block_h) as u64;
+    let mut res = Vec::new();
+    for by in 0..src_height / block_h {
+        for bx in 0..src_width / block_w {
+            for c in 0..channels {
+                let mut sum = 0u64;
+                for y in by * block_h..(by + 1) * block_h {
+                    for x in bx * block_w..(bx + 1) * block_w {
+                        sum += src[(y * src_width + x) * channels + c] as u64;
+                    }
+                }
+                res.push(((sum + area / 2) / area) as u8);
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn integral_image_eq_block_average() {
+    let src_width = 64;
+    let src_height = 48;
+    for (pixel_type, channels) in [(PixelType::U8x3, 3), (PixelType::U8x4, 4)] {
+        let pixels: Vec<u8> = (0..src_width * src_height * channels)
+            .map(|i| ((i * 37 + i / 7) % 256) as u8)
+            .collect();
+        let src_image = Image::from_vec_u8(
+            NonZeroU32::new(src_width as u32).unwrap(),
+            NonZeroU32::new(src_height as u32).unwrap(),
+            pixels.clone(),
+            pixel_type,
+        )
+        .unwrap();
+        let mut dst_image = Image::new(
+            NonZeroU32::new(8).unwrap(),
+            NonZeroU32::new(6).unwrap(),
+            pixel_type,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::IntegralImage);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+
+        let expected = block_average(&pixels, src_width, channels, (8, 8));
+        assert_eq!(dst_image.buffer(), expected.as_slice());
+    }
+}
+
+fn nearest_with_offset(src_image: &Image, dst_width: u32, offset: (f32, f32)) -> Vec<u8> {
+    let dst_height = NonZeroU32::new(src_image.height().get()).unwrap();
+    let mut dst_image = Image::new(
+        NonZeroU32::new(dst_width).unwrap(),
+        dst_height,
+        PixelType::U8,
+    );
+    let mut resizer = Resizer::new(ResizeAlg::Nearest);
+    resizer.set_nearest_offset(offset.0, offset.1);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image.buffer().to_vec()
+}
+
+#[test]
+fn nearest_with_subpixel_offset() {
+    let width = NonZeroU32::new(4).unwrap();
+    let src_image = Image::from_vec_u8(width, width, (0..16).collect(), PixelType::U8).unwrap();
+
+    // Without offset
+    let result = nearest_with_offset(&src_image, 4, (0., 0.));
+    assert_eq!(result, src_image.buffer());
+
+    // Centers of pixels are shifted to the boundaries between source
+    // pixels, so every pixel is taken from the next column or row.
+    // The last column and row are repeated.
+    let result = nearest_with_offset(&src_image, 4, (0.5, 0.));
+    assert_eq!(&result[..4], &[1, 2, 3, 3]);
+    let result = nearest_with_offset(&src_image, 4, (0., 0.5));
+    assert_eq!(&result[..4], &[4, 5, 6, 7]);
+    assert_eq!(&result[12..], &[12, 13, 14, 15]);
+
+    // Negative offset is clamped by the first column.
+    let result = nearest_with_offset(&src_image, 4, (-0.6, 0.));
+    assert_eq!(&result[..4], &[0, 0, 1, 2]);
+
+    // Downscaling: centers of destination pixels are at 1.0 and 3.0
+    let result = nearest_with_offset(&src_image, 2, (0., 0.));
+    assert_eq!(&result[..2], &[1, 3]);
+    let result = nearest_with_offset(&src_image, 2, (-0.5, 0.));
+    assert_eq!(&result[..2], &[0, 2]);
+    let result = nearest_with_offset(&src_image, 2, (1.5, 0.));
+    assert_eq!(&result[..2], &[2, 3]);
+
+    // Huge offset never reads outside of the crop box,
+    // only top-right pixel of the crop box is sampled.
+    let mut src_view = src_image.view();
+    src_view
+        .set_crop_box(CropBox {
+            left: 1.,
+            top: 1.,
+            width: 2.,
+            height: 2.,
+        })
+        .unwrap();
+    let two = NonZeroU32::new(2).unwrap();
+    let mut dst_image = Image::new(two, two, PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Nearest);
+    resizer.set_nearest_offset(100., -100.);
+    resizer
+        .resize(&src_view, &mut dst_image.view_mut())
+        .unwrap();
+    assert_eq!(dst_image.buffer(), &[6, 6, 6, 6]);
+}
+
+#[test]
+fn bilinear_eq_bilinear_convolution() {
+    let src_image = U8x4::load_small_src_image();
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    cpu_extensions_vec.extend([CpuExtensions::Sse4_1, CpuExtensions::Avx2]);
+
+    for cpu_extensions in cpu_extensions_vec {
+        for (dst_width, dst_height) in [(1000, 700), (852, 1134), (1704, 1134), (2131, 1001)] {
+            let dst_width = NonZeroU32::new(dst_width).unwrap();
+            let dst_height = NonZeroU32::new(dst_height).unwrap();
+            let mut results = Vec::new();
+            for resize_alg in [
+                ResizeAlg::Bilinear,
+                ResizeAlg::Convolution(FilterType::Bilinear),
+            ] {
+                let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+                let mut resizer = Resizer::new(resize_alg);
+                resizer.set_cpu_extensions(cpu_extensions);
+                resizer
+                    .resize(&src_image.view(), &mut dst_image.view_mut())
+                    .unwrap();
+                results.push(dst_image);
+            }
+            for (&a, &b) in results[0].buffer().iter().zip(results[1].buffer()) {
+                assert!(
+                    (a as i16 - b as i16).abs() <= 1,
+                    "{} != {} ({:?}, {}x{})",
+                    a,
+                    b,
+                    cpu_extensions,
+                    dst_width,
+                    dst_height
+                );
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn simd_bilinear_eq_native() {
+    let src_image = U8x4::load_small_src_image();
+    for cpu_extensions in [CpuExtensions::Sse4_1, CpuExtensions::Avx2] {
+        for (dst_width, dst_height) in [(852, 567), (1703, 1135), (4000, 600), (853, 3000)] {
+            let dst_width = NonZeroU32::new(dst_width).unwrap();
+            let dst_height = NonZeroU32::new(dst_height).unwrap();
+            let mut results = Vec::new();
+            for cpu_extensions in [CpuExtensions::None, cpu_extensions] {
+                let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+                let mut resizer = Resizer::new(ResizeAlg::Bilinear);
+                resizer.set_cpu_extensions(cpu_extensions);
+                resizer
+                    .resize(&src_image.view(), &mut dst_image.view_mut())
+                    .unwrap();
+                results.push(dst_image);
+            }
+            assert!(
+                results[0].buffer() == results[1].buffer(),
+                "{:?}, {}x{}",
+                cpu_extensions,
+                dst_width,
+                dst_height
+            );
+        }
+    }
+}
+
+#[test]
+fn build_mipmaps_levels() {
+    let src_image = Image::from_vec_u8(
+        NonZeroU32::new(1024).unwrap(),
+        NonZeroU32::new(512).unwrap(),
+        vec![100; 1024 * 512 * 4],
+        PixelType::U8x4,
+    )
+    .unwrap();
+    let levels = fast_image_resize::build_mipmaps(&src_image, ResizeAlg::default());
+    assert_eq!(levels.len(), 11);
+    assert!(levels[0].buffer() == src_image.buffer());
+    for (prev, level) in levels.iter().zip(&levels[1..]) {
+        assert_eq!(level.width().get(), (prev.width().get() / 2).max(1));
+        assert_eq!(level.height().get(), (prev.height().get() / 2).max(1));
+        assert!(level.buffer().iter().all(|&c| c == 100));
+    }
+    let last = &levels[10];
+    assert_eq!((last.width().get(), last.height().get()), (1, 1));
+
+    // Odd dimensions
+    let src_image = Image::new(
+        NonZeroU32::new(5).unwrap(),
+        NonZeroU32::new(3).unwrap(),
+        PixelType::U8,
+    );
+    let levels = fast_image_resize::build_mipmaps(&src_image, ResizeAlg::Nearest);
+    let sizes: Vec<_> = levels
+        .iter()
+        .map(|l| (l.width().get(), l.height().get()))
+        .collect();
+    assert_eq!(sizes, vec![(5, 3), (2, 1), (1, 1)]);
+}
+
+#[test]
+fn resize_f32x4_keeps_fractional_values() {
+    let src_size = NonZeroU32::new(64).unwrap();
+    let buffer: Vec<u32> = [0.25f32, 0.5, 0.75, 1.].map(f32::to_bits).repeat(64 * 64);
+    let src_image = Image::from_vec_u32(src_size, src_size, buffer, PixelType::F32x4).unwrap();
+    let dst_image = fast_image_resize::resize(&src_image, 17, 17, ResizeAlg::default()).unwrap();
+    let dst_pixels = unsafe { dst_image.buffer().align_to::<[f32; 4]>().1 };
+    for pixel in dst_pixels {
+        for (&c, expected) in pixel.iter().zip([0.25, 0.5, 0.75, 1.]) {
+            assert!((c - expected).abs() < 1e-6, "{:?}", pixel);
+        }
+    }
+}
+
+#[test]
+fn crop_box_with_fractional_position() {
+    // Horizontal gradient: 0, 10, 20, ..., 70
+    let width = NonZeroU32::new(8).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let buffer: Vec<u8> = (0..8).map(|x| x * 10).collect();
+    let src_image = Image::from_vec_u8(width, height, buffer, PixelType::U8).unwrap();
+    let dst_width = NonZeroU32::new(4).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+
+    let mut resize_with_left = |left: f64| {
+        let mut src_view = src_image.view();
+        src_view
+            .set_crop_box(CropBox {
+                left,
+                top: 0.,
+                width: 4.,
+                height: 1.,
+            })
+            .unwrap();
+        let mut dst_image = Image::new(dst_width, height, PixelType::U8);
+        resizer
+            .resize(&src_view, &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_vec()
+    };
+
+    assert_eq!(resize_with_left(0.), [0, 10, 20, 30]);
+    assert_eq!(resize_with_left(1.), [10, 20, 30, 40]);
+    // Shift by half of pixel gives values between source pixels
+    // instead of snapping to the nearest integer position.
+    assert_eq!(resize_with_left(0.5), [5, 15, 25, 35]);
+    assert_eq!(resize_with_left(2.5), [25, 35, 45, 55]);
+}
+
+#[test]
+fn crop_box_with_fractional_position_u8x4() {
+    let src_image = U8x4::load_big_src_image();
+    let dst_size = NonZeroU32::new(100).unwrap();
+    for gamma_correction in [false, true] {
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_gamma_correction(gamma_correction);
+        check_fractional_crop_box_u8x4(&src_image, dst_size, &mut resizer);
+    }
+}
+
+fn check_fractional_crop_box_u8x4(src_image: &Image, dst_size: NonZeroU32, resizer: &mut Resizer) {
+    let mut resize_with_left = |left: f64| {
+        let mut src_view = src_image.view();
+        src_view
+            .set_crop_box(CropBox {
+                left,
+                top: 1000.5,
+                width: 200.,
+                height: 200.,
+            })
+            .unwrap();
+        let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x4);
+        resizer
+            .resize(&src_view, &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_vec()
+    };
+
+    let left = resize_with_left(1000.);
+    let middle = resize_with_left(1000.5);
+    let right = resize_with_left(1001.);
+    assert!(middle != left);
+    assert!(middle != right);
+    // Every component of the shifted image lies near to the range
+    // between components of the two snapped images.
+    for ((&m, &l), &r) in middle.iter().zip(&left).zip(&right) {
+        assert!(m as i32 >= l.min(r) as i32 - 4 && m as i32 <= l.max(r) as i32 + 4);
+    }
+}
+
+#[test]
+fn crop_box_out_of_image_boundaries() {
+    let size = NonZeroU32::new(8).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8);
+    let mut src_view = src_image.view();
+    let crop_box = CropBox {
+        left: 0.,
+        top: 0.,
+        width: 8.,
+        height: 8.,
+    };
+
+    assert!(src_view.set_crop_box(crop_box).is_ok());
+    assert!(src_view
+        .set_crop_box(CropBox {
+            left: 7.5,
+            width: 0.5,
+            ..crop_box
+        })
+        .is_ok());
+    assert!(matches!(
+        src_view.set_crop_box(CropBox {
+            left: -0.5,
+            width: 1.,
+            ..crop_box
+        }),
+        Err(CropBoxError::PositionIsOutOfImageBoundaries)
+    ));
+    assert!(matches!(
+        src_view.set_crop_box(CropBox {
+            top: f64::NAN,
+            ..crop_box
+        }),
+        Err(CropBoxError::PositionIsOutOfImageBoundaries)
+    ));
+    assert!(matches!(
+        src_view.set_crop_box(CropBox {
+            width: 0.,
+            ..crop_box
+        }),
+        Err(CropBoxError::WidthOrHeightLessOrEqualToZero)
+    ));
+    assert!(matches!(
+        src_view.set_crop_box(CropBox {
+            top: 0.5,
+            ..crop_box
+        }),
+        Err(CropBoxError::SizeIsOutOfImageBoundaries)
+    ));
+    assert_eq!(src_view.crop_box().left, 7.5);
+}
+
+#[test]
+fn zero_size_crop_box_is_error() {
+    let size = NonZeroU32::new(8).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8);
+    let mut src_view = src_image.view();
+    let crop_box = CropBox {
+        left: 2.,
+        top: 2.,
+        width: 4.,
+        height: 4.,
+    };
+    for (width, height) in [(0., 4.), (4., 0.), (0., 0.), (-1., 4.), (f64::NAN, 4.)] {
+        assert!(matches!(
+            src_view.set_crop_box(CropBox {
+                width,
+                height,
+                ..crop_box
+            }),
+            Err(CropBoxError::WidthOrHeightLessOrEqualToZero)
+        ));
+    }
+    assert!(matches!(
+        src_view.set_crop_box(CropBox {
+            width: f64::INFINITY,
+            ..crop_box
+        }),
+        Err(CropBoxError::SizeIsOutOfImageBoundaries)
+    ));
+    // The crop box of the view is not changed by invalid boxes.
+    assert_eq!(src_view.crop_box().width, 8.);
+}
+
+#[test]
+fn crop_box_to_fit_extreme_aspect_ratio() {
+    let src_image = Image::new(
+        NonZeroU32::new(1000).unwrap(),
+        NonZeroU32::new(1).unwrap(),
+        PixelType::U8,
+    );
+    let mut src_view = src_image.view();
+    // Crop box is rounded to one pixel instead of zero.
+    src_view.set_crop_box_to_fit_dst_size(
+        NonZeroU32::new(1).unwrap(),
+        NonZeroU32::new(1000).unwrap(),
+        None,
+    );
+    let crop_box = src_view.crop_box();
+    assert_eq!((crop_box.width, crop_box.height), (1., 1.));
+    assert_eq!(crop_box.left, 500.);
+
+    let mut dst_image = Image::new(
+        NonZeroU32::new(1).unwrap(),
+        NonZeroU32::new(1000).unwrap(),
+        PixelType::U8,
+    );
+    Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3))
+        .resize(&src_view, &mut dst_image.view_mut())
+        .unwrap();
+
+    // Rounded crop box is kept inside the image.
+    let src_image = Image::new(
+        NonZeroU32::new(10).unwrap(),
+        NonZeroU32::new(1).unwrap(),
+        PixelType::U8,
+    );
+    let mut src_view = src_image.view();
+    src_view.set_crop_box_to_fit_dst_size(
+        NonZeroU32::new(5).unwrap(),
+        NonZeroU32::new(2).unwrap(),
+        Some((1., 0.)),
+    );
+    let crop_box = src_view.crop_box();
+    assert_eq!((crop_box.left, crop_box.width), (7., 3.));
+}
+
+/// Returns sum of undershoot and overshoot of the result of
+/// upscaling of the image with sharp vertical edge.
+fn edge_overshoot(filter_type: FilterType) -> u8 {
+    let src_width = NonZeroU32::new(16).unwrap();
+    let height = NonZeroU32::new(4).unwrap();
+    let buffer: Vec<u8> = (0..16 * 4)
+        .map(|i| if i % 16 < 8 { 50 } else { 200 })
+        .collect();
+    let src_image = Image::from_vec_u8(src_width, height, buffer, PixelType::U8).unwrap();
+    let dst_width = NonZeroU32::new(61).unwrap();
+    let mut dst_image = Image::new(dst_width, height, PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    let min = *dst_image.buffer().iter().min().unwrap();
+    let max = *dst_image.buffer().iter().max().unwrap();
+    (50 - min) + (max - 200)
+}
+
+#[test]
+fn hermite_filter_is_monotonic_on_step_edge() {
+    assert_eq!(edge_overshoot(FilterType::Hermite), 0);
+
+    let src_width = NonZeroU32::new(16).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let buffer: Vec<u8> = (0..16).map(|x| if x < 8 { 50 } else { 200 }).collect();
+    let src_image = Image::from_vec_u8(src_width, height, buffer, PixelType::U8).unwrap();
+    for dst_width in [5, 11, 61] {
+        let mut dst_image = Image::new(NonZeroU32::new(dst_width).unwrap(), height, PixelType::U8);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Hermite));
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        let row = dst_image.buffer();
+        assert!(
+            row.windows(2).all(|w| w[0] <= w[1]),
+            "{}: {:?}",
+            dst_width,
+            row
+        );
+    }
+}
+
+#[test]
+fn anti_ringing_removes_overshoot_on_edge() {
+    // Dark square on light background
+    let src_size = NonZeroU32::new(16).unwrap();
+    let buffer: Vec<u8> = (0..16 * 16u32)
+        .flat_map(|i| {
+            let (x, y) = (i % 16, i / 16);
+            let v = if (4..12).contains(&x) && (4..12).contains(&y) {
+                50
+            } else {
+                200
+            };
+            [v, v, v, 255]
+        })
+        .collect();
+    let src_image = Image::from_vec_u8(src_size, src_size, buffer, PixelType::U8x4).unwrap();
+    let overshoot = |anti_ringing: bool, dst_width: u32, dst_height: u32| {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_width).unwrap(),
+            NonZeroU32::new(dst_height).unwrap(),
+            PixelType::U8x4,
+        );
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_anti_ringing(anti_ringing);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        let colors = dst_image.buffer().chunks_exact(4).map(|p| p[0]);
+        let min = colors.clone().min().unwrap();
+        let max = colors.max().unwrap();
+        50u8.saturating_sub(min) + max.saturating_sub(200)
+    };
+    for (dst_width, dst_height) in [(61, 61), (61, 16), (16, 61), (11, 13)] {
+        assert!(overshoot(false, dst_width, dst_height) > 0);
+        assert_eq!(overshoot(true, dst_width, dst_height), 0);
+    }
+}
+
+/// Returns sum of squared differences between neighbouring pixels
+/// of the result of 2x downscaling of the noisy image.
+fn local_variance_of_downscaled_noise(filter_type: FilterType) -> u64 {
+    let src_size = NonZeroU32::new(64).unwrap();
+    let buffer: Vec<u8> = (0..64 * 64u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect();
+    let src_image = Image::from_vec_u8(src_size, src_size, buffer, PixelType::U8).unwrap();
+    let dst_size = NonZeroU32::new(32).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image
+        .buffer()
+        .chunks(32)
+        .flat_map(|row| row.windows(2))
+        .map(|w| (w[0] as i64 - w[1] as i64).pow(2) as u64)
+        .sum()
+}
+
+#[test]
+fn filters_report_documented_support() {
+    fn wide_triangle(x: f64) -> f64 {
+        (1.0 - x.abs() / 2.5).max(0.0)
+    }
+    let filters = [
+        (FilterType::Box, 0.5),
+        (FilterType::Bilinear, 1.0),
+        (FilterType::Triangle, 1.0),
+        (FilterType::Hamming, 1.0),
+        (FilterType::CatmullRom, 2.0),
+        (FilterType::Mitchell, 2.0),
+        (FilterType::Hermite, 1.0),
+        (FilterType::BSpline, 2.0),
+        (FilterType::Lanczos2, 2.0),
+        (FilterType::Lanczos3, 3.0),
+        (FilterType::Blackman, 3.0),
+        (FilterType::Welch, 1.0),
+        (FilterType::Bartlett, 3.0),
+        (FilterType::Hann, 1.0),
+        (FilterType::Gaussian { sigma: 0.5 }, 1.5),
+        (FilterType::Gaussian { sigma: 2. }, 6.0),
+        // Sigma is clamped to 0.05
+        (FilterType::Gaussian { sigma: 0.01 }, 0.15),
+        (FilterType::Kaiser { beta: 8.6 }, 3.0),
+        (
+            FilterType::Custom(CustomFilter::new(wide_triangle, 2.5).unwrap()),
+            2.5,
+        ),
+    ];
+    let src_size = NonZeroU32::new(400).unwrap();
+    let dst_size = NonZeroU32::new(100).unwrap();
+    for (filter_type, support) in filters {
+        assert_eq!(filter_type.support(), support, "{:?}", filter_type);
+
+        // On 4x downscaling the filter covers `support * 4`
+        // source pixels on each side of the center.
+        let radius = support as f64 * 4.;
+        let coefficients = Coefficients::new(filter_type, src_size, dst_size);
+        let chunks = coefficients.get_chunks();
+        let max_len = chunks.iter().map(|c| c.values.len()).max().unwrap() as f64;
+        assert!(
+            max_len >= 2. * radius.floor() && max_len <= 2. * radius.ceil() + 1.,
+            "{:?}: {}",
+            filter_type,
+            max_len
+        );
+    }
+}
+
+#[test]
+fn b_spline_is_smoother_than_triangle() {
+    assert_eq!(edge_overshoot(FilterType::BSpline), 0);
+    let b_spline = local_variance_of_downscaled_noise(FilterType::BSpline);
+    let triangle = local_variance_of_downscaled_noise(FilterType::Triangle);
+    assert!(b_spline < triangle, "{} >= {}", b_spline, triangle);
+
+    // Shifted copies of cubic B-spline sum to 1, so weights are
+    // normalized even without normalization.
+    let size = NonZeroU32::new(16).unwrap();
+    let coefficients = Coefficients::new_unnormalized(FilterType::BSpline, size, size);
+    for chunk in &coefficients.get_chunks()[2..14] {
+        let sum: f64 = chunk.values.iter().sum();
+        assert!((sum - 1.).abs() < 1e-9, "{:?}", chunk.values);
+    }
+    for (src_size, dst_size) in [(64, 7), (7, 64)] {
+        let coefficients = Coefficients::new(
+            FilterType::BSpline,
+            NonZeroU32::new(src_size).unwrap(),
+            NonZeroU32::new(dst_size).unwrap(),
+        );
+        for chunk in coefficients.get_chunks() {
+            let sum: f64 = chunk.values.iter().sum();
+            assert!((sum - 1.).abs() < 1e-9, "{:?}", chunk.values);
+        }
+    }
+}
+
+#[test]
+fn lanczos2_has_less_overshoot_than_lanczos3() {
+    let lanczos2 = edge_overshoot(FilterType::Lanczos2);
+    let lanczos3 = edge_overshoot(FilterType::Lanczos3);
+    assert!(lanczos2 > 0);
+    assert!(lanczos2 < lanczos3, "{} >= {}", lanczos2, lanczos3);
+}
+
+#[test]
+fn blackman_has_less_overshoot_than_lanczos3() {
+    let blackman = edge_overshoot(FilterType::Blackman);
+    let lanczos3 = edge_overshoot(FilterType::Lanczos3);
+    assert!(blackman < lanczos3, "{} >= {}", blackman, lanczos3);
+}
+
+/// Checks that results of downscaling with given filter are equal
+/// (up to rounding) to results calculated like in
+/// ImagingResampleHorizontal() of Pillow with reference `kernel`.
+fn assert_filter_eq_kernel(filter_type: FilterType, kernel: fn(f64) -> f64, support: f64) {
+    let src_pixels: [u8; 11] = [0, 255, 10, 200, 30, 180, 60, 150, 90, 120, 100];
+    let src_width = NonZeroU32::new(src_pixels.len() as u32).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let src_image =
+        Image::from_vec_u8(src_width, height, src_pixels.to_vec(), PixelType::U8).unwrap();
+
+    for dst_width in [3u32, 4, 7] {
+        let scale = src_pixels.len() as f64 / dst_width as f64;
+        let filter_scale = scale.max(1.0);
+        let support = support * filter_scale;
+        let expected: Vec<u8> = (0..dst_width)
+            .map(|x| {
+                let center = (x as f64 + 0.5) * scale;
+                let x_min = (center - support + 0.5).max(0.) as usize;
+                let x_max = ((center + support + 0.5) as usize).min(src_pixels.len());
+                let weights: Vec<f64> = (x_min..x_max)
+                    .map(|i| kernel((i as f64 - center + 0.5) / filter_scale))
+                    .collect();
+                let sum: f64 = weights.iter().sum();
+                let value: f64 = weights
+                    .iter()
+                    .zip(&src_pixels[x_min..x_max])
+                    .map(|(w, &p)| w / sum * p as f64)
+                    .sum();
+                value.round().clamp(0., 255.) as u8
+            })
+            .collect();
+
+        let mut dst_image = Image::new(NonZeroU32::new(dst_width).unwrap(), height, PixelType::U8);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        for (&result, &expected) in dst_image.buffer().iter().zip(&expected) {
+            assert!(
+                (result as i32 - expected as i32).abs() <= 1,
+                "dst_width={}: {:?} != {:?}",
+                dst_width,
+                dst_image.buffer(),
+                expected
+            );
+        }
+    }
+}
+
+/// Hamming-windowed sinc as it is defined in Pillow.
+fn pillow_hamming(x: f64) -> f64 {
+    let x = x.abs();
+    if x == 0.0 {
+        1.0
+    } else if x >= 1.0 {
+        0.0
+    } else {
+        let x = x * std::f64::consts::PI;
+        x.sin() / x * (0.54 + 0.46 * x.cos())
+    }
+}
+
+#[test]
+fn hamming_eq_pillow_kernel() {
+    assert_filter_eq_kernel(FilterType::Hamming, pillow_hamming, 1.0);
+}
+
+/// Sinc windowed by Blackman window
+/// `w(n) = 0.42 - 0.5 * cos(2πn / N) + 0.08 * cos(4πn / N)`
+/// with `N = 6` and `n = x + 3`.
+fn reference_blackman(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    if x.abs() >= 3.0 {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    let n = x + 3.0;
+    let window = 0.42 - 0.5 * (2. * PI * n / 6.).cos() + 0.08 * (4. * PI * n / 6.).cos();
+    sinc * window
+}
+
+#[test]
+fn blackman_eq_reference_kernel() {
+    assert_filter_eq_kernel(FilterType::Blackman, reference_blackman, 3.0);
+}
+
+/// Sinc windowed by Welch window with support 1.0
+fn reference_welch(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    sinc * (1. - x * x)
+}
+
+#[test]
+fn welch_eq_reference_kernel() {
+    assert_filter_eq_kernel(FilterType::Welch, reference_welch, 1.0);
+}
+
+/// Sinc windowed by Hann window
+/// `w(n) = 0.5 - 0.5 * cos(2πn / N)`
+/// with `N = 2` and `n = x + 1`.
+fn reference_hann(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    let n = x + 1.0;
+    sinc * (0.5 - 0.5 * (2. * PI * n / 2.).cos())
+}
+
+#[test]
+fn hann_eq_reference_kernel() {
+    assert_filter_eq_kernel(FilterType::Hann, reference_hann, 1.0);
+    assert_coefficients_eq_kernel(FilterType::Hann, reference_hann);
+}
+
+/// Zeroth-order modified Bessel function of the first kind
+/// calculated by its integral representation.
+fn reference_bessel_i0(x: f64) -> f64 {
+    let steps = 10_000;
+    let step = std::f64::consts::PI / steps as f64;
+    // Trapezoidal rule
+    let sum: f64 = (0..=steps)
+        .map(|i| {
+            let v = (x * (i as f64 * step).cos()).exp();
+            if i == 0 || i == steps {
+                v / 2.
+            } else {
+                v
+            }
+        })
+        .sum();
+    sum * step / std::f64::consts::PI
+}
+
+/// Sinc windowed by Kaiser window with `beta = 8.6` and support 3.0
+fn reference_kaiser(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    let beta = 8.6;
+    if x.abs() >= 3.0 {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    let window =
+        reference_bessel_i0(beta * (1. - (x / 3.).powi(2)).sqrt()) / reference_bessel_i0(beta);
+    sinc * window
+}
+
+/// Compares normalized weights of convolution for downscaling
+/// and upscaling with values of the reference kernel.
+fn assert_coefficients_eq_kernel(filter_type: FilterType, kernel: fn(f64) -> f64) {
+    for (src_size, dst_size) in [(11u32, 4u32), (4, 11)] {
+        let coefficients = Coefficients::new(
+            filter_type,
+            NonZeroU32::new(src_size).unwrap(),
+            NonZeroU32::new(dst_size).unwrap(),
+        );
+        let scale = src_size as f64 / dst_size as f64;
+        let filter_scale = scale.max(1.0);
+        for (x, chunk) in coefficients.get_chunks().iter().enumerate() {
+            let center = (x as f64 + 0.5) * scale;
+            let weights: Vec<f64> = (0..chunk.values.len())
+                .map(|i| {
+                    let src_x = chunk.start as f64 + i as f64;
+                    kernel((src_x - center + 0.5) / filter_scale)
+                })
+                .collect();
+            let sum: f64 = weights.iter().sum();
+            for (&value, weight) in chunk.values.iter().zip(weights) {
+                assert!(
+                    (value - weight / sum).abs() < 1e-6,
+                    "{}x{}: {:?}",
+                    src_size,
+                    dst_size,
+                    chunk.values
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn kaiser_eq_reference_kernel() {
+    let filter_type = FilterType::kaiser(8.6).unwrap();
+    assert_eq!(filter_type, FilterType::Kaiser { beta: 8.6 });
+    assert_filter_eq_kernel(filter_type, reference_kaiser, 3.0);
+    assert_coefficients_eq_kernel(filter_type, reference_kaiser);
+}
+
+/// Sinc windowed by Bartlett window with support 3.0
+fn reference_bartlett(x: f64) -> f64 {
+    use std::f64::consts::PI;
+    if x.abs() >= 3.0 {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    sinc * (1. - x.abs() / 3.)
+}
+
+#[test]
+fn bartlett_eq_reference_kernel() {
+    assert_filter_eq_kernel(FilterType::Bartlett, reference_bartlett, 3.0);
+    assert_coefficients_eq_kernel(FilterType::Bartlett, reference_bartlett);
+    // Unlike `Triangle`, the filter has negative lobes of sinc
+    let has_negative_weights = |filter_type| {
+        let size = NonZeroU32::new(11).unwrap();
+        let coefficients = Coefficients::new(filter_type, size, size);
+        let chunks = coefficients.get_chunks();
+        chunks.iter().any(|c| c.values.iter().any(|&v| v < 0.))
+    };
+    assert!(has_negative_weights(FilterType::Bartlett));
+    assert!(!has_negative_weights(FilterType::Triangle));
+}
+
+#[test]
+fn gaussian_with_invalid_sigma_is_error() {
+    for sigma in [0., -1., f32::NAN, f32::INFINITY] {
+        assert!(FilterType::gaussian(sigma).is_err(), "sigma={}", sigma);
+    }
+    assert!(FilterType::gaussian(0.5).is_ok());
+}
+
+#[test]
+fn gaussian_with_out_of_range_sigma_is_clamped() {
+    let src_image = gradient_u8_image(64, 16, 0, 255);
+    let mut dst_image = Image::new(
+        NonZeroU32::new(32).unwrap(),
+        NonZeroU32::new(8).unwrap(),
+        PixelType::U8,
+    );
+    for sigma in [f32::INFINITY, f32::MAX, 1e30, f32::NAN, f32::NEG_INFINITY] {
+        let filter_type = FilterType::Gaussian { sigma };
+        assert!(filter_type.support().is_finite(), "sigma={}", sigma);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+    }
+}
+
+#[test]
+fn kaiser_with_invalid_beta_is_error() {
+    for beta in [0., -1., f32::NAN, f32::INFINITY] {
+        assert!(FilterType::kaiser(beta).is_err(), "beta={}", beta);
+    }
+}
+
+const fn assert_send_sync<T: Send + Sync>() {}
+
+// Compile-time checks that these types may be shared between threads.
+const _: () = assert_send_sync::<Resizer>();
+const _: () = assert_send_sync::<ResizeAlg>();
+const _: () = assert_send_sync::<FilterType>();
+const _: () = assert_send_sync::<CpuExtensions>();
+const _: () = assert_send_sync::<PreparedResize>();
+
+#[test]
+fn adaptive_filter_depends_on_ratio() {
+    let threshold = ResizeAlg::ADAPTIVE_THRESHOLD;
+    for (ratio, expected) in [
+        (0.25, FilterType::Lanczos3),
+        (1., FilterType::Lanczos3),
+        (2.5, FilterType::Lanczos3),
+        (4., FilterType::Lanczos3),
+        (4.01, FilterType::Box),
+        (100., FilterType::Box),
+    ] {
+        assert_eq!(
+            ResizeAlg::adaptive_filter(threshold, ratio),
+            expected,
+            "{}",
+            ratio
+        );
+    }
+    assert_eq!(ResizeAlg::adaptive_filter(2., 2.5), FilterType::Box);
+    assert_eq!(
+        ResizeAlg::adaptive_filter(f64::INFINITY, 100.),
+        FilterType::Lanczos3
+    );
+}
+
+#[test]
+fn adaptive_resize_selects_filter_per_axis() {
+    let src_image = U8x4::load_small_src_image();
+    let src_view = src_image.view();
+    let (src_width, src_height) = (src_image.width().get(), src_image.height().get());
+    let resize = |algorithm: ResizeAlg, width: u32, height: u32| {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(width).unwrap(),
+            NonZeroU32::new(height).unwrap(),
+            PixelType::U8x4,
+        );
+        Resizer::new(algorithm)
+            .resize(&src_view, &mut dst_image.view_mut())
+            .unwrap();
+        dst_image.buffer().to_vec()
+    };
+    let sizes = [
+        // Large reduction of width, small reduction of height.
+        (
+            src_width / 8,
+            src_height / 2,
+            FilterType::Box,
+            FilterType::Lanczos3,
+        ),
+        // Upscaling of width, large reduction of height.
+        (
+            src_width * 2,
+            src_height / 5,
+            FilterType::Lanczos3,
+            FilterType::Box,
+        ),
+        (
+            src_width / 3,
+            src_height / 3,
+            FilterType::Lanczos3,
+            FilterType::Lanczos3,
+        ),
+        (
+            src_width / 6,
+            src_height / 6,
+            FilterType::Box,
+            FilterType::Box,
+        ),
+    ];
+    for (width, height, horizontal, vertical) in sizes {
+        let expected = resize(
+            ResizeAlg::ConvolutionPerAxis {
+                horizontal,
+                vertical,
+            },
+            width,
+            height,
+        );
+        let result = resize(ResizeAlg::adaptive(), width, height);
+        assert!(result == expected, "{}x{}", width, height);
+    }
+
+    // Tuned threshold selects box filter for smaller reductions.
+    let result = resize(
+        ResizeAlg::Adaptive { threshold: 2. },
+        src_width / 3,
+        src_height,
+    );
+    let expected = resize(
+        ResizeAlg::Convolution(FilterType::Box),
+        src_width / 3,
+        src_height,
+    );
+    assert!(result == expected);
+}
+
+/// Box filter selected for large reductions gives nearly the same
+/// result as Lanczos3 filter.
+#[test]
+fn adaptive_resize_quality_on_large_reduction() {
+    let src_image = U8x4::load_small_src_image();
+    let (src_width, src_height) = (src_image.width().get(), src_image.height().get());
+    for (width, height) in [(src_width / 16, src_height / 16), (1, 1)] {
+        let mut results = Vec::new();
+        for algorithm in [
+            ResizeAlg::adaptive(),
+            ResizeAlg::Convolution(FilterType::Lanczos3),
+        ] {
+            let mut dst_image = Image::new(
+                NonZeroU32::new(width).unwrap(),
+                NonZeroU32::new(height).unwrap(),
+                PixelType::U8x4,
+            );
+            Resizer::new(algorithm)
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap();
+            results.push(dst_image.buffer().to_vec());
+        }
+        let diffs: Vec<u8> = results[0]
+            .iter()
+            .zip(&results[1])
+            .map(|(&a, &b)| a.abs_diff(b))
+            .collect();
+        let mean_diff = diffs.iter().map(|&d| d as f64).sum::<f64>() / diffs.len() as f64;
+        assert!(mean_diff < 5., "{}x{}: {}", width, height, mean_diff);
+    }
+}
+
+#[test]
+fn resizer_can_be_moved_into_another_thread() {
+    let src_image = U8x4::load_small_src_image();
+    let dst_width = NonZeroU32::new(120).unwrap();
+    let dst_height = NonZeroU32::new(80).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&src_image.view(), &mut expected.view_mut())
+        .unwrap();
+
+    let result = std::thread::spawn(move || {
+        let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image
+    })
+    .join()
+    .unwrap();
+    assert!(result.buffer() == expected.buffer());
+}
+
+#[test]
+fn cloned_resizer_gives_the_same_result() {
+    let src_image = U8x4::load_small_src_image();
+    let dst_width = NonZeroU32::new(120).unwrap();
+    let dst_height = NonZeroU32::new(80).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::SuperSampling(FilterType::Lanczos3, 2));
+    resizer.set_gamma_correction(true);
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&src_image.view(), &mut expected.view_mut())
+        .unwrap();
+    assert!(resizer.size_of_internal_buffers() > 0);
+
+    let mut cloned_resizer = resizer.clone();
+    assert!(cloned_resizer.gamma_correction());
+    assert_eq!(cloned_resizer.cpu_extensions(), resizer.cpu_extensions());
+    // Internal buffers are not copied.
+    assert_eq!(cloned_resizer.size_of_internal_buffers(), 0);
+
+    let mut result = Image::new(dst_width, dst_height, PixelType::U8x4);
+    cloned_resizer
+        .resize(&src_image.view(), &mut result.view_mut())
+        .unwrap();
+    assert!(result.buffer() == expected.buffer());
+}
+
+#[test]
+fn triangle_upscale_is_linear_interpolation() {
+    let src_width = NonZeroU32::new(8).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    let src_pixels: Vec<u8> = (0..8).map(|i| i * 32).collect();
+    let src_image = Image::from_vec_u8(src_width, height, src_pixels, PixelType::U8).unwrap();
+
+    let dst_width = NonZeroU32::new(16).unwrap();
+    let mut dst_image = Image::new(dst_width, height, PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Triangle));
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    // Centers of destination pixels are placed at 1/4 and 3/4 between
+    // centers of source pixels, except the first and the last pixels,
+    // which are outside the source pixels and are equal to edge pixels.
+    let mut expected = vec![0];
+    for i in 0..7u32 {
+        let (a, b) = (i * 32, (i + 1) * 32);
+        expected.push(((a * 3 + b) / 4) as u8);
+        expected.push(((a + b * 3) / 4) as u8);
+    }
+    expected.push(224);
+    assert_eq!(dst_image.buffer(), expected);
+
+    let mut bilinear_image = Image::new(dst_width, height, PixelType::U8);
+    resizer.algorithm = ResizeAlg::Convolution(FilterType::Bilinear);
+    resizer
+        .resize(&src_image.view(), &mut bilinear_image.view_mut())
+        .unwrap();
+    assert_eq!(dst_image.buffer(), bilinear_image.buffer());
+}
+
+/// Copies the rectangular part of U8x4 image into a new image.
+fn copy_u8x4_region(image: &Image, left: u32, top: u32, width: u32, height: u32) -> Image<'static> {
+    let row_size = image.width().get() as usize * 4;
+    let buffer: Vec<u8> = image
+        .buffer()
+        .chunks_exact(row_size)
+        .skip(top as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[left as usize * 4..(left + width) as usize * 4])
+        .copied()
+        .collect();
+    Image::from_vec_u8(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        buffer,
+        PixelType::U8x4,
+    )
+    .unwrap()
+}
+
+#[test]
+fn resize_region_eq_resize_of_copied_region() {
+    let src_image = U8x4::load_small_src_image();
+    let dst_width = NonZeroU32::new(71).unwrap();
+    let dst_height = NonZeroU32::new(43).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    let region = CropBox {
+        left: 300.,
+        top: 200.,
+        width: 150.,
+        height: 100.,
+    };
+    let mut result = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize_region(&src_image.view(), region, &mut result.view_mut())
+        .unwrap();
+    let copied_region = copy_u8x4_region(&src_image, 300, 200, 150, 100);
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&copied_region.view(), &mut expected.view_mut())
+        .unwrap();
+    assert!(result.buffer() == expected.buffer());
+
+    // Fractional region is resized as the crop box inside
+    // of integral bounding box of the region.
+    let region = CropBox {
+        left: 300.5,
+        top: 200.25,
+        width: 149.,
+        height: 99.5,
+    };
+    resizer
+        .resize_region(&src_image.view(), region, &mut result.view_mut())
+        .unwrap();
+    let copied_region = copy_u8x4_region(&src_image, 300, 200, 150, 100);
+    let mut copied_view = copied_region.view();
+    copied_view
+        .set_crop_box(CropBox {
+            left: 0.5,
+            top: 0.25,
+            width: 149.,
+            height: 99.5,
+        })
+        .unwrap();
+    resizer
+        .resize(&copied_view, &mut expected.view_mut())
+        .unwrap();
+    assert!(result.buffer() == expected.buffer());
+}
+
+#[test]
+fn resize_region_out_of_image_boundaries() {
+    let src_image = U8x4::load_small_src_image();
+    let mut dst_image = Image::new(
+        NonZeroU32::new(10).unwrap(),
+        NonZeroU32::new(10).unwrap(),
+        PixelType::U8x4,
+    );
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let region = CropBox {
+        left: 800.,
+        top: 0.,
+        width: 100.,
+        height: 100.,
+    };
+    let result = resizer.resize_region(&src_image.view(), region, &mut dst_image.view_mut());
+    assert!(matches!(
+        result,
+        Err(ResizeRegionError::InvalidRegion(
+            CropBoxError::SizeIsOutOfImageBoundaries
+        ))
+    ));
+}
+
+#[test]
+fn resize_into_zero_dimension_is_error() {
+    let size = NonZeroU32::new(1000).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8x4);
+    // Scale factor is too small, so the width of the result is rounded to zero.
+    let dst_width = (1000. * 0.0001f32).round() as u32;
+    let result = fast_image_resize::resize(&src_image, dst_width, 1000, ResizeAlg::default());
+    assert!(matches!(result, Err(ResizeError::ZeroDimension)));
+    let result = fast_image_resize::resize(&src_image, 1000, 0, ResizeAlg::default());
+    assert!(matches!(result, Err(ResizeError::ZeroDimension)));
+}
+
+#[test]
+fn resize_by_factor_dimensions() {
+    let src_image = Image::new(
+        NonZeroU32::new(100).unwrap(),
+        NonZeroU32::new(50).unwrap(),
+        PixelType::U8x4,
+    );
+    for (factor, expected) in [
+        (0.5, (50, 25)),
+        (2.0, (200, 100)),
+        (0.333, (33, 17)),
+        (0.001, (1, 1)),
+    ] {
+        let dst_image =
+            fast_image_resize::resize_by_factor(&src_image, factor, ResizeAlg::default()).unwrap();
+        assert_eq!(
+            (dst_image.width().get(), dst_image.height().get()),
+            expected,
+            "factor={}",
+            factor
+        );
+        assert_eq!(dst_image.pixel_type(), PixelType::U8x4);
+    }
+}
+
+#[test]
+fn resize_by_invalid_factor_is_error() {
+    let size = NonZeroU32::new(10).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8x4);
+    for factor in [0., -0.5, f64::NAN, f64::INFINITY] {
+        let result = fast_image_resize::resize_by_factor(&src_image, factor, ResizeAlg::default());
+        assert!(
+            matches!(result, Err(ResizeError::InvalidScaleFactor)),
+            "factor={}",
+            factor
+        );
+    }
+}
+
+/// Downscales one row of pixels in two times with help of box filter,
+/// so every result is an average of two neighbouring pixels.
+fn halve_row(src_image: &Image, rounding_mode: RoundingMode) -> Image<'static> {
+    let dst_width = NonZeroU32::new(src_image.width().get() / 2).unwrap();
+    let mut dst_image = Image::new(dst_width, src_image.height(), src_image.pixel_type());
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Box));
+    assert_eq!(resizer.rounding_mode(), RoundingMode::NearestAway);
+    resizer.set_rounding_mode(rounding_mode);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image
+}
+
+const ROUNDING_MODES: [RoundingMode; 3] = [
+    RoundingMode::NearestAway,
+    RoundingMode::NearestEven,
+    RoundingMode::Truncate,
+];
+
+#[test]
+fn rounding_modes_of_u8_convolution() {
+    let width = NonZeroU32::new(6).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    // Averages are 2.5, 4.5 and 5.5
+    let src_image =
+        Image::from_vec_u8(width, height, vec![2, 3, 4, 5, 5, 6], PixelType::U8).unwrap();
+    for (rounding_mode, expected) in
+        ROUNDING_MODES
+            .into_iter()
+            .zip([[3, 5, 6], [2, 4, 6], [2, 4, 5]])
+    {
+        let dst_image = halve_row(&src_image, rounding_mode);
+        assert_eq!(dst_image.buffer(), expected, "{:?}", rounding_mode);
+    }
+}
+
+#[test]
+fn rounding_modes_of_u16_convolution() {
+    let width = NonZeroU32::new(6).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    // Averages are 1000.5, 1001.5 and 65534.5
+    let values = [1000u16, 1001, 1001, 1002, 65534, 65535];
+    let buffer: Vec<u8> = values
+        .iter()
+        .flat_map(|&v| [v; 3])
+        .flat_map(|v| v.to_ne_bytes())
+        .collect();
+    let src_image = Image::from_vec_u8(width, height, buffer, PixelType::U16x3).unwrap();
+    for (rounding_mode, expected) in ROUNDING_MODES.into_iter().zip([
+        [1001u16, 1002, 65535],
+        [1000, 1002, 65534],
+        [1000, 1001, 65534],
+    ]) {
+        let dst_image = halve_row(&src_image, rounding_mode);
+        let result: Vec<u16> = dst_image
+            .buffer()
+            .chunks_exact(6)
+            .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(result, expected, "{:?}", rounding_mode);
+    }
+}
+
+#[test]
+fn rounding_modes_of_i32_convolution() {
+    let width = NonZeroU32::new(6).unwrap();
+    let height = NonZeroU32::new(1).unwrap();
+    // Averages are 2.5, 3.5 and -2.5
+    let values = [2i32, 3, 3, 4, -3, -2];
+    let buffer: Vec<u8> = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+    let src_image = Image::from_vec_u8(width, height, buffer, PixelType::I32).unwrap();
+    for (rounding_mode, expected) in
+        ROUNDING_MODES
+            .into_iter()
+            .zip([[3, 4, -3], [2, 4, -2], [2, 3, -2]])
+    {
+        let dst_image = halve_row(&src_image, rounding_mode);
+        let result: Vec<i32> = dst_image
+            .buffer()
+            .chunks_exact(4)
+            .map(|c| i32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        assert_eq!(result, expected, "{:?}", rounding_mode);
+    }
+}
+
+#[test]
+fn resize_i16_image_with_negative_values() {
+    let src_width = NonZeroU32::new(16).unwrap();
+    let height = NonZeroU32::new(3).unwrap();
+    // Rows: step edge between extreme values, step edge between
+    // negative values and negative gradient.
+    let values: Vec<i32> = (0..16)
+        .map(|x| {
+            if x < 8 {
+                i16::MIN as i32
+            } else {
+                i16::MAX as i32
+            }
+        })
+        .chain((0..16).map(|x| if x < 8 { -1000 } else { -300 }))
+        .chain((0..16).map(|x| -100 * x - 100))
+        .collect();
+    let i16_buffer: Vec<u8> = values
+        .iter()
+        .flat_map(|&v| (v as i16).to_ne_bytes())
+        .collect();
+    let i32_buffer: Vec<u8> = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+    let i16_image = Image::from_vec_u8(src_width, height, i16_buffer, PixelType::I16).unwrap();
+    let i32_image = Image::from_vec_u8(src_width, height, i32_buffer, PixelType::I32).unwrap();
+
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_cpu_extensions(CpuExtensions::None);
+    for dst_width in [5, 16, 61] {
+        let dst_width = NonZeroU32::new(dst_width).unwrap();
+        let mut i16_result = Image::new(dst_width, height, PixelType::I16);
+        resizer
+            .resize(&i16_image.view(), &mut i16_result.view_mut())
+            .unwrap();
+        let i16_result: Vec<i16> = i16_result
+            .buffer()
+            .chunks_exact(2)
+            .map(|c| i16::from_ne_bytes([c[0], c[1]]))
+            .collect();
+
+        // Result of resizing of `I32` image, saturated into range of `i16`.
+        let mut i32_result = Image::new(dst_width, height, PixelType::I32);
+        resizer
+            .resize(&i32_image.view(), &mut i32_result.view_mut())
+            .unwrap();
+        let expected: Vec<i16> = i32_result
+            .buffer()
+            .chunks_exact(4)
+            .map(|c| {
+                let v = i32::from_ne_bytes([c[0], c[1], c[2], c[3]]);
+                v.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+            })
+            .collect();
+        assert_eq!(i16_result, expected, "{}", dst_width);
+
+        let row_size = dst_width.get() as usize;
+        let extreme_row = &i16_result[..row_size];
+        let negative_rows = &i16_result[row_size..];
+        assert!(negative_rows.iter().all(|&v| v < 0), "{}", dst_width);
+        if dst_width.get() > 16 {
+            // Ringing of Lanczos3 is saturated at extreme values.
+            assert!(extreme_row.contains(&i16::MIN), "{}", dst_width);
+            assert!(extreme_row.contains(&i16::MAX), "{}", dst_width);
+        }
+    }
+}
+
+/// Creates `U8` image from `tiles_x * tiles_y` copies of the tileable
+/// texture with pseudo-random pixels. Every copy is flipped along
+/// axes for which `flip` returns `true` for the index of the copy.
+fn tiled_texture(
+    (width, height): (u32, u32),
+    (tiles_x, tiles_y): (u32, u32),
+    flip: impl Fn(u32) -> bool,
+) -> Image<'static> {
+    let texture = |x: u32, y: u32| ((x + y * width).wrapping_mul(2654435761) >> 24) as u8;
+    let buffer: Vec<u8> = (0..height * tiles_y)
+        .flat_map(|y| (0..width * tiles_x).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (x, tile_x) = (x % width, x / width);
+            let (y, tile_y) = (y % height, y / height);
+            let x = if flip(tile_x) { width - 1 - x } else { x };
+            let y = if flip(tile_y) { height - 1 - y } else { y };
+            texture(x, y)
+        })
+        .collect();
+    Image::from_vec_u8(
+        NonZeroU32::new(width * tiles_x).unwrap(),
+        NonZeroU32::new(height * tiles_y).unwrap(),
+        buffer,
+        PixelType::U8,
+    )
+    .unwrap()
+}
+
+/// Checks that the result of resizing of the texture with given
+/// boundary mode is equal to the central tile of the result of resizing
+/// of 3x3 tiles of the texture, where neighbour tiles are flipped
+/// if `flip_neighbours` is `true`.
+fn check_boundary_mode(boundary_mode: BoundaryMode, flip_neighbours: bool) {
+    let size = (64, 48);
+    let src_image = tiled_texture(size, (1, 1), |_| false);
+    let tiled_image = tiled_texture(size, (3, 3), |i| flip_neighbours && i != 1);
+    for filter_type in [FilterType::Lanczos3, FilterType::Bilinear] {
+        for (dst_width, dst_height) in [(24, 20), (100, 70), (64, 17)] {
+            let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+            resizer.set_boundary_mode(boundary_mode);
+            let mut dst_image = Image::new(
+                NonZeroU32::new(dst_width).unwrap(),
+                NonZeroU32::new(dst_height).unwrap(),
+                PixelType::U8,
+            );
+            resizer
+                .resize(&src_image.view(), &mut dst_image.view_mut())
+                .unwrap();
+
+            resizer.set_boundary_mode(BoundaryMode::Clamp);
+            let mut tiled_dst_image = Image::new(
+                NonZeroU32::new(dst_width * 3).unwrap(),
+                NonZeroU32::new(dst_height * 3).unwrap(),
+                PixelType::U8,
+            );
+            resizer
+                .resize(&tiled_image.view(), &mut tiled_dst_image.view_mut())
+                .unwrap();
+            let row_size = dst_width as usize * 3;
+            let expected: Vec<u8> = tiled_dst_image
+                .buffer()
+                .chunks_exact(row_size)
+                .skip(dst_height as usize)
+                .take(dst_height as usize)
+                .flat_map(|row| &row[dst_width as usize..2 * dst_width as usize])
+                .copied()
+                .collect();
+            assert!(
+                dst_image
+                    .buffer()
+                    .iter()
+                    .zip(&expected)
+                    .all(|(&r, &e)| (r as i32 - e as i32).abs() <= 1),
+                "{:?} {:?} {}x{}",
+                boundary_mode,
+                filter_type,
+                dst_width,
+                dst_height
+            );
+        }
+    }
+}
+
+#[test]
+fn wrap_boundary_mode_keeps_texture_seamless() {
+    check_boundary_mode(BoundaryMode::Wrap, false);
+}
+
+#[test]
+fn mirror_boundary_mode_eq_resize_of_mirrored_image() {
+    check_boundary_mode(BoundaryMode::Mirror, true);
+}
+
+/// Returns the maximal difference between neighbour pixels in rows
+/// of the `U8x3` or `U8x4` image.
+fn max_edge_contrast(image: &Image, pixel_size: usize) -> u8 {
+    let row_size = image.width().get() as usize * pixel_size;
+    image
+        .buffer()
+        .chunks_exact(row_size)
+        .flat_map(|row| {
+            row.chunks_exact(pixel_size)
+                .zip(row.chunks_exact(pixel_size).skip(1))
+                .map(|(l, r)| l[0].abs_diff(r[0]))
+        })
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn unsharp_mask_increases_edge_contrast() {
+    // Gradient edge from 60 to 190
+    let (width, height) = (64u32, 16u32);
+    let buffer: Vec<u8> = (0..width * height)
+        .flat_map(|i| {
+            let x = i % width;
+            let v = (60 + (x.saturating_sub(28) * 130 / 8).min(130)) as u8;
+            [v, v, v, 200]
+        })
+        .collect();
+    let src_image = Image::from_vec_u8(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        buffer,
+        PixelType::U8x4,
+    )
+    .unwrap();
+    let dst_width = NonZeroU32::new(16).unwrap();
+    let dst_height = NonZeroU32::new(4).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    let contrast = max_edge_contrast(&dst_image, 4);
+
+    let mask = UnsharpMask::new(1.0, 1.0, 0).unwrap();
+    resizer.set_unsharp_mask(Some(mask));
+    let mut sharpened = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&src_image.view(), &mut sharpened.view_mut())
+        .unwrap();
+    let sharpened_contrast = max_edge_contrast(&sharpened, 4);
+    assert!(
+        sharpened_contrast as f32 > contrast as f32 * 1.2,
+        "{} {}",
+        contrast,
+        sharpened_contrast
+    );
+    // Alpha channel isn't changed.
+    assert!(sharpened.buffer().chunks_exact(4).all(|p| p[3] == 200));
+
+    // Resizing with unsharp mask is the same as sharpening of the result.
+    resizer.sharpen(&mut dst_image.view_mut(), mask).unwrap();
+    assert!(dst_image.buffer() == sharpened.buffer());
+
+    // Differences less than threshold are not sharpened.
+    let rgb: Vec<u8> = src_image
+        .buffer()
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let src_width = src_image.width();
+    let src_height = src_image.height();
+    let mut sharpened =
+        Image::from_vec_u8(src_width, src_height, rgb.clone(), PixelType::U8x3).unwrap();
+    resizer
+        .sharpen(
+            &mut sharpened.view_mut(),
+            UnsharpMask::new(1.0, 1.0, 255).unwrap(),
+        )
+        .unwrap();
+    assert!(sharpened.buffer() == rgb.as_slice());
+
+    let mut image = Image::new(dst_width, dst_height, PixelType::U16x3);
+    assert!(resizer.sharpen(&mut image.view_mut(), mask).is_err());
+    assert!(UnsharpMask::new(1.0, f32::NAN, 0).is_err());
+    assert!(UnsharpMask::new(-1.0, 1.0, 0).is_err());
+}
+
+#[test]
+fn box_filter_without_normalization_sums_pixels() {
+    let size = NonZeroU32::new(4).unwrap();
+    let dst_size = NonZeroU32::new(2).unwrap();
+    let values: Vec<u8> = (1..=16u32)
+        .flat_map(|v| (2. * v as f32).to_ne_bytes())
+        .collect();
+    let src_image = Image::from_vec_u8(size, size, values, PixelType::F32).unwrap();
+    let resize = |resizer: &mut Resizer| -> Vec<f32> {
+        let mut dst_image = Image::new(dst_size, dst_size, PixelType::F32);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image
+            .buffer()
+            .chunks_exact(4)
+            .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    };
+
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Box));
+    assert!(resizer.normalization());
+    // Averages of 2x2 blocks
+    assert_eq!(resize(&mut resizer), [7., 11., 23., 27.]);
+
+    resizer.set_normalization(false);
+    assert!(!resizer.normalization());
+    // Sums of 2x2 blocks
+    assert_eq!(resize(&mut resizer), [28., 44., 92., 108.]);
+
+    // Images with U8 pixels are always normalized
+    let src_image = Image::from_vec_u8(size, size, vec![100; 16], PixelType::U8).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert_eq!(dst_image.buffer(), [100; 4]);
+}
+
+#[test]
+fn unnormalized_coefficients() {
+    let src_size = NonZeroU32::new(6).unwrap();
+    let dst_size = NonZeroU32::new(2).unwrap();
+    let coefficients = Coefficients::new_unnormalized(FilterType::Box, src_size, dst_size);
+    for chunk in coefficients.get_chunks() {
+        assert_eq!(chunk.values, [1., 1., 1.]);
+    }
+    let coefficients = Coefficients::new(FilterType::Box, src_size, dst_size);
+    for chunk in coefficients.get_chunks() {
+        assert_eq!(chunk.values, [1. / 3., 1. / 3., 1. / 3.]);
+    }
+}
+
+/// Returns `U8x4` image with opaque green pixels surrounded by
+/// fully transparent red ones.
+fn green_image_with_transparent_red_border(size: u32) -> Image<'static> {
+    let pixels = (0..size * size)
+        .map(|i| {
+            let (x, y) = (i % size, i / size);
+            if x == 0 || y == 0 || x == size - 1 || y == size - 1 {
+                u32::from_le_bytes([255, 0, 0, 0])
+            } else {
+                u32::from_le_bytes([0, 255, 0, 255])
+            }
+        })
+        .collect();
+    let size = NonZeroU32::new(size).unwrap();
+    Image::from_vec_u32(size, size, pixels, PixelType::U8x4).unwrap()
+}
+
+#[test]
+fn premultiplied_alpha_mode_prevents_color_bleeding() {
+    let src_image = green_image_with_transparent_red_border(12);
+    let dst_size = NonZeroU32::new(5).unwrap();
+    let max_red_of_visible_pixels = |alpha_mode: AlphaMode| {
+        let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x4);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+        assert_eq!(resizer.alpha_mode(), AlphaMode::None);
+        resizer.set_alpha_mode(alpha_mode);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image
+            .buffer()
+            .chunks_exact(4)
+            .filter(|pixel| pixel[3] > 0)
+            .map(|pixel| pixel[0])
+            .max()
+            .unwrap()
+    };
+    assert!(max_red_of_visible_pixels(AlphaMode::None) > 0);
+    assert_eq!(max_red_of_visible_pixels(AlphaMode::Premultiplied), 0);
+}
+
+#[test]
+fn premultiplied_alpha_mode_keeps_opaque_image() {
+    let src_image = U8x4::load_small_src_image();
+    let dst_width = NonZeroU32::new(255).unwrap();
+    let dst_height = NonZeroU32::new(170).unwrap();
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8x4);
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+    let mut resizer = Resizer::default();
+    resizer
+        .resize(&src_image.view(), &mut expected.view_mut())
+        .unwrap();
+    resizer.set_alpha_mode(AlphaMode::Premultiplied);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert!(dst_image.buffer() == expected.buffer());
+}
+
+#[test]
+fn resize_premultiplied_eq_manual_mul_div() {
+    let dst_width = NonZeroU32::new(255).unwrap();
+    let dst_height = NonZeroU32::new(170).unwrap();
+    let mul_div = MulDiv::default();
+    let src_images = [
+        U8x4::load_small_rgba_image(),
+        U16x4::load_small_rgba_image(),
+    ];
+    for src_image in src_images.iter() {
+        let pixel_type = src_image.pixel_type();
+        for crop_box in [
+            None,
+            Some(CropBox {
+                left: 10.,
+                top: 20.,
+                width: 500.,
+                height: 300.,
+            }),
+        ] {
+            let mut src_view = src_image.view();
+            if let Some(crop_box) = crop_box {
+                src_view.set_crop_box(crop_box).unwrap();
+            }
+            let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+            let mut premultiplied = Image::new(src_image.width(), src_image.height(), pixel_type);
+            mul_div
+                .multiply_alpha(&src_image.view(), &mut premultiplied.view_mut())
+                .unwrap();
+            let mut premultiplied_view = premultiplied.view();
+            if let Some(crop_box) = crop_box {
+                premultiplied_view.set_crop_box(crop_box).unwrap();
+            }
+            let mut expected = Image::new(dst_width, dst_height, pixel_type);
+            resizer
+                .resize(&premultiplied_view, &mut expected.view_mut())
+                .unwrap();
+            mul_div
+                .divide_alpha_inplace(&mut expected.view_mut())
+                .unwrap();
+
+            let mut dst_image = Image::new(dst_width, dst_height, pixel_type);
+            resizer
+                .resize_premultiplied(&src_view, &mut dst_image.view_mut())
+                .unwrap();
+            assert!(
+                dst_image.buffer() == expected.buffer(),
+                "{:?} {:?}",
+                pixel_type,
+                crop_box
+            );
+            assert_eq!(resizer.alpha_mode(), AlphaMode::None);
+
+            // The buffer for premultiplied image is reused.
+            let buffers_size = resizer.size_of_internal_buffers();
+            resizer
+                .resize_premultiplied(&src_view, &mut dst_image.view_mut())
+                .unwrap();
+            assert_eq!(resizer.size_of_internal_buffers(), buffers_size);
+        }
+    }
+
+    let mut resizer = Resizer::default();
+    let src_image = U8x3::load_small_src_image();
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x3);
+    assert!(matches!(
+        resizer.resize_premultiplied(&src_image.view(), &mut dst_image.view_mut()),
+        Err(ResizePremultipliedError::UnsupportedPixelType)
+    ));
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+    assert!(matches!(
+        resizer.resize_premultiplied(&src_image.view(), &mut dst_image.view_mut()),
+        Err(ResizePremultipliedError::DifferentTypesOfPixels)
+    ));
+}
+
+#[test]
+fn already_premultiplied_alpha_mode_avoids_round_trip_error() {
+    let src_image = U8x4::load_small_src_image();
+    let (width, height) = (src_image.width(), src_image.height());
+    // Premultiplied image with variable alpha channel.
+    let mut pixels = src_image.buffer().to_vec();
+    for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i % width.get() as usize, i / width.get() as usize);
+        pixel[3] = ((x * 7 + y * 3) % 256) as u8;
+    }
+    let mut src_image = Image::from_vec_u8(width, height, pixels, PixelType::U8x4).unwrap();
+    src_image.multiply_alpha_in_place().unwrap();
+
+    let dst_width = NonZeroU32::new(255).unwrap();
+    let dst_height = NonZeroU32::new(170).unwrap();
+    let resize = |src_image: &Image, alpha_mode: AlphaMode| {
+        let mut dst_image = Image::new(dst_width, dst_height, src_image.pixel_type());
+        let mut resizer = Resizer::default();
+        resizer.set_alpha_mode(alpha_mode);
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        dst_image
+    };
+
+    // Reference result of resizing of 16-bit premultiplied image.
+    let src_u16: Vec<u8> = src_image
+        .buffer()
+        .iter()
+        .flat_map(|&c| (c as u16 * 257).to_ne_bytes())
+        .collect();
+    let src_u16 = Image::from_vec_u8(width, height, src_u16, PixelType::U16x4).unwrap();
+    let expected: Vec<u8> = resize(&src_u16, AlphaMode::AlreadyPremultiplied)
+        .buffer()
+        .chunks_exact(2)
+        .map(|c| ((u16::from_ne_bytes([c[0], c[1]]) as u32 + 128) / 257) as u8)
+        .collect();
+
+    let result = resize(&src_image, AlphaMode::AlreadyPremultiplied);
+    // Color channels never exceed alpha channel of premultiplied pixels.
+    assert!(result
+        .buffer()
+        .chunks_exact(4)
+        .all(|p| p[0] <= p[3] && p[1] <= p[3] && p[2] <= p[3]));
+
+    // Dividing by alpha before resizing and multiplying after it
+    // loses precision of pixels with small alpha.
+    let mut straight_image = src_image.convert(PixelType::U8x4).unwrap();
+    straight_image.divide_alpha_in_place().unwrap();
+    let mut round_trip = resize(&straight_image, AlphaMode::Premultiplied);
+    round_trip.multiply_alpha_in_place().unwrap();
+
+    let mean_error = |image: &Image| {
+        let sum: u32 = image
+            .buffer()
+            .iter()
+            .zip(&expected)
+            .map(|(&a, &b)| a.abs_diff(b) as u32)
+            .sum();
+        sum as f64 / expected.len() as f64
+    };
+    let error = mean_error(&result);
+    let round_trip_error = mean_error(&round_trip);
+    assert!(error < 0.2, "{} {}", error, round_trip_error);
+    assert!(
+        error * 1.5 < round_trip_error,
+        "{} {}",
+        error,
+        round_trip_error
+    );
+}
+
+#[test]
+fn horiz_convolution_eq_resize_of_width() {
+    let src_image = U8x4::load_small_src_image();
+    let (width, height) = (src_image.width(), src_image.height());
+    let dst_width = NonZeroU32::new(width.get() * 2).unwrap();
+    let filter_type = FilterType::Lanczos3;
+
+    let expected = fast_image_resize::resize(
+        &src_image,
+        dst_width.get(),
+        height.get(),
+        ResizeAlg::Convolution(filter_type),
+    )
+    .unwrap();
+
+    let coeffs = Coefficients::new(filter_type, width, dst_width);
+    let mut dst_image = Image::new(dst_width, height, PixelType::U8x4);
+    let mut resizer = Resizer::default();
+    resizer
+        .horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs)
+        .unwrap();
+    assert!(dst_image.buffer() == expected.buffer());
+}
+
+#[test]
+fn vert_convolution_eq_resize_of_height() {
+    let src_image = U8x4::load_small_src_image();
+    let (width, height) = (src_image.width(), src_image.height());
+    let dst_height = NonZeroU32::new(height.get() / 3).unwrap();
+    let filter_type = FilterType::Mitchell;
+
+    let expected = fast_image_resize::resize(
+        &src_image,
+        width.get(),
+        dst_height.get(),
+        ResizeAlg::Convolution(filter_type),
+    )
+    .unwrap();
+
+    let coeffs = Coefficients::new(filter_type, height, dst_height);
+    let mut dst_image = Image::new(width, dst_height, PixelType::U8x4);
+    let mut resizer = Resizer::default();
+    resizer
+        .vert_convolution(&src_image.view(), &mut dst_image.view_mut(), &coeffs)
+        .unwrap();
+    assert!(dst_image.buffer() == expected.buffer());
+}
+
+#[test]
+fn horiz_convolution_with_custom_coefficients() {
+    let src_image = Image::from_vec_u8(
+        NonZeroU32::new(4).unwrap(),
+        NonZeroU32::new(2).unwrap(),
+        vec![10, 20, 30, 40, 50, 60, 70, 80],
+        PixelType::U8,
+    )
+    .unwrap();
+    // Averages of pairs of neighbouring pixels
+    let coeffs = Coefficients::from_chunks(&[
+        CoefficientsChunk {
+            start: 0,
+            values: &[0.5, 0.5],
+        },
+        CoefficientsChunk {
+            start: 1,
+            values: &[0.5, 0.5],
+        },
+        CoefficientsChunk {
+            start: 3,
+            values: &[1.],
+        },
+    ]);
+    assert_eq!(coeffs.get_chunks().len(), 3);
+    let mut dst_image = Image::new(
+        NonZeroU32::new(3).unwrap(),
+        NonZeroU32::new(1).unwrap(),
+        PixelType::U8,
+    );
+    let mut resizer = Resizer::default();
+    resizer
+        .horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 1, &coeffs)
+        .unwrap();
+    assert_eq!(dst_image.buffer(), &[55, 65, 80]);
+}
+
+#[test]
+fn convolution_with_invalid_params() {
+    let size = NonZeroU32::new(8).unwrap();
+    let small_size = NonZeroU32::new(4).unwrap();
+    let src_image = Image::new(size, size, PixelType::U8x4);
+    let mut resizer = Resizer::default();
+
+    let mut dst_image = Image::new(small_size, size, PixelType::U8);
+    let coeffs = Coefficients::new(FilterType::Box, size, small_size);
+    let result =
+        resizer.horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs);
+    assert!(matches!(
+        result,
+        Err(ConvolutionError::DifferentTypesOfPixels)
+    ));
+
+    let mut dst_image = Image::new(small_size, small_size, PixelType::U8x4);
+    let result =
+        resizer.horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 5, &coeffs);
+    assert!(matches!(
+        result,
+        Err(ConvolutionError::OffsetIsOutOfImageBoundaries)
+    ));
+
+    let coeffs = Coefficients::new(FilterType::Box, size, size);
+    let result =
+        resizer.horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs);
+    assert!(matches!(
+        result,
+        Err(ConvolutionError::InvalidCountOfChunks)
+    ));
+
+    let chunk = CoefficientsChunk {
+        start: 6,
+        values: &[0.5, 0.5, 0.5],
+    };
+    let coeffs = Coefficients::from_chunks(&[chunk; 4]);
+    let result =
+        resizer.horiz_convolution(&src_image.view(), &mut dst_image.view_mut(), 0, &coeffs);
+    assert!(matches!(
+        result,
+        Err(ConvolutionError::CoefficientsAreOutOfImageBoundaries)
+    ));
+    let result = resizer.vert_convolution(&src_image.view(), &mut dst_image.view_mut(), &coeffs);
+    assert!(matches!(result, Err(ConvolutionError::DifferentWidths)));
+}
+
+#[test]
+fn resize_batch_eq_resize_of_every_image() {
+    let src_image = U8x4::load_small_src_image();
+    let (width, height) = (src_image.width(), src_image.height());
+    // Images with the same size and different pixels.
+    let mut src_images: Vec<Image> = (0..4u8)
+        .map(|i| {
+            let buffer = src_image.buffer().iter().map(|&v| v ^ (i * 37)).collect();
+            Image::from_vec_u8(width, height, buffer, PixelType::U8x4).unwrap()
+        })
+        .collect();
+    // Images with another size and type of pixels are resized too.
+    src_images.push(src_image.rotate_90());
+    src_images.push(U8x3::load_small_src_image());
+
+    let dst_width = NonZeroU32::new(213).unwrap();
+    let dst_height = NonZeroU32::new(97).unwrap();
+    for algorithm in [
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        ResizeAlg::adaptive(),
+        ResizeAlg::Nearest,
+        ResizeAlg::SuperSampling(FilterType::Bilinear, 2),
+    ] {
+        for num_threads in [0, 1] {
+            let mut resizer = Resizer::new(algorithm);
+            resizer.set_num_threads(num_threads);
+            let results = resizer.resize_batch(&src_images, dst_width, dst_height);
+            assert_eq!(results.len(), src_images.len());
+            for (src_image, result) in src_images.iter().zip(&results) {
+                let mut expected = Image::new(dst_width, dst_height, src_image.pixel_type());
+                Resizer::new(algorithm)
+                    .resize(&src_image.view(), &mut expected.view_mut())
+                    .unwrap();
+                assert_eq!(result.pixel_type(), src_image.pixel_type());
+                assert!(
+                    result.buffer() == expected.buffer(),
+                    "{:?} {:?}",
+                    algorithm,
+                    src_image.pixel_type()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn resize_into_allocated_image() {
+    let src_image = U8x4::load_small_src_image();
+    let dst_width = NonZeroU32::new(120).unwrap();
+    let dst_height = NonZeroU32::new(80).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    let expected = fast_image_resize::resize(
+        &src_image,
+        dst_width.get(),
+        dst_height.get(),
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+    )
+    .unwrap();
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+    let buffer_ptr = dst_image.buffer().as_ptr();
+    let black_image = Image::new(src_image.width(), src_image.height(), PixelType::U8x4);
+    for _ in 0..3 {
+        resizer
+            .resize_into_allocated(&src_image.view(), &mut dst_image, dst_width, dst_height)
+            .unwrap();
+        assert!(dst_image.buffer() == expected.buffer());
+        assert_eq!(dst_image.buffer().as_ptr(), buffer_ptr);
+
+        resizer
+            .resize_into_allocated(&black_image.view(), &mut dst_image, dst_width, dst_height)
+            .unwrap();
+        assert!(dst_image.buffer().iter().all(|&c| c == 0));
+        assert_eq!(dst_image.buffer().as_ptr(), buffer_ptr);
+    }
+
+    let result =
+        resizer.resize_into_allocated(&src_image.view(), &mut dst_image, dst_height, dst_width);
+    assert!(matches!(
+        result,
+        Err(ResizeIntoAllocatedError::DifferentSize)
+    ));
+
+    let mut u8_image = Image::new(dst_width, dst_height, PixelType::U8);
+    let result =
+        resizer.resize_into_allocated(&src_image.view(), &mut u8_image, dst_width, dst_height);
+    assert!(matches!(
+        result,
+        Err(ResizeIntoAllocatedError::DifferentTypesOfPixels)
+    ));
+}
+
+#[test]
+fn resize_u8x2_with_transparent_edge() {
+    // Left half is transparent white, right half is opaque black.
+    let src_width = NonZeroU32::new(16).unwrap();
+    let height = NonZeroU32::new(4).unwrap();
+    let buffer: Vec<u8> = (0..16 * 4)
+        .flat_map(|i| if i % 16 < 8 { [255, 0] } else { [0, 255] })
+        .collect();
+    let mut image = Image::from_vec_u8(src_width, height, buffer, PixelType::U8x2).unwrap();
+    image.multiply_alpha_in_place().unwrap();
+
+    let dst_width = NonZeroU32::new(6).unwrap();
+    let mut dst_image = Image::new(dst_width, height, PixelType::U8x2);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+    resizer
+        .resize(&image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image.divide_alpha_in_place().unwrap();
+
+    let row = &dst_image.buffer()[..dst_width.get() as usize * 2];
+    let alphas: Vec<u8> = row.chunks_exact(2).map(|p| p[1]).collect();
+    // Alpha is interpolated across the edge ...
+    assert_eq!(alphas[0], 0);
+    assert_eq!(alphas[5], 255);
+    assert!(alphas[2] > 0 && alphas[3] < 255);
+    // ... but color of transparent pixels doesn't bleed into
+    // visible pixels, so all visible pixels stay black.
+    for pixel in row.chunks_exact(2) {
+        if pixel[1] > 0 {
+            assert_eq!(pixel[0], 0, "{:?}", row);
+        }
+    }
+}
+
+#[test]
+fn resize_u16x2_eq_manual_average() {
+    // Every 2x2 block of source image is reduced into one pixel,
+    // so the result must be equal to the average of the block.
+    let src_width = NonZeroU32::new(4).unwrap();
+    let src_height = NonZeroU32::new(2).unwrap();
+    let src_pixels: Vec<[u16; 2]> = (0..8u16)
+        .map(|i| [i * 8000 + 1000, 65535 - i * 4000])
+        .collect();
+    let buffer: Vec<u8> = src_pixels
+        .iter()
+        .flatten()
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    let src_image = Image::from_vec_u8(src_width, src_height, buffer, PixelType::U16x2).unwrap();
+
+    let dst_width = NonZeroU32::new(2).unwrap();
+    let dst_height = NonZeroU32::new(1).unwrap();
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U16x2);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Box));
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+
+    let result: Vec<u16> = dst_image
+        .buffer()
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    let mut expected = vec![];
+    for x in [0, 2] {
+        let mut sum = [0u32; 2];
+        for i in [x, x + 1, x + 4, x + 5] {
+            let [luma, alpha] = src_pixels[i];
+            sum[0] += luma as u32;
+            sum[1] += alpha as u32;
+        }
+        expected.extend(sum.map(|s| (s / 4) as u16));
+    }
+    assert_eq!(result, expected);
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn resize_f16_gradient_eq_f32x4() {
+    use half::f16;
+
+    let src_width = NonZeroU32::new(64).unwrap();
+    let src_height = NonZeroU32::new(8).unwrap();
+    let values: Vec<f32> = (0..64 * 8)
+        .map(|i| ((i % 64) as f32 / 63.) * 1000. + (i / 64) as f32)
+        .collect();
+    let f16_buffer: Vec<u8> = values
+        .iter()
+        .flat_map(|&v| f16::from_f32(v).to_le_bytes())
+        .collect();
+    // Source values for F32x4 image are rounded into `f16` too,
+    // so both images have the same source pixels.
+    let f32x4_buffer: Vec<u8> = values
+        .iter()
+        .flat_map(|&v| [f16::from_f32(v).to_f32(); 4])
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    let f16_image = Image::from_vec_u8(src_width, src_height, f16_buffer, PixelType::F16).unwrap();
+    let f32x4_image =
+        Image::from_vec_u8(src_width, src_height, f32x4_buffer, PixelType::F32x4).unwrap();
+
+    let dst_width = NonZeroU32::new(23).unwrap();
+    let dst_height = NonZeroU32::new(5).unwrap();
+    let mut f16_result = Image::new(dst_width, dst_height, PixelType::F16);
+    let mut f32x4_result = Image::new(dst_width, dst_height, PixelType::F32x4);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer
+        .resize(&f16_image.view(), &mut f16_result.view_mut())
+        .unwrap();
+    resizer
+        .resize(&f32x4_image.view(), &mut f32x4_result.view_mut())
+        .unwrap();
+
+    let f16_values = f16_result
+        .buffer()
+        .chunks_exact(2)
+        .map(|c| f16::from_le_bytes([c[0], c[1]]).to_f32());
+    let f32_values = f32x4_result
+        .buffer()
+        .chunks_exact(16)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+    for (f16_value, f32_value) in f16_values.zip(f32_values) {
+        // Result is rounded twice (after horizontal and vertical passes),
+        // so it may differ by a bit more than half of the f16 epsilon.
+        let tolerance = f32_value.abs().max(1.) * f16::EPSILON.to_f32();
+        assert!(
+            (f16_value - f32_value).abs() <= tolerance,
+            "{} != {}",
+            f16_value,
+            f32_value
+        );
+    }
+}
+
+#[test]
+fn resize_with_progress_reports_increasing_fractions() {
+    let src_image = U8x4::load_big_src_image();
+    let mut dst_image = Image::new(
+        NonZeroU32::new(256).unwrap(),
+        NonZeroU32::new(128).unwrap(),
+        PixelType::U8x4,
+    );
+    let algorithms = [
+        ResizeAlg::Nearest,
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        ResizeAlg::SuperSampling(FilterType::Bilinear, 2),
+    ];
+    for algorithm in algorithms {
+        let mut resizer = Resizer::new(algorithm);
+        let mut fractions = Vec::new();
+        resizer
+            .resize_with_progress(&src_image.view(), &mut dst_image.view_mut(), &mut |f| {
+                fractions.push(f);
+                true
+            })
+            .unwrap();
+        assert_eq!(fractions.last(), Some(&1.), "{:?}", algorithm);
+        assert!(
+            fractions.windows(2).all(|w| w[0] < w[1]),
+            "{:?}: {:?}",
+            algorithm,
+            fractions
+        );
+        if matches!(algorithm, ResizeAlg::Convolution(_)) {
+            assert!(fractions.len() > 2);
+        }
+
+        let mut expected = Image::new(dst_image.width(), dst_image.height(), PixelType::U8x4);
+        resizer
+            .resize(&src_image.view(), &mut expected.view_mut())
+            .unwrap();
+        assert_eq!(dst_image.buffer(), expected.buffer(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn cancel_resize_after_first_chunk() {
+    let size = NonZeroU32::new(64).unwrap();
+    let src_image = Image::from_vec_u8(size, size, vec![255; 64 * 64], PixelType::U8).unwrap();
+    // Only horizontal pass is required, so rows of the destination
+    // image are written directly by every processed chunk.
+    let mut dst_image = Image::new(NonZeroU32::new(32).unwrap(), size, PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+    let mut calls = 0;
+    let result =
+        resizer.resize_with_progress(&src_image.view(), &mut dst_image.view_mut(), &mut |_| {
+            calls += 1;
+            false
+        });
+    assert!(matches!(result, Err(ResizeWithProgressError::Cancelled)));
+    assert_eq!(calls, 1);
+
+    let rows: Vec<&[u8]> = dst_image.buffer().chunks_exact(32).collect();
+    let processed = rows.iter().take_while(|row| row.iter().all(|&v| v == 255));
+    let processed_count = processed.count();
+    assert!(processed_count > 0 && processed_count < rows.len());
+    // Rows after the first chunk are left untouched.
+    assert!(rows[processed_count..]
+        .iter()
+        .all(|row| row.iter().all(|&v| v == 0)));
+
+    // Cancellation in the horizontal pass skips the vertical pass.
+    let mut dst_image = Image::new(size, NonZeroU32::new(32).unwrap(), PixelType::U8);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let mut src_view = src_image.view();
+    src_view
+        .set_crop_box(CropBox {
+            left: 0.,
+            top: 0.,
+            width: 48.,
+            height: 64.,
+        })
+        .unwrap();
+    let result = resizer.resize_with_progress(&src_view, &mut dst_image.view_mut(), &mut |_| false);
+    assert!(matches!(result, Err(ResizeWithProgressError::Cancelled)));
+    assert!(dst_image.buffer().iter().all(|&v| v == 0));
+}
+
+#[test]
+fn resize_to_luma_eq_convert_then_resize() {
+    let dst_width = NonZeroU32::new(213).unwrap();
+    let dst_height = NonZeroU32::new(142).unwrap();
+    let src_u8x3 = U8x3::load_small_src_image();
+    let src_u8x4 = U8x4::load_small_src_image();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    // Luma is calculated before convolution.
+    let luma = src_u8x3.convert(PixelType::U8).unwrap();
+    let mut expected = Image::new(dst_width, dst_height, PixelType::U8);
+    resizer
+        .resize(&luma.view(), &mut expected.view_mut())
+        .unwrap();
+
+    for src_image in [&src_u8x3, &src_u8x4] {
+        let mut result = Image::new(dst_width, dst_height, PixelType::U8);
+        resizer
+            .resize_to_luma(&src_image.view(), &mut result.view_mut())
+            .unwrap();
+        assert_eq!(result.buffer(), expected.buffer());
+    }
+
+    // Resizing of RGB image before conversion gives nearly the same
+    // result, but requires convolution of three channels.
+    let mut resized = Image::new(dst_width, dst_height, PixelType::U8x3);
+    resizer
+        .resize(&src_u8x3.view(), &mut resized.view_mut())
+        .unwrap();
+    let resized_luma = resized.convert(PixelType::U8).unwrap();
+    let diffs: Vec<u8> = expected
+        .buffer()
+        .iter()
+        .zip(resized_luma.buffer())
+        .map(|(&a, &b)| a.abs_diff(b))
+        .collect();
+    let mean_diff = diffs.iter().map(|&d| d as f64).sum::<f64>() / diffs.len() as f64;
+    assert!(diffs.iter().all(|&d| d <= 2));
+    assert!(mean_diff < 0.25, "{}", mean_diff);
+}
+
+#[test]
+fn resize_to_luma_of_unsupported_types() {
+    let size = NonZeroU32::new(8).unwrap();
+    let mut resizer = Resizer::default();
+    let src_image = Image::new(size, size, PixelType::U16x3);
+    let mut dst_image = Image::new(size, size, PixelType::U8);
+    assert!(matches!(
+        resizer.resize_to_luma(&src_image.view(), &mut dst_image.view_mut()),
+        Err(UnsupportedConversionError)
+    ));
+    let src_image = Image::new(size, size, PixelType::U8x3);
+    let mut dst_image = Image::new(size, size, PixelType::U8x3);
+    assert!(matches!(
+        resizer.resize_to_luma(&src_image.view(), &mut dst_image.view_mut()),
+        Err(UnsupportedConversionError)
+    ));
+}
+
+#[test]
+fn resize_with_conversion_eq_resize_then_convert() {
+    let dst_width = NonZeroU32::new(213).unwrap();
+    let dst_height = NonZeroU32::new(142).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    for src_image in [U8x4::load_small_src_image(), U16x3::load_small_src_image()] {
+        let mut resized = Image::new(dst_width, dst_height, src_image.pixel_type());
+        resizer
+            .resize(&src_image.view(), &mut resized.view_mut())
+            .unwrap();
+        let expected = resized.convert(PixelType::U8x3).unwrap();
+
+        let mut result = Image::new(dst_width, dst_height, PixelType::U8x3);
+        resizer
+            .resize_with_conversion(&src_image.view(), &mut result.view_mut())
+            .unwrap();
+        assert_eq!(result.width(), dst_width);
+        assert_eq!(result.height(), dst_height);
+        assert!(
+            result.buffer() == expected.buffer(),
+            "{:?}",
+            src_image.pixel_type()
+        );
+    }
+}
+
+#[test]
+fn resize_with_conversion_of_channels() {
+    let src_size = NonZeroU32::new(16).unwrap();
+    let dst_size = NonZeroU32::new(5).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+
+    // Alpha-channel is dropped.
+    let pixels = [10u8, 20, 30, 40].repeat(16 * 16);
+    let src_image = Image::from_vec_u8(src_size, src_size, pixels, PixelType::U8x4).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x3);
+    resizer
+        .resize_with_conversion(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert!(dst_image
+        .buffer()
+        .chunks_exact(3)
+        .all(|p| p == [10, 20, 30]));
+
+    // Components are scaled from `u16` into `u8`.
+    let pixels: Vec<u8> = [65535u16, 32896, 257]
+        .repeat(16 * 16)
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let src_image = Image::from_vec_u8(src_size, src_size, pixels, PixelType::U16x3).unwrap();
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x3);
+    resizer
+        .resize_with_conversion(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    assert!(dst_image
+        .buffer()
+        .chunks_exact(3)
+        .all(|p| p == [255, 128, 1]));
+
+    // Only conversions supported by `Image::convert()` are allowed.
+    let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8x4);
+    assert!(matches!(
+        resizer.resize_with_conversion(&src_image.view(), &mut dst_image.view_mut()),
+        Err(UnsupportedConversionError)
+    ));
+}
+
+#[test]
+fn dithering_reduces_banding_of_u16x3_gradient() {
+    let (width, height) = (256, 16);
+    // Smooth gradient that covers only five 8-bit values.
+    let values: Vec<f32> = (0..width)
+        .map(|x| 100. + 4. * x as f32 / (width - 1) as f32)
+        .collect();
+    let pixels: Vec<u8> = (0..height)
+        .flat_map(|_| values.iter())
+        .flat_map(|&v| [(v * 257.).round() as u16; 3])
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let size = (
+        NonZeroU32::new(width as u32).unwrap(),
+        NonZeroU32::new(height as u32).unwrap(),
+    );
+    let src_image = Image::from_vec_u8(size.0, size.1, pixels, PixelType::U16x3).unwrap();
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let mut convert = |dithering: bool| {
+        resizer.set_dithering(dithering);
+        let mut dst_image = Image::new(size.0, size.1, PixelType::U8x3);
+        resizer
+            .resize_with_conversion(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        // The first channel of every pixel
+        let result: Vec<u8> = dst_image.buffer().iter().step_by(3).copied().collect();
+        result
+    };
+    let rounded = convert(false);
+    let dithered = convert(true);
+    assert_eq!(dithered, convert(true));
+
+    let count_changes = |image: &[u8]| {
+        image
+            .chunks_exact(width)
+            .map(|row| row.windows(2).filter(|w| w[0] != w[1]).count())
+            .sum::<usize>()
+    };
+    // Rounding gives five bands with four edges in every row.
+    assert_eq!(count_changes(&rounded), 4 * height);
+    assert!(count_changes(&dithered) > 50 * height);
+
+    // Average value of every 4x4 block of dithered image is close
+    // to the average value of the gradient.
+    let max_block_error = |image: &[u8]| {
+        let mut max_error = 0f32;
+        for block_y in (0..height).step_by(4) {
+            for block_x in (0..width).step_by(4) {
+                let mut sum = 0.;
+                let mut expected = 0.;
+                for y in block_y..block_y + 4 {
+                    for x in block_x..block_x + 4 {
+                        sum += image[y * width + x] as f32;
+                        expected += values[x];
+                    }
+                }
+                max_error = max_error.max((sum - expected).abs() / 16.);
+            }
+        }
+        max_error
+    };
+    assert!(
+        max_block_error(&dithered) < 0.1,
+        "{}",
+        max_block_error(&dithered)
+    );
+    assert!(max_block_error(&rounded) > 0.4);
+}
+
+#[test]
+fn same_size_resize_is_copy() {
+    let src_images = [
+        U8::load_small_src_image(),
+        U8x3::load_small_src_image(),
+        U8x4::load_small_src_image(),
+        U16x3::load_small_src_image(),
+        U16x4::load_small_src_image(),
+        F32::load_small_src_image(),
+    ];
+    let algorithms = [
+        ResizeAlg::Nearest,
+        ResizeAlg::Convolution(FilterType::Mitchell),
+        ResizeAlg::Convolution(FilterType::Gaussian { sigma: 1.5 }),
+        ResizeAlg::SuperSampling(FilterType::Lanczos3, 2),
+        ResizeAlg::IntegralImage,
+        ResizeAlg::Bilinear,
+    ];
+    for src_image in src_images.iter() {
+        let (width, height) = (src_image.width(), src_image.height());
+        let pixel_size = src_image.buffer().len() / (width.get() * height.get()) as usize;
+        // Whole image and crop box aligned to pixels
+        let crop_boxes = [(0, 0, width.get(), height.get()), (13, 7, 100, 50)];
+        for (left, top, crop_width, crop_height) in crop_boxes {
+            let mut src_view = src_image.view();
+            src_view
+                .set_crop_box(CropBox {
+                    left: left as f64,
+                    top: top as f64,
+                    width: crop_width as f64,
+                    height: crop_height as f64,
+                })
+                .unwrap();
+            let row_size = width.get() as usize * pixel_size;
+            let expected: Vec<u8> = src_image
+                .buffer()
+                .chunks_exact(row_size)
+                .skip(top as usize)
+                .take(crop_height as usize)
+                .flat_map(|row| {
+                    let start = left as usize * pixel_size;
+                    &row[start..start + crop_width as usize * pixel_size]
+                })
+                .copied()
+                .collect();
+
+            for algorithm in algorithms {
+                let mut resizer = Resizer::new(algorithm);
+                resizer.set_alpha_mode(AlphaMode::Premultiplied);
+                resizer.set_gamma_correction(true);
+                let mut dst_image = Image::new(
+                    NonZeroU32::new(crop_width).unwrap(),
+                    NonZeroU32::new(crop_height).unwrap(),
+                    src_image.pixel_type(),
+                );
+                resizer
+                    .resize(&src_view, &mut dst_image.view_mut())
+                    .unwrap();
+                assert!(
+                    dst_image.buffer() == expected,
+                    "Result of same-size resizing of {:?} image with {:?} isn't a copy",
+                    src_image.pixel_type(),
+                    algorithm
+                );
+                assert_eq!(resizer.used_cpu_extensions(), Some(CpuExtensions::None));
+            }
+        }
+    }
+}
+
+#[test]
+fn convolution_per_axis_uses_filter_of_each_axis() {
+    fn resize_with(src_image: &ImageView, dst_size: (u32, u32), alg: ResizeAlg) -> Image<'static> {
+        let mut dst_image = Image::new(
+            NonZeroU32::new(dst_size.0).unwrap(),
+            NonZeroU32::new(dst_size.1).unwrap(),
+            src_image.pixel_type(),
+        );
+        Resizer::new(alg)
+            .resize(src_image, &mut dst_image.view_mut())
+            .unwrap();
+        dst_image
+    }
+
+    let src_image = U8x4::load_small_src_image();
+    let src_view = src_image.view();
+    let (src_width, src_height) = (src_view.width().get(), src_view.height().get());
+    let per_axis = ResizeAlg::ConvolutionPerAxis {
+        horizontal: FilterType::Lanczos3,
+        vertical: FilterType::Triangle,
+    };
+    let horiz_alg = ResizeAlg::Convolution(FilterType::Lanczos3);
+    let vert_alg = ResizeAlg::Convolution(FilterType::Triangle);
+
+    // Only horizontal pass
+    let dst_size = (src_width / 3, src_height);
+    let result = resize_with(&src_view, dst_size, per_axis);
+    let expected = resize_with(&src_view, dst_size, horiz_alg);
+    assert!(result.buffer() == expected.buffer());
+    let other = resize_with(&src_view, dst_size, vert_alg);
+    assert!(result.buffer() != other.buffer());
+
+    // Only vertical pass
+    let dst_size = (src_width, src_height / 3);
+    let result = resize_with(&src_view, dst_size, per_axis);
+    let expected = resize_with(&src_view, dst_size, vert_alg);
+    assert!(result.buffer() == expected.buffer());
+    let other = resize_with(&src_view, dst_size, horiz_alg);
+    assert!(result.buffer() != other.buffer());
+
+    // Both passes give the same result as two separate resizes
+    let dst_size = (src_width / 3, src_height / 3);
+    let result = resize_with(&src_view, dst_size, per_axis);
+    let tmp_image = resize_with(&src_view, (dst_size.0, src_height), horiz_alg);
+    let expected = resize_with(&tmp_image.view(), dst_size, vert_alg);
+    assert!(result.buffer() == expected.buffer());
+}
+
+#[test]
+fn resize_by_separate_passes_eq_resize() {
+    let crop_box = CropBox {
+        left: 10.5,
+        top: 20.25,
+        width: 700.,
+        height: 500.75,
+    };
+    let dst_width = NonZeroU32::new(213).unwrap();
+    for src_image in [U8x4::load_small_src_image(), U16x3::load_small_src_image()] {
+        let mut src_view = src_image.view();
+        src_view.set_crop_box(crop_box).unwrap();
+        for algorithm in [
+            ResizeAlg::Convolution(FilterType::Lanczos3),
+            ResizeAlg::adaptive(),
+        ] {
+            let mut resizer = Resizer::new(algorithm);
+            resizer.set_alpha_mode(AlphaMode::None);
+            let intermediate = resizer.resize_horizontally(&src_view, dst_width).unwrap();
+            assert_eq!(intermediate.width(), dst_width);
+            assert_eq!(intermediate.height(), src_image.height());
+
+            // The intermediate image is reused for several heights.
+            let mut intermediate_view = intermediate.view();
+            intermediate_view
+                .set_crop_box(CropBox {
+                    left: 0.,
+                    width: dst_width.get() as f64,
+                    ..crop_box
+                })
+                .unwrap();
+            for dst_height in [97, 500, 1000] {
+                let dst_height = NonZeroU32::new(dst_height).unwrap();
+                let result = resizer
+                    .resize_vertically(&intermediate_view, dst_height)
+                    .unwrap();
+                let mut expected = Image::new(dst_width, dst_height, src_image.pixel_type());
+                resizer.resize(&src_view, &mut expected.view_mut()).unwrap();
+                assert!(
+                    result.buffer() == expected.buffer(),
+                    "{:?} {:?} {}",
+                    algorithm,
+                    src_image.pixel_type(),
+                    dst_height
+                );
+            }
+        }
+    }
+
+    let mut resizer = Resizer::new(ResizeAlg::Nearest);
+    let src_image = U8x4::load_small_src_image();
+    assert!(matches!(
+        resizer.resize_horizontally(&src_image.view(), dst_width),
+        Err(UnsupportedAlgorithmError)
+    ));
+}
+
+#[test]
+fn max_filter_radius_bounds_cost_of_extreme_downscaling() {
+    let (src_width, src_height) = (10_000u32, 1000u32);
+    let buffer: Vec<u8> = (0..src_height)
+        .flat_map(|y| {
+            (0..src_width).map(move |x| (x * 128 / src_width + y * 127 / src_height) as u8)
+        })
+        .collect();
+    let src_image = Image::from_vec_u8(
+        NonZeroU32::new(src_width).unwrap(),
+        NonZeroU32::new(src_height).unwrap(),
+        buffer,
+        PixelType::U8,
+    )
+    .unwrap();
+    let dst_size = NonZeroU32::new(10).unwrap();
+    let resize = |max_filter_radius| {
+        let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+        resizer.set_max_filter_radius(max_filter_radius);
+        assert_eq!(resizer.max_filter_radius(), max_filter_radius);
+        let mut dst_image = Image::new(dst_size, dst_size, PixelType::U8);
+        let start = std::time::Instant::now();
+        resizer
+            .resize(&src_image.view(), &mut dst_image.view_mut())
+            .unwrap();
+        (start.elapsed(), dst_image)
+    };
+
+    let (capped_time, capped) = resize(NonZeroU32::new(16));
+    let (full_time, full) = resize(None);
+    assert!(
+        capped_time * 10 < full_time,
+        "{:?} >= {:?}",
+        capped_time,
+        full_time
+    );
+    // Smooth gradient is resized almost the same as without the limit
+    // (the full filter is asymmetrically cut by borders of the image).
+    for (&a, &b) in capped.buffer().iter().zip(full.buffer()) {
+        assert!(a.abs_diff(b) <= 3, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn u16_convolution_with_large_support_does_not_overflow() {
+    let mut cpu_extensions_vec = vec![CpuExtensions::None];
+    #[cfg(target_arch = "x86_64")]
+    cpu_extensions_vec.extend([CpuExtensions::Sse4_1, CpuExtensions::Avx2]);
+    #[cfg(target_arch = "aarch64")]
+    cpu_extensions_vec.push(CpuExtensions::Neon);
+    let filter_types = [FilterType::Lanczos3, FilterType::Gaussian { sigma: 50. }];
+    let pixel_types = [PixelType::U16x2, PixelType::U16x3, PixelType::U16x4];
+    for (src_width, src_height) in [(12_000, 8), (8, 12_000)] {
+        for pixel_type in pixel_types {
+            let src_width = NonZeroU32::new(src_width).unwrap();
+            let src_height = NonZeroU32::new(src_height).unwrap();
+            let buffer = vec![0xff; pixel_type.buffer_len(src_width, src_height).unwrap()];
+            let src_image = Image::from_vec_u8(src_width, src_height, buffer, pixel_type).unwrap();
+            let dst_size = NonZeroU32::new(2).unwrap();
+            for &cpu_extensions in cpu_extensions_vec.iter() {
+                if !cpu_extensions.is_supported() {
+                    continue;
+                }
+                for filter_type in filter_types {
+                    let mut resizer = Resizer::new(ResizeAlg::Convolution(filter_type));
+                    resizer.set_cpu_extensions(cpu_extensions);
+                    let mut dst_image = Image::new(dst_size, dst_size, pixel_type);
+                    resizer
+                        .resize(&src_image.view(), &mut dst_image.view_mut())
+                        .unwrap();
+                    // Full-white image stays white instead of wrapping of sums.
+                    assert!(
+                        dst_image.buffer().iter().all(|&v| v == 0xff),
+                        "{:?} {:?} {:?} {:?}",
+                        pixel_type,
+                        cpu_extensions,
+                        filter_type,
+                        dst_image.buffer()
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cmyk_channels_are_resized_equally_without_alpha_mode() {
+    let src_image = U8x4::load_small_src_image();
+    let (width, height) = (src_image.width(), src_image.height());
+    // Replace alpha channel by K channel with pixels that would be
+    // fully transparent (K = 0) or mostly transparent if it was alpha.
+    let mut cmyk = src_image.buffer().to_vec();
+    for (i, pixel) in cmyk.chunks_exact_mut(4).enumerate() {
+        pixel[3] = if (i / 17) % 3 == 0 { 0 } else { (i % 64) as u8 };
+    }
+    let src_image = Image::from_vec_u8(width, height, cmyk, PixelType::U8x4).unwrap();
+    let dst_width = NonZeroU32::new(301).unwrap();
+    let dst_height = NonZeroU32::new(199).unwrap();
+
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_alpha_mode(AlphaMode::None);
+    let mut dst_image = Image::new(dst_width, dst_height, PixelType::U8x4);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+
+    // Every channel is equal to the result of resizing of
+    // the channel as a separate image.
+    for channel in 0..4 {
+        let src_channel: Vec<u8> = src_image
+            .buffer()
+            .iter()
+            .skip(channel)
+            .step_by(4)
+            .copied()
+            .collect();
+        let src_channel = Image::from_vec_u8(width, height, src_channel, PixelType::U8).unwrap();
+        let mut dst_channel = Image::new(dst_width, dst_height, PixelType::U8);
+        resizer
+            .resize(&src_channel.view(), &mut dst_channel.view_mut())
+            .unwrap();
+        let result: Vec<u8> = dst_image
+            .buffer()
+            .iter()
+            .skip(channel)
+            .step_by(4)
+            .copied()
+            .collect();
+        assert!(result == dst_channel.buffer(), "channel {}", channel);
+    }
+}
+
+fn gaussian_2d(x: f64, y: f64) -> f64 {
+    // sigma = 0.5
+    (-(x * x + y * y) / 0.5).exp()
+}
+
+#[test]
+fn convolution_2d_with_separable_filter_eq_convolution() {
+    let filter = CustomFilter2D::new(gaussian_2d, 1.5).unwrap();
+    assert!(CustomFilter2D::new(gaussian_2d, 0.).is_err());
+    let src_image = U8x4::load_small_src_image();
+    let mut src_view = src_image.view();
+    src_view
+        .set_crop_box(CropBox {
+            left: 10.5,
+            top: 0.,
+            width: 600.,
+            height: 550.25,
+        })
+        .unwrap();
+    for (dst_width, dst_height) in [(213, 97), (1000, 800)] {
+        let dst_width = NonZeroU32::new(dst_width).unwrap();
+        let dst_height = NonZeroU32::new(dst_height).unwrap();
+        let mut expected = Image::new(dst_width, dst_height, PixelType::U8x4);
+        Resizer::new(ResizeAlg::Convolution(FilterType::Gaussian { sigma: 0.5 }))
+            .resize(&src_view, &mut expected.view_mut())
+            .unwrap();
+        let mut result = Image::new(dst_width, dst_height, PixelType::U8x4);
+        let mut resizer = Resizer::new(ResizeAlg::Convolution2D(filter));
+        resizer.resize(&src_view, &mut result.view_mut()).unwrap();
+        assert_eq!(resizer.used_cpu_extensions(), Some(CpuExtensions::None));
+        // Separable convolution rounds the result of the first pass.
+        for (&a, &b) in result.buffer().iter().zip(expected.buffer()) {
+            assert!(a.abs_diff(b) <= 1, "{} != {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn convolution_2d_blurs_image_of_the_same_size() {
+    let filter = CustomFilter2D::new(gaussian_2d, 1.5).unwrap();
+    let size = NonZeroU32::new(5).unwrap();
+    let mut pixels = vec![0u8; 5 * 5];
+    pixels[2 * 5 + 2] = 255;
+    let src_image = Image::from_vec_u8(size, size, pixels, PixelType::U8).unwrap();
+    let mut result = Image::new(size, size, PixelType::U8);
+    Resizer::new(ResizeAlg::Convolution2D(filter))
+        .resize(&src_image.view(), &mut result.view_mut())
+        .unwrap();
+    let buffer = result.buffer();
+    assert!(buffer[2 * 5 + 2] < 255);
+    assert!(buffer[2 * 5 + 1] > 0);
+    assert!(buffer[5 + 2] > 0);
+    assert_eq!(buffer[2 * 5 + 1], buffer[2 * 5 + 3]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn u8x4_nearest_simd_eq_native() {
+    if !CpuExtensions::Avx2.is_supported() {
+        return;
+    }
+    let src_image = U8x4::load_small_src_image();
+    let mut src_view = src_image.view();
+    for crop_box in [
+        None,
+        Some(CropBox {
+            left: 10.5,
+            top: 7.25,
+            width: 421.,
+            height: 300.5,
+        }),
+    ] {
+        if let Some(crop_box) = crop_box {
+            src_view.set_crop_box(crop_box).unwrap();
+        }
+        // Widths aren't multiple of count of pixels gathered by
+        // one SIMD-instruction, so tails of rows are checked too.
+        for (width, height) in [(1, 1), (13, 999), (427, 300), (4999, 3001)] {
+            for offset in [(0., 0.), (-0.4, 0.3)] {
+                let mut results =
+                    [CpuExtensions::None, CpuExtensions::Avx2].map(|cpu_extensions| {
+                        let mut dst_image = Image::new(
+                            NonZeroU32::new(width).unwrap(),
+                            NonZeroU32::new(height).unwrap(),
+                            PixelType::U8x4,
+                        );
+                        let mut resizer = Resizer::new(ResizeAlg::Nearest);
+                        resizer.set_nearest_offset(offset.0, offset.1);
+                        resizer.set_cpu_extensions(cpu_extensions);
+                        resizer
+                            .resize(&src_view, &mut dst_image.view_mut())
+                            .unwrap();
+                        assert_eq!(resizer.used_cpu_extensions(), Some(cpu_extensions));
+                        dst_image
+                    });
+                assert!(
+                    results[0].buffer() == results[1].buffer(),
+                    "{:?} {}x{} {:?}",
+                    crop_box,
+                    width,
+                    height,
+                    offset
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "serde")]
+use fast_image_resize::{
+    AlphaMode, BoundaryMode, CpuExtensions, CustomFilter, FilterType, ResizeAlg, RoundingMode,
+};
+
+fn filters() -> Vec<(FilterType, &'static str)> {
+    vec![
+        (FilterType::Box, r#""box""#),
+        (FilterType::Bilinear, r#""bilinear""#),
+        (FilterType::Triangle, r#""triangle""#),
+        (FilterType::Hamming, r#""hamming""#),
+        (FilterType::CatmullRom, r#""catmull_rom""#),
+        (FilterType::Mitchell, r#""mitchell""#),
+        (FilterType::Hermite, r#""hermite""#),
+        (FilterType::BSpline, r#""b_spline""#),
+        (FilterType::Lanczos2, r#""lanczos2""#),
+        (FilterType::Lanczos3, r#""lanczos3""#),
+        (FilterType::Blackman, r#""blackman""#),
+        (FilterType::Welch, r#""welch""#),
+        (FilterType::Bartlett, r#""bartlett""#),
+        (FilterType::Hann, r#""hann""#),
+        (
+            FilterType::Gaussian { sigma: 0.75 },
+            r#"{"gaussian":{"sigma":0.75}}"#,
+        ),
+        (
+            FilterType::Kaiser { beta: 8.5 },
+            r#"{"kaiser":{"beta":8.5}}"#,
+        ),
+    ]
+}
+
+#[test]
+fn filter_type_round_trip() {
+    for (filter_type, json) in filters() {
+        assert_eq!(serde_json::to_string(&filter_type).unwrap(), json);
+        let result: FilterType = serde_json::from_str(json).unwrap();
+        assert_eq!(result, filter_type);
+    }
+}
+
+#[test]
+fn custom_filter_is_not_serializable() {
+    let filter = CustomFilter::new(|x| (1.0 - x.abs()).max(0.0), 1.0).unwrap();
+    assert!(serde_json::to_string(&FilterType::Custom(filter)).is_err());
+    assert!(serde_json::from_str::<FilterType>(r#""custom""#).is_err());
+}
+
+#[test]
+fn resize_alg_round_trip() {
+    let mut algorithms = vec![
+        (ResizeAlg::Nearest, r#""nearest""#.to_string()),
+        (ResizeAlg::IntegralImage, r#""integral_image""#.to_string()),
+        (ResizeAlg::Bilinear, r#""bilinear""#.to_string()),
+        (
+            ResizeAlg::adaptive(),
+            r#"{"adaptive":{"threshold":4.0}}"#.to_string(),
+        ),
+    ];
+    for (filter_type, filter_json) in filters() {
+        algorithms.push((
+            ResizeAlg::Convolution(filter_type),
+            format!(r#"{{"convolution":{}}}"#, filter_json),
+        ));
+        algorithms.push((
+            ResizeAlg::ConvolutionPerAxis {
+                horizontal: filter_type,
+                vertical: FilterType::Triangle,
+            },
+            format!(
+                r#"{{"convolution_per_axis":{{"horizontal":{},"vertical":"triangle"}}}}"#,
+                filter_json
+            ),
+        ));
+        algorithms.push((
+            ResizeAlg::SuperSampling(filter_type, 2),
+            format!(r#"{{"super_sampling":[{},2]}}"#, filter_json),
+        ));
+    }
+    for (algorithm, json) in algorithms {
+        assert_eq!(serde_json::to_string(&algorithm).unwrap(), json);
+        let result: ResizeAlg = serde_json::from_str(&json).unwrap();
+        assert_eq!(result, algorithm);
+    }
+}
+
+#[test]
+fn cpu_extensions_round_trip() {
+    let mut extensions = vec![(CpuExtensions::None, r#""none""#)];
+    #[cfg(target_arch = "x86_64")]
+    extensions.extend([
+        (CpuExtensions::Sse4_1, r#""sse4_1""#),
+        (CpuExtensions::Avx2, r#""avx2""#),
+        (CpuExtensions::Avx512, r#""avx512""#),
+    ]);
+    #[cfg(target_arch = "aarch64")]
+    extensions.push((CpuExtensions::Neon, r#""neon""#));
+    #[cfg(target_arch = "wasm32")]
+    extensions.push((CpuExtensions::Simd128, r#""simd128""#));
+    for (cpu_extensions, json) in extensions {
+        assert_eq!(serde_json::to_string(&cpu_extensions).unwrap(), json);
+        let result: CpuExtensions = serde_json::from_str(json).unwrap();
+        assert_eq!(result, cpu_extensions);
+    }
+}
+
+#[test]
+fn alpha_mode_round_trip() {
+    for (alpha_mode, json) in [
+        (AlphaMode::None, r#""none""#),
+        (AlphaMode::Premultiplied, r#""premultiplied""#),
+        (
+            AlphaMode::AlreadyPremultiplied,
+            r#""already_premultiplied""#,
+        ),
+    ] {
+        assert_eq!(serde_json::to_string(&alpha_mode).unwrap(), json);
+        let result: AlphaMode = serde_json::from_str(json).unwrap();
+        assert_eq!(result, alpha_mode);
+    }
+}
+
+#[test]
+fn rounding_mode_round_trip() {
+    for (rounding_mode, json) in [
+        (RoundingMode::NearestAway, r#""nearest_away""#),
+        (RoundingMode::NearestEven, r#""nearest_even""#),
+        (RoundingMode::Truncate, r#""truncate""#),
+    ] {
+        assert_eq!(serde_json::to_string(&rounding_mode).unwrap(), json);
+        let result: RoundingMode = serde_json::from_str(json).unwrap();
+        assert_eq!(result, rounding_mode);
+    }
+}
+
+#[test]
+fn boundary_mode_round_trip() {
+    for (boundary_mode, json) in [
+        (BoundaryMode::Clamp, r#""clamp""#),
+        (BoundaryMode::Mirror, r#""mirror""#),
+        (BoundaryMode::Wrap, r#""wrap""#),
+    ] {
+        assert_eq!(serde_json::to_string(&boundary_mode).unwrap(), json);
+        let result: BoundaryMode = serde_json::from_str(json).unwrap();
+        assert_eq!(result, boundary_mode);
+    }
+}
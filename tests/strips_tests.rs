@@ -0,0 +1,234 @@
+use std::num::NonZeroU32;
+
+use fast_image_resize::{
+    AlphaMode, BoundaryMode, FilterType, Image, PixelType, ResizeAlg, ResizeByStripsError, Resizer,
+};
+
+fn nz(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap()
+}
+
+/// Creates image with pseudo-random pixels and sharp edges.
+fn synthetic_image(width: u32, height: u32, pixel_type: PixelType) -> Image<'static> {
+    let image = Image::new(nz(width), nz(height), pixel_type);
+    let size = image.buffer().len();
+    let buffer: Vec<u8> = (0..size as u32)
+        .map(|i| {
+            let v = i.wrapping_mul(2654435761) >> 24;
+            if (i / 1031) % 3 == 0 {
+                (v & 1) as u8 * 255
+            } else {
+                v as u8
+            }
+        })
+        .collect();
+    Image::from_vec_u8(nz(width), nz(height), buffer, pixel_type).unwrap()
+}
+
+/// Resizes image by strips and checks that rows of source image
+/// are requested in order and only once.
+fn resize_by_strips(
+    resizer: &mut Resizer,
+    src_image: &Image,
+    dst_size: (u32, u32),
+    strip_height: u32,
+) -> Vec<u8> {
+    let pixel_type = src_image.pixel_type();
+    let src_bytes = src_image.buffer();
+    let src_row_size = src_bytes.len() / src_image.height().get() as usize;
+    let dst_row_size = src_row_size / src_image.width().get() as usize * dst_size.0 as usize;
+    let mut result = vec![0u8; dst_row_size * dst_size.1 as usize];
+    let mut next_src_row = 0;
+    let mut next_dst_row = 0;
+    resizer
+        .resize_by_strips(
+            (src_image.width(), src_image.height()),
+            (nz(dst_size.0), nz(dst_size.1)),
+            pixel_type,
+            nz(strip_height),
+            &mut |first_row, rows| {
+                assert_eq!(first_row, next_src_row);
+                assert_eq!(rows.len() % src_row_size, 0);
+                let start = first_row as usize * src_row_size;
+                rows.copy_from_slice(&src_bytes[start..start + rows.len()]);
+                next_src_row += (rows.len() / src_row_size) as u32;
+                true
+            },
+            &mut |first_row, strip| {
+                assert_eq!(first_row, next_dst_row);
+                let rows_count = (strip.len() / dst_row_size) as u32;
+                assert!(rows_count == strip_height || first_row + rows_count == dst_size.1);
+                let start = first_row as usize * dst_row_size;
+                result[start..start + strip.len()].copy_from_slice(strip);
+                next_dst_row += rows_count;
+                true
+            },
+        )
+        .unwrap();
+    assert_eq!(next_dst_row, dst_size.1);
+    result
+}
+
+fn resize_whole(resizer: &mut Resizer, src_image: &Image, dst_size: (u32, u32)) -> Vec<u8> {
+    let mut dst_image = Image::new(nz(dst_size.0), nz(dst_size.1), src_image.pixel_type());
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image.buffer().to_vec()
+}
+
+#[test]
+fn resize_by_strips_eq_resize() {
+    let algorithms = [
+        ResizeAlg::Convolution(FilterType::Lanczos3),
+        ResizeAlg::Convolution(FilterType::Box),
+        ResizeAlg::ConvolutionPerAxis {
+            horizontal: FilterType::CatmullRom,
+            vertical: FilterType::Mitchell,
+        },
+    ];
+    let pixel_types = [
+        PixelType::U8,
+        PixelType::U8x3,
+        PixelType::U8x4,
+        PixelType::U16x3,
+        PixelType::F32,
+    ];
+    for pixel_type in pixel_types {
+        let src_image = synthetic_image(97, 613, pixel_type);
+        for algorithm in algorithms {
+            let mut resizer = Resizer::new(algorithm);
+            for dst_size in [(31, 47), (97, 200), (45, 613), (97, 613), (200, 1301)] {
+                let expected = resize_whole(&mut resizer, &src_image, dst_size);
+                for strip_height in [1, 7, 64, 2000] {
+                    let result = resize_by_strips(&mut resizer, &src_image, dst_size, strip_height);
+                    assert!(
+                        result == expected,
+                        "{:?} {:?} {:?} with strips of {} rows",
+                        pixel_type,
+                        algorithm,
+                        dst_size,
+                        strip_height
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn resize_by_strips_with_premultiplied_alpha() {
+    let src_image = synthetic_image(128, 517, PixelType::U8x4);
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    for alpha_mode in [AlphaMode::Premultiplied, AlphaMode::AlreadyPremultiplied] {
+        resizer.set_alpha_mode(alpha_mode);
+        for dst_size in [(50, 101), (128, 1000), (128, 517)] {
+            let expected = resize_whole(&mut resizer, &src_image, dst_size);
+            let result = resize_by_strips(&mut resizer, &src_image, dst_size, 16);
+            assert!(result == expected, "{:?} {:?}", alpha_mode, dst_size);
+        }
+    }
+}
+
+#[test]
+fn resize_by_strips_with_mirror_boundary_mode() {
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_boundary_mode(BoundaryMode::Mirror);
+    let sizes = [
+        ((8, 100), (8, 50)),
+        ((8, 10), (8, 97)),
+        ((97, 613), (31, 1301)),
+    ];
+    for (src_size, dst_size) in sizes {
+        let src_image = synthetic_image(src_size.0, src_size.1, PixelType::U8);
+        let expected = resize_whole(&mut resizer, &src_image, dst_size);
+        for strip_height in [1, 10, 64] {
+            let result = resize_by_strips(&mut resizer, &src_image, dst_size, strip_height);
+            assert!(result == expected, "{:?} {}", dst_size, strip_height);
+        }
+    }
+}
+
+#[test]
+fn resize_by_strips_keeps_only_used_rows() {
+    let (width, height) = (nz(64), nz(10_000));
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    let mut max_rows = 0;
+    resizer
+        .resize_by_strips(
+            (width, height),
+            (nz(32), nz(1000)),
+            PixelType::U8,
+            nz(10),
+            &mut |_, rows| {
+                max_rows = max_rows.max(rows.len() / 64);
+                true
+            },
+            &mut |_, _| true,
+        )
+        .unwrap();
+    // Strip of 10 rows uses 100 source rows and 60 rows of overlap
+    // with the previous strip for 10x downscaling with Lanczos3.
+    assert!(max_rows <= 160, "{}", max_rows);
+}
+
+#[test]
+fn resize_by_strips_errors() {
+    let size = (nz(16), nz(16));
+    let dst_size = (nz(8), nz(8));
+    let mut resizer = Resizer::new(ResizeAlg::Nearest);
+    assert!(matches!(
+        resizer.resize_by_strips(
+            size,
+            dst_size,
+            PixelType::U8,
+            nz(4),
+            &mut |_, _| true,
+            &mut |_, _| true
+        ),
+        Err(ResizeByStripsError::UnsupportedAlgorithm)
+    ));
+
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_boundary_mode(BoundaryMode::Wrap);
+    assert!(matches!(
+        resizer.resize_by_strips(
+            size,
+            dst_size,
+            PixelType::U8,
+            nz(4),
+            &mut |_, _| true,
+            &mut |_, _| true
+        ),
+        Err(ResizeByStripsError::UnsupportedBoundaryMode)
+    ));
+
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Bilinear));
+    assert!(matches!(
+        resizer.resize_by_strips(
+            size,
+            dst_size,
+            PixelType::U8,
+            nz(4),
+            &mut |_, _| false,
+            &mut |_, _| true
+        ),
+        Err(ResizeByStripsError::Cancelled)
+    ));
+    let mut strips = 0;
+    assert!(matches!(
+        resizer.resize_by_strips(
+            size,
+            dst_size,
+            PixelType::U8,
+            nz(4),
+            &mut |_, _| true,
+            &mut |_, _| {
+                strips += 1;
+                false
+            }
+        ),
+        Err(ResizeByStripsError::Cancelled)
+    ));
+    assert_eq!(strips, 1);
+}
@@ -27,11 +27,18 @@ pub trait PixelExt: Pixel {
     fn pixel_type_str() -> &'static str {
         match Self::pixel_type() {
             PixelType::U8 => "u8",
+            PixelType::U8x2 => "u8x2",
             PixelType::U8x3 => "u8x3",
             PixelType::U8x4 => "u8x4",
+            PixelType::U16x2 => "u16x2",
             PixelType::U16x3 => "u16x3",
+            PixelType::U16x4 => "u16x4",
+            PixelType::I16 => "i16",
             PixelType::I32 => "i32",
             PixelType::F32 => "f32",
+            PixelType::F32x4 => "f32x4",
+            #[cfg(feature = "half")]
+            PixelType::F16 => "f16",
         }
     }
 
@@ -86,6 +93,12 @@ impl PixelExt for U8 {
     }
 }
 
+impl PixelExt for U8x2 {
+    fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
+        img.to_luma_alpha8().into_raw()
+    }
+}
+
 impl PixelExt for U8x3 {
     fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
         img.to_rgb8().into_raw()
@@ -98,6 +111,16 @@ impl PixelExt for U8x4 {
     }
 }
 
+impl PixelExt for U16x2 {
+    fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
+        img.to_luma_alpha8()
+            .as_raw()
+            .iter()
+            .flat_map(|&c| [c, c])
+            .collect()
+    }
+}
+
 impl PixelExt for U16x3 {
     fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
         img.to_rgb8()
@@ -108,6 +131,29 @@ impl PixelExt for U16x3 {
     }
 }
 
+impl PixelExt for U16x4 {
+    fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
+        img.to_rgba8()
+            .as_raw()
+            .iter()
+            .flat_map(|&c| [c, c])
+            .collect()
+    }
+}
+
+impl PixelExt for I16 {
+    fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
+        // Luma values are shifted into range of `i16`
+        // to get pixels with negative values.
+        img.to_luma16()
+            .as_raw()
+            .iter()
+            .map(|&p| (p as i32 + i16::MIN as i32) as i16)
+            .flat_map(|val| val.to_le_bytes())
+            .collect()
+    }
+}
+
 impl PixelExt for I32 {
     fn img_into_bytes(img: DynamicImage) -> Vec<u8> {
         img.to_luma16()
@@ -139,8 +185,11 @@ pub fn save_result(image: &Image, name: &str) {
     let color_type = match image.pixel_type() {
         PixelType::U8x3 => ColorType::Rgb8,
         PixelType::U8x4 => ColorType::Rgba8,
+        PixelType::U16x2 => ColorType::La16,
         PixelType::U16x3 => ColorType::Rgb16,
+        PixelType::U16x4 => ColorType::Rgba16,
         PixelType::U8 => ColorType::L8,
+        PixelType::U8x2 => ColorType::La8,
         _ => panic!("Unsupported type of pixels"),
     };
     image::save_buffer(
@@ -160,5 +209,11 @@ pub fn cpu_ext_into_str(cpu_extensions: CpuExtensions) -> &'static str {
         CpuExtensions::Sse4_1 => "sse41",
         #[cfg(target_arch = "x86_64")]
         CpuExtensions::Avx2 => "avx2",
+        #[cfg(target_arch = "x86_64")]
+        CpuExtensions::Avx512 => "avx512",
+        #[cfg(target_arch = "aarch64")]
+        CpuExtensions::Neon => "neon",
+        #[cfg(target_arch = "wasm32")]
+        CpuExtensions::Simd128 => "simd128",
     }
 }
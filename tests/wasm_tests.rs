@@ -0,0 +1,38 @@
+#![cfg(target_arch = "wasm32")]
+use std::num::NonZeroU32;
+
+use wasm_bindgen_test::*;
+
+use fast_image_resize::{CpuExtensions, FilterType, Image, PixelType, ResizeAlg, Resizer};
+
+fn get_src_image() -> Image<'static> {
+    let size = NonZeroU32::new(500).unwrap();
+    let pixels: Vec<u32> = (0..500 * 500u32)
+        .map(|i| i.wrapping_mul(2654435761))
+        .collect();
+    Image::from_vec_u32(size, size, pixels, PixelType::U8x4).unwrap()
+}
+
+fn resize(src_image: &Image, width: u32, height: u32, cpu_extensions: CpuExtensions) -> Vec<u8> {
+    let mut dst_image = Image::new(
+        NonZeroU32::new(width).unwrap(),
+        NonZeroU32::new(height).unwrap(),
+        src_image.pixel_type(),
+    );
+    let mut resizer = Resizer::new(ResizeAlg::Convolution(FilterType::Lanczos3));
+    resizer.set_cpu_extensions(cpu_extensions);
+    resizer
+        .resize(&src_image.view(), &mut dst_image.view_mut())
+        .unwrap();
+    dst_image.buffer().to_owned()
+}
+
+#[wasm_bindgen_test]
+fn simd128_u8x4_eq_native() {
+    let src_image = get_src_image();
+    for (width, height) in [(150, 150), (499, 500), (500, 233), (1001, 1003)] {
+        let native_result = resize(&src_image, width, height, CpuExtensions::None);
+        let simd_result = resize(&src_image, width, height, CpuExtensions::Simd128);
+        assert!(native_result == simd_result, "{}x{}", width, height);
+    }
+}